@@ -60,5 +60,31 @@ fn bench_fastqr_qrcode(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, bench_fastqr_qrcode);
+/// Building evaluates the 8 masks, compare with and without the `rayon` feature:
+/// `cargo bench --bench qr -- masks` then `cargo bench --bench qr -F rayon -- masks`
+fn bench_masks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("masks");
+    group.measurement_time(Duration::from_secs(10));
+
+    for (id, version) in [
+        ("V20H", fast_qr::Version::V20),
+        ("V30H", fast_qr::Version::V30),
+        ("V35H", fast_qr::Version::V35),
+        ("V40H", fast_qr::Version::V40),
+    ] {
+        group.bench_function(id, |b| {
+            b.iter(|| {
+                QRBuilder::new(black_box("https://example.com/"))
+                    .ecl(fast_qr::ECL::H)
+                    .version(version)
+                    .build()
+                    .unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_fastqr_qrcode, bench_masks);
 criterion_main!(benches);
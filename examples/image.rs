@@ -1,6 +1,9 @@
 fn main() {
     use fast_qr::{
-        convert::{image::ImageBuilder, Builder, Shape},
+        convert::{
+            image::{ImageBuilder, ImageFormat},
+            Builder, Shape,
+        },
         QRBuilder, Version, ECL,
     };
 
@@ -22,4 +25,11 @@ fn main() {
         .fit_width(512)
         .background_color([255, 255, 255, 255]) // opaque
         .to_bytes(&qrcode);
+
+    // Or in another format.
+    let _image_as_webp = ImageBuilder::default()
+        .shape(Shape::RoundedSquare)
+        .fit_width(512)
+        .format(ImageFormat::Webp)
+        .to_bytes(&qrcode);
 }
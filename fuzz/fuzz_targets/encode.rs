@@ -0,0 +1,23 @@
+//! Builds a `QRCode` with arbitrary options and data, building may fail but never panic
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+mod options;
+
+fuzz_target!(|data: &[u8]| {
+    let mut options = options::Options(data);
+    let max_version = options.version();
+    let builder = options.qr_builder();
+
+    let built = builder.build();
+    assert_eq!(builder.validate().is_ok(), built.is_ok());
+    let _ = builder.estimate_version();
+    let _ = builder.build_codewords();
+    let _ = builder.build_multi(max_version);
+
+    if let Ok(qrcode) = built {
+        let _ = qrcode.to_str();
+    }
+});
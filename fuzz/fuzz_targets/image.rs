@@ -0,0 +1,23 @@
+//! Rasterizes a `QRCode` with arbitrary options, rendering may fail but never panic
+
+#![no_main]
+
+use fast_qr::convert::image::ImageBuilder;
+use libfuzzer_sys::fuzz_target;
+
+mod options;
+
+fuzz_target!(|data: &[u8]| {
+    let mut options = options::Options(data);
+    let qrcode = match options.qr_builder().build() {
+        Ok(qrcode) => qrcode,
+        Err(_) => return,
+    };
+
+    let mut builder = ImageBuilder::default();
+    options.style(&mut builder);
+    // Keeps the pixmaps small enough for the fuzzer
+    builder.fit_width(u32::from(options.byte()));
+
+    let _ = builder.to_pixmap(&qrcode);
+});
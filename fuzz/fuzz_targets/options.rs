@@ -0,0 +1,227 @@
+//! Reads builder options from the first bytes of the fuzzer input
+
+// Each target only uses some of the options
+#![allow(dead_code)]
+
+use fast_qr::convert::{Builder, EyeShape, Gradient, ImageBackgroundShape, Shape};
+use fast_qr::{
+    EciCode, Fnc1, Mask, MicroVersion, Mode, ModuleType, QRBuilder, Segment, Version, ECL,
+};
+
+pub const ECLS: [ECL; 4] = [ECL::L, ECL::M, ECL::Q, ECL::H];
+pub const MODES: [Mode; 3] = [Mode::Numeric, Mode::Alphanumeric, Mode::Byte];
+pub const MASKS: [Mask; 8] = [
+    Mask::Checkerboard,
+    Mask::HorizontalLines,
+    Mask::VerticalLines,
+    Mask::DiagonalLines,
+    Mask::LargeCheckerboard,
+    Mask::Fields,
+    Mask::Diamonds,
+    Mask::Meadow,
+];
+pub const SHAPES: [Shape; 12] = [
+    Shape::Square,
+    Shape::Circle,
+    Shape::RoundedSquare,
+    Shape::Vertical,
+    Shape::Horizontal,
+    Shape::Diamond,
+    Shape::Blob,
+    Shape::Dot,
+    Shape::Hexagon,
+    Shape::Octagon,
+    Shape::Star,
+    Shape::Heart,
+];
+pub const EYE_SHAPES: [EyeShape; 3] = [EyeShape::Square, EyeShape::RoundedSquare, EyeShape::Circle];
+pub const IMAGE_BACKGROUND_SHAPES: [ImageBackgroundShape; 3] = [
+    ImageBackgroundShape::Square,
+    ImageBackgroundShape::Circle,
+    ImageBackgroundShape::RoundedSquare,
+];
+pub const MICRO_VERSIONS: [MicroVersion; 4] = [
+    MicroVersion::M1,
+    MicroVersion::M2,
+    MicroVersion::M3,
+    MicroVersion::M4,
+];
+
+/// Consumes the fuzzer input, `0` once empty
+pub struct Options<'a>(pub &'a [u8]);
+
+impl<'a> Options<'a> {
+    pub fn byte(&mut self) -> u8 {
+        match self.0.split_first() {
+            Some((&byte, rest)) => {
+                self.0 = rest;
+                byte
+            }
+            None => 0,
+        }
+    }
+
+    pub fn pick<T: Copy>(&mut self, values: &[T]) -> T {
+        values[usize::from(self.byte()) % values.len()]
+    }
+
+    pub fn version(&mut self) -> Version {
+        let number = usize::from(self.byte() % 40) + 1;
+        Version::try_from(number).expect("Versions go from 1 to 40")
+    }
+
+    /// Any `f64`, NaN and infinities included
+    pub fn float(&mut self) -> f64 {
+        let mut bytes = [0; 8];
+        bytes.iter_mut().for_each(|byte| *byte = self.byte());
+        f64::from_le_bytes(bytes)
+    }
+
+    /// Up to `len` bytes
+    fn take(&mut self, len: usize) -> &'a [u8] {
+        let (bytes, rest) = self.0.split_at(len.min(self.0.len()));
+        self.0 = rest;
+        bytes
+    }
+
+    /// Up to 255 bytes
+    pub fn bytes(&mut self) -> &'a [u8] {
+        let len = self.byte();
+        self.take(usize::from(len))
+    }
+
+    /// A builder of up to 65535 bytes of data, with the options set by flag bytes
+    pub fn qr_builder(&mut self) -> QRBuilder {
+        let len = u16::from_le_bytes([self.byte(), self.byte()]);
+        let mut builder = QRBuilder::new(self.take(usize::from(len)));
+
+        let flags = u16::from_le_bytes([self.byte(), self.byte()]);
+        let flag = |bit: u16| flags & (1 << bit) != 0;
+
+        if flag(0) {
+            builder.mode(self.pick(&MODES));
+        }
+        if flag(1) {
+            builder.ecl(self.pick(&ECLS));
+        }
+        if flag(2) {
+            builder.disable_ecl_boost();
+        }
+        if flag(3) {
+            builder.version(self.version());
+        }
+        if flag(4) {
+            builder.version_range(self.version(), self.version());
+        }
+        if flag(5) {
+            builder.mask(self.pick(&MASKS));
+        }
+        if flag(6) {
+            let masks = self.bytes().iter().map(|&i| MASKS[usize::from(i) % 8]);
+            builder.mask_candidates(&masks.collect::<Vec<_>>());
+        }
+        if flag(7) {
+            builder.eci(EciCode::Utf8);
+        }
+        if flag(8) {
+            builder.fnc1(Fnc1::Second(self.byte()));
+        }
+        if flag(9) {
+            let segments = (0..self.byte() % 4)
+                .filter_map(|_| {
+                    let mode = self.pick(&MODES);
+                    Segment::new(mode, self.bytes())
+                })
+                .collect();
+            builder.segments(segments);
+        }
+        if flag(10) {
+            let bits = self.bytes().iter().map(|&byte| byte & 1 != 0);
+            builder.raw_segment(self.pick(&MODES), &bits.collect::<Vec<_>>());
+        }
+        if flag(11) {
+            builder.micro();
+        }
+        if flag(12) {
+            builder.micro_version(self.pick(&MICRO_VERSIONS));
+        }
+
+        builder
+    }
+
+    /// Any text, lossily decoded
+    pub fn text(&mut self) -> String {
+        String::from_utf8_lossy(self.bytes()).into_owned()
+    }
+
+    /// Sets the options of the [`Builder`] trait with a flag byte
+    pub fn style<B: Builder>(&mut self, builder: &mut B) {
+        let flags = u16::from_le_bytes([self.byte(), self.byte()]);
+        let flag = |bit: u16| flags & (1 << bit) != 0;
+
+        if flag(0) {
+            builder.margin(usize::from(self.byte()));
+        }
+        if flag(1) {
+            builder.module_color(self.text());
+        }
+        if flag(2) {
+            let stops = vec![(self.float(), self.text()), (self.float(), self.text())];
+            builder.module_gradient(Gradient::linear(self.float(), stops));
+        }
+        if flag(3) {
+            builder
+                .finder_color(self.text())
+                .eye_ball_color(self.text());
+        }
+        if flag(4) {
+            builder
+                .eye_frame_shape(self.pick(&EYE_SHAPES))
+                .eye_ball_shape(self.pick(&EYE_SHAPES));
+        }
+        if flag(5) {
+            for _ in 0..self.byte() % 4 {
+                builder.shape(self.pick(&SHAPES));
+            }
+        }
+        if flag(6) {
+            // Mostly radii in range, so the outlines are traced and rounded
+            let radius = match self.byte() {
+                0 => self.float(),
+                byte => f64::from(byte) / 510f64,
+            };
+            builder.merge_modules(radius);
+        }
+        if flag(7) {
+            builder.dot_radius(self.float());
+        }
+        if flag(8) {
+            builder.image_bytes(self.bytes(), "image/png");
+        }
+        if flag(9) {
+            builder.image_background_shape(self.pick(&IMAGE_BACKGROUND_SHAPES));
+        }
+        if flag(10) {
+            builder.image_size(self.float());
+        }
+        if flag(11) {
+            builder.image_gap(self.float());
+        }
+        if flag(12) {
+            builder.image_position(self.float(), self.float());
+        }
+        if flag(13) {
+            builder.background_color(self.text());
+        }
+        if flag(14) {
+            builder.module_gap(self.float());
+        }
+        if flag(15) {
+            // Modules with their own shape are left out of the merged outlines
+            for _ in 0..self.byte() % 4 {
+                let module_type = ModuleType::from(self.byte() % 8);
+                builder.shape_for(module_type, self.pick(&SHAPES));
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Draws a `QRCode` as svg with arbitrary options, drawing never panics
+
+#![no_main]
+
+use fast_qr::convert::svg::{FrameStyle, LabelPosition, SvgBuilder};
+use libfuzzer_sys::fuzz_target;
+
+mod options;
+
+fuzz_target!(|data: &[u8]| {
+    let mut options = options::Options(data);
+    let qrcode = match options.qr_builder().build() {
+        Ok(qrcode) => qrcode,
+        Err(_) => return,
+    };
+
+    let mut builder = SvgBuilder::default();
+    options.style(&mut builder);
+
+    let flags = u16::from_le_bytes([options.byte(), options.byte()]);
+    let flag = |bit: u16| flags & (1 << bit) != 0;
+    if flag(0) {
+        let size = options.float();
+        builder.center_text(options.text(), size, options.text());
+    }
+    if flag(1) {
+        builder.frame(FrameStyle::Rounded);
+    }
+    if flag(2) {
+        builder.label(options.text(), options.text(), LabelPosition::Bottom);
+    }
+    if flag(3) {
+        builder.precision(usize::from(options.byte()));
+    }
+    if flag(4) {
+        builder.scale(usize::from(options.byte()));
+    }
+    if flag(5) {
+        builder.background_radius(options.float());
+    }
+    builder.minify(flag(6)).swiss_cross(flag(7));
+    if flag(8) {
+        let mut margin = || usize::from(options.byte() % 16);
+        builder.margins(margin(), margin(), margin(), margin());
+    }
+    builder.invert(flag(9)).use_symbols(flag(10));
+
+    let _ = builder.to_str(&qrcode);
+    let _ = builder.check_image(&qrcode);
+});
@@ -0,0 +1,85 @@
+#ifndef FAST_QR_H
+#define FAST_QR_H
+
+/* Generated with cbindgen, do not edit by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Lets `fast_qr_build` choose the error correction level
+#define FAST_QR_ECL_AUTO -1
+
+// Low error correction level, 7%
+#define FAST_QR_ECL_L 0
+
+// Medium error correction level, 15%
+#define FAST_QR_ECL_M 1
+
+// Quartile error correction level, 25%
+#define FAST_QR_ECL_Q 2
+
+// High error correction level, 30%
+#define FAST_QR_ECL_H 3
+
+// Opaque [`QRCode`], created by [`fast_qr_build`] and released by [`fast_qr_free`]
+typedef struct FastQrCode FastQrCode;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Builds a QR code of `data`, returns null if the data is too long for `version`.
+//
+// `ecl` is one of the `FAST_QR_ECL_*` constants, `version` goes from 1 to 40 or is 0 to use
+// the smallest fitting version.
+//
+// # Safety
+// `data` must be valid for `len` bytes, it can be null when `len` is 0
+struct FastQrCode *fast_qr_build(const uint8_t *data, size_t len, int32_t ecl, int32_t version);
+
+// Releases a QR code returned by [`fast_qr_build`], does nothing if `qr` is null
+//
+// # Safety
+// `qr` must come from [`fast_qr_build`] and not be used afterwards
+void fast_qr_free(struct FastQrCode *qr);
+
+// Returns the width & height of the QR code in modules, without margin
+//
+// # Safety
+// `qr` must come from [`fast_qr_build`]
+size_t fast_qr_size(const struct FastQrCode *qr);
+
+// Returns whether the module at row `y`, column `x` is dark, false when out of bounds
+//
+// # Safety
+// `qr` must come from [`fast_qr_build`]
+bool fast_qr_module(const struct FastQrCode *qr, size_t y, size_t x);
+
+// Writes the modules row by row, 1 for dark and 0 for light, returns `size * size`
+//
+// # Safety
+// `qr` must come from [`fast_qr_build`], `buffer` must be null or valid for `len` bytes
+size_t fast_qr_modules(const struct FastQrCode *qr, uint8_t *buffer, size_t len);
+
+// Writes the SVG as a null-terminated string, returns its length including the terminator
+//
+// # Safety
+// `qr` must come from [`fast_qr_build`], `buffer` must be null or valid for `len` bytes
+size_t fast_qr_to_svg(const struct FastQrCode *qr, uint8_t *buffer, size_t len);
+
+#if defined(FAST_QR_IMAGE)
+// Writes a PNG fitting in `width` pixels, returns its length, or 0 if encoding failed
+//
+// # Safety
+// `qr` must come from [`fast_qr_build`], `buffer` must be null or valid for `len` bytes
+size_t fast_qr_to_png(const struct FastQrCode *qr, uint32_t width, uint8_t *buffer, size_t len);
+#endif
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* FAST_QR_H */
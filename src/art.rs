@@ -0,0 +1,311 @@
+//! Draws into a [`QRCode`] by flipping data modules, within the error correction budget
+//!
+//! Each flipped module damages the codeword holding it, and each error correction block can
+//! only recover so many damaged codewords. [`ArtCanvas`] tracks the codewords damaged in every
+//! block and refuses the flips that would use more than a share of what the block can recover.
+//! Function patterns (finders, timing, alignment, format and version information) are never
+//! changed.
+//!
+//! ```rust
+//! use fast_qr::art::ArtCanvas;
+//! use fast_qr::{decode, QRBuilder, ECL};
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").ecl(ECL::H).build().unwrap();
+//!
+//! let mut canvas = ArtCanvas::new(&qrcode).unwrap();
+//! // A 5x5 dark square in the bottom right corner, where there are only data modules
+//! let picture = [[true; 5]; 5];
+//! // Modules over the budget are left as is
+//! let refused = canvas.draw(qrcode.size - 6, qrcode.size - 6, &picture);
+//! println!("{refused} modules could not be drawn");
+//!
+//! let art = canvas.into_qrcode();
+//! assert_eq!(decode::decode(&art).unwrap(), b"https://example.com/");
+//! ```
+
+#![deny(unsafe_code)]
+#![deny(clippy::unwrap_used)]
+#![warn(missing_docs)]
+
+use core::fmt::Formatter;
+
+use alloc::{vec, vec::Vec};
+
+use crate::module::ModuleType;
+use crate::{hardcode, QRCode, Version, ECL};
+
+/// Share of the correctable errors a block may use (default: half)
+const DEFAULT_THRESHOLD: f64 = 0.5;
+
+/// Why a module could not be changed by an [`ArtCanvas`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtError {
+    /// `QRCode` is a Micro `QRCode` or was not built with [`crate::QRBuilder`]
+    Unsupported,
+    /// Coordinates are outside of the `QRCode`
+    OutOfBounds,
+    /// Module is part of a function pattern, not of the data
+    FunctionModule,
+    /// Block of the module would have more damaged codewords than the threshold allows
+    Budget {
+        /// Error correction block of the module
+        block: usize,
+    },
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ArtError {}
+
+impl core::fmt::Display for ArtError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ArtError::Unsupported => f.write_str("Only built regular QR codes can be drawn into"),
+            ArtError::OutOfBounds => f.write_str("Module is outside of the QR code"),
+            ArtError::FunctionModule => f.write_str("Module is part of a function pattern"),
+            ArtError::Budget { block } => {
+                write!(f, "Error correction budget of block {block} is exhausted")
+            }
+        }
+    }
+}
+
+/// Damaged codewords of an error correction block, see [`ArtCanvas::budget`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockBudget {
+    /// Codewords with at least one flipped module
+    pub used: usize,
+    /// Damaged codewords allowed by the threshold
+    pub allowed: usize,
+    /// Damaged codewords the block can recover
+    pub correctable: usize,
+}
+
+/// [`QRCode`] whose data modules can be flipped while it still decodes
+///
+/// # Example
+/// ```rust
+/// use fast_qr::art::{ArtCanvas, ArtError};
+/// use fast_qr::QRBuilder;
+///
+/// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+/// let mut canvas = ArtCanvas::new(&qrcode).unwrap();
+///
+/// // Finder patterns are left alone
+/// assert_eq!(canvas.flip(0, 0), Err(ArtError::FunctionModule));
+///
+/// let (y, x) = (qrcode.size - 1, qrcode.size - 1);
+/// canvas.flip(y, x).unwrap();
+/// assert_eq!(canvas.budget()[0].used, 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArtCanvas {
+    qr: QRCode,
+    original: QRCode,
+    /// Codeword of each module, row by row, `None` for function and remainder modules
+    codewords: Vec<Option<usize>>,
+    /// Block of each codeword
+    blocks: Vec<usize>,
+    /// Flipped modules in each codeword
+    flipped: Vec<usize>,
+    /// Damaged codewords in each block
+    used: Vec<usize>,
+    /// Damaged codewords each block can recover
+    correctable: Vec<usize>,
+    threshold: f64,
+}
+
+impl ArtCanvas {
+    /// Creates a canvas over a copy of `qr`
+    ///
+    /// # Errors
+    /// - `ArtError::Unsupported` for Micro `QRCode`s and `QRCode`s not built with
+    ///   [`crate::QRBuilder`]
+    pub fn new(qr: &QRCode) -> Result<Self, ArtError> {
+        let (version, ecl) = match (qr.version, qr.micro_version) {
+            (Some(version), None) if qr.size == version.size() => (version, qr.ecl),
+            _ => return Err(ArtError::Unsupported),
+        };
+
+        let blocks = Self::codeword_blocks(version, ecl);
+        let error_len = hardcode::get_polynomial(version, ecl).len() - 1;
+        let correctable = (error_len - misdecode_protection(version, ecl)) / 2;
+        let block_count = blocks.iter().max().map_or(0, |&last| last + 1);
+
+        Ok(ArtCanvas {
+            qr: qr.clone(),
+            original: qr.clone(),
+            codewords: Self::module_codewords(qr, blocks.len()),
+            flipped: vec![0; blocks.len()],
+            blocks,
+            used: vec![0; block_count],
+            correctable: vec![correctable; block_count],
+            threshold: DEFAULT_THRESHOLD,
+        })
+    }
+
+    /// Returns the codeword of each module, following the placement of the data, referring to
+    /// 7.7.3 of the spec
+    fn module_codewords(qr: &QRCode, codeword_count: usize) -> Vec<Option<usize>> {
+        let n = qr.size;
+        let mut codewords = vec![None; n * n];
+        let mut bit = 0;
+        let mut upward = true;
+
+        for x in (0..6).chain(7..n).rev().step_by(2) {
+            for i in 0..n {
+                let y = if upward { n - 1 - i } else { i };
+
+                for x in [x, x - 1] {
+                    if qr[y][x].module_type() == ModuleType::Data {
+                        // Remainder bits, after the last codeword, are not read
+                        codewords[y * n + x] = Some(bit / 8).filter(|&c| c < codeword_count);
+                        bit += 1;
+                    }
+                }
+            }
+
+            upward = !upward;
+        }
+
+        codewords
+    }
+
+    /// Returns the block of each interleaved codeword, referring to 7.6 of the spec
+    fn codeword_blocks(version: Version, ecl: ECL) -> Vec<usize> {
+        let [(g1_count, g1_size), (g2_count, g2_size)] = hardcode::ecc_to_groups(ecl, version);
+        let error_len = hardcode::get_polynomial(version, ecl).len() - 1;
+        let block_count = g1_count + g2_count;
+        let size = |block: usize| if block < g1_count { g1_size } else { g2_size };
+
+        let mut blocks = Vec::with_capacity(version.max_bytes());
+        for i in 0..core::cmp::max(g1_size, g2_size) {
+            blocks.extend((0..block_count).filter(|&block| i < size(block)));
+        }
+        for _ in 0..error_len {
+            blocks.extend(0..block_count);
+        }
+
+        blocks
+    }
+
+    /// Sets the share of the correctable errors each block may use, between 0 and 1
+    /// (default: 0.5)
+    ///
+    /// Scanners also misread modules, a lower threshold leaves them more room. Modules already
+    /// flipped are kept.
+    pub fn threshold(&mut self, threshold: f64) -> &mut Self {
+        self.threshold = threshold.clamp(0f64, 1f64);
+        self
+    }
+
+    /// Damaged codewords allowed in a block by the threshold
+    fn allowed(&self, block: usize) -> usize {
+        (self.correctable[block] as f64 * self.threshold) as usize
+    }
+
+    /// Flips the module at (`y`, `x`)
+    ///
+    /// Flipping a module back to its original value gives its budget back.
+    ///
+    /// # Errors
+    /// - `ArtError::OutOfBounds` if (`y`, `x`) is outside of the `QRCode`
+    /// - `ArtError::FunctionModule` if the module is not a data module
+    /// - `ArtError::Budget` if its block would have more damaged codewords than the threshold
+    ///   allows, the module is then left as is
+    pub fn flip(&mut self, y: usize, x: usize) -> Result<(), ArtError> {
+        let n = self.qr.size;
+        if y >= n || x >= n {
+            return Err(ArtError::OutOfBounds);
+        }
+        if self.qr[y][x].module_type() != ModuleType::Data {
+            return Err(ArtError::FunctionModule);
+        }
+
+        let restoring = self.qr[y][x].value() != self.original[y][x].value();
+        if let Some(codeword) = self.codewords[y * n + x] {
+            let block = self.blocks[codeword];
+            match (restoring, self.flipped[codeword]) {
+                (false, 0) if self.used[block] >= self.allowed(block) => {
+                    return Err(ArtError::Budget { block });
+                }
+                (false, 0) => self.used[block] += 1,
+                (true, 1) => self.used[block] -= 1,
+                _ => {}
+            }
+
+            if restoring {
+                self.flipped[codeword] -= 1;
+            } else {
+                self.flipped[codeword] += 1;
+            }
+        }
+
+        let value = self.qr[y][x].value();
+        self.qr[y][x].set(!value);
+        Ok(())
+    }
+
+    /// Makes the module at (`y`, `x`) dark or light, flipping it if needed
+    ///
+    /// # Errors
+    /// - Same as [`ArtCanvas::flip`], when the module has to be flipped
+    pub fn set(&mut self, y: usize, x: usize, dark: bool) -> Result<(), ArtError> {
+        let n = self.qr.size;
+        if y < n && x < n && self.qr[y][x].value() == dark {
+            return Ok(());
+        }
+        self.flip(y, x)
+    }
+
+    /// Draws `picture`, dark where `true`, with its top left corner at (`top`, `left`)
+    ///
+    /// Modules outside of the `QRCode`, function modules and modules over the budget are
+    /// skipped. Returns the number of modules that differ from `picture`.
+    pub fn draw<R: AsRef<[bool]>>(&mut self, top: usize, left: usize, picture: &[R]) -> usize {
+        let mut refused = 0;
+        for (dy, row) in picture.iter().enumerate() {
+            for (dx, &dark) in row.as_ref().iter().enumerate() {
+                if self.set(top + dy, left + dx, dark).is_err() {
+                    refused += 1;
+                }
+            }
+        }
+
+        refused
+    }
+
+    /// Returns the damaged codewords of each error correction block
+    #[must_use]
+    pub fn budget(&self) -> Vec<BlockBudget> {
+        (0..self.used.len())
+            .map(|block| BlockBudget {
+                used: self.used[block],
+                allowed: self.allowed(block),
+                correctable: self.correctable[block],
+            })
+            .collect()
+    }
+
+    /// Returns the `QRCode` with the flipped modules
+    #[must_use]
+    pub const fn qrcode(&self) -> &QRCode {
+        &self.qr
+    }
+
+    /// Returns the `QRCode` with the flipped modules
+    #[must_use]
+    pub fn into_qrcode(self) -> QRCode {
+        self.qr
+    }
+}
+
+/// Error correction codewords kept to detect misdecodes instead of correcting errors,
+/// referring to Table 9 of the spec
+const fn misdecode_protection(version: Version, ecl: ECL) -> usize {
+    match (version, ecl) {
+        (Version::V01, ECL::L) => 3,
+        (Version::V01, ECL::M) | (Version::V02, ECL::L) => 2,
+        (Version::V01, ECL::Q | ECL::H) | (Version::V03, ECL::L) => 1,
+        _ => 0,
+    }
+}
@@ -0,0 +1,288 @@
+//! `fast-qr` command-line tool, generates a QR code as svg, png or terminal text
+//!
+//! ```txt
+//! fast-qr "https://example.com/" -o qr.svg
+//! echo "https://example.com/" | fast-qr --shape circle --format terminal
+//! ```
+
+use std::io::{self, Read, Write};
+use std::process;
+
+use fast_qr::convert::svg::SvgBuilder;
+use fast_qr::convert::term::{TermBuilder, TermColor};
+use fast_qr::convert::{Builder, Shape};
+use fast_qr::{QRBuilder, QRCode, Version, ECL};
+
+const USAGE: &str = "\
+Usage: fast-qr [OPTIONS] [TEXT]
+
+Generates a QR code of TEXT, read from stdin when missing or `-`
+
+Options:
+  -e, --ecl <L|M|Q|H>            Error correction level
+  -v, --version <1-40>           Version, the smallest fitting one by default
+  -s, --shape <SHAPE>            square, circle, rounded_square, vertical, horizontal,
+                                 diamond, blob, dot, hexagon, octagon, star or heart
+      --module-color <COLOR>     Module color, `#rrggbb` for the terminal
+      --background-color <COLOR> Background color, `#rrggbb` for the terminal
+  -m, --margin <MODULES>         Margin around the QR code
+  -f, --format <FORMAT>          svg, png or terminal, guessed from the output extension
+  -o, --output <FILE>            Output file, stdout by default
+  -h, --help                     Prints this message
+";
+
+const VERSIONS: [Version; 40] = [
+    Version::V01,
+    Version::V02,
+    Version::V03,
+    Version::V04,
+    Version::V05,
+    Version::V06,
+    Version::V07,
+    Version::V08,
+    Version::V09,
+    Version::V10,
+    Version::V11,
+    Version::V12,
+    Version::V13,
+    Version::V14,
+    Version::V15,
+    Version::V16,
+    Version::V17,
+    Version::V18,
+    Version::V19,
+    Version::V20,
+    Version::V21,
+    Version::V22,
+    Version::V23,
+    Version::V24,
+    Version::V25,
+    Version::V26,
+    Version::V27,
+    Version::V28,
+    Version::V29,
+    Version::V30,
+    Version::V31,
+    Version::V32,
+    Version::V33,
+    Version::V34,
+    Version::V35,
+    Version::V36,
+    Version::V37,
+    Version::V38,
+    Version::V39,
+    Version::V40,
+];
+
+/// Output encoding
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Svg,
+    Png,
+    Terminal,
+}
+
+/// Parsed command-line arguments
+#[derive(Default)]
+struct Options {
+    text: Option<String>,
+    ecl: Option<ECL>,
+    version: Option<Version>,
+    shape: Option<Shape>,
+    module_color: Option<String>,
+    background_color: Option<String>,
+    margin: Option<usize>,
+    format: Option<Format>,
+    output: Option<String>,
+}
+
+/// Returns the parsed arguments, `Ok(None)` when help was requested
+fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options::default();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for `{arg}`"));
+
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-e" | "--ecl" => {
+                options.ecl = Some(match value()?.to_uppercase().as_str() {
+                    "L" => ECL::L,
+                    "M" => ECL::M,
+                    "Q" => ECL::Q,
+                    "H" => ECL::H,
+                    ecl => return Err(format!("Invalid ECL `{ecl}`, expected L, M, Q or H")),
+                });
+            }
+            "-v" | "--version" => {
+                let version = value()?;
+                let index = version
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| n.checked_sub(1))
+                    .filter(|&index| index < VERSIONS.len())
+                    .ok_or(format!("Invalid version `{version}`, expected 1 to 40"))?;
+                options.version = Some(VERSIONS[index]);
+            }
+            "-s" | "--shape" => {
+                let shape = value()?;
+                options.shape =
+                    Some(Shape::from_name(&shape).ok_or(format!("Invalid shape `{shape}`"))?);
+            }
+            "--module-color" => options.module_color = Some(value()?),
+            "--background-color" => options.background_color = Some(value()?),
+            "-m" | "--margin" => {
+                let margin = value()?;
+                options.margin = Some(
+                    margin
+                        .parse()
+                        .map_err(|_| format!("Invalid margin `{margin}`"))?,
+                );
+            }
+            "-f" | "--format" => {
+                options.format = Some(match value()?.as_str() {
+                    "svg" => Format::Svg,
+                    "png" => Format::Png,
+                    "terminal" => Format::Terminal,
+                    format => return Err(format!("Invalid format `{format}`")),
+                });
+            }
+            "-o" | "--output" => options.output = Some(value()?),
+            "-" => options.text = None,
+            _ if arg.starts_with('-') => return Err(format!("Unknown option `{arg}`")),
+            _ if options.text.is_some() => return Err(format!("Unexpected argument `{arg}`")),
+            _ => options.text = Some(arg),
+        }
+    }
+
+    Ok(Some(options))
+}
+
+/// Parses a `#rrggbb` color for the terminal
+fn term_color(color: &str) -> Result<TermColor, String> {
+    let hex = color
+        .strip_prefix('#')
+        .filter(|hex| hex.len() == 6 && hex.is_ascii())
+        .ok_or(format!(
+            "Invalid terminal color `{color}`, expected #rrggbb"
+        ))?;
+
+    let mut rgb = [0; 3];
+    for (i, channel) in rgb.iter_mut().enumerate() {
+        *channel = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .map_err(|_| format!("Invalid terminal color `{color}`, expected #rrggbb"))?;
+    }
+
+    Ok(TermColor::TrueColor(rgb))
+}
+
+/// Applies the shared svg & png options
+fn style<B: Builder>(builder: &mut B, options: &Options) {
+    if let Some(shape) = options.shape {
+        builder.shape(shape);
+    }
+    if let Some(color) = &options.module_color {
+        builder.module_color(color.as_str());
+    }
+    if let Some(color) = &options.background_color {
+        builder.background_color(color.as_str());
+    }
+    if let Some(margin) = options.margin {
+        builder.margin(margin);
+    }
+}
+
+/// Renders `qrcode` in `format`
+fn render(qrcode: &QRCode, format: Format, options: &Options) -> Result<Vec<u8>, String> {
+    match format {
+        Format::Svg => {
+            let mut builder = SvgBuilder::default();
+            style(&mut builder, options);
+            Ok(builder.to_str(qrcode).into_bytes())
+        }
+        #[cfg(feature = "image")]
+        Format::Png => {
+            let mut builder = fast_qr::convert::image::ImageBuilder::default();
+            style(&mut builder, options);
+            builder.fit_width(512);
+            builder.to_bytes(qrcode).map_err(|err| err.to_string())
+        }
+        #[cfg(not(feature = "image"))]
+        Format::Png => Err(String::from("png output requires the `image` feature")),
+        Format::Terminal => {
+            let mut builder = TermBuilder::default();
+            if let Some(color) = &options.module_color {
+                builder.module_color(term_color(color)?);
+            }
+            if let Some(color) = &options.background_color {
+                builder.background_color(term_color(color)?);
+            }
+            if let Some(margin) = options.margin {
+                builder.margin(margin);
+            }
+            Ok(builder.to_str(qrcode).into_bytes())
+        }
+    }
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let text = match &options.text {
+        Some(text) => text.clone().into_bytes(),
+        None => {
+            let mut text = Vec::new();
+            io::stdin()
+                .read_to_end(&mut text)
+                .map_err(|err| format!("Failed to read stdin: {err}"))?;
+            // Drops the newline of `echo`
+            if text.last() == Some(&b'\n') {
+                text.pop();
+            }
+            text
+        }
+    };
+
+    let mut builder = QRBuilder::new(text);
+    if let Some(ecl) = options.ecl {
+        builder.ecl(ecl);
+    }
+    if let Some(version) = options.version {
+        builder.version(version);
+    }
+    let qrcode = builder
+        .build()
+        .map_err(|err| format!("Failed to build the QR code: {err}"))?;
+
+    let format = options.format.unwrap_or(match &options.output {
+        Some(output) if output.ends_with(".png") => Format::Png,
+        Some(_) => Format::Svg,
+        None => Format::Terminal,
+    });
+    let bytes = render(&qrcode, format, options)?;
+
+    match &options.output {
+        Some(output) => std::fs::write(output, bytes)
+            .map_err(|err| format!("Failed to write `{output}`: {err}")),
+        None => io::stdout()
+            .write_all(&bytes)
+            .map_err(|err| format!("Failed to write to stdout: {err}")),
+    }
+}
+
+fn main() {
+    let options = match parse(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            print!("{USAGE}");
+            return;
+        }
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            process::exit(2);
+        }
+    };
+
+    if let Err(err) = run(&options) {
+        eprintln!("{err}");
+        process::exit(1);
+    }
+}
@@ -5,6 +5,10 @@
 
 use core::fmt::{Display, Formatter};
 
+#[cfg(test)]
+use alloc::vec;
+use alloc::{string::String, vec::Vec};
+
 use crate::Version;
 
 /// Values to keep last X bits of a u8
@@ -58,6 +62,7 @@ pub const KEEP_LAST: [usize; 33] = [
 ];
 
 /// `CompactQR` is a struct that contains a `Vec<u8>` to store boolean values as bits.
+#[derive(Clone)]
 pub struct CompactQR {
     pub len: usize,
     pub data: Vec<u8>,
@@ -97,11 +102,22 @@ impl CompactQR {
         }
     }
 
-    pub fn from_version(version: Version) -> Self {
-        let len = version.max_bytes();
-        let data = vec![0; len * 8];
+    /// Empties the `CompactQR` and sizes it for `version`, keeping its allocation.
+    pub fn reset(&mut self, version: Version) {
+        self.data.clear();
+        self.data.resize(version.max_bytes() * 8, 0);
+        self.len = 0;
+    }
 
-        CompactQR { len: 0, data }
+    /// Replaces the content with an already created array, keeping the allocation, zero-padded
+    /// up to `len` bits
+    pub fn set_array(&mut self, data: &[u8], len: usize) {
+        self.data.clear();
+        self.data.extend_from_slice(data);
+        if self.data.len() * 8 < len {
+            self.data.resize((len + 7) / 8, 0);
+        }
+        self.len = len;
     }
 
     /// Instantiates a new `CompactQR`, with a given length, expects the length to be a multiple of 8.
@@ -123,6 +139,7 @@ impl CompactQR {
     }
 
     /// Instantiates a new `CompactQR` from an already created array
+    #[cfg(test)]
     pub fn from_array(data: &[u8], len: usize) -> Self {
         CompactQR {
             len,
@@ -0,0 +1,362 @@
+//! Converts a sequence of [`QRCode`]s to an animation, an svg with CSS keyframes or a GIF
+//! (`gif` feature)
+//!
+//! Frames are shown one after the other, e.g. rotating one-time tokens or the parts of a
+//! structured append.
+//!
+//! ```rust
+//! use fast_qr::convert::animated::AnimatedBuilder;
+//! use fast_qr::convert::{Builder, Shape};
+//! use fast_qr::qr::QRBuilder;
+//!
+//! let mut builder = AnimatedBuilder::default();
+//! builder.shape(Shape::RoundedSquare).duration(2000);
+//! for token in ["482913", "105728", "930461"] {
+//!     // QRBuilde::new can fail if content is too big for version,
+//!     // please check before unwrapping.
+//!     builder.frame(QRBuilder::new(token).build().unwrap());
+//! }
+//!
+//! let svg = builder.to_svg();
+//! assert!(svg.contains("@keyframes"));
+//! ```
+
+use super::svg::SvgBuilder;
+use super::{Builder, Color, EyeShape, Gradient, ImageBackgroundShape, NeighborFunction, Shape};
+use crate::{Module, ModuleType, QRCode};
+
+/// Builder for animations, contains an [`SvgBuilder`] styling every frame and adds the frames
+/// and their duration
+pub struct AnimatedBuilder {
+    /// Frames with their duration in milliseconds, `None` uses `duration`
+    frames: Vec<(QRCode, Option<u32>)>,
+    /// Duration of a frame in milliseconds, default is 1000
+    duration: u32,
+    /// Restarts from the first frame after the last one, default is true
+    repeat: bool,
+    /// Side of a module in pixels for the GIF, default is 8
+    module_size: usize,
+    svg_builder: SvgBuilder,
+}
+
+/// Creates an `AnimatedBuilder` instance
+impl Default for AnimatedBuilder {
+    fn default() -> Self {
+        AnimatedBuilder {
+            frames: Vec::new(),
+            duration: 1000,
+            repeat: true,
+            module_size: 8,
+            svg_builder: SvgBuilder::default(),
+        }
+    }
+}
+
+impl Builder for AnimatedBuilder {
+    fn margin(&mut self, margin: usize) -> &mut Self {
+        self.svg_builder.margin(margin);
+        self
+    }
+
+    fn module_color<C: Into<Color>>(&mut self, module_color: C) -> &mut Self {
+        self.svg_builder.module_color(module_color);
+        self
+    }
+
+    fn background_color<C: Into<Color>>(&mut self, background_color: C) -> &mut Self {
+        self.svg_builder.background_color(background_color);
+        self
+    }
+
+    fn module_gradient(&mut self, gradient: Gradient) -> &mut Self {
+        self.svg_builder.module_gradient(gradient);
+        self
+    }
+
+    fn finder_color<C: Into<Color>>(&mut self, finder_color: C) -> &mut Self {
+        self.svg_builder.finder_color(finder_color);
+        self
+    }
+
+    fn eye_frame_color<C: Into<Color>>(&mut self, color: C) -> &mut Self {
+        self.svg_builder.eye_frame_color(color);
+        self
+    }
+
+    fn eye_ball_color<C: Into<Color>>(&mut self, color: C) -> &mut Self {
+        self.svg_builder.eye_ball_color(color);
+        self
+    }
+
+    fn eye_frame_shape(&mut self, shape: EyeShape) -> &mut Self {
+        self.svg_builder.eye_frame_shape(shape);
+        self
+    }
+
+    fn eye_ball_shape(&mut self, shape: EyeShape) -> &mut Self {
+        self.svg_builder.eye_ball_shape(shape);
+        self
+    }
+
+    fn shape(&mut self, shape: Shape) -> &mut Self {
+        self.svg_builder.shape(shape);
+        self
+    }
+
+    fn shape_fn<F>(&mut self, shape: F) -> &mut Self
+    where
+        F: Fn(usize, usize, Module) -> String + Send + Sync + 'static,
+    {
+        self.svg_builder.shape_fn(shape);
+        self
+    }
+
+    fn dot_radius(&mut self, radius: f64) -> &mut Self {
+        self.svg_builder.dot_radius(radius);
+        self
+    }
+
+    fn module_gap(&mut self, gap: f64) -> &mut Self {
+        self.svg_builder.module_gap(gap);
+        self
+    }
+
+    fn shape_neighbors(&mut self, shape: NeighborFunction) -> &mut Self {
+        self.svg_builder.shape_neighbors(shape);
+        self
+    }
+
+    fn shape_for(&mut self, module_type: ModuleType, shape: Shape) -> &mut Self {
+        self.svg_builder.shape_for(module_type, shape);
+        self
+    }
+
+    fn merge_modules(&mut self, corner_radius: f64) -> &mut Self {
+        self.svg_builder.merge_modules(corner_radius);
+        self
+    }
+
+    fn image(&mut self, image: String) -> &mut Self {
+        self.svg_builder.image(image);
+        self
+    }
+
+    fn image_background_color<C: Into<Color>>(&mut self, image_background_color: C) -> &mut Self {
+        self.svg_builder
+            .image_background_color(image_background_color);
+        self
+    }
+
+    fn image_background_shape(
+        &mut self,
+        image_background_shape: ImageBackgroundShape,
+    ) -> &mut Self {
+        self.svg_builder
+            .image_background_shape(image_background_shape);
+        self
+    }
+
+    fn image_size(&mut self, image_size: f64) -> &mut Self {
+        self.svg_builder.image_size(image_size);
+        self
+    }
+
+    fn image_gap(&mut self, gap: f64) -> &mut Self {
+        self.svg_builder.image_gap(gap);
+        self
+    }
+
+    fn image_position(&mut self, x: f64, y: f64) -> &mut Self {
+        self.svg_builder.image_position(x, y);
+        self
+    }
+
+    fn background_image(&mut self, image: String) -> &mut Self {
+        self.svg_builder.background_image(image);
+        self
+    }
+
+    fn background_image_opacity(&mut self, opacity: f64) -> &mut Self {
+        self.svg_builder.background_image_opacity(opacity);
+        self
+    }
+
+    fn shape_color<C: Into<Color>>(&mut self, shape: Shape, color: C) -> &mut Self {
+        self.svg_builder.shape_color(shape, color);
+        self
+    }
+}
+
+impl AnimatedBuilder {
+    /// Adds a frame, shown for [`AnimatedBuilder::duration`]
+    pub fn frame(&mut self, qr: QRCode) -> &mut Self {
+        self.frames.push((qr, None));
+        self
+    }
+
+    /// Adds a frame, shown for `duration` milliseconds
+    pub fn frame_with_duration(&mut self, qr: QRCode, duration: u32) -> &mut Self {
+        self.frames.push((qr, Some(duration)));
+        self
+    }
+
+    /// Updates the duration in milliseconds of the frames added without one (default: 1000)
+    pub fn duration(&mut self, duration: u32) -> &mut Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Restarts from the first frame after the last one, or stops on the last frame
+    /// (default: true)
+    pub fn repeat(&mut self, repeat: bool) -> &mut Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Updates the side of a module in pixels for the GIF (default: 8)
+    pub fn module_size(&mut self, module_size: usize) -> &mut Self {
+        self.module_size = module_size.max(1);
+        self
+    }
+
+    /// Frames with their duration in milliseconds
+    fn durations(&self) -> impl Iterator<Item = (&QRCode, u32)> {
+        self.frames
+            .iter()
+            .map(move |(qr, duration)| (qr, duration.unwrap_or(self.duration)))
+    }
+
+    /// Return a string containing the animated svg
+    ///
+    /// Each frame is a nested svg, shown in turn with CSS keyframes. Without CSS animations,
+    /// only the first frame is shown. Frames of different versions are scaled to the same size.
+    pub fn to_svg(&self) -> String {
+        let total = self
+            .durations()
+            .map(|(_, duration)| duration)
+            .sum::<u32>()
+            .max(1);
+        let percent = |ms: u32| (f64::from(ms) * 10000f64 / f64::from(total)).round() / 100f64;
+        let iterations = if self.repeat {
+            "infinite"
+        } else {
+            "1 forwards"
+        };
+
+        let mut style = String::new();
+        let mut frames = String::new();
+        let mut start = 0;
+        for (i, (qr, duration)) in self.durations().enumerate() {
+            let end = start + duration;
+            let last = i + 1 == self.frames.len();
+
+            style.push_str(&format!("@keyframes fast_qr_{i}{{"));
+            if start > 0 {
+                style.push_str("0%{visibility:hidden}");
+            }
+            style.push_str(&format!("{}%{{visibility:visible}}", percent(start)));
+            if last {
+                style.push_str("100%{visibility:visible}}");
+            } else {
+                style.push_str(&format!("{}%,100%{{visibility:hidden}}}}", percent(end)));
+            }
+            style.push_str(&format!(
+                ".fast_qr_{i}{{visibility:{};animation:fast_qr_{i} {total}ms step-end {iterations}}}",
+                if i == 0 { "visible" } else { "hidden" }
+            ));
+
+            frames.push_str(&format!(r#"<g class="fast_qr_{i}">"#));
+            frames.push_str(&self.svg_builder.to_str(qr));
+            frames.push_str("</g>");
+
+            start = end;
+        }
+
+        format!(
+            r#"<svg viewBox="0 0 100 100" xmlns="http://www.w3.org/2000/svg"><style>{style}</style>{frames}</svg>"#
+        )
+    }
+
+    /// Returns the animated GIF
+    ///
+    /// Frames are drawn with square modules, in the module and background colors, other
+    /// options only apply to [`AnimatedBuilder::to_svg`]. Smaller versions are centered on the
+    /// largest one and a transparent background stays transparent.
+    ///
+    /// # Errors
+    /// - `ConvertError::Gif` if the GIF is larger than 65535 pixels, or has no frame
+    #[cfg(feature = "gif")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gif")))]
+    pub fn to_gif(&self) -> Result<Vec<u8>, super::ConvertError> {
+        use super::ConvertError;
+        use gif::{Encoder, Frame, Repeat};
+
+        let margin = self.svg_builder.margin;
+        let n = self
+            .frames
+            .iter()
+            .map(|(qr, _)| qr.size)
+            .max()
+            .ok_or_else(|| {
+                ConvertError::Gif(String::from("An animation needs at least one frame"))
+            })?;
+        let to_pixels = |units: usize| {
+            let pixels = units * self.module_size;
+            u16::try_from(pixels)
+                .map_err(|_| ConvertError::Gif(format!("{pixels} pixels is too large for a GIF")))
+        };
+        let (width, height) = (to_pixels(margin.width(n))?, to_pixels(margin.height(n))?);
+
+        let background = self.svg_builder.background_color.to_rgba();
+        let module = self.svg_builder.dot_color.to_rgba();
+        let background = background.unwrap_or([255; 4]);
+        let module = module.unwrap_or([0, 0, 0, 255]);
+        let palette = [
+            background[0],
+            background[1],
+            background[2],
+            module[0],
+            module[1],
+            module[2],
+        ];
+        let transparent = if background[3] == 0 { Some(0) } else { None };
+
+        let mut out = Vec::new();
+        {
+            let gif_error = |err: gif::EncodingError| ConvertError::Gif(err.to_string());
+            let mut encoder = Encoder::new(&mut out, width, height, &palette).map_err(gif_error)?;
+            let repeat = if self.repeat {
+                Repeat::Infinite
+            } else {
+                Repeat::Finite(0)
+            };
+            encoder.set_repeat(repeat).map_err(gif_error)?;
+
+            let (width, height) = (usize::from(width), usize::from(height));
+            for (qr, duration) in self.durations() {
+                // Smaller versions are centered
+                let offset = (n - qr.size) / 2;
+                let (top, left) = margin.offset(offset, offset);
+                let pixels = (0..width * height)
+                    .map(|i| {
+                        let (y, x) = (i / width / self.module_size, i % width / self.module_size);
+                        match (y.checked_sub(top), x.checked_sub(left)) {
+                            (Some(y), Some(x)) if y < qr.size && x < qr.size => {
+                                u8::from(qr[y][x].value())
+                            }
+                            _ => 0,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut frame =
+                    Frame::from_indexed_pixels(width as u16, height as u16, &pixels, transparent);
+                // GIF delays are in hundredths of a second
+                frame.delay = u16::try_from(duration / 10).unwrap_or(u16::MAX);
+                encoder.write_frame(&frame).map_err(gif_error)?;
+            }
+        }
+
+        Ok(out)
+    }
+}
@@ -0,0 +1,124 @@
+//! Converts [`QRCode`] to 1-bit BMP images, without any graphics dependency
+//!
+//! Windows and most image viewers open them directly.
+//!
+//! ```rust
+//! use fast_qr::convert::bmp::BmpBuilder;
+//! use fast_qr::qr::QRBuilder;
+//!
+//! // QRBuilde::new can fail if content is too big for version,
+//! // please check before unwrapping.
+//! let qrcode = QRBuilder::new("https://example.com/")
+//!     .build()
+//!     .unwrap();
+//!
+//! let bmp = BmpBuilder::default().module_size(8).to_bytes(&qrcode);
+//! assert!(bmp.starts_with(b"BM"));
+//! ```
+
+use std::io;
+
+use crate::QRCode;
+
+/// Size of the file header, the info header and the palette of 2 colors
+const HEADER_SIZE: usize = 14 + 40 + 2 * 4;
+
+/// Resolution stored in the header, 72 dpi in pixels per meter
+const PIXELS_PER_METER: u32 = 2835;
+
+/// Builder for 1-bit BMP images, can set the margin and module size
+pub struct BmpBuilder {
+    /// The margin around the [`QRCode`] in modules, default is 4
+    margin: usize,
+    /// Side of a module in pixels, default is 1
+    module_size: usize,
+}
+
+/// Creates a `BmpBuilder` instance
+impl Default for BmpBuilder {
+    fn default() -> Self {
+        BmpBuilder {
+            margin: 4,
+            module_size: 1,
+        }
+    }
+}
+
+impl BmpBuilder {
+    /// Updates margin in modules (default: 4)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Updates the side of a module in pixels (default: 1)
+    pub fn module_size(&mut self, module_size: usize) -> &mut Self {
+        self.module_size = module_size.max(1);
+        self
+    }
+
+    /// Returns whether the pixel at (`y`, `x`) is dark
+    fn is_dark(&self, qr: &QRCode, y: usize, x: usize) -> bool {
+        let (y, x) = (y / self.module_size, x / self.module_size);
+        match (y.checked_sub(self.margin), x.checked_sub(self.margin)) {
+            (Some(y), Some(x)) if y < qr.size && x < qr.size => qr[y][x].value(),
+            _ => false,
+        }
+    }
+
+    /// Returns the image in a byte buffer
+    ///
+    /// Pixels are indices in a palette of white (0) and black (1), rows are stored from the
+    /// bottom up, each padded to 4 bytes.
+    pub fn to_bytes(&self, qr: &QRCode) -> Vec<u8> {
+        let side = (qr.size + self.margin * 2) * self.module_size;
+        let stride = (side + 31) / 32 * 4;
+        let file_size = HEADER_SIZE + stride * side;
+
+        let mut out = Vec::with_capacity(file_size);
+        let u16_le = |out: &mut Vec<u8>, value: u16| out.extend_from_slice(&value.to_le_bytes());
+        let u32_le = |out: &mut Vec<u8>, value: u32| out.extend_from_slice(&value.to_le_bytes());
+
+        // BITMAPFILEHEADER
+        out.extend_from_slice(b"BM");
+        u32_le(&mut out, file_size as u32);
+        u32_le(&mut out, 0);
+        u32_le(&mut out, HEADER_SIZE as u32);
+
+        // BITMAPINFOHEADER, a positive height stores the rows from the bottom up
+        u32_le(&mut out, 40);
+        u32_le(&mut out, side as u32);
+        u32_le(&mut out, side as u32);
+        u16_le(&mut out, 1);
+        u16_le(&mut out, 1);
+        u32_le(&mut out, 0);
+        u32_le(&mut out, (stride * side) as u32);
+        u32_le(&mut out, PIXELS_PER_METER);
+        u32_le(&mut out, PIXELS_PER_METER);
+        u32_le(&mut out, 2);
+        u32_le(&mut out, 0);
+
+        // Palette, blue green red and a reserved byte
+        out.extend_from_slice(&[255, 255, 255, 0, 0, 0, 0, 0]);
+
+        for y in (0..side).rev() {
+            let start = out.len();
+            out.extend((0..side).step_by(8).map(|start| {
+                (start..side.min(start + 8)).fold(0u8, |byte, x| {
+                    byte | u8::from(self.is_dark(qr, y, x)) << (7 - (x - start))
+                })
+            }));
+            out.resize(start + stride, 0);
+        }
+
+        out
+    }
+
+    /// Saves the image for a QRCode to a file
+    ///
+    /// # Errors
+    /// - `io::Error` if the file cannot be written
+    pub fn to_file(&self, qr: &QRCode, file: &str) -> io::Result<()> {
+        std::fs::write(file, self.to_bytes(qr))
+    }
+}
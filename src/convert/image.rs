@@ -1,10 +1,15 @@
 //! Converts [`QRCode`] to an image
 //!
+//! [`ImageBuilder`] (`image` feature) rasterizes the svg with resvg:
+//!
 //! ```rust
+//! # #[cfg(feature = "image")]
 //! use fast_qr::convert::ConvertError;
+//! # #[cfg(feature = "image")]
 //! use fast_qr::convert::{image::ImageBuilder, Builder, Shape};
 //! use fast_qr::qr::QRBuilder;
 //!
+//! # #[cfg(feature = "image")]
 //! # fn main() -> Result<(), ConvertError> {
 //! // QRBuilde::new can fail if content is too big for version,
 //! // please check before unwrapping.
@@ -20,26 +25,93 @@
 //! #     std::fs::remove_file("out.png");
 //! #     Ok(())
 //! # }
+//! # #[cfg(not(feature = "image"))]
+//! # fn main() {}
+//! ```
+//!
+//! [`SquareRasterizer`] (`raster` feature) only draws square modules, without any dependency:
+//!
+//! ```rust
+//! use fast_qr::convert::image::{RasterBackend, SquareRasterizer};
+//! use fast_qr::qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//!
+//! let png = SquareRasterizer::default()
+//!     .module_size(10)
+//!     .to_png(&qrcode)
+//!     .unwrap();
+//! assert_eq!(&png[1..4], b"PNG");
 //! ```
 
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+
 use std::fmt::Formatter;
 use std::io;
 
 use crate::QRCode;
+#[cfg(feature = "image")]
+use crate::{Module, ModuleType, QRBuilder};
 
 use super::Color;
-use super::{svg::SvgBuilder, Builder, Shape};
+#[cfg(feature = "image")]
+use super::{
+    preset::Preset,
+    svg::{positive_finite, FrameStyle, LabelPosition, Margin, MarginStyle, SvgBuilder},
+    Builder, EyeShape, Gradient, ModuleCommand, NeighborFunction, Shape,
+};
 
+#[cfg(feature = "image")]
 use resvg::tiny_skia::{self, Pixmap};
+#[cfg(feature = "image")]
 use resvg::usvg;
 
+#[cfg(feature = "image")]
 /// [`ImageBuilder`] contains an [`SvgBuilder`] and adds some options \
 /// - fit_height adds a max-height boundary
 /// - fit_width adds a max-width boundary
+/// - dpi & physical_size_mm size the image for print
+/// - rasterization chooses between smooth and hard edges
+#[derive(Debug, Clone)]
 pub struct ImageBuilder {
     fit_height: Option<u32>,
     fit_width: Option<u32>,
-    svg_builder: SvgBuilder,
+    /// Resolution stored in the PNG & JPEG metadata
+    dpi: Option<u32>,
+    /// Printed side in millimeters, replaces `fit_height` & `fit_width`
+    physical_size: Option<f64>,
+    raster: Raster,
+    format: ImageFormat,
+    pub(crate) svg_builder: SvgBuilder,
+}
+
+#[cfg(feature = "image")]
+/// Rasterization of the modules, see [`ImageBuilder::rasterization`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Raster {
+    /// Smooth edges, modules may have a fractional size in pixels, the default
+    AntiAliased,
+    /// Hard edges, each module is a whole number of pixels: the image may be slightly smaller
+    /// than `fit_width` or `fit_height`
+    Crisp,
+}
+
+#[cfg(feature = "image")]
+/// Encoding used by [`ImageBuilder::to_file`] and [`ImageBuilder::to_bytes`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ImageFormat {
+    /// Lossless PNG, the default
+    Png,
+    /// Lossy JPEG, transparent pixels are blended over white
+    Jpeg {
+        /// Quality between 1 and 100
+        quality: u8,
+    },
+    /// Lossless WebP
+    Webp,
 }
 
 /// Error when converting to image
@@ -53,7 +125,14 @@ pub enum ImageError {
     EncodingError(String),
 }
 
-impl std::error::Error for ImageError {}
+impl std::error::Error for ImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ImageError::IoError(io_err) => Some(io_err),
+            ImageError::ImageError(_) | ImageError::EncodingError(_) => None,
+        }
+    }
+}
 
 impl std::fmt::Display for ImageError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -65,17 +144,216 @@ impl std::fmt::Display for ImageError {
     }
 }
 
+/// Pixels of a rasterized QRCode, see [`RasterBackend`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbaImage {
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// RGBA channels of each pixel, row by row, not premultiplied
+    pub data: Vec<u8>,
+}
+
+#[cfg(feature = "dynamic-image")]
+impl From<RgbaImage> for image_crate::RgbaImage {
+    fn from(image: RgbaImage) -> Self {
+        let (width, height) = (image.width, image.height);
+        // Rasterizers fill `width * height` pixels, edited data is padded with transparent
+        // pixels or truncated
+        let mut data = image.data;
+        data.resize(width as usize * height as usize * 4, 0);
+        image_crate::RgbaImage::from_raw(width, height, data)
+            .unwrap_or_else(|| image_crate::RgbaImage::new(width, height))
+    }
+}
+
+#[cfg(feature = "dynamic-image")]
+impl From<RgbaImage> for image_crate::DynamicImage {
+    fn from(image: RgbaImage) -> Self {
+        image_crate::DynamicImage::ImageRgba8(image.into())
+    }
+}
+
+/// Rasterizes a QRCode, implemented with resvg by [`ImageBuilder`] (`image` feature) and
+/// without any dependency by [`SquareRasterizer`]
+pub trait RasterBackend {
+    /// Returns the pixels of the QRCode
+    ///
+    /// # Errors
+    /// - `ImageError::ImageError` if the QRCode cannot be rasterized with these settings
+    fn rasterize(&self, qr: &QRCode) -> Result<RgbaImage, ImageError>;
+
+    /// Returns the PNG image of the QRCode
+    ///
+    /// The default implementation uses a built-in encoder: indexed colors, without compression.
+    ///
+    /// # Errors
+    /// - Same as [`RasterBackend::rasterize`]
+    fn to_png(&self, qr: &QRCode) -> Result<Vec<u8>, ImageError> {
+        Ok(write_png(&self.rasterize(qr)?))
+    }
+
+    /// Returns the QRCode as an [`image::DynamicImage`](image_crate::DynamicImage), to keep
+    /// processing it with the `image` crate (`dynamic-image` feature)
+    ///
+    /// # Errors
+    /// - Same as [`RasterBackend::rasterize`]
+    #[cfg(feature = "dynamic-image")]
+    fn to_dynamic_image(&self, qr: &QRCode) -> Result<image_crate::DynamicImage, ImageError> {
+        self.rasterize(qr).map(image_crate::DynamicImage::from)
+    }
+}
+
+/// [`RasterBackend`] drawing square modules in pure Rust, in the module & background colors
+pub struct SquareRasterizer {
+    /// Side of a module in pixels, default is 8
+    module_size: u32,
+    /// The margin in modules, default is 4
+    margin: usize,
+    /// The color of the modules, default is #000000
+    module_color: Color,
+    /// The background color, default is #FFFFFF
+    background_color: Color,
+    /// Swaps dark and light modules, margin included, default is false
+    invert: bool,
+}
+
+/// Creates a SquareRasterizer instance
+impl Default for SquareRasterizer {
+    fn default() -> Self {
+        SquareRasterizer {
+            module_size: 8,
+            margin: 4,
+            module_color: [0, 0, 0, 255].into(),
+            background_color: [255; 4].into(),
+            invert: false,
+        }
+    }
+}
+
+impl SquareRasterizer {
+    /// Updates the side of a module in pixels (default: 8)
+    pub fn module_size(&mut self, module_size: u32) -> &mut Self {
+        self.module_size = module_size.max(1);
+        self
+    }
+
+    /// Updates the margin in modules (default: 4)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Updates the color of the modules, `#rrggbb` or `#rrggbbaa` (default: #000000)
+    pub fn module_color<C: Into<Color>>(&mut self, module_color: C) -> &mut Self {
+        self.module_color = module_color.into();
+        self
+    }
+
+    /// Updates the background color, `#rrggbb` or `#rrggbbaa` (default: #FFFFFF)
+    pub fn background_color<C: Into<Color>>(&mut self, background_color: C) -> &mut Self {
+        self.background_color = background_color.into();
+        self
+    }
+
+    /// Swaps dark and light modules, the margin included, for light on dark codes
+    /// (default: false)
+    pub fn invert(&mut self, invert: bool) -> &mut Self {
+        self.invert = invert;
+        self
+    }
+}
+
+impl RasterBackend for SquareRasterizer {
+    fn rasterize(&self, qr: &QRCode) -> Result<RgbaImage, ImageError> {
+        let rgba = |color: &Color| {
+            color.to_rgba().ok_or_else(|| {
+                ImageError::ImageError(format!(
+                    "{} is not a #rrggbb or #rrggbbaa color",
+                    color.to_str()
+                ))
+            })
+        };
+        let module = rgba(&self.module_color)?;
+        let background = rgba(&self.background_color)?;
+
+        let units = qr.size + self.margin * 2;
+        let side = u32::try_from(units)
+            .ok()
+            .and_then(|units| units.checked_mul(self.module_size))
+            .ok_or_else(|| ImageError::ImageError("Image is too large".to_string()))?;
+
+        let (side_pixels, module_size) = (side as usize, self.module_size as usize);
+        let mut data = Vec::with_capacity(side_pixels * side_pixels * 4);
+        for py in 0..side_pixels {
+            for px in 0..side_pixels {
+                let (y, x) = (py / module_size, px / module_size);
+                let dark = match (y.checked_sub(self.margin), x.checked_sub(self.margin)) {
+                    (Some(y), Some(x)) if y < qr.size && x < qr.size => qr[y][x].value(),
+                    _ => false,
+                };
+                data.extend_from_slice(if dark != self.invert {
+                    &module
+                } else {
+                    &background
+                });
+            }
+        }
+
+        Ok(RgbaImage {
+            width: side,
+            height: side,
+            data,
+        })
+    }
+}
+
+/// Rasterized with resvg, see [`ImageBuilder::to_pixmap`]
+#[cfg(feature = "image")]
+impl RasterBackend for ImageBuilder {
+    fn rasterize(&self, qr: &QRCode) -> Result<RgbaImage, ImageError> {
+        let pixmap = self.to_pixmap(qr)?;
+        let data = pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect();
+
+        Ok(RgbaImage {
+            width: pixmap.width(),
+            height: pixmap.height(),
+            data,
+        })
+    }
+
+    /// Compressed, with the resolution of [`ImageBuilder::dpi`]
+    fn to_png(&self, qr: &QRCode) -> Result<Vec<u8>, ImageError> {
+        let pixmap = self.to_pixmap(qr)?;
+        self.png(&pixmap)
+    }
+}
+
+#[cfg(feature = "image")]
 /// Creates an ImageBuilder instance, which contains an [`SvgBuilder`]
 impl Default for ImageBuilder {
     fn default() -> Self {
         ImageBuilder {
             fit_height: None,
             fit_width: None,
+            dpi: None,
+            physical_size: None,
+            raster: Raster::AntiAliased,
+            format: ImageFormat::Png,
             svg_builder: Default::default(),
         }
     }
 }
 
+#[cfg(feature = "image")]
 impl Builder for ImageBuilder {
     fn margin(&mut self, margin: usize) -> &mut Self {
         self.svg_builder.margin(margin);
@@ -92,11 +370,74 @@ impl Builder for ImageBuilder {
         self
     }
 
+    fn module_gradient(&mut self, gradient: Gradient) -> &mut Self {
+        self.svg_builder.module_gradient(gradient);
+        self
+    }
+
+    fn finder_color<C: Into<Color>>(&mut self, finder_color: C) -> &mut Self {
+        self.svg_builder.finder_color(finder_color);
+        self
+    }
+
+    fn eye_frame_color<C: Into<Color>>(&mut self, color: C) -> &mut Self {
+        self.svg_builder.eye_frame_color(color);
+        self
+    }
+
+    fn eye_ball_color<C: Into<Color>>(&mut self, color: C) -> &mut Self {
+        self.svg_builder.eye_ball_color(color);
+        self
+    }
+
+    fn eye_frame_shape(&mut self, shape: EyeShape) -> &mut Self {
+        self.svg_builder.eye_frame_shape(shape);
+        self
+    }
+
+    fn eye_ball_shape(&mut self, shape: EyeShape) -> &mut Self {
+        self.svg_builder.eye_ball_shape(shape);
+        self
+    }
+
     fn shape(&mut self, shape: Shape) -> &mut Self {
         self.svg_builder.shape(shape);
         self
     }
 
+    fn shape_fn<F>(&mut self, shape: F) -> &mut Self
+    where
+        F: Fn(usize, usize, Module) -> String + Send + Sync + 'static,
+    {
+        self.svg_builder.shape_fn(shape);
+        self
+    }
+
+    fn dot_radius(&mut self, radius: f64) -> &mut Self {
+        self.svg_builder.dot_radius(radius);
+        self
+    }
+
+    fn module_gap(&mut self, gap: f64) -> &mut Self {
+        self.svg_builder.module_gap(gap);
+        self
+    }
+
+    fn shape_neighbors(&mut self, shape: NeighborFunction) -> &mut Self {
+        self.svg_builder.shape_neighbors(shape);
+        self
+    }
+
+    fn shape_for(&mut self, module_type: ModuleType, shape: Shape) -> &mut Self {
+        self.svg_builder.shape_for(module_type, shape);
+        self
+    }
+
+    fn merge_modules(&mut self, corner_radius: f64) -> &mut Self {
+        self.svg_builder.merge_modules(corner_radius);
+        self
+    }
+
     fn image(&mut self, image: String) -> &mut Self {
         self.svg_builder.image(image);
         self
@@ -132,13 +473,32 @@ impl Builder for ImageBuilder {
         self
     }
 
+    fn background_image(&mut self, image: String) -> &mut Self {
+        self.svg_builder.background_image(image);
+        self
+    }
+
+    fn background_image_opacity(&mut self, opacity: f64) -> &mut Self {
+        self.svg_builder.background_image_opacity(opacity);
+        self
+    }
+
     fn shape_color<C: Into<Color>>(&mut self, shape: Shape, color: C) -> &mut Self {
         self.svg_builder.shape_color(shape, color);
         self
     }
 }
 
+#[cfg(feature = "image")]
 impl ImageBuilder {
+    /// Creates a builder with the configuration of `preset`, svg options only for a preset
+    /// made from an [`SvgBuilder`]
+    pub fn from_preset(preset: &Preset) -> Self {
+        let mut builder = preset.image.clone().unwrap_or_default();
+        builder.svg_builder = preset.svg.clone();
+        builder
+    }
+
     /// Add a max-height boundary
     pub fn fit_height(&mut self, height: u32) -> &mut Self {
         self.fit_height = Some(height);
@@ -151,54 +511,1111 @@ impl ImageBuilder {
         self
     }
 
+    /// Sets the resolution in dots per inch, stored in the PNG `pHYs` chunk and the JPEG header
+    /// (default: none, 300 with [`ImageBuilder::physical_size_mm`])
+    ///
+    /// WebP has no resolution metadata.
+    pub fn dpi(&mut self, dpi: u32) -> &mut Self {
+        self.dpi = Some(dpi.max(1));
+        self
+    }
+
+    /// Rasterizes the image to be `size` millimeters wide when printed at [`ImageBuilder::dpi`],
+    /// replaces `fit_width` & `fit_height`
+    ///
+    /// ```rust
+    /// # use fast_qr::convert::image::ImageBuilder;
+    /// # use fast_qr::qr::QRBuilder;
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    ///
+    /// // 1 inch at 300 dpi
+    /// let pixmap = ImageBuilder::default()
+    ///     .dpi(300)
+    ///     .physical_size_mm(25.4)
+    ///     .to_pixmap(&qrcode)
+    ///     .unwrap();
+    /// assert_eq!(pixmap.width(), 300);
+    /// ```
+    pub fn physical_size_mm(&mut self, size: f64) -> &mut Self {
+        self.physical_size = Some(positive_finite(size).unwrap_or(0f64));
+        self
+    }
+
+    /// Chooses between smooth and hard edges (default: [`Raster::AntiAliased`])
+    ///
+    /// ```rust
+    /// # use fast_qr::convert::image::{ImageBuilder, Raster};
+    /// # use fast_qr::qr::QRBuilder;
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    ///
+    /// // 33 modules with the margin, 3 pixels each
+    /// let pixmap = ImageBuilder::default()
+    ///     .rasterization(Raster::Crisp)
+    ///     .fit_width(100)
+    ///     .to_pixmap(&qrcode)
+    ///     .unwrap();
+    /// assert_eq!(pixmap.width(), 99);
+    /// ```
+    pub fn rasterization(&mut self, raster: Raster) -> &mut Self {
+        self.raster = raster;
+        self
+    }
+
+    /// Resolution written in the metadata
+    fn resolution(&self) -> Option<u32> {
+        self.dpi.or(self.physical_size.map(|_| 300))
+    }
+
+    /// Returns the max width & height in pixels, from the physical size or the boundaries
+    fn fit(&self) -> (Option<u32>, Option<u32>) {
+        match (self.physical_size, self.resolution()) {
+            (Some(size), Some(dpi)) => {
+                let side = (size / 25.4 * f64::from(dpi)).round().max(1f64) as u32;
+                (Some(side), Some(side))
+            }
+            _ => (self.fit_width, self.fit_height),
+        }
+    }
+
+    /// Changes the encoding of the image (default: [`ImageFormat::Png`])
+    pub fn format(&mut self, format: ImageFormat) -> &mut Self {
+        self.format = format;
+        self
+    }
+
+    /// Draws a border around the qr code and its label, see [`SvgBuilder::frame`]
+    pub fn frame(&mut self, frame: FrameStyle) -> &mut Self {
+        self.svg_builder.frame(frame);
+        self
+    }
+
+    /// Draws `text` in a banner above or below the qr code, see [`SvgBuilder::label`]
+    ///
+    /// The font is looked up in the fonts installed on the system.
+    pub fn label<T: Into<String>, F: Into<String>>(
+        &mut self,
+        text: T,
+        font: F,
+        position: LabelPosition,
+    ) -> &mut Self {
+        self.svg_builder.label(text, font, position);
+        self
+    }
+
+    /// Sets the margin of each side in modules, see [`SvgBuilder::margins`]
+    pub fn margins(&mut self, top: usize, right: usize, bottom: usize, left: usize) -> &mut Self {
+        self.svg_builder.margins(top, right, bottom, left);
+        self
+    }
+
+    /// Decorates the margin outside of the quiet zone, see [`SvgBuilder::margin_style`]
+    pub fn margin_style(&mut self, style: MarginStyle) -> &mut Self {
+        self.svg_builder.margin_style(style);
+        self
+    }
+
+    /// Swaps dark and light modules, the margin included, see [`SvgBuilder::invert`]
+    pub fn invert(&mut self, invert: bool) -> &mut Self {
+        self.svg_builder.invert(invert);
+        self
+    }
+
+    /// Refuses colors with a contrast ratio below `ratio` between the modules and the
+    /// background, see [`SvgBuilder::min_contrast`]
+    pub fn min_contrast(&mut self, ratio: f64) -> &mut Self {
+        self.svg_builder.min_contrast(ratio);
+        self
+    }
+
+    /// Builds the [`QRCode`] of `builder` so that the image fits, see [`SvgBuilder::build_qr`]
+    ///
+    /// # Errors
+    /// - `ImageError::ImageError` if `builder` fails to build, if the image hides more
+    ///   modules than the ECL can recover, or if the colors have too little contrast
+    pub fn build_qr(&self, builder: &QRBuilder) -> Result<QRCode, ImageError> {
+        self.svg_builder
+            .build_qr(builder)
+            .map_err(|error| ImageError::ImageError(error.to_string()))
+    }
+
+    /// Draws `text` in the center instead of an image, see [`SvgBuilder::center_text`]
+    ///
+    /// The font is looked up in the fonts installed on the system.
+    pub fn center_text<T: Into<String>, C: Into<Color>>(
+        &mut self,
+        text: T,
+        font_size: f64,
+        color: C,
+    ) -> &mut Self {
+        self.svg_builder.center_text(text, font_size, color);
+        self
+    }
+
+    /// Returns the width of the image in pixels for the max width & height, `units` being the
+    /// width & height of the svg in modules, see [`ImageBuilder::canvas_units`]
+    fn side(&self, (units, height): (usize, usize), fit: (Option<u32>, Option<u32>)) -> u32 {
+        // A label makes the image taller than wide, the height bounds the width less
+        let fit = (
+            fit.0,
+            fit.1.map(|h| (h as usize * units / height.max(1)) as u32),
+        );
+        let side = match fit {
+            (Some(w), Some(h)) => w.min(h),
+            (Some(w), None) => w,
+            (None, Some(h)) => h,
+            _ => (units * self.svg_builder.scale) as u32,
+        };
+
+        match self.raster {
+            Raster::AntiAliased => side,
+            // Rounds down to a whole number of pixels per module
+            Raster::Crisp => {
+                let units = (units as u32).max(1);
+                (side / units).max(1) * units
+            }
+        }
+    }
+
+    /// Width & height of the image in modules, margin, frame and label included
+    fn canvas_units(&self, qr: &QRCode) -> (usize, usize) {
+        let (left, top, right, bottom) = self.svg_builder.frame_insets();
+        let margin = self.svg_builder.margin;
+        (
+            margin.width(qr.size) + left + right,
+            margin.height(qr.size) + top + bottom,
+        )
+    }
+
+    /// Layers of the direct rasterizer, painted over the background color
+    ///
+    /// Only available for built-in shapes, hexadecimal colors, without gradient, merged modules,
+    /// embedded image nor background picture, returns `None` otherwise.
+    fn direct_layers(&self) -> Option<DirectLayers> {
+        let svg = &self.svg_builder;
+        if svg.image.is_some()
+            || svg.center_text.is_some()
+            || svg.background_image.is_some()
+            || svg.background_radius > 0f64
+            || svg.module_gradient.is_some()
+            || svg.merge_radius.is_some()
+            || svg.eye_frame_color.is_some()
+            || svg.eye_ball_color.is_some()
+            || svg.eye_frame_shape.is_some()
+            || svg.eye_ball_shape.is_some()
+            || svg.frame.is_some()
+            || svg.label.is_some()
+            || svg.margin_style != MarginStyle::Plain
+            || svg.margin != Margin::uniform(svg.margin.top)
+            || svg.invert
+            || svg.module_gap > 0f64
+        {
+            return None;
+        }
+
+        let default_command = [ModuleCommand::Function(Shape::square)];
+        let commands: &[ModuleCommand] = if svg.commands.is_empty() {
+            &default_command
+        } else {
+            &svg.commands
+        };
+
+        // Layers are painted in the svg order: shapes, finder patterns, then role shapes
+        let mut layers = Vec::with_capacity(commands.len() * 2 + svg.role_commands.len());
+        for (i, command) in commands.iter().enumerate() {
+            let shape = Shape::from_function(command.function()?).filter(|&shape| direct(shape))?;
+            let color = svg.command_colors.get(i).and_then(Option::as_ref);
+            let color = color.unwrap_or(&svg.dot_color).to_rgba()?;
+            layers.push((shape, premultiply(color), Group::Default));
+        }
+        let finder_color = match &svg.finder_color {
+            Some(finder_color) => Some(premultiply(finder_color.to_rgba()?)),
+            None => None,
+        };
+        if let Some(color) = finder_color {
+            let finder_layers = layers
+                .iter()
+                .map(|&(shape, _, _)| (shape, color, Group::Finder))
+                .collect::<Vec<_>>();
+            layers.extend(finder_layers);
+        }
+        let dot_color = premultiply(svg.dot_color.to_rgba()?);
+        for &(role, command) in &svg.role_commands {
+            let color = match finder_color {
+                Some(color) if role == ModuleType::FinderPattern => color,
+                _ => dot_color,
+            };
+            // Dots depend on the module type, they are drawn by usvg
+            let shape = Shape::from_function(*command)
+                .filter(|&shape| shape != Shape::Dot && direct(shape))?;
+            layers.push((shape, color, Group::Role(role)));
+        }
+        let background = premultiply(svg.background_color.to_rgba()?);
+
+        Some(DirectLayers {
+            layers,
+            finder_color,
+            background,
+        })
+    }
+
+    /// Rasterizes the QRCode directly in the pixmap, without going through usvg.
+    fn draw_direct(&self, qr: &QRCode, direct: &DirectLayers, pixmap: &mut Pixmap) {
+        // Subpixel samples per axis, used for anti-aliasing
+        let samples: usize = match self.raster {
+            Raster::AntiAliased => 4,
+            Raster::Crisp => 1,
+        };
+
+        let svg = &self.svg_builder;
+        let DirectLayers {
+            layers,
+            finder_color,
+            background,
+        } = direct;
+
+        // Margins are the same on each side, see `direct_layers`
+        let margin = svg.margin.top;
+        let units = qr.size + margin * 2;
+        let side = self.side(
+            (units, units),
+            (Some(pixmap.width()), Some(pixmap.height())),
+        );
+        let scale = f64::from(side) / units as f64;
+        let width = pixmap.width() as usize;
+
+        let mut coverage = vec![0; layers.len()];
+
+        for (i, pixel) in pixmap.data_mut().chunks_exact_mut(4).enumerate() {
+            let (py, px) = (i / width, i % width);
+            // Outside of the QRCode when the pixmap isn't a square
+            if py >= side as usize || px >= side as usize {
+                pixel.fill(0);
+                continue;
+            }
+            coverage.iter_mut().for_each(|c| *c = 0);
+
+            for sample in 0..samples * samples {
+                let y = (py as f64 + ((sample / samples) as f64 + 0.5) / samples as f64) / scale;
+                let x = (px as f64 + ((sample % samples) as f64 + 0.5) / samples as f64) / scale;
+
+                let (row, col) = (y as usize, x as usize);
+                if row < margin || col < margin {
+                    continue;
+                }
+                let (row, col) = (row - margin, col - margin);
+                if row >= qr.size || col >= qr.size || !qr[row][col].value() {
+                    continue;
+                }
+                let module_type = qr[row][col].module_type();
+                let group = if svg
+                    .role_commands
+                    .iter()
+                    .any(|&(role, _)| role == module_type)
+                {
+                    Group::Role(module_type)
+                } else if finder_color.is_some() && module_type == ModuleType::FinderPattern {
+                    Group::Finder
+                } else {
+                    Group::Default
+                };
+
+                for (count, &(shape, _, layer_group)) in coverage.iter_mut().zip(layers.iter()) {
+                    if layer_group == group && covers(shape, y.fract(), x.fract()) {
+                        *count += 1;
+                    }
+                }
+            }
+
+            let mut color = *background;
+            for (&count, &(_, layer, _)) in coverage.iter().zip(layers.iter()) {
+                let alpha = count as f64 / (samples * samples) as f64;
+                let remaining = 1f64 - layer[3] * alpha;
+                for (channel, value) in color.iter_mut().zip(layer) {
+                    *channel = value * alpha + *channel * remaining;
+                }
+            }
+
+            for (byte, channel) in pixel.iter_mut().zip(color) {
+                *byte = (channel * 255f64).round() as u8;
+            }
+        }
+    }
+
     // From https://github.com/RazrFalcon/resvg/blob/374a25f/crates/resvg/tests/integration/main.rs
     /// Return a pixmap containing the svg for a QRCode
-    pub fn to_pixmap(&self, qr: &QRCode) -> Pixmap {
-        let opt = usvg::Options::default();
-
-        // Do not unwrap on the from_data line, because panic will poison GLOBAL_OPT.
-        let tree = {
-            let svg_data = self.svg_builder.to_str(qr);
-            let tree = usvg::Tree::from_data(svg_data.as_bytes(), &opt);
-            tree.expect("Failed to parse SVG")
+    ///
+    /// Built-in shapes are rasterized directly, custom commands, closures and embedded images go
+    /// through usvg. PNG and JPEG images, given as a file path or a base64 `data:` URI, are
+    /// decoded and composited on the pixmap with bicubic filtering and alpha blending.
+    ///
+    /// # Errors
+    /// - `ImageError::IoError` if the embedded image or background picture file cannot be read
+    /// - `ImageError::ImageError` if the svg cannot be parsed (i.e. malformed embedded image),
+    ///   the image hides too many modules, the colors or the background picture leave too
+    ///   little contrast, the pixmap cannot be allocated or rendered
+    pub fn to_pixmap(&self, qr: &QRCode) -> Result<Pixmap, ImageError> {
+        let mut pixmaps = self.to_pixmaps_fit(qr, &[self.fit()])?;
+        Ok(pixmaps.remove(0))
+    }
+
+    /// Return a pixmap for each width, like [`ImageBuilder::to_pixmap`] with `fit_width`
+    ///
+    /// The svg is only parsed once and the embedded image decoded once, which is faster than
+    /// calling [`ImageBuilder::to_pixmap`] for each size.
+    ///
+    /// ```rust
+    /// # use fast_qr::convert::image::ImageBuilder;
+    /// # use fast_qr::qr::QRBuilder;
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    ///
+    /// let pixmaps = ImageBuilder::default()
+    ///     .to_pixmaps(&qrcode, &[256, 512, 1024])
+    ///     .unwrap();
+    /// assert_eq!(pixmaps[2].width(), 1024);
+    /// ```
+    ///
+    /// # Errors
+    /// - Same as [`ImageBuilder::to_pixmap`]
+    pub fn to_pixmaps(&self, qr: &QRCode, widths: &[u32]) -> Result<Vec<Pixmap>, ImageError> {
+        let fits = widths
+            .iter()
+            .map(|&width| (Some(width), None))
+            .collect::<Vec<_>>();
+        self.to_pixmaps_fit(qr, &fits)
+    }
+
+    /// Draws the QRCode in `pixmap`, reusing its allocation, like [`ImageBuilder::to_pixmap`]
+    /// with the size of the pixmap
+    ///
+    /// The previous content is replaced. The QRCode is a square drawn in the top left corner, as
+    /// large as the smallest side of the pixmap.
+    ///
+    /// ```rust
+    /// # use fast_qr::convert::image::ImageBuilder;
+    /// # use fast_qr::qr::QRBuilder;
+    /// use resvg::tiny_skia::Pixmap;
+    ///
+    /// let builder = ImageBuilder::default();
+    /// let mut pixmap = Pixmap::new(512, 512).unwrap();
+    /// for input in ["first", "second", "third"] {
+    ///     let qrcode = QRBuilder::new(input).build().unwrap();
+    ///     builder.render_into(&qrcode, &mut pixmap).unwrap();
+    ///     let _png = pixmap.encode_png().unwrap();
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// - Same as [`ImageBuilder::to_pixmap`]
+    pub fn render_into(&self, qr: &QRCode, pixmap: &mut Pixmap) -> Result<(), ImageError> {
+        self.render(qr, std::slice::from_mut(pixmap))
+    }
+
+    /// Allocates then renders the pixmap for each max width & height
+    fn to_pixmaps_fit(
+        &self,
+        qr: &QRCode,
+        fits: &[(Option<u32>, Option<u32>)],
+    ) -> Result<Vec<Pixmap>, ImageError> {
+        let (width, height) = self.canvas_units(qr);
+        let mut pixmaps = fits
+            .iter()
+            .map(|&fit| {
+                let side = self.side((width, height), fit);
+                // Rounded up, so `side` is found back from the height of the pixmap
+                let pixels = (side as usize * height + width - 1) / width;
+                Pixmap::new(side, pixels as u32)
+                    .ok_or_else(|| ImageError::ImageError("Failed to create pixmap".to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.render(qr, &mut pixmaps)?;
+        Ok(pixmaps)
+    }
+
+    /// Draws the QRCode in each pixmap, the svg is parsed once
+    fn render(&self, qr: &QRCode, pixmaps: &mut [Pixmap]) -> Result<(), ImageError> {
+        self.svg_builder
+            .check_contrast()
+            .map_err(|err| ImageError::ImageError(err.to_string()))?;
+
+        if let Some(direct) = self.direct_layers() {
+            for pixmap in pixmaps {
+                self.draw_direct(qr, &direct, pixmap);
+            }
+            return Ok(());
+        }
+
+        self.svg_builder
+            .check_image(qr)
+            .map_err(|err| ImageError::ImageError(err.to_string()))?;
+
+        let logo = match &self.svg_builder.image {
+            Some(image) => decode_logo(image)?,
+            None => None,
         };
 
-        let fit_to = match (self.fit_width, self.fit_height) {
-            (Some(w), Some(h)) => usvg::FitTo::Size(w, h),
-            (Some(w), None) => usvg::FitTo::Width(w),
-            (None, Some(h)) => usvg::FitTo::Height(h),
-            _ => usvg::FitTo::Original,
+        let mut opt = usvg::Options::default();
+        if self.raster == Raster::Crisp {
+            opt.shape_rendering = usvg::ShapeRendering::CrispEdges;
+        }
+        let caption = matches!(
+            self.svg_builder.margin_style,
+            MarginStyle::Band {
+                caption: Some(_),
+                ..
+            }
+        );
+        let text =
+            self.svg_builder.label.is_some() || self.svg_builder.center_text.is_some() || caption;
+
+        // usvg only resolves data URIs, local pictures are inlined
+        let background_image = match &self.svg_builder.background_image {
+            Some(image) => read_local(image)?.and_then(|bytes| data_uri(&bytes)),
+            None => None,
         };
 
-        let size = fit_to
-            .fit_to(tree.size.to_screen_size())
-            .unwrap_or(tree.size.to_screen_size());
-        let mut pixmap =
-            tiny_skia::Pixmap::new(size.width(), size.height()).expect("Failed to create pixmap");
-        resvg::render(
-            &tree,
-            fit_to,
-            tiny_skia::Transform::default(),
-            pixmap.as_mut(),
-        )
-        .unwrap();
+        let svg_data =
+            self.svg_builder
+                .to_str_for_raster(qr, logo.is_none(), background_image.as_deref());
+        let tree = parse_svg(&svg_data, &opt, text)?;
+
+        let canvas = self.canvas_units(qr);
+        let units = canvas.0;
+        // Pixels of the qr code are offset by the frame and label
+        let (left, top, _, _) = self.svg_builder.frame_insets();
+        for pixmap in pixmaps {
+            let side = self.side(canvas, (Some(pixmap.width()), Some(pixmap.height())));
+
+            pixmap.fill(tiny_skia::Color::TRANSPARENT);
+            resvg::render(
+                &tree,
+                usvg::FitTo::Width(side),
+                tiny_skia::Transform::default(),
+                pixmap.as_mut(),
+            )
+            .ok_or_else(|| ImageError::ImageError("Failed to render SVG".to_string()))?;
+
+            if self.svg_builder.background_image.is_some() {
+                let margin = self.svg_builder.margin;
+                let scale = f64::from(side) / units as f64;
+                let offset = (margin.left + left, margin.top + top);
+                check_contrast(qr, pixmap, scale, offset, self.svg_builder.invert)?;
+            }
+
+            if let Some(logo) = &logo {
+                let (_, (x, y, image_size)) = self.svg_builder.image_layout(qr.size);
+                let scale = f64::from(side) / units as f64;
+
+                // Fits the logo in the image square while keeping its aspect ratio, like svg does
+                let fit = image_size * scale / f64::from(logo.width().max(logo.height()));
+                let (x, y) = ((x + left as f64) * scale, (y + top as f64) * scale);
+                let x = x + (image_size * scale - f64::from(logo.width()) * fit) / 2f64;
+                let y = y + (image_size * scale - f64::from(logo.height()) * fit) / 2f64;
+
+                let paint = tiny_skia::PixmapPaint {
+                    quality: tiny_skia::FilterQuality::Bicubic,
+                    ..Default::default()
+                };
+                let transform = tiny_skia::Transform::from_row(
+                    fit as f32, 0f32, 0f32, fit as f32, x as f32, y as f32,
+                );
+                pixmap
+                    .draw_pixmap(0, 0, logo.as_ref(), &paint, transform, None)
+                    .ok_or_else(|| ImageError::ImageError("Failed to draw image".to_string()))?;
+            }
+        }
 
-        pixmap
+        Ok(())
     }
 
-    /// Saves the image for a QRCode to a file
+    /// Saves the image for a QRCode to a file, encoded with [`ImageBuilder::format`]
     pub fn to_file(&self, qr: &QRCode, file: &str) -> Result<(), ImageError> {
-        use io::{Error, ErrorKind};
-
-        self.to_pixmap(qr)
-            .save_png(file)
-            .map_err(|err| ImageError::IoError(Error::new(ErrorKind::Other, err.to_string())))
+        let bytes = self.to_bytes(qr)?;
+        std::fs::write(file, bytes).map_err(ImageError::IoError)
     }
 
-    /// Saves the image for a QRCode in a byte buffer
+    /// Saves the image for a QRCode in a byte buffer, encoded with [`ImageBuilder::format`]
+    ///
+    /// Useful to serve the image without touching the filesystem.
+    ///
+    /// ```rust
+    /// # use fast_qr::convert::{image::{ImageBuilder, ImageFormat}, Builder};
+    /// # use fast_qr::qr::QRBuilder;
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    ///
+    /// let png = ImageBuilder::default().fit_width(512).to_bytes(&qrcode).unwrap();
+    /// assert_eq!(&png[1..4], b"PNG");
+    ///
+    /// let webp = ImageBuilder::default()
+    ///     .format(ImageFormat::Webp)
+    ///     .to_bytes(&qrcode)
+    ///     .unwrap();
+    /// assert_eq!(&webp[8..12], b"WEBP");
+    /// ```
     pub fn to_bytes(&self, qr: &QRCode) -> Result<Vec<u8>, ImageError> {
-        let out = self.to_pixmap(qr);
-        out.encode_png()
-            .map_err(|err| ImageError::EncodingError(err.to_string()))
+        let pixmap = self.to_pixmap(qr)?;
+        self.encode(&pixmap)
+    }
+
+    /// Saves the image for a QRCode to each file, at the given width, encoded with
+    /// [`ImageBuilder::format`]
+    ///
+    /// The svg is only parsed once, see [`ImageBuilder::to_pixmaps`].
+    ///
+    /// ```rust
+    /// # use fast_qr::convert::image::ImageBuilder;
+    /// # use fast_qr::qr::QRBuilder;
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    ///
+    /// ImageBuilder::default()
+    ///     .to_files_multi(&qrcode, &[("small.png", 256), ("large.png", 1024)])
+    ///     .unwrap();
+    /// # std::fs::remove_file("small.png");
+    /// # std::fs::remove_file("large.png");
+    /// ```
+    pub fn to_files_multi(&self, qr: &QRCode, files: &[(&str, u32)]) -> Result<(), ImageError> {
+        let widths = files.iter().map(|&(_, width)| width).collect::<Vec<_>>();
+        let pixmaps = self.to_pixmaps(qr, &widths)?;
+        for (&(file, _), pixmap) in files.iter().zip(&pixmaps) {
+            let bytes = self.encode(pixmap)?;
+            std::fs::write(file, bytes).map_err(ImageError::IoError)?;
+        }
+
+        Ok(())
+    }
+
+    /// Encodes the pixmap with [`ImageBuilder::format`]
+    fn encode(&self, pixmap: &Pixmap) -> Result<Vec<u8>, ImageError> {
+        match self.format {
+            ImageFormat::Png => self.png(pixmap),
+            ImageFormat::Jpeg { quality } => encode_jpeg(
+                pixmap.data(),
+                pixmap.width(),
+                pixmap.height(),
+                quality,
+                self.resolution(),
+            ),
+            ImageFormat::Webp => encode_webp(pixmap.data(), pixmap.width(), pixmap.height()),
+        }
+    }
+
+    /// Encodes the pixmap to PNG, with the resolution when set
+    fn png(&self, pixmap: &Pixmap) -> Result<Vec<u8>, ImageError> {
+        let png = pixmap
+            .encode_png()
+            .map_err(|err| ImageError::EncodingError(err.to_string()))?;
+        Ok(match self.resolution() {
+            Some(dpi) => png_with_dpi(png, dpi),
+            None => png,
+        })
+    }
+}
+
+/// Serializable settings of [`ImageBuilder`], the svg settings are nested
+#[cfg(all(feature = "image", feature = "serde"))]
+#[derive(serde::Serialize)]
+#[serde(rename = "ImageBuilder")]
+struct ImageConfigRef<'a> {
+    fit_height: Option<u32>,
+    fit_width: Option<u32>,
+    dpi: Option<u32>,
+    physical_size_mm: Option<f64>,
+    rasterization: Raster,
+    format: ImageFormat,
+    svg: &'a SvgBuilder,
+}
+
+/// Owned [`ImageConfigRef`], missing settings keep their default value
+#[cfg(all(feature = "image", feature = "serde"))]
+#[derive(serde::Deserialize)]
+#[serde(rename = "ImageBuilder", default)]
+struct ImageConfig {
+    fit_height: Option<u32>,
+    fit_width: Option<u32>,
+    dpi: Option<u32>,
+    physical_size_mm: Option<f64>,
+    rasterization: Raster,
+    format: ImageFormat,
+    svg: SvgBuilder,
+}
+
+#[cfg(all(feature = "image", feature = "serde"))]
+impl Default for ImageConfig {
+    fn default() -> Self {
+        let builder = ImageBuilder::default();
+        ImageConfig {
+            fit_height: builder.fit_height,
+            fit_width: builder.fit_width,
+            dpi: builder.dpi,
+            physical_size_mm: builder.physical_size,
+            rasterization: builder.raster,
+            format: builder.format,
+            svg: builder.svg_builder,
+        }
+    }
+}
+
+/// Serializes the settings, fails if a custom shape is used
+#[cfg(all(feature = "image", feature = "serde"))]
+impl serde::Serialize for ImageBuilder {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ImageConfigRef {
+            fit_height: self.fit_height,
+            fit_width: self.fit_width,
+            dpi: self.dpi,
+            physical_size_mm: self.physical_size,
+            rasterization: self.raster,
+            format: self.format,
+            svg: &self.svg_builder,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(all(feature = "image", feature = "serde"))]
+impl<'de> serde::Deserialize<'de> for ImageBuilder {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = ImageConfig::deserialize(deserializer)?;
+
+        Ok(ImageBuilder {
+            fit_height: config.fit_height,
+            fit_width: config.fit_width,
+            dpi: config.dpi,
+            physical_size: config.physical_size_mm,
+            raster: config.rasterization,
+            format: config.format,
+            svg_builder: config.svg,
+        })
+    }
+}
+
+#[cfg(feature = "image")]
+/// Encodes premultiplied RGBA pixels to JPEG, blending them over white
+/// Decodes a PNG or JPEG logo, from a file path or a base64 `data:` URI
+///
+/// Returns `None` for other images (i.e. svg or remote urls), which are left to usvg.
+fn decode_logo(image: &str) -> Result<Option<Pixmap>, ImageError> {
+    use base64::Engine;
+
+    let bytes = if let Some(uri) = image.strip_prefix("data:") {
+        match uri.split_once(";base64,") {
+            Some((_, payload)) => base64::engine::general_purpose::STANDARD
+                .decode(payload.trim())
+                .map_err(|err| ImageError::ImageError(format!("Invalid image data: {err}")))?,
+            None => return Ok(None),
+        }
+    } else {
+        match read_local(image)? {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        }
+    };
+
+    if bytes.starts_with(b"\x89PNG") {
+        let logo = Pixmap::decode_png(&bytes)
+            .map_err(|err| ImageError::ImageError(format!("Invalid png image: {err}")))?;
+        Ok(Some(logo))
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        decode_jpeg(&bytes).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(feature = "image")]
+/// Parses the svg, turning its text into paths drawn with the system fonts if `text` is set
+///
+/// Text left as is is not rendered by resvg.
+pub(crate) fn parse_svg(
+    svg: &str,
+    opt: &usvg::Options,
+    text: bool,
+) -> Result<usvg::Tree, ImageError> {
+    use resvg::usvg_text_layout::{fontdb, TreeTextToPath};
+
+    let mut tree = usvg::Tree::from_data(svg.as_bytes(), opt)
+        .map_err(|err| ImageError::ImageError(format!("Failed to parse SVG: {err}")))?;
+    if text {
+        let mut fontdb = fontdb::Database::new();
+        fontdb.load_system_fonts();
+        tree.convert_text(&fontdb, opt.keep_named_groups);
+    }
+
+    Ok(tree)
+}
+
+#[cfg(feature = "image")]
+/// Reads `image` if it is a local file, returns `None` for urls and missing files
+fn read_local(image: &str) -> Result<Option<Vec<u8>>, ImageError> {
+    if image.starts_with("data:") || image.contains("://") || !std::path::Path::new(image).is_file()
+    {
+        return Ok(None);
+    }
+
+    std::fs::read(image).map(Some).map_err(ImageError::IoError)
+}
+
+#[cfg(feature = "image")]
+/// Embeds a PNG, JPEG or svg picture as a base64 `data:` URI, `None` for other formats
+fn data_uri(bytes: &[u8]) -> Option<String> {
+    let mime = super::sniff_mime(bytes)?;
+    Some(format!("data:{mime};base64,{}", super::base64(bytes)))
+}
+
+#[cfg(feature = "image")]
+/// Checks that the modules still decode once the rendered pixmap is binarized, the background
+/// picture may leave too little contrast between light and dark modules
+fn check_contrast(
+    qr: &QRCode,
+    pixmap: &Pixmap,
+    scale: f64,
+    (left, top): (usize, usize),
+    invert: bool,
+) -> Result<(), ImageError> {
+    // Luminance at the center of each module, blended over white
+    let luma = (0..qr.size * qr.size)
+        .map(|i| {
+            let y = ((i / qr.size + top) as f64 + 0.5) * scale;
+            let x = ((i % qr.size + left) as f64 + 0.5) * scale;
+            let pixel = pixmap
+                .pixel(x as u32, y as u32)
+                .unwrap_or(tiny_skia::PremultipliedColorU8::TRANSPARENT);
+            let white = f64::from(255 - pixel.alpha());
+            0.2126 * (f64::from(pixel.red()) + white)
+                + 0.7152 * (f64::from(pixel.green()) + white)
+                + 0.0722 * (f64::from(pixel.blue()) + white)
+        })
+        .collect::<Vec<f64>>();
+
+    // Global threshold, halfway between the darkest and lightest modules
+    let min = luma.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = luma.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let threshold = (min + max) / 2f64;
+    let matrix = luma
+        .chunks(qr.size)
+        .map(|row| {
+            row.iter()
+                .map(|&l| (l < threshold) != invert)
+                .collect::<Vec<bool>>()
+        })
+        .collect::<Vec<_>>();
+
+    // Nothing to compare with if the qr code itself does not decode
+    let expected = crate::decode::decode(qr).ok();
+    if expected.is_some() && crate::decode::decode_matrix(&matrix).ok() != expected {
+        return Err(ImageError::ImageError(String::from(
+            "The background image leaves too little contrast with the modules",
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "image")]
+/// Decodes a JPEG in an opaque pixmap
+fn decode_jpeg(bytes: &[u8]) -> Result<Pixmap, ImageError> {
+    use jpeg_decoder::PixelFormat;
+
+    let invalid = |err: String| ImageError::ImageError(format!("Invalid jpeg image: {err}"));
+
+    let mut decoder = jpeg_decoder::Decoder::new(bytes);
+    let pixels = decoder.decode().map_err(|err| invalid(err.to_string()))?;
+    let info = decoder
+        .info()
+        .ok_or_else(|| invalid("missing header".to_string()))?;
+
+    let rgba: Vec<u8> = match info.pixel_format {
+        PixelFormat::L8 => pixels.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+        // Big endian, only the most significant byte is kept
+        PixelFormat::L16 => pixels
+            .chunks_exact(2)
+            .flat_map(|l| [l[0], l[0], l[0], 255])
+            .collect(),
+        PixelFormat::RGB24 => pixels
+            .chunks_exact(3)
+            .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+            .collect(),
+        PixelFormat::CMYK32 => pixels
+            .chunks_exact(4)
+            .flat_map(|cmyk| {
+                let k = u16::from(cmyk[3]);
+                let channel = |c: u8| (u16::from(c) * k / 255) as u8;
+                [channel(cmyk[0]), channel(cmyk[1]), channel(cmyk[2]), 255]
+            })
+            .collect(),
+    };
+
+    let mut logo = Pixmap::new(u32::from(info.width), u32::from(info.height))
+        .ok_or_else(|| invalid("empty image".to_string()))?;
+    if logo.data().len() != rgba.len() {
+        return Err(invalid("wrong pixel count".to_string()));
+    }
+    logo.data_mut().copy_from_slice(&rgba);
+
+    Ok(logo)
+}
+
+#[cfg(feature = "image")]
+fn encode_jpeg(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    quality: u8,
+    dpi: Option<u32>,
+) -> Result<Vec<u8>, ImageError> {
+    let too_big = |_| ImageError::EncodingError("Image is too big for JPEG".to_string());
+    let width = u16::try_from(width).map_err(too_big)?;
+    let height = u16::try_from(height).map_err(too_big)?;
+
+    let rgb = data
+        .chunks_exact(4)
+        .flat_map(|pixel| {
+            let transparency = 255 - pixel[3];
+            [
+                pixel[0] + transparency,
+                pixel[1] + transparency,
+                pixel[2] + transparency,
+            ]
+        })
+        .collect::<Vec<u8>>();
+
+    let mut out = Vec::new();
+    let mut encoder = jpeg_encoder::Encoder::new(&mut out, quality.clamp(1, 100));
+    if let Some(dpi) = dpi {
+        let dpi = u16::try_from(dpi).unwrap_or(u16::MAX);
+        encoder.set_density(jpeg_encoder::Density::Inch { x: dpi, y: dpi });
+    }
+    encoder
+        .encode(&rgb, width, height, jpeg_encoder::ColorType::Rgb)
+        .map_err(|err| ImageError::EncodingError(err.to_string()))?;
+
+    Ok(out)
+}
+
+#[cfg(feature = "image")]
+/// Adds a `pHYs` chunk with the resolution right after the `IHDR` chunk of `png`
+fn png_with_dpi(mut png: Vec<u8>, dpi: u32) -> Vec<u8> {
+    // Signature, then IHDR: length, type, 13 bytes of data and crc
+    const IHDR_END: usize = 8 + 4 + 4 + 13 + 4;
+
+    // Pixels per meter
+    let ppm = (f64::from(dpi) / 0.0254).round() as u32;
+    let mut chunk = Vec::with_capacity(21);
+    chunk.extend_from_slice(&9u32.to_be_bytes());
+    chunk.extend_from_slice(b"pHYs");
+    chunk.extend_from_slice(&ppm.to_be_bytes());
+    chunk.extend_from_slice(&ppm.to_be_bytes());
+    // The unit is the meter
+    chunk.push(1);
+    let crc = crc32(&chunk[4..]);
+    chunk.extend_from_slice(&crc.to_be_bytes());
+
+    png.splice(IHDR_END..IHDR_END, chunk);
+    png
+}
+
+/// Encodes the pixels to PNG without compression, with a palette when there are at most 256
+/// colors
+fn write_png(image: &RgbaImage) -> Vec<u8> {
+    let width = image.width as usize;
+    let pixels = image
+        .data
+        .chunks_exact(4)
+        .map(|pixel| [pixel[0], pixel[1], pixel[2], pixel[3]])
+        .collect::<Vec<_>>();
+
+    let mut palette = Vec::new();
+    for pixel in &pixels {
+        if !palette.contains(pixel) {
+            palette.push(*pixel);
+            if palette.len() > 256 {
+                break;
+            }
+        }
+    }
+
+    // Filtered rows, each starting with the filter type (none)
+    let mut raw = Vec::new();
+    let (color_type, depth) = if palette.len() <= 256 {
+        let depth = match palette.len() {
+            0..=2 => 1,
+            3..=4 => 2,
+            5..=16 => 4,
+            _ => 8,
+        };
+        let per_byte = 8 / depth;
+        for row in pixels.chunks(width.max(1)) {
+            raw.push(0);
+            let start = raw.len();
+            raw.resize(start + (width * depth + 7) / 8, 0);
+            for (x, pixel) in row.iter().enumerate() {
+                let index = palette.iter().position(|color| color == pixel).unwrap_or(0) as u8;
+                raw[start + x / per_byte] |= index << (8 - depth * (x % per_byte + 1));
+            }
+        }
+        (3, depth as u8)
+    } else {
+        for row in image.data.chunks(width.max(1) * 4) {
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+        (6, 8)
+    };
+
+    // zlib stream of stored deflate blocks
+    let mut zlib = vec![0x78, 0x01];
+    let blocks = raw.chunks(usize::from(u16::MAX)).collect::<Vec<_>>();
+    for (i, block) in blocks.iter().enumerate() {
+        zlib.push(u8::from(i + 1 == blocks.len()));
+        let len = block.len() as u16;
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in &raw {
+        a = (a + u32::from(byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    zlib.extend_from_slice(&((b << 16) | a).to_be_bytes());
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    let mut chunk = |kind: &[u8; 4], data: &[u8]| {
+        png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let start = png.len();
+        png.extend_from_slice(kind);
+        png.extend_from_slice(data);
+        let crc = crc32(&png[start..]);
+        png.extend_from_slice(&crc.to_be_bytes());
+    };
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&image.width.to_be_bytes());
+    header.extend_from_slice(&image.height.to_be_bytes());
+    header.extend_from_slice(&[depth, color_type, 0, 0, 0]);
+    chunk(b"IHDR", &header);
+    if color_type == 3 {
+        let rgb = palette
+            .iter()
+            .flat_map(|color| [color[0], color[1], color[2]])
+            .collect::<Vec<_>>();
+        chunk(b"PLTE", &rgb);
+        if palette.iter().any(|color| color[3] != 255) {
+            let alpha = palette.iter().map(|color| color[3]).collect::<Vec<_>>();
+            chunk(b"tRNS", &alpha);
+        }
+    }
+    chunk(b"IDAT", &zlib);
+    chunk(b"IEND", &[]);
+
+    png
+}
+
+/// CRC-32 of the type and data of a PNG chunk
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(feature = "image")]
+/// Encodes premultiplied RGBA pixels to lossless WebP
+fn encode_webp(data: &[u8], width: u32, height: u32) -> Result<Vec<u8>, ImageError> {
+    let rgba = data
+        .chunks_exact(4)
+        .flat_map(|pixel| match pixel[3] {
+            0 => [0; 4],
+            alpha => {
+                let demultiply = |c: u8| (u16::from(c) * 255 / u16::from(alpha)) as u8;
+                [
+                    demultiply(pixel[0]),
+                    demultiply(pixel[1]),
+                    demultiply(pixel[2]),
+                    alpha,
+                ]
+            }
+        })
+        .collect::<Vec<u8>>();
+
+    let mut out = Vec::new();
+    image_webp::WebPEncoder::new(&mut out)
+        .encode(&rgba, width, height, image_webp::ColorType::Rgba8)
+        .map_err(|err| ImageError::EncodingError(err.to_string()))?;
+
+    Ok(out)
+}
+
+#[cfg(feature = "image")]
+/// Shapes and colors of the direct rasterizer
+struct DirectLayers {
+    /// Shape, premultiplied color and modules of each layer, in the svg order
+    layers: Vec<(Shape, [f64; 4], Group)>,
+    /// Premultiplied color of the finder patterns, if it differs
+    finder_color: Option<[f64; 4]>,
+    /// Premultiplied background color
+    background: [f64; 4],
+}
+
+#[cfg(feature = "image")]
+/// Modules drawn by a layer of the direct rasterizer
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Group {
+    /// Modules drawn by the shapes list
+    Default,
+    /// Finder patterns drawn by the shapes list with `finder_color`
+    Finder,
+    /// Modules of a type drawn by its own shapes
+    Role(ModuleType),
+}
+
+#[cfg(feature = "image")]
+/// Converts a color to premultiplied channels between 0 and 1
+fn premultiply(color: [u8; 4]) -> [f64; 4] {
+    let alpha = f64::from(color[3]) / 255f64;
+    [
+        f64::from(color[0]) / 255f64 * alpha,
+        f64::from(color[1]) / 255f64 * alpha,
+        f64::from(color[2]) / 255f64 * alpha,
+        alpha,
+    ]
+}
+
+#[cfg(feature = "image")]
+/// Whether the direct rasterizer draws `shape`, the others are drawn by usvg
+fn direct(shape: Shape) -> bool {
+    !matches!(shape, Shape::Star | Shape::Heart)
+}
+
+#[cfg(feature = "image")]
+/// Returns whether `shape` covers the point (`y`, `x`) of its module, both between 0 and 1,
+/// matching the paths of the svg
+fn covers(shape: Shape, y: f64, x: f64) -> bool {
+    let (dy, dx) = ((y - 0.5).abs(), (x - 0.5).abs());
+    match shape {
+        Shape::Square => true,
+        Shape::Circle => dx * dx + dy * dy <= 0.25,
+        // Square from .2 to .8 with a round stroke of width .3
+        Shape::RoundedSquare => {
+            let (dy, dx) = ((dy - 0.3).max(0f64), (dx - 0.3).max(0f64));
+            dx * dx + dy * dy <= 0.15 * 0.15
+        }
+        Shape::Vertical => dx <= 0.4,
+        Shape::Horizontal => dy <= 0.4,
+        Shape::Diamond => dx + dy <= 0.5,
+        Shape::Hexagon => dy <= 0.433 && dy <= 3f64.sqrt() * (0.5 - dx),
+        Shape::Octagon => dx + dy <= 0.707,
+        // Drawn by usvg
+        Shape::Blob | Shape::Dot | Shape::Star | Shape::Heart => false,
+        #[cfg(not(feature = "wasm-bindgen"))]
+        Shape::Command(_) => false,
     }
 }
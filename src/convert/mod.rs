@@ -1,20 +1,46 @@
 //! Converts a [`crate::QRCode`] to image or SVG you will need to activate associated feature flag
 
+#[cfg(feature = "svg")]
+#[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
+pub mod animated;
+pub mod bmp;
+mod css;
+pub mod escpos;
+pub mod netpbm;
+pub mod term;
+pub mod text;
+pub mod xbm;
+pub mod zpl;
+
+#[cfg(feature = "svg")]
+#[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
+pub mod preset;
+#[cfg(feature = "svg")]
+#[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
+pub mod sheet;
+#[cfg(feature = "svg")]
+#[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
+pub mod styled;
 #[cfg(feature = "svg")]
 #[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
 pub mod svg;
 use core::ops::Deref;
 
+#[cfg(feature = "svg")]
+use std::sync::Arc;
+
 #[cfg(feature = "svg")]
 use svg::SvgError;
 
-#[cfg(feature = "image")]
-#[cfg_attr(docsrs, doc(cfg(feature = "image")))]
+#[cfg(feature = "raster")]
+#[cfg_attr(docsrs, doc(cfg(feature = "raster")))]
 pub mod image;
-#[cfg(feature = "image")]
+#[cfg(feature = "raster")]
 use image::ImageError;
 
-use crate::Module;
+#[cfg(feature = "svg")]
+use crate::QRCode;
+use crate::{Module, ModuleType};
 
 /// Converts a position to a module svg
 /// # Example
@@ -29,6 +55,166 @@ use crate::Module;
 /// ```
 pub type ModuleFunction = fn(usize, usize, Module) -> String;
 
+/// Converts a position to a module svg, knowing its dark [`Neighbors`]
+/// # Example
+///
+/// A square with its top-left corner rounded when there is no module above nor left of it
+///
+/// ```rust
+/// # use fast_qr::convert::Neighbors;
+/// # use fast_qr::Module;
+/// fn corner(y: usize, x: usize, _module: Module, neighbors: Neighbors) -> String {
+///     if neighbors.has(Neighbors::TOP) || neighbors.has(Neighbors::LEFT) {
+///         format!("M{x},{y}h1v1h-1")
+///     } else {
+///         format!("M{x},{y}.5a.5,.5 0 0 1 .5,-.5h.5v1h-1")
+///     }
+/// }
+/// ```
+pub type NeighborFunction = fn(usize, usize, Module, Neighbors) -> String;
+
+/// Dark modules around a module, as a bitmask, given to a [`NeighborFunction`]
+///
+/// Modules outside of the [`crate::QRCode`] are light.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Neighbors(pub u8);
+
+impl Neighbors {
+    /// Module above
+    pub const TOP: u8 = 1 << 0;
+    /// Module above and to the right
+    pub const TOP_RIGHT: u8 = 1 << 1;
+    /// Module to the right
+    pub const RIGHT: u8 = 1 << 2;
+    /// Module below and to the right
+    pub const BOTTOM_RIGHT: u8 = 1 << 3;
+    /// Module below
+    pub const BOTTOM: u8 = 1 << 4;
+    /// Module below and to the left
+    pub const BOTTOM_LEFT: u8 = 1 << 5;
+    /// Module to the left
+    pub const LEFT: u8 = 1 << 6;
+    /// Module above and to the left
+    pub const TOP_LEFT: u8 = 1 << 7;
+
+    /// Returns whether all the neighbors of `mask` are dark
+    pub const fn has(self, mask: u8) -> bool {
+        self.0 & mask == mask
+    }
+
+    /// Dark neighbors of the module at (`y`, `x`)
+    #[cfg(feature = "svg")]
+    pub(crate) fn of(qr: &QRCode, y: usize, x: usize) -> Self {
+        // Offsets in the order of the bits
+        const OFFSETS: [(isize, isize); 8] = [
+            (-1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+            (1, 0),
+            (1, -1),
+            (0, -1),
+            (-1, -1),
+        ];
+
+        let mut mask = 0;
+        for (bit, &(dy, dx)) in OFFSETS.iter().enumerate() {
+            let (y, x) = (y as isize + dy, x as isize + dx);
+            if (0..qr.size as isize).contains(&y)
+                && (0..qr.size as isize).contains(&x)
+                && qr[y as usize][x as usize].value()
+            {
+                mask |= 1 << bit;
+            }
+        }
+
+        Neighbors(mask)
+    }
+}
+
+/// Function or shared closure drawing a module, see [`Builder::shape_fn`]
+#[cfg(feature = "svg")]
+#[derive(Clone)]
+pub(crate) enum ModuleCommand {
+    /// Built-in shape or [`Shape::Command`]
+    Function(ModuleFunction),
+    /// Closure given to [`Builder::shape_fn`]
+    Closure(Arc<dyn Fn(usize, usize, Module) -> String + Send + Sync>),
+    /// [`Shape::Blob`] or function given to [`Builder::shape_neighbors`]
+    Neighbors(NeighborFunction),
+    /// [`Shape::Dot`], sized by [`Builder::dot_radius`]
+    Dot,
+}
+
+#[cfg(feature = "svg")]
+impl ModuleCommand {
+    /// Returns the svg path of the module at (`y`, `x`)
+    pub(crate) fn draw(
+        &self,
+        y: usize,
+        x: usize,
+        module: Module,
+        neighbors: Neighbors,
+        dot_radius: f64,
+    ) -> String {
+        match self {
+            ModuleCommand::Function(function) => function(y, x, module),
+            ModuleCommand::Closure(closure) => closure(y, x, module),
+            ModuleCommand::Neighbors(function) => function(y, x, module, neighbors),
+            ModuleCommand::Dot => Shape::dot_sized(y, x, module, dot_radius),
+        }
+    }
+
+    /// Returns the function pointer, `None` for closures, neighbor-aware shapes and dots
+    pub(crate) fn function(&self) -> Option<ModuleFunction> {
+        match self {
+            ModuleCommand::Function(function) => Some(*function),
+            ModuleCommand::Closure(_) | ModuleCommand::Neighbors(_) | ModuleCommand::Dot => None,
+        }
+    }
+
+    /// Returns whether drawing needs the [`Neighbors`] of each module
+    pub(crate) const fn needs_neighbors(&self) -> bool {
+        matches!(self, ModuleCommand::Neighbors(_))
+    }
+
+    /// Returns the built-in [`Shape`] drawn, `None` for custom commands
+    pub(crate) fn shape(&self) -> Option<Shape> {
+        match self {
+            ModuleCommand::Function(function) => Shape::from_function(*function),
+            ModuleCommand::Neighbors(function)
+                if *function as usize == Shape::blob as NeighborFunction as usize =>
+            {
+                Some(Shape::Blob)
+            }
+            ModuleCommand::Dot => Some(Shape::Dot),
+            _ => None,
+        }
+    }
+}
+
+/// Built-in shapes by name, custom shapes as `Custom`
+#[cfg(feature = "svg")]
+impl core::fmt::Debug for ModuleCommand {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.shape() {
+            Some(shape) => write!(f, "{shape:?}"),
+            None => f.write_str("Custom"),
+        }
+    }
+}
+
+#[cfg(feature = "svg")]
+impl From<Shape> for ModuleCommand {
+    fn from(shape: Shape) -> Self {
+        match shape {
+            Shape::Blob => ModuleCommand::Neighbors(Shape::blob),
+            Shape::Dot => ModuleCommand::Dot,
+            _ => ModuleCommand::Function(*shape),
+        }
+    }
+}
+
 #[cfg(all(target_arch = "wasm32", feature = "wasm-bindgen"))]
 use wasm_bindgen::prelude::*;
 
@@ -50,11 +236,24 @@ pub enum Shape {
     Horizontal,
     /// Diamond Shape
     Diamond,
+    /// Blob Shape, corners are rounded only where there is no dark neighbor
+    Blob,
+    /// Dot Shape, data modules are circles sized by `dot_radius`, the others stay square
+    Dot,
+    /// Hexagon Shape, flat on the top and bottom
+    Hexagon,
+    /// Octagon Shape
+    Octagon,
+    /// Star Shape, five-pointed
+    Star,
+    /// Heart Shape
+    Heart,
 }
 
 /// Different possible Shapes to represent modules in a [`crate::QRCode`]
 #[cfg(not(feature = "wasm-bindgen"))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd)]
+#[allow(unpredictable_function_pointer_comparisons)]
 pub enum Shape {
     /// Square Shape
     Square,
@@ -68,6 +267,24 @@ pub enum Shape {
     Horizontal,
     /// Diamond Shape
     Diamond,
+    /// Blob Shape, corners are rounded only where there is no dark neighbor, giving smooth
+    /// outlines to groups of modules
+    ///
+    /// Modules drawn without their neighbors (i.e. with [`Builder::shape_for`]) are circles.
+    Blob,
+    /// Dot Shape, data modules are circles of radius [`Builder::dot_radius`] while finder
+    /// patterns and other function patterns stay square, giving a light dotted style
+    ///
+    /// Modules drawn with [`Builder::shape_for`] use the default radius.
+    Dot,
+    /// Hexagon Shape, flat on the top and bottom
+    Hexagon,
+    /// Octagon Shape
+    Octagon,
+    /// Star Shape, five-pointed
+    Star,
+    /// Heart Shape
+    Heart,
     /// Custom Shape with a function / closure
     /// # Example
     /// ```rust
@@ -95,47 +312,25 @@ pub enum Shape {
 
 impl From<Shape> for usize {
     fn from(shape: Shape) -> Self {
-        match shape {
-            Shape::Square => 0,
-            Shape::Circle => 1,
-            Shape::RoundedSquare => 2,
-            Shape::Vertical => 3,
-            Shape::Horizontal => 4,
-            Shape::Diamond => 5,
-            #[cfg(not(feature = "wasm-bindgen"))]
-            Shape::Command(_) => 6,
-        }
+        Shape::REGISTRY
+            .iter()
+            .position(|&(registered, _, _)| registered == shape)
+            .unwrap_or(Shape::REGISTRY.len())
     }
 }
 
 impl From<String> for Shape {
-    #[allow(clippy::match_same_arms)]
     fn from(shape: String) -> Self {
-        match shape.to_lowercase().as_str() {
-            "square" => Shape::Square,
-            "circle" => Shape::Circle,
-            "rounded_square" => Shape::RoundedSquare,
-            "vertical" => Shape::Vertical,
-            "horizontal" => Shape::Horizontal,
-            "diamond" => Shape::Diamond,
-
-            _ => Shape::Square,
-        }
+        Shape::from_name(&shape.to_lowercase()).unwrap_or(Shape::Square)
     }
 }
 
 impl From<Shape> for &str {
     fn from(shape: Shape) -> Self {
-        match shape {
-            Shape::Square => "square",
-            Shape::Circle => "circle",
-            Shape::RoundedSquare => "rounded_square",
-            Shape::Vertical => "vertical",
-            Shape::Horizontal => "horizontal",
-            Shape::Diamond => "diamond",
-            #[cfg(not(feature = "wasm-bindgen"))]
-            Shape::Command(_) => "command",
-        }
+        Shape::REGISTRY
+            .iter()
+            .find(|&&(registered, _, _)| registered == shape)
+            .map_or("command", |&(_, name, _)| name)
     }
 }
 
@@ -164,32 +359,172 @@ impl Shape {
         format!("M{x}.5,{y}l.5,.5l-.5,.5l-.5,-.5z")
     }
 
-    const FUNCTIONS: [ModuleFunction; 6] = [
-        Shape::square,
-        Shape::circle,
-        Shape::rounded_square,
-        Shape::vertical,
-        Shape::horizontal,
-        Shape::diamond,
+    pub(crate) fn hexagon(y: usize, x: usize, _: Module) -> String {
+        format!("M{x},{y}.5l.25,-.433h.5l.25,.433l-.25,.433h-.5z")
+    }
+
+    pub(crate) fn octagon(y: usize, x: usize, _: Module) -> String {
+        format!("M{x}.293,{y}h.414l.293,.293v.414l-.293,.293h-.414l-.293,-.293v-.414z")
+    }
+
+    pub(crate) fn star(y: usize, x: usize, _: Module) -> String {
+        format!(
+            "M{x}.5,{y}.048l.147,.298l.329,.048l-.238,.232l.056,.327\
+            l-.294,-.155l-.294,.155l.056,-.327l-.238,-.232l.329,-.048z"
+        )
+    }
+
+    pub(crate) fn heart(y: usize, x: usize, _: Module) -> String {
+        format!("M{x}.5,{y}.92l-.4,-.42a.256,.256 0 0 1 .4,-.32a.256,.256 0 0 1 .4,.32z")
+    }
+
+    #[cfg(feature = "svg")]
+    pub(crate) fn blob(y: usize, x: usize, _: Module, neighbors: Neighbors) -> String {
+        // A corner is rounded when both of its sides have no dark neighbor
+        let radius = |sides: u8| if neighbors.0 & sides == 0 { 0.5 } else { 0f64 };
+        let top_left = radius(Neighbors::TOP | Neighbors::LEFT);
+        let top_right = radius(Neighbors::TOP | Neighbors::RIGHT);
+        let bottom_right = radius(Neighbors::BOTTOM | Neighbors::RIGHT);
+        let bottom_left = radius(Neighbors::BOTTOM | Neighbors::LEFT);
+
+        let arc = |radius: f64, dx: f64, dy: f64| {
+            if radius > 0f64 {
+                format!("a.5,.5 0 0 1 {dx},{dy}")
+            } else {
+                String::new()
+            }
+        };
+
+        format!(
+            "M{},{y}h{}{}v{}{}h{}{}v{}{}z",
+            x as f64 + top_left,
+            1f64 - top_left - top_right,
+            arc(top_right, 0.5, 0.5),
+            1f64 - top_right - bottom_right,
+            arc(bottom_right, -0.5, 0.5),
+            -(1f64 - bottom_right - bottom_left),
+            arc(bottom_left, -0.5, -0.5),
+            -(1f64 - bottom_left - top_left),
+            arc(top_left, 0.5, -0.5),
+        )
+    }
+
+    /// Default radius of [`Shape::Dot`]
+    pub(crate) const DOT_RADIUS: f64 = 0.4;
+
+    pub(crate) fn dot(y: usize, x: usize, module: Module) -> String {
+        Self::dot_sized(y, x, module, Self::DOT_RADIUS)
+    }
+
+    pub(crate) fn dot_sized(y: usize, x: usize, module: Module, radius: f64) -> String {
+        if module.module_type() != ModuleType::Data {
+            return Self::square(y, x, module);
+        }
+
+        format!(
+            "M{},{y}.5a{radius},{radius} 0 1,1 {},0a{radius},{radius} 0 1,1 {},0",
+            x as f64 + 0.5 - radius,
+            radius * 2f64,
+            -radius * 2f64,
+        )
+    }
+
+    /// Built-in shapes with their name and the function drawing a module, in the order of the
+    /// variants
+    ///
+    /// A new built-in shape only needs its variant and an entry here. `Shape::Blob` without
+    /// neighbors is a circle.
+    const REGISTRY: [(Shape, &'static str, ModuleFunction); 12] = [
+        (Shape::Square, "square", Shape::square),
+        (Shape::Circle, "circle", Shape::circle),
+        (
+            Shape::RoundedSquare,
+            "rounded_square",
+            Shape::rounded_square,
+        ),
+        (Shape::Vertical, "vertical", Shape::vertical),
+        (Shape::Horizontal, "horizontal", Shape::horizontal),
+        (Shape::Diamond, "diamond", Shape::diamond),
+        (Shape::Blob, "blob", Shape::circle),
+        (Shape::Dot, "dot", Shape::dot),
+        (Shape::Hexagon, "hexagon", Shape::hexagon),
+        (Shape::Octagon, "octagon", Shape::octagon),
+        (Shape::Star, "star", Shape::star),
+        (Shape::Heart, "heart", Shape::heart),
     ];
+
+    /// Names of the built-in shapes, as used by the CLI and serde
+    pub const NAMES: [&'static str; Self::REGISTRY.len()] = Self::names();
+
+    const fn names() -> [&'static str; Self::REGISTRY.len()] {
+        let mut names = [""; Self::REGISTRY.len()];
+        let mut i = 0;
+        while i < names.len() {
+            names[i] = Self::REGISTRY[i].1;
+            i += 1;
+        }
+        names
+    }
+
+    /// Returns the built-in [`Shape`] named `name` (i.e. `"rounded_square"`), see
+    /// [`Shape::NAMES`]
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Shape> {
+        Self::REGISTRY
+            .iter()
+            .find(|&&(_, registered, _)| registered == name)
+            .map(|&(shape, _, _)| shape)
+    }
+
+    /// Returns the built-in [`Shape`] drawn by `function`, `None` for custom commands
+    #[cfg(feature = "svg")]
+    pub(crate) fn from_function(function: ModuleFunction) -> Option<Shape> {
+        Self::REGISTRY
+            .iter()
+            .find(|&&(_, _, f)| f as usize == function as usize)
+            .map(|&(shape, _, _)| shape)
+    }
 }
 
 impl Deref for Shape {
     type Target = ModuleFunction;
 
     fn deref(&self) -> &Self::Target {
-        let index: usize = (*self).into();
         match self {
             #[cfg(not(feature = "wasm-bindgen"))]
             Self::Command(func) => func,
-            _ => &Self::FUNCTIONS[index],
+            _ => &Self::REGISTRY[usize::from(*self)].2,
         }
     }
 }
 
+/// Serializes built-in shapes by name, custom [`Shape::Command`]s can't be serialized
+#[cfg(feature = "serde")]
+impl serde::Serialize for Shape {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            #[cfg(not(feature = "wasm-bindgen"))]
+            Shape::Command(_) => Err(serde::ser::Error::custom(
+                "custom shapes can't be serialized",
+            )),
+            _ => serializer.serialize_str((*self).into()),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Shape {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Shape::from_name(&name)
+            .ok_or_else(|| serde::de::Error::unknown_variant(&name, &Shape::NAMES))
+    }
+}
+
 /// Different possible image background shapes
 #[cfg_attr(feature = "wasm-bindgen", repr(C), wasm_bindgen)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ImageBackgroundShape {
     /// Square shape
     Square,
@@ -199,6 +534,19 @@ pub enum ImageBackgroundShape {
     RoundedSquare,
 }
 
+/// Shape of the frames or centers of the finder patterns, drawn as a whole, see
+/// [`Builder::eye_frame_shape`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EyeShape {
+    /// Square shape
+    Square,
+    /// Rounded square shape
+    RoundedSquare,
+    /// Circle shape
+    Circle,
+}
+
 /// Contains possible errors for a conversion
 #[derive(Debug)]
 pub enum ConvertError {
@@ -207,13 +555,47 @@ pub enum ConvertError {
     #[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
     Svg(String),
     /// Contains error message for an Image conversion
-    #[cfg(feature = "image")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "image")))]
+    #[cfg(feature = "raster")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "raster")))]
     Image(String),
+    /// Contains error message for a GIF animation
+    #[cfg(feature = "gif")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gif")))]
+    Gif(String),
     /// Contains error message if a file write failed
     Io(std::io::Error),
 }
 
+impl std::error::Error for ConvertError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConvertError::Io(io_err) => Some(io_err),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            #[cfg(feature = "svg")]
+            ConvertError::Svg(error) => write!(f, "SVG conversion failed: {error}"),
+            #[cfg(feature = "raster")]
+            ConvertError::Image(error) => write!(f, "Image conversion failed: {error}"),
+            #[cfg(feature = "gif")]
+            ConvertError::Gif(error) => write!(f, "GIF conversion failed: {error}"),
+            ConvertError::Io(io_err) => write!(f, "Failed to write file: {io_err}"),
+        }
+    }
+}
+
+impl From<std::io::Error> for ConvertError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
 #[cfg(feature = "svg")]
 #[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
 impl From<SvgError> for ConvertError {
@@ -226,8 +608,8 @@ impl From<SvgError> for ConvertError {
     }
 }
 
-#[cfg(feature = "image")]
-#[cfg_attr(docsrs, doc(cfg(feature = "image")))]
+#[cfg(feature = "raster")]
+#[cfg_attr(docsrs, doc(cfg(feature = "raster")))]
 impl From<ImageError> for ConvertError {
     fn from(err: ImageError) -> Self {
         match err {
@@ -238,6 +620,26 @@ impl From<ImageError> for ConvertError {
     }
 }
 
+/// Why a string is not a color, see [`Color::parse`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColorError {
+    /// Starts with `#` but is not `#rgb`, `#rgba`, `#rrggbb` nor `#rrggbbaa`
+    InvalidHex(String),
+    /// Neither a hexadecimal color nor a CSS named color
+    UnknownName(String),
+}
+
+impl std::error::Error for ColorError {}
+
+impl core::fmt::Display for ColorError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ColorError::InvalidHex(color) => write!(f, "Invalid hexadecimal color: {color}"),
+            ColorError::UnknownName(color) => write!(f, "Unknown color name: {color}"),
+        }
+    }
+}
+
 /// Converts an array of pixel color to it's hexadecimal representation
 /// # Example
 /// ```rust
@@ -261,14 +663,141 @@ pub fn rgba2hex(color: [u8; 4]) -> String {
 }
 
 /// Allows to take String, string slices, arrays or slices of u8 (3 or 4) to create a [Color]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct Color(pub String);
 
 impl Color {
+    /// Parses a CSS color: `#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa`, a named color such as
+    /// `rebeccapurple` or `transparent`, stored as `#rrggbb` or `#rrggbbaa`
+    ///
+    /// Unlike `From<&str>`, which keeps any string as is, invalid colors are reported.
+    ///
+    /// # Errors
+    /// - `ColorError::InvalidHex` if the color starts with `#` but is not hexadecimal
+    /// - `ColorError::UnknownName` for any other unknown color
+    ///
+    /// ```rust
+    /// use fast_qr::convert::{Color, ColorError};
+    ///
+    /// assert_eq!(Color::parse("#1A2B3C"), Ok(Color::from("#1a2b3c")));
+    /// assert_eq!(Color::parse("#fff"), Ok(Color::from("#ffffff")));
+    /// assert_eq!(Color::parse("Navy"), Ok(Color::from("#000080")));
+    /// assert_eq!(Color::parse("transparent"), Ok(Color::from("#00000000")));
+    /// assert_eq!(Color::parse("#12345"), Err(ColorError::InvalidHex("#12345".into())));
+    /// ```
+    pub fn parse(color: &str) -> Result<Color, ColorError> {
+        let color = color.trim();
+        let hex = match color.strip_prefix('#') {
+            Some(hex) => hex,
+            None if color.eq_ignore_ascii_case("transparent") => {
+                return Ok(Color::from([0, 0, 0, 0]))
+            }
+            None => {
+                return css::named_color(color)
+                    .map(Color::from)
+                    .ok_or_else(|| ColorError::UnknownName(color.to_string()))
+            }
+        };
+
+        let invalid = || ColorError::InvalidHex(color.to_string());
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let digit = |i: usize| u8::from_str_radix(&hex[i..=i], 16).map_err(|_| invalid());
+        let mut rgba = [255; 4];
+        match hex.len() {
+            // Short notation, each digit is repeated
+            3 | 4 => {
+                for (i, channel) in rgba.iter_mut().enumerate().take(hex.len()) {
+                    *channel = digit(i)? * 17;
+                }
+            }
+            6 | 8 => {
+                for (i, channel) in rgba.iter_mut().enumerate().take(hex.len() / 2) {
+                    *channel = digit(i * 2)? * 16 + digit(i * 2 + 1)?;
+                }
+            }
+            _ => return Err(invalid()),
+        }
+
+        Ok(Color::from(rgba))
+    }
+
     /// Returns the contained color
     #[must_use]
     pub fn to_str(&self) -> &str {
         &self.0
     }
+
+    /// Parses a `#rrggbb` or `#rrggbbaa` color, `None` for any other notation
+    #[cfg(any(feature = "svg", feature = "raster"))]
+    pub(crate) fn to_rgba(&self) -> Option<[u8; 4]> {
+        let hex = self.0.strip_prefix('#')?;
+        if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+            return None;
+        }
+
+        let mut rgba = [255; 4];
+        for (i, channel) in rgba.iter_mut().enumerate().take(hex.len() / 2) {
+            *channel = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+        }
+
+        Some(rgba)
+    }
+
+    /// Returns the WCAG 2 contrast ratio (1 to 21) of this color drawn over `background`,
+    /// `None` unless both are `#rrggbb` or `#rrggbbaa`
+    ///
+    /// Translucent colors are blended: `background` over white, then this color over it.
+    ///
+    /// ```rust
+    /// use fast_qr::convert::Color;
+    ///
+    /// let black = Color::from("#000000");
+    /// assert_eq!(black.contrast_ratio(&"#ffffff".into()), Some(21f64));
+    /// assert_eq!(black.contrast_ratio(&"white".into()), None);
+    /// ```
+    #[cfg(any(feature = "svg", feature = "raster"))]
+    #[must_use]
+    pub fn contrast_ratio(&self, background: &Color) -> Option<f64> {
+        let blend = |[r, g, b, a]: [u8; 4], under: [f64; 3]| {
+            let alpha = f64::from(a) / 255f64;
+            [
+                f64::from(r) / 255f64 * alpha + under[0] * (1f64 - alpha),
+                f64::from(g) / 255f64 * alpha + under[1] * (1f64 - alpha),
+                f64::from(b) / 255f64 * alpha + under[2] * (1f64 - alpha),
+            ]
+        };
+        // Relative luminance of sRGB channels between 0 and 1
+        let luminance = |rgb: [f64; 3]| {
+            let linear = rgb.map(|c| {
+                if c <= 0.040_45 {
+                    c / 12.92
+                } else {
+                    ((c + 0.055) / 1.055).powf(2.4)
+                }
+            });
+            0.2126 * linear[0] + 0.7152 * linear[1] + 0.0722 * linear[2]
+        };
+
+        let background = blend(background.to_rgba()?, [1f64; 3]);
+        let foreground = blend(self.to_rgba()?, background);
+        let (a, b) = (luminance(foreground), luminance(background));
+        Some((a.max(b) + 0.05) / (a.min(b) + 0.05))
+    }
+}
+
+impl core::str::FromStr for Color {
+    type Err = ColorError;
+
+    fn from_str(color: &str) -> Result<Self, Self::Err> {
+        Color::parse(color)
+    }
 }
 
 impl From<String> for Color {
@@ -313,6 +842,46 @@ impl From<Vec<u8>> for Color {
     }
 }
 
+/// Gradient filling the modules, stops are `(offset, color)` with an offset between 0 and 1
+/// # Example
+/// ```rust
+/// use fast_qr::convert::Gradient;
+/// let gradient = Gradient::linear(45f64, vec![(0f64, "#1e3a8a"), (1f64, "#9333ea")]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gradient {
+    /// Linear gradient, from left to right when `angle` is 0, rotated clockwise (in degrees)
+    Linear {
+        /// Rotation in degrees
+        angle: f64,
+        /// Offsets and colors
+        stops: Vec<(f64, Color)>,
+    },
+    /// Radial gradient, from the center to the edges
+    Radial {
+        /// Offsets and colors
+        stops: Vec<(f64, Color)>,
+    },
+}
+
+impl Gradient {
+    /// Creates a linear gradient, `angle` is in degrees
+    pub fn linear<C: Into<Color>>(angle: f64, stops: Vec<(f64, C)>) -> Self {
+        Gradient::Linear {
+            angle,
+            stops: stops.into_iter().map(|(o, c)| (o, c.into())).collect(),
+        }
+    }
+
+    /// Creates a radial gradient
+    pub fn radial<C: Into<Color>>(stops: Vec<(f64, C)>) -> Self {
+        Gradient::Radial {
+            stops: stops.into_iter().map(|(o, c)| (o, c.into())).collect(),
+        }
+    }
+}
+
 /// Trait for `SvgBuilder` and `ImageBuilder`
 pub trait Builder {
     /// Updates margin (default: 4)
@@ -321,25 +890,216 @@ pub trait Builder {
     fn module_color<C: Into<Color>>(&mut self, module_color: C) -> &mut Self;
     /// Updates background color (default: #FFFFFF)
     fn background_color<C: Into<Color>>(&mut self, background_color: C) -> &mut Self;
+    /// Updates module color from a CSS color, see [`Color::parse`]
+    ///
+    /// # Errors
+    /// - `ColorError` if `module_color` is not a valid color, the color is left as is
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "svg")]
+    /// # {
+    /// use fast_qr::convert::{svg::SvgBuilder, Builder};
+    ///
+    /// let mut builder = SvgBuilder::default();
+    /// builder.module_color_str("#1a2b3c").unwrap().background_color_str("ivory").unwrap();
+    ///
+    /// assert!(builder.module_color_str("#zzz").is_err());
+    /// # }
+    /// ```
+    fn module_color_str(&mut self, module_color: &str) -> Result<&mut Self, ColorError> {
+        Ok(self.module_color(Color::parse(module_color)?))
+    }
+    /// Updates background color from a CSS color, see [`Color::parse`]
+    ///
+    /// # Errors
+    /// - `ColorError` if `background_color` is not a valid color, the color is left as is
+    fn background_color_str(&mut self, background_color: &str) -> Result<&mut Self, ColorError> {
+        Ok(self.background_color(Color::parse(background_color)?))
+    }
+    /// Makes the background, margin included, transparent
+    fn transparent_background(&mut self) -> &mut Self {
+        self.background_color([0, 0, 0, 0])
+    }
+    /// Fills modules with a gradient instead of the module color, shapes with a specific color
+    /// are not affected
+    fn module_gradient(&mut self, gradient: Gradient) -> &mut Self;
+    /// Updates the color of the three finder patterns (default: module color)
+    fn finder_color<C: Into<Color>>(&mut self, finder_color: C) -> &mut Self;
+    /// Updates the color of the 7x7 frames of the finder patterns (default: finder color)
+    fn eye_frame_color<C: Into<Color>>(&mut self, color: C) -> &mut Self;
+    /// Updates the color of the 3x3 centers of the finder patterns (default: finder color)
+    fn eye_ball_color<C: Into<Color>>(&mut self, color: C) -> &mut Self;
+    /// Draws the 7x7 frames of the finder patterns as one shape each, instead of module by module
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "svg")]
+    /// # {
+    /// use fast_qr::convert::{svg::SvgBuilder, Builder, EyeShape};
+    ///
+    /// // Circle frames around square centers
+    /// let _builder = SvgBuilder::default()
+    ///     .eye_frame_shape(EyeShape::Circle)
+    ///     .eye_ball_shape(EyeShape::Square)
+    ///     .eye_ball_color("#e11d48");
+    /// # }
+    /// ```
+    fn eye_frame_shape(&mut self, shape: EyeShape) -> &mut Self;
+    /// Draws the 3x3 centers of the finder patterns as one shape each, instead of module by
+    /// module
+    fn eye_ball_shape(&mut self, shape: EyeShape) -> &mut Self;
     /// Adds a shape to the shapes list
+    ///
+    /// Every shape is drawn over each dark module, as its own layer (a `<path>` for svg), in
+    /// the order they were added.
     fn shape(&mut self, shape: Shape) -> &mut Self;
-    /// Add a shape to the shapes list with a specific color
+    /// Add a shape to the shapes list with a specific color, drawn as its own layer
     fn shape_color<C: Into<Color>>(&mut self, shape: Shape, color: C) -> &mut Self;
+    /// Adds a closure to the shapes list, returning the svg path of the module at (y, x)
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "svg")]
+    /// # {
+    /// use fast_qr::convert::{svg::SvgBuilder, Builder};
+    ///
+    /// let height = 0.5;
+    /// let _builder = SvgBuilder::default()
+    ///     .shape_fn(move |y, x, _module| format!("M{x},{y}h1v{height}h-1"));
+    /// # }
+    /// ```
+    fn shape_fn<F>(&mut self, shape: F) -> &mut Self
+    where
+        F: Fn(usize, usize, Module) -> String + Send + Sync + 'static;
+    /// Adds a shape used only for modules of `module_type`, replacing the shapes list for them
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "svg")]
+    /// # {
+    /// use fast_qr::convert::{svg::SvgBuilder, Builder, Shape};
+    /// use fast_qr::ModuleType;
+    ///
+    /// let _builder = SvgBuilder::default()
+    ///     .shape(Shape::Circle)
+    ///     .shape_for(ModuleType::FinderPattern, Shape::RoundedSquare);
+    /// # }
+    /// ```
+    fn shape_for(&mut self, module_type: ModuleType, shape: Shape) -> &mut Self;
+    /// Adds a function to the shapes list, receiving the dark [`Neighbors`] of the module
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "svg")]
+    /// # {
+    /// use fast_qr::convert::{svg::SvgBuilder, Builder, Neighbors};
+    ///
+    /// // Thinner modules when isolated horizontally
+    /// let _builder = SvgBuilder::default().shape_neighbors(|y, x, _module, neighbors| {
+    ///     if neighbors.0 & (Neighbors::LEFT | Neighbors::RIGHT) == 0 {
+    ///         format!("M{x}.2,{y}h.6v1h-.6")
+    ///     } else {
+    ///         format!("M{x},{y}h1v1h-1")
+    ///     }
+    /// });
+    /// # }
+    /// ```
+    fn shape_neighbors(&mut self, shape: NeighborFunction) -> &mut Self;
+    /// Merges adjacent dark modules into outlines instead of drawing each module, replacing the
+    /// shapes list. Outer corners are rounded by `corner_radius` (between 0 and 0.5 module)
+    fn merge_modules(&mut self, corner_radius: f64) -> &mut Self;
+    /// Updates the radius of [`Shape::Dot`] data modules, between 0 and 0.5 module (default: 0.4)
+    fn dot_radius(&mut self, radius: f64) -> &mut Self;
+    /// Shrinks every module around its center, leaving `gap` of its side empty, between 0 and 1
+    /// (default: 0)
+    ///
+    /// Applies to every shape drawn module by module, custom ones included. Merged modules and
+    /// the eye shapes are drawn whole, without gap.
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "svg")]
+    /// # {
+    /// use fast_qr::convert::{svg::SvgBuilder, Builder};
+    /// use fast_qr::QRBuilder;
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    /// // Squares of .9 module, centered in their cell
+    /// let svg = SvgBuilder::default().module_gap(0.1).to_str(&qrcode);
+    /// assert!(svg.contains("M4.05,4.05h.9v.9h-.9"));
+    /// # }
+    /// ```
+    fn module_gap(&mut self, gap: f64) -> &mut Self;
 
     // Manages the image part
 
     /// Provides the image path or an base64 encoded image
+    ///
+    /// The builder owns the string, e.g. a data URI built for each request, no need to leak it.
     fn image(&mut self, image: String) -> &mut Self;
+    /// Provides the image as raw bytes of the given `mime` type (`image/png`, `image/svg+xml`...),
+    /// embedded as a base64 `data:` URI
+    fn image_bytes(&mut self, bytes: &[u8], mime: &str) -> &mut Self {
+        self.image(format!("data:{mime};base64,{}", base64(bytes)))
+    }
     /// Updates the image background color (default: #FFFFFF)
     fn image_background_color<C: Into<Color>>(&mut self, image_background_color: C) -> &mut Self;
     /// Updates the image background shape (default: Square)
     fn image_background_shape(&mut self, image_background_shape: ImageBackgroundShape)
         -> &mut Self;
     /// Updates the image size and the gap between the image and the [`crate::QRCode`]
-    /// Default is around 30% of the [`crate::QRCode`] size
+    /// Default is around 30% of the [`crate::QRCode`] size, also used for negative or
+    /// non-finite sizes
     fn image_size(&mut self, image_size: f64) -> &mut Self;
-    /// Updates the gap between the image and the [`crate::QRCode`]
+    /// Updates the gap between the image and the [`crate::QRCode`], negative or non-finite
+    /// gaps keep the default
     fn image_gap(&mut self, gap: f64) -> &mut Self;
-    /// Updates the image position, anchor is the center of the image. Default is the center of the [`crate::QRCode`]
+    /// Updates the image position, anchor is the center of the image. Default is the center of the [`crate::QRCode`],
+    /// also used for non-finite coordinates
     fn image_position(&mut self, x: f64, y: f64) -> &mut Self;
+
+    // Manages the background picture
+
+    /// Provides a picture path or a base64 encoded picture, drawn under the modules and
+    /// covering the whole [`crate::QRCode`], margin included
+    fn background_image(&mut self, image: String) -> &mut Self;
+    /// Provides the background picture as raw bytes of the given `mime` type, embedded as a
+    /// base64 `data:` URI
+    fn background_image_bytes(&mut self, bytes: &[u8], mime: &str) -> &mut Self {
+        self.background_image(format!("data:{mime};base64,{}", base64(bytes)))
+    }
+    /// Updates the opacity of the background picture, between 0 and 1 (default: 1)
+    fn background_image_opacity(&mut self, opacity: f64) -> &mut Self;
+}
+
+#[cfg(feature = "svg")]
+/// Mime type of a PNG, JPEG or svg picture, `None` for other formats
+pub(crate) fn sniff_mime(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'<') {
+        Some("image/svg+xml")
+    } else {
+        None
+    }
+}
+
+/// Encodes `bytes` in base64 with padding, referring to RFC 4648 section 4
+pub(crate) fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0, |group, (i, &byte)| {
+            group | (byte as usize) << (16 - 8 * i)
+        });
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[group >> (18 - 6 * i) & 0x3F] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+
+    out
 }
@@ -0,0 +1,146 @@
+//! Converts [`QRCode`] to netpbm images (PBM & PGM), without any graphics dependency
+//!
+//! Thermal printers and point of sale systems often read these formats directly.
+//!
+//! ```rust
+//! use fast_qr::convert::netpbm::{NetpbmBuilder, NetpbmFormat};
+//! use fast_qr::qr::QRBuilder;
+//!
+//! // QRBuilde::new can fail if content is too big for version,
+//! // please check before unwrapping.
+//! let qrcode = QRBuilder::new("https://example.com/")
+//!     .build()
+//!     .unwrap();
+//!
+//! let pbm = NetpbmBuilder::default()
+//!     .format(NetpbmFormat::RawPbm)
+//!     .module_size(8)
+//!     .to_bytes(&qrcode);
+//! assert!(pbm.starts_with(b"P4\n264 264\n"));
+//! ```
+
+use std::io;
+
+use crate::QRCode;
+
+/// Encoding used by [`NetpbmBuilder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetpbmFormat {
+    /// `P1`, bitmap written as ASCII `1` (dark) and `0` (light)
+    PlainPbm,
+    /// `P4`, bitmap packed 8 pixels per byte, the default
+    RawPbm,
+    /// `P2`, grayscale written as ASCII numbers, 0 being dark
+    PlainPgm,
+    /// `P5`, grayscale with one byte per pixel, 0 being dark
+    RawPgm,
+}
+
+/// Builder for netpbm images, can set the format, margin and module size
+pub struct NetpbmBuilder {
+    /// The encoding, default is [`NetpbmFormat::RawPbm`]
+    format: NetpbmFormat,
+    /// The margin around the [`QRCode`] in modules, default is 4
+    margin: usize,
+    /// Side of a module in pixels, default is 1
+    module_size: usize,
+}
+
+/// Creates a `NetpbmBuilder` instance
+impl Default for NetpbmBuilder {
+    fn default() -> Self {
+        NetpbmBuilder {
+            format: NetpbmFormat::RawPbm,
+            margin: 4,
+            module_size: 1,
+        }
+    }
+}
+
+impl NetpbmBuilder {
+    /// Changes the encoding (default: [`NetpbmFormat::RawPbm`])
+    pub fn format(&mut self, format: NetpbmFormat) -> &mut Self {
+        self.format = format;
+        self
+    }
+
+    /// Updates margin in modules (default: 4)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Updates the side of a module in pixels (default: 1)
+    pub fn module_size(&mut self, module_size: usize) -> &mut Self {
+        self.module_size = module_size.max(1);
+        self
+    }
+
+    /// Returns whether the pixel at (`y`, `x`) is dark
+    fn is_dark(&self, qr: &QRCode, y: usize, x: usize) -> bool {
+        let (y, x) = (y / self.module_size, x / self.module_size);
+        match (y.checked_sub(self.margin), x.checked_sub(self.margin)) {
+            (Some(y), Some(x)) if y < qr.size && x < qr.size => qr[y][x].value(),
+            _ => false,
+        }
+    }
+
+    /// Returns the image in a byte buffer, encoded with [`NetpbmBuilder::format`]
+    pub fn to_bytes(&self, qr: &QRCode) -> Vec<u8> {
+        let side = (qr.size + self.margin * 2) * self.module_size;
+
+        let header = match self.format {
+            NetpbmFormat::PlainPbm => format!("P1\n{side} {side}\n"),
+            NetpbmFormat::RawPbm => format!("P4\n{side} {side}\n"),
+            NetpbmFormat::PlainPgm => format!("P2\n{side} {side}\n255\n"),
+            NetpbmFormat::RawPgm => format!("P5\n{side} {side}\n255\n"),
+        };
+        let mut out = Vec::with_capacity(header.len() + side * side * 2);
+        out.extend_from_slice(header.as_bytes());
+
+        for y in 0..side {
+            let row = (0..side).map(|x| self.is_dark(qr, y, x));
+
+            match self.format {
+                NetpbmFormat::PlainPbm => {
+                    // Plain lines should not be longer than 70 characters
+                    for (x, dark) in row.enumerate() {
+                        out.push(if dark { b'1' } else { b'0' });
+                        out.push(if (x + 1) % 35 == 0 { b'\n' } else { b' ' });
+                    }
+                    out.pop();
+                    out.push(b'\n');
+                }
+                // Rows are padded to a whole byte
+                NetpbmFormat::RawPbm => {
+                    let row = row.collect::<Vec<_>>();
+                    out.extend(row.chunks(8).map(|chunk| {
+                        chunk
+                            .iter()
+                            .enumerate()
+                            .fold(0u8, |byte, (i, &dark)| byte | u8::from(dark) << (7 - i))
+                    }));
+                }
+                NetpbmFormat::PlainPgm => {
+                    for (x, dark) in row.enumerate() {
+                        out.extend_from_slice(if dark { b"0" } else { b"255" });
+                        out.push(if (x + 1) % 17 == 0 { b'\n' } else { b' ' });
+                    }
+                    out.pop();
+                    out.push(b'\n');
+                }
+                NetpbmFormat::RawPgm => out.extend(row.map(|dark| if dark { 0 } else { 255 })),
+            }
+        }
+
+        out
+    }
+
+    /// Saves the image for a QRCode to a file, encoded with [`NetpbmBuilder::format`]
+    ///
+    /// # Errors
+    /// - `io::Error` if the file cannot be written
+    pub fn to_file(&self, qr: &QRCode, file: &str) -> io::Result<()> {
+        std::fs::write(file, self.to_bytes(qr))
+    }
+}
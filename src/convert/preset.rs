@@ -0,0 +1,69 @@
+//! Rendering configuration built once and shared between threads, see [`Preset`]
+//!
+//! ```rust
+//! use std::sync::Arc;
+//!
+//! use fast_qr::convert::preset::Preset;
+//! use fast_qr::convert::svg::SvgBuilder;
+//! use fast_qr::convert::{Builder, Shape};
+//! use fast_qr::QRBuilder;
+//!
+//! let mut brand = SvgBuilder::default();
+//! brand.shape(Shape::RoundedSquare).module_color("#1e3a8a");
+//! let preset = Arc::new(Preset::from(brand));
+//!
+//! let workers = (0..4)
+//!     .map(|id| {
+//!         let preset = Arc::clone(&preset);
+//!         std::thread::spawn(move || {
+//!             let qrcode = QRBuilder::new(format!("https://example.com/{id}")).build().unwrap();
+//!             SvgBuilder::from_preset(&preset).to_str(&qrcode)
+//!         })
+//!     })
+//!     .collect::<Vec<_>>();
+//!
+//! for worker in workers {
+//!     assert!(worker.join().unwrap().contains("#1e3a8a"));
+//! }
+//! ```
+
+#[cfg(feature = "image")]
+use super::image::ImageBuilder;
+use super::svg::SvgBuilder;
+
+/// Complete configuration of an [`SvgBuilder`], and of an [`ImageBuilder`] with the `image`
+/// feature, applied to new builders with [`SvgBuilder::from_preset`] and
+/// [`ImageBuilder::from_preset`]
+///
+/// Presets are `Send` and `Sync`: build them once, then keep them in a `static` (i.e. a
+/// `OnceLock`) or in the state of a web server. Closures given to [`Builder::shape_fn`] and
+/// [`SvgBuilder::module_color_fn`] are shared by every builder made from the preset.
+///
+/// [`Builder::shape_fn`]: super::Builder::shape_fn
+#[derive(Debug, Clone)]
+pub struct Preset {
+    pub(crate) svg: SvgBuilder,
+    /// Image options, its svg options are in `svg`
+    #[cfg(feature = "image")]
+    pub(crate) image: Option<ImageBuilder>,
+}
+
+impl From<SvgBuilder> for Preset {
+    fn from(svg: SvgBuilder) -> Self {
+        Preset {
+            svg,
+            #[cfg(feature = "image")]
+            image: None,
+        }
+    }
+}
+
+#[cfg(feature = "image")]
+impl From<ImageBuilder> for Preset {
+    fn from(mut image: ImageBuilder) -> Self {
+        Preset {
+            svg: core::mem::take(&mut image.svg_builder),
+            image: Some(image),
+        }
+    }
+}
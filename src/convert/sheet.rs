@@ -0,0 +1,419 @@
+//! Lays out many [`QRCode`]s in a grid, with an optional caption under each, to print badge
+//! or label sheets
+//!
+//! Lengths are in millimeters. The page is as large as the grid: `columns` cells wide, and
+//! `rows` cells high when set, the cells then continue on the next pages.
+//!
+//! ```rust
+//! use fast_qr::convert::sheet::SheetBuilder;
+//! use fast_qr::QRBuilder;
+//!
+//! let mut sheet = SheetBuilder::new(3);
+//! sheet.rows(4).cell_size(40f64).spacing(5f64);
+//! for id in 0..20 {
+//!     let qrcode = QRBuilder::new(format!("https://example.com/badge/{id}")).build().unwrap();
+//!     sheet.push_with_caption(qrcode, format!("Attendee #{id}"));
+//! }
+//!
+//! assert_eq!(sheet.pages(), 2);
+//! let _svg = sheet.to_svg(0);
+//! let _pdf = sheet.to_pdf();
+//! ```
+
+use core::fmt::Write;
+
+use crate::QRCode;
+
+#[cfg(feature = "image")]
+use super::image::ImageError;
+use super::svg::{coordinate, escape_xml, SvgBuilder};
+
+/// Points per millimeter, PDF lengths are in points
+const PT_PER_MM: f64 = 72f64 / 25.4;
+
+/// Grid of [`QRCode`]s drawn as svg, PNG (`image` feature) or PDF pages
+///
+/// Every cell is drawn with the same [`SvgBuilder`], see [`SheetBuilder::style`].
+pub struct SheetBuilder {
+    style: SvgBuilder,
+    cells: Vec<(QRCode, Option<String>)>,
+    columns: usize,
+    rows: Option<usize>,
+    cell_size: f64,
+    spacing: f64,
+    margin: f64,
+    caption_size: f64,
+}
+
+impl SheetBuilder {
+    /// Creates an empty sheet of `columns` cells per row (at least 1)
+    #[must_use]
+    pub fn new(columns: usize) -> Self {
+        SheetBuilder {
+            style: SvgBuilder::default(),
+            cells: Vec::new(),
+            columns: columns.max(1),
+            rows: None,
+            cell_size: 40f64,
+            spacing: 5f64,
+            margin: 10f64,
+            caption_size: 4f64,
+        }
+    }
+
+    /// Limits the rows per page, further cells are laid out on the next pages
+    /// (default: a single page)
+    pub fn rows(&mut self, rows: usize) -> &mut Self {
+        self.rows = Some(rows.max(1));
+        self
+    }
+
+    /// Updates the side of each qr code in millimeters (default: 40)
+    pub fn cell_size(&mut self, cell_size: f64) -> &mut Self {
+        self.cell_size = cell_size.max(0f64);
+        self
+    }
+
+    /// Updates the space between cells in millimeters (default: 5)
+    pub fn spacing(&mut self, spacing: f64) -> &mut Self {
+        self.spacing = spacing.max(0f64);
+        self
+    }
+
+    /// Updates the space around the grid in millimeters (default: 10)
+    pub fn margin(&mut self, margin: f64) -> &mut Self {
+        self.margin = margin.max(0f64);
+        self
+    }
+
+    /// Updates the font size of the captions in millimeters (default: 4)
+    pub fn caption_size(&mut self, caption_size: f64) -> &mut Self {
+        self.caption_size = caption_size.max(0f64);
+        self
+    }
+
+    /// Draws every cell with `style` (default: `SvgBuilder::default()`)
+    ///
+    /// PDF pages only use its margin and colors, modules are drawn as squares.
+    pub fn style(&mut self, style: SvgBuilder) -> &mut Self {
+        self.style = style;
+        self
+    }
+
+    /// Adds a cell without caption
+    pub fn push(&mut self, qr: QRCode) -> &mut Self {
+        self.cells.push((qr, None));
+        self
+    }
+
+    /// Adds a cell with `caption` written under the qr code
+    pub fn push_with_caption<S: Into<String>>(&mut self, qr: QRCode, caption: S) -> &mut Self {
+        self.cells.push((qr, Some(caption.into())));
+        self
+    }
+
+    /// Returns the number of pages, at least 1
+    #[must_use]
+    pub fn pages(&self) -> usize {
+        let per_page = self.per_page();
+        ((self.cells.len() + per_page - 1) / per_page).max(1)
+    }
+
+    /// Cells on each page
+    fn per_page(&self) -> usize {
+        let rows = self
+            .rows
+            .unwrap_or_else(|| (self.cells.len() + self.columns - 1) / self.columns);
+        self.columns * rows.max(1)
+    }
+
+    /// Height of the captions, 0 without caption
+    fn caption_height(&self) -> f64 {
+        if self.cells.iter().any(|(_, caption)| caption.is_some()) {
+            self.caption_size * 1.5
+        } else {
+            0f64
+        }
+    }
+
+    /// Returns the width & height of each page in millimeters
+    #[must_use]
+    pub fn page_size(&self) -> (f64, f64) {
+        let rows = self.per_page() / self.columns;
+        let side = |count: usize, cell: f64| {
+            self.margin * 2f64 + count as f64 * cell + count.saturating_sub(1) as f64 * self.spacing
+        };
+
+        (
+            side(self.columns, self.cell_size),
+            side(rows, self.cell_size + self.caption_height()),
+        )
+    }
+
+    /// Cells of `page`, with the top left corner of each in millimeters
+    fn page_cells(
+        &self,
+        page: usize,
+    ) -> impl Iterator<Item = (f64, f64, &(QRCode, Option<String>))> {
+        let per_page = self.per_page();
+        let row_height = self.cell_size + self.caption_height() + self.spacing;
+
+        self.cells
+            .iter()
+            .skip(page * per_page)
+            .take(per_page)
+            .enumerate()
+            .map(move |(i, cell)| {
+                let x = self.margin + (i % self.columns) as f64 * (self.cell_size + self.spacing);
+                let y = self.margin + (i / self.columns) as f64 * row_height;
+                (x, y, cell)
+            })
+    }
+
+    /// Returns the svg of `page`, starting at 0, empty past the last page
+    #[must_use]
+    pub fn to_svg(&self, page: usize) -> String {
+        let (width, height) = self.page_size();
+        let (width, height) = (coordinate(width), coordinate(height));
+
+        let mut out = format!(
+            r#"<svg viewBox="0 0 {width} {height}" width="{width}mm" height="{height}mm" xmlns="http://www.w3.org/2000/svg"><rect width="{width}" height="{height}" fill="{}"/>"#,
+            self.style.background_color.to_str()
+        );
+
+        let size = coordinate(self.cell_size);
+        for (x, y, (qr, caption)) in self.page_cells(page) {
+            // Nested svg, scaled to the cell
+            let cell = self.style.to_str_sized(qr, false);
+            let _ = write!(
+                out,
+                r#"<svg x="{}" y="{}" width="{size}" height="{size}" {}"#,
+                coordinate(x),
+                coordinate(y),
+                cell.trim_start_matches("<svg ")
+            );
+
+            if let Some(caption) = caption {
+                let _ = write!(
+                    out,
+                    r#"<text x="{}" y="{}" font-size="{}" font-family="sans-serif" text-anchor="middle" fill="{}">{}</text>"#,
+                    coordinate(x + self.cell_size / 2f64),
+                    coordinate(y + self.cell_size + self.caption_size * 1.2),
+                    coordinate(self.caption_size),
+                    self.style.dot_color.to_str(),
+                    escape_xml(caption)
+                );
+            }
+        }
+
+        out.push_str("</svg>");
+        out
+    }
+
+    /// Returns the PNG of `page`, `width` pixels wide
+    ///
+    /// # Errors
+    /// - `ImageError::ImageError` if the svg cannot be parsed or rendered
+    /// - `ImageError::EncodingError` if the PNG cannot be encoded
+    #[cfg(feature = "image")]
+    pub fn to_png(&self, page: usize, width: u32) -> Result<Vec<u8>, ImageError> {
+        use resvg::{tiny_skia, usvg};
+
+        let opt = usvg::Options::default();
+        let tree = super::image::parse_svg(&self.to_svg(page), &opt, true)?;
+
+        let (page_width, page_height) = self.page_size();
+        let height = (f64::from(width) * page_height / page_width).round() as u32;
+        let mut pixmap = tiny_skia::Pixmap::new(width, height)
+            .ok_or_else(|| ImageError::ImageError("Failed to create pixmap".to_string()))?;
+        resvg::render(
+            &tree,
+            usvg::FitTo::Width(width),
+            tiny_skia::Transform::default(),
+            pixmap.as_mut(),
+        )
+        .ok_or_else(|| ImageError::ImageError("Failed to render SVG".to_string()))?;
+
+        pixmap
+            .encode_png()
+            .map_err(|err| ImageError::EncodingError(err.to_string()))
+    }
+
+    /// Returns a PDF document with every page
+    ///
+    /// Modules are drawn as squares with the margin and `#rrggbb` colors of the style (black on
+    /// white otherwise), captions in Helvetica.
+    #[must_use]
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let (width, height) = self.page_size();
+        let (width, height) = (width * PT_PER_MM, height * PT_PER_MM);
+        let pages = self.pages();
+
+        // Catalog, page tree and font, then each page and its content
+        let mut objects = vec![
+            String::from("<< /Type /Catalog /Pages 2 0 R >>"),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {pages} >>",
+                (0..pages)
+                    .map(|page| format!("{} 0 R", 4 + page * 2))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            String::from(
+                "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>",
+            ),
+        ];
+        for page in 0..pages {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                coordinate(width),
+                coordinate(height),
+                5 + page * 2
+            ));
+            let content = self.pdf_content(page, height);
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{content}\nendstream",
+                content.len()
+            ));
+        }
+
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            let _ = write!(pdf, "{} 0 obj\n{object}\nendobj\n", i + 1);
+        }
+
+        let xref = pdf.len();
+        let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(pdf, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            pdf,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        );
+
+        pdf.into_bytes()
+    }
+
+    /// Content stream of `page`, `height` in points flips the y axis
+    fn pdf_content(&self, page: usize, height: f64) -> String {
+        let rgb = |color: &super::Color, default: [u8; 4]| {
+            let [r, g, b, _] = color.to_rgba().unwrap_or(default);
+            let channel = |c: u8| coordinate(f64::from(c) / 255f64);
+            format!("{} {} {} rg\n", channel(r), channel(g), channel(b))
+        };
+        let dark = rgb(&self.style.dot_color, [0, 0, 0, 255]);
+        let light = rgb(&self.style.background_color, [255; 4]);
+        let point = |mm: f64| coordinate(mm * PT_PER_MM);
+
+        let mut out = String::new();
+        for (x, y, (qr, caption)) in self.page_cells(page) {
+            let margin = self.style.margin;
+            let side = margin.width(qr.size).max(margin.height(qr.size));
+            let module = self.cell_size / side as f64;
+            let origin = (
+                x + margin.left as f64 * module,
+                y + margin.top as f64 * module,
+            );
+
+            out.push_str(&light);
+            let bottom = height - (y + self.cell_size) * PT_PER_MM;
+            let _ = writeln!(
+                out,
+                "{} {} {} {} re f",
+                point(x),
+                coordinate(bottom),
+                point(self.cell_size),
+                point(self.cell_size)
+            );
+
+            // Runs of dark modules on each row
+            out.push_str(&dark);
+            for (row, modules) in qr.rows().enumerate() {
+                let mut column = 0;
+                while column < qr.size {
+                    if !modules[column].value() {
+                        column += 1;
+                        continue;
+                    }
+                    let start = column;
+                    while column < qr.size && modules[column].value() {
+                        column += 1;
+                    }
+
+                    let top = origin.1 + (row + 1) as f64 * module;
+                    let _ = write!(
+                        out,
+                        "{} {} {} {} re ",
+                        point(origin.0 + start as f64 * module),
+                        coordinate(height - top * PT_PER_MM),
+                        point((column - start) as f64 * module),
+                        point(module)
+                    );
+                }
+            }
+            out.push_str("f\n");
+
+            if let Some(caption) = caption {
+                let (text, text_width) = win_ansi(caption);
+                let size = self.caption_size * PT_PER_MM;
+                let left = (x + self.cell_size / 2f64) * PT_PER_MM - text_width * size / 2f64;
+                let baseline = y + self.cell_size + self.caption_size * 1.2;
+                let _ = writeln!(
+                    out,
+                    "BT /F1 {} Tf {} {} Td ({text}) Tj ET",
+                    coordinate(size),
+                    coordinate(left),
+                    coordinate(height - baseline * PT_PER_MM)
+                );
+            }
+        }
+
+        out
+    }
+}
+
+/// Encodes `text` as a PDF string in WinAnsi, with its width in Helvetica for a font size of 1
+///
+/// Characters outside of Latin-1 are replaced by `?`.
+fn win_ansi(text: &str) -> (String, f64) {
+    /// Widths of the ASCII characters from space to `~`, in thousandths of the font size
+    #[rustfmt::skip]
+    const HELVETICA: [u16; 95] = [
+        278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+        556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+        1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+        667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+        333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+        556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+    ];
+
+    let mut out = String::with_capacity(text.len());
+    let mut width = 0u32;
+    for c in text.chars() {
+        let c = match c {
+            ' '..='~' | '\u{a0}'..='\u{ff}' => c,
+            _ => '?',
+        };
+        width += HELVETICA
+            .get((c as usize).wrapping_sub(' ' as usize))
+            .map_or(556, |&width| u32::from(width));
+
+        match c {
+            '(' | ')' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            // Octal escape, the content stream stays ASCII
+            _ => {
+                let _ = write!(out, "\\{:03o}", c as u32);
+            }
+        }
+    }
+
+    (out, f64::from(width) / 1000f64)
+}
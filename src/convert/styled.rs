@@ -0,0 +1,255 @@
+//! One builder to encode and draw a [`QRCode`], for the common case of a styled svg or PNG
+//!
+//! [`StyledQr`] holds the [`QRBuilder`] options and the [`Builder`] style, and makes sure an
+//! embedded logo still decodes (see [`SvgBuilder::build_qr`]).
+//!
+//! ```rust
+//! use fast_qr::convert::{styled::StyledQr, Builder, Shape};
+//! use fast_qr::ECL;
+//!
+//! let svg = StyledQr::new("https://example.com/")
+//!     .ecl(ECL::H)
+//!     .shape(Shape::Circle)
+//!     .module_color("#1e3a8a")
+//!     .to_svg()
+//!     .unwrap();
+//! assert!(svg.starts_with("<svg"));
+//! ```
+
+#[cfg(feature = "image")]
+use super::image::ImageBuilder;
+use super::svg::SvgBuilder;
+use super::{
+    Builder, Color, ConvertError, EyeShape, Gradient, ImageBackgroundShape, NeighborFunction, Shape,
+};
+use crate::{Mask, Module, ModuleType, QRBuilder, QRCode, Version, ECL};
+
+/// Draws with resvg when the `image` feature is enabled, to also rasterize
+#[cfg(feature = "image")]
+type Renderer = ImageBuilder;
+#[cfg(not(feature = "image"))]
+type Renderer = SvgBuilder;
+
+/// Builder for a styled `QRCode`, contains a [`QRBuilder`] for the content and the style
+/// options of [`Builder`]
+pub struct StyledQr {
+    qr_builder: QRBuilder,
+    renderer: Renderer,
+}
+
+impl StyledQr {
+    /// Creates a `StyledQr` for `input`, with the default [`QRBuilder`] and style
+    #[must_use]
+    pub fn new<I: Into<Vec<u8>>>(input: I) -> Self {
+        StyledQr {
+            qr_builder: QRBuilder::new(input),
+            renderer: Renderer::default(),
+        }
+    }
+
+    /// Forces the Encoding Level, see [`QRBuilder::ecl`]
+    ///
+    /// Without it, the ECL is raised to `ECL::H` when a logo is embedded.
+    pub fn ecl(&mut self, ecl: ECL) -> &mut Self {
+        self.qr_builder.ecl(ecl);
+        self
+    }
+
+    /// Forces the version, see [`QRBuilder::version`]
+    pub fn version(&mut self, version: Version) -> &mut Self {
+        self.qr_builder.version(version);
+        self
+    }
+
+    /// Forces the mask, see [`QRBuilder::mask`]
+    pub fn mask(&mut self, mask: Mask) -> &mut Self {
+        self.qr_builder.mask(mask);
+        self
+    }
+
+    /// Embeds a PNG, JPEG or svg logo in the center, from its raw bytes
+    pub fn logo(&mut self, bytes: &[u8]) -> &mut Self {
+        let mime = super::sniff_mime(bytes).unwrap_or("image/png");
+        self.image_bytes(bytes, mime)
+    }
+
+    /// Gives access to the other [`QRBuilder`] options
+    pub fn qr_builder(&mut self) -> &mut QRBuilder {
+        &mut self.qr_builder
+    }
+
+    fn svg_builder(&self) -> &SvgBuilder {
+        #[cfg(feature = "image")]
+        return &self.renderer.svg_builder;
+        #[cfg(not(feature = "image"))]
+        return &self.renderer;
+    }
+
+    /// Builds the [`QRCode`], with `ECL::H` for a logo unless forced
+    ///
+    /// # Errors
+    /// - `ConvertError::Svg` if the content does not fit, or if the logo hides more modules
+    ///   than the ECL can recover
+    pub fn build(&self) -> Result<QRCode, ConvertError> {
+        Ok(self.svg_builder().build_qr(&self.qr_builder)?)
+    }
+
+    /// Builds and draws the `QRCode` as an svg
+    ///
+    /// # Errors
+    /// - Same as [`StyledQr::build`]
+    pub fn to_svg(&self) -> Result<String, ConvertError> {
+        let qr = self.build()?;
+        Ok(self.svg_builder().to_str(&qr))
+    }
+
+    /// Builds and draws the `QRCode` as a `width` pixels wide PNG
+    ///
+    /// # Errors
+    /// - Same as [`StyledQr::build`]
+    /// - `ConvertError::Image` if the svg could not be rasterized or encoded
+    #[cfg(feature = "image")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "image")))]
+    pub fn to_png(&mut self, width: u32) -> Result<Vec<u8>, ConvertError> {
+        let qr = self.build()?;
+        let bytes = self
+            .renderer
+            .fit_width(width)
+            .format(super::image::ImageFormat::Png)
+            .to_bytes(&qr)?;
+        Ok(bytes)
+    }
+}
+
+impl Builder for StyledQr {
+    fn margin(&mut self, margin: usize) -> &mut Self {
+        self.renderer.margin(margin);
+        self
+    }
+
+    fn module_color<C: Into<Color>>(&mut self, module_color: C) -> &mut Self {
+        self.renderer.module_color(module_color);
+        self
+    }
+
+    fn background_color<C: Into<Color>>(&mut self, background_color: C) -> &mut Self {
+        self.renderer.background_color(background_color);
+        self
+    }
+
+    fn module_gradient(&mut self, gradient: Gradient) -> &mut Self {
+        self.renderer.module_gradient(gradient);
+        self
+    }
+
+    fn finder_color<C: Into<Color>>(&mut self, finder_color: C) -> &mut Self {
+        self.renderer.finder_color(finder_color);
+        self
+    }
+
+    fn eye_frame_color<C: Into<Color>>(&mut self, color: C) -> &mut Self {
+        self.renderer.eye_frame_color(color);
+        self
+    }
+
+    fn eye_ball_color<C: Into<Color>>(&mut self, color: C) -> &mut Self {
+        self.renderer.eye_ball_color(color);
+        self
+    }
+
+    fn eye_frame_shape(&mut self, shape: EyeShape) -> &mut Self {
+        self.renderer.eye_frame_shape(shape);
+        self
+    }
+
+    fn eye_ball_shape(&mut self, shape: EyeShape) -> &mut Self {
+        self.renderer.eye_ball_shape(shape);
+        self
+    }
+
+    fn shape(&mut self, shape: Shape) -> &mut Self {
+        self.renderer.shape(shape);
+        self
+    }
+
+    fn shape_fn<F>(&mut self, shape: F) -> &mut Self
+    where
+        F: Fn(usize, usize, Module) -> String + Send + Sync + 'static,
+    {
+        self.renderer.shape_fn(shape);
+        self
+    }
+
+    fn dot_radius(&mut self, radius: f64) -> &mut Self {
+        self.renderer.dot_radius(radius);
+        self
+    }
+
+    fn module_gap(&mut self, gap: f64) -> &mut Self {
+        self.renderer.module_gap(gap);
+        self
+    }
+
+    fn shape_neighbors(&mut self, shape: NeighborFunction) -> &mut Self {
+        self.renderer.shape_neighbors(shape);
+        self
+    }
+
+    fn shape_for(&mut self, module_type: ModuleType, shape: Shape) -> &mut Self {
+        self.renderer.shape_for(module_type, shape);
+        self
+    }
+
+    fn merge_modules(&mut self, corner_radius: f64) -> &mut Self {
+        self.renderer.merge_modules(corner_radius);
+        self
+    }
+
+    fn image(&mut self, image: String) -> &mut Self {
+        self.renderer.image(image);
+        self
+    }
+
+    fn image_background_color<C: Into<Color>>(&mut self, image_background_color: C) -> &mut Self {
+        self.renderer.image_background_color(image_background_color);
+        self
+    }
+
+    fn image_background_shape(
+        &mut self,
+        image_background_shape: ImageBackgroundShape,
+    ) -> &mut Self {
+        self.renderer.image_background_shape(image_background_shape);
+        self
+    }
+
+    fn image_size(&mut self, image_size: f64) -> &mut Self {
+        self.renderer.image_size(image_size);
+        self
+    }
+
+    fn image_gap(&mut self, gap: f64) -> &mut Self {
+        self.renderer.image_gap(gap);
+        self
+    }
+
+    fn image_position(&mut self, x: f64, y: f64) -> &mut Self {
+        self.renderer.image_position(x, y);
+        self
+    }
+
+    fn background_image(&mut self, image: String) -> &mut Self {
+        self.renderer.background_image(image);
+        self
+    }
+
+    fn background_image_opacity(&mut self, opacity: f64) -> &mut Self {
+        self.renderer.background_image_opacity(opacity);
+        self
+    }
+
+    fn shape_color<C: Into<Color>>(&mut self, shape: Shape, color: C) -> &mut Self {
+        self.renderer.shape_color(shape, color);
+        self
+    }
+}
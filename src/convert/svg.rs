@@ -21,29 +21,124 @@
 //! # }
 //! ```
 
-use crate::{QRCode, Version};
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
 
-use super::{Builder, Color, ImageBackgroundShape, ModuleFunction, Shape};
+use std::sync::Arc;
+
+use crate::{Module, ModuleType, QRBuilder, QRCode, Version, ECL};
+
+use super::preset::Preset;
+use super::{
+    Builder, Color, EyeShape, Gradient, ImageBackgroundShape, ModuleCommand, ModuleFunction,
+    NeighborFunction, Neighbors, Shape,
+};
+
+/// Computes the color of the module at (`x`, `y`), see [`SvgBuilder::module_color_fn`]
+pub type ModuleColorFunction = Arc<dyn Fn(usize, usize, Module) -> [u8; 4] + Send + Sync>;
+
+/// Shape of the modules of a type, shown as the shape name in `Debug`
+#[derive(Clone, Copy)]
+pub(crate) struct RoleShape(ModuleFunction);
+
+impl core::ops::Deref for RoleShape {
+    type Target = ModuleFunction;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::fmt::Debug for RoleShape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        ModuleCommand::Function(self.0).fmt(f)
+    }
+}
+
+/// [`ModuleColorFunction`] shown as `Custom` in `Debug`
+#[derive(Clone)]
+struct ModuleColorFn(ModuleColorFunction);
+
+impl core::ops::Deref for ModuleColorFn {
+    type Target = ModuleColorFunction;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::fmt::Debug for ModuleColorFn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Custom")
+    }
+}
+
+/// Version of the svg output, bumped whenever [`SvgBuilder`] draws the same input differently
+const RENDER_VERSION: u32 = 1;
+
+/// Returns the version of the svg output
+///
+/// The same `QRCode` and options give byte-identical svgs on every platform: numbers are
+/// rounded before being formatted and attributes are written in a fixed order. The output only
+/// changes along with this version, svgs cached or stored on a CDN can be keyed on it.
+///
+/// ```rust
+/// use fast_qr::convert::svg::render_version;
+///
+/// let cache_key = format!("qr/v{}/{}", render_version(), "https://example.com/");
+/// ```
+#[must_use]
+pub const fn render_version() -> u32 {
+    RENDER_VERSION
+}
 
 /// Builder for svg, can set shape, margin, background_color, dot_color
+///
+/// Builders are `Send` and `Sync`, they can be kept in the state of a web server and cloned
+/// for each request. Clones share the closures given to [`Builder::shape_fn`] and
+/// [`SvgBuilder::module_color_fn`].
+#[derive(Debug, Clone)]
 pub struct SvgBuilder {
     /// Command vector allows predefined or custom shapes
-    /// The default is square, commands can be added using `.shape()`
-    commands: Vec<ModuleFunction>,
+    /// The default is square, commands can be added using `.shape()` or `.shape_fn()`
+    pub(crate) commands: Vec<ModuleCommand>,
     /// Commands can also have a custom color
     /// The default is `dot_color`, commands with specific colors can be
     /// added using `.shape_color()`
-    command_colors: Vec<Option<Color>>,
-    /// The margin for the svg, default is 4
-    margin: usize,
+    pub(crate) command_colors: Vec<Option<Color>>,
+    /// Shapes replacing `commands` for modules of a specific type, added using `.shape_for()`
+    pub(crate) role_commands: Vec<(ModuleType, RoleShape)>,
+    /// Merges adjacent modules into outlines with this corner radius, replacing `commands`
+    pub(crate) merge_radius: Option<f64>,
+    /// Radius of the data modules drawn with `Shape::Dot`, default is 0.4
+    pub(crate) dot_radius: f64,
+    /// Share of each module left empty around its shape, default is 0
+    pub(crate) module_gap: f64,
+    /// The margin for the svg, default is 4 on each side
+    pub(crate) margin: Margin,
     /// The background color for the svg, default is #FFFFFF
-    background_color: Color,
+    pub(crate) background_color: Color,
     /// The color for each module, default is #000000
-    dot_color: Color,
+    pub(crate) dot_color: Color,
+    /// The gradient for each module, replaces `dot_color` when set
+    pub(crate) module_gradient: Option<Gradient>,
+    /// The color of the finder patterns, default is the module color
+    pub(crate) finder_color: Option<Color>,
+    /// Color of the 7x7 frames of the finder patterns, default is `finder_color`
+    pub(crate) eye_frame_color: Option<Color>,
+    /// Color of the 3x3 centers of the finder patterns, default is `finder_color`
+    pub(crate) eye_ball_color: Option<Color>,
+    /// Draws the finder pattern frames as a whole, default is the shapes list
+    pub(crate) eye_frame_shape: Option<EyeShape>,
+    /// Draws the finder pattern centers as a whole, default is the shapes list
+    pub(crate) eye_ball_shape: Option<EyeShape>,
+    /// Computes the color of each module, replaces `dot_color`, `module_gradient` and the
+    /// colors of `shape_color` when set
+    module_color_fn: Option<ModuleColorFn>,
 
     // Image Embedding
     /// Image to embed in the svg, can be a path or a base64 string
-    image: Option<String>,
+    pub(crate) image: Option<String>,
     /// Background color for the image, default is #FFFFFF
     image_background_color: Color,
     /// Background shape for the image, default is square
@@ -54,6 +149,180 @@ pub struct SvgBuilder {
     image_gap: Option<f64>,
     /// Position of the image, default is center
     image_position: Option<(f64, f64)>,
+    /// Text drawn instead of the image, with its font size (in module size) and color
+    pub(crate) center_text: Option<(String, f64, Color)>,
+
+    /// Picture drawn under the modules, can be a path or a base64 string
+    pub(crate) background_image: Option<String>,
+    /// Opacity of the background picture, default is 1
+    pub(crate) background_image_opacity: f64,
+
+    /// Width & height attributes of the svg, default is none: only the viewBox is set
+    size: Option<(f64, SvgUnit)>,
+    /// Corner radius of the background (in module size), default is 0
+    pub(crate) background_radius: f64,
+    /// Merges square modules into runs drawn with relative moves, default is false
+    minify: bool,
+    /// Draws each module as a `<use>` of its shape, defined once, default is false
+    use_symbols: bool,
+    /// Maximum number of decimals of the coordinates, default is none: 3 without leading zero
+    precision: Option<usize>,
+    /// User units per module, default is 1
+    pub(crate) scale: usize,
+    /// Draws the Swiss cross of the Swiss QR-bill in the center, default is false
+    swiss_cross: bool,
+    /// Border around the qr code and its label, default is none
+    pub(crate) frame: Option<FrameStyle>,
+    /// Text, font family and position of the banner, default is none
+    pub(crate) label: Option<(String, String, LabelPosition)>,
+    /// Decoration of the margin outside of the quiet zone, default is plain
+    pub(crate) margin_style: MarginStyle,
+    /// Lowest contrast ratio between the module and background colors, default is none
+    min_contrast: Option<f64>,
+    /// Swaps dark and light modules, margin included, default is false
+    pub(crate) invert: bool,
+}
+
+/// Part of a finder pattern
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EyePart {
+    /// 7x7 frame
+    Frame,
+    /// 3x3 center
+    Ball,
+}
+
+/// Finder pattern modules drawn with their own color
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EyeGroup {
+    /// `finder_color`
+    Finder,
+    /// `eye_frame_color`
+    Frame,
+    /// `eye_ball_color`
+    Ball,
+}
+
+impl EyeGroup {
+    const ALL: [EyeGroup; 3] = [EyeGroup::Finder, EyeGroup::Frame, EyeGroup::Ball];
+}
+
+/// Width of the frame, in modules
+const FRAME_WIDTH: usize = 1;
+/// Outer corner radius of [`FrameStyle::Rounded`], in modules
+const FRAME_RADIUS: f64 = 2f64;
+/// Height of the label banner, in modules
+const LABEL_HEIGHT: usize = 4;
+/// Font size of the label, in modules
+const LABEL_FONT_SIZE: f64 = 2.5;
+/// Font size of the margin caption, relative to the band width
+const CAPTION_FONT_SIZE: f64 = 0.6;
+/// Highlight of the modules dark in the drawn qr code and light in the other one, see
+/// [`SvgBuilder::to_diff_str`]
+const DIFF_DARK_COLOR: &str = "#dc2626";
+/// Highlight of the modules light in the drawn qr code and dark in the other one
+const DIFF_LIGHT_COLOR: &str = "#16a34a";
+
+/// Border drawn around the qr code and its label, see [`SvgBuilder::frame`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrameStyle {
+    /// Square corners
+    Square,
+    /// Rounded corners
+    Rounded,
+}
+
+/// Light modules around the `QRCode` on each side, see [`SvgBuilder::margins`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Margin {
+    pub(crate) top: usize,
+    pub(crate) right: usize,
+    pub(crate) bottom: usize,
+    pub(crate) left: usize,
+}
+
+impl Margin {
+    /// Same margin on each side
+    pub(crate) const fn uniform(margin: usize) -> Self {
+        Margin {
+            top: margin,
+            right: margin,
+            bottom: margin,
+            left: margin,
+        }
+    }
+
+    /// Width of a `QRCode` of `n` modules with its margin
+    pub(crate) const fn width(self, n: usize) -> usize {
+        self.left + n + self.right
+    }
+
+    /// Height of a `QRCode` of `n` modules with its margin
+    pub(crate) const fn height(self, n: usize) -> usize {
+        self.top + n + self.bottom
+    }
+
+    /// Offset of module (`y`, `x`) in the svg
+    pub(crate) const fn offset(self, y: usize, x: usize) -> (usize, usize) {
+        (y + self.top, x + self.left)
+    }
+}
+
+/// Decoration of the margin, see [`SvgBuilder::margin_style`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MarginStyle {
+    /// Background color only
+    Plain,
+    /// Band of `color` in the margin outside of the quiet zone, with `caption` (i.e. a URL)
+    /// centered in its bottom side, in the background color
+    Band {
+        /// Color of the band
+        color: Color,
+        /// Text written in the bottom side of the band
+        caption: Option<String>,
+    },
+}
+
+/// Side of the qr code where the label is drawn, see [`SvgBuilder::label`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LabelPosition {
+    /// Above the qr code
+    Top,
+    /// Below the qr code
+    Bottom,
+}
+
+/// Unit of the width & height of the svg, see [`SvgBuilder::size`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SvgUnit {
+    /// Pixels, 96 per inch
+    Px,
+    /// Millimeters
+    Mm,
+    /// Centimeters
+    Cm,
+    /// Inches
+    In,
+    /// Points, 72 per inch
+    Pt,
+}
+
+impl SvgUnit {
+    /// Returns the CSS suffix of the unit
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            SvgUnit::Px => "px",
+            SvgUnit::Mm => "mm",
+            SvgUnit::Cm => "cm",
+            SvgUnit::In => "in",
+            SvgUnit::Pt => "pt",
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -66,15 +335,46 @@ pub enum SvgError {
     SvgError(String),
 }
 
+impl std::error::Error for SvgError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            #[cfg(not(feature = "wasm-bindgen"))]
+            SvgError::IoError(io_err) => Some(io_err),
+            SvgError::SvgError(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for SvgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(not(feature = "wasm-bindgen"))]
+            SvgError::IoError(io_err) => write!(f, "Failed to write svg: {io_err}"),
+            SvgError::SvgError(error) => f.write_str(error),
+        }
+    }
+}
+
 /// Creates a Builder instance
 impl Default for SvgBuilder {
     fn default() -> Self {
         SvgBuilder {
             background_color: [255; 4].into(),
             dot_color: [0, 0, 0, 255].into(),
-            margin: 4,
+            module_gradient: None,
+            finder_color: None,
+            eye_frame_color: None,
+            eye_ball_color: None,
+            eye_frame_shape: None,
+            eye_ball_shape: None,
+            module_color_fn: None,
+            margin: Margin::uniform(4),
             commands: Vec::new(),
             command_colors: Vec::new(),
+            role_commands: Vec::new(),
+            merge_radius: None,
+            dot_radius: Shape::DOT_RADIUS,
+            module_gap: 0f64,
 
             // Image Embedding
             image: None,
@@ -83,13 +383,30 @@ impl Default for SvgBuilder {
             image_size: None,
             image_gap: None,
             image_position: None,
+            center_text: None,
+
+            background_image: None,
+            background_image_opacity: 1f64,
+
+            size: None,
+            background_radius: 0f64,
+            minify: false,
+            use_symbols: false,
+            swiss_cross: false,
+            min_contrast: None,
+            invert: false,
+            frame: None,
+            label: None,
+            margin_style: MarginStyle::Plain,
+            precision: None,
+            scale: 1,
         }
     }
 }
 
 impl Builder for SvgBuilder {
     fn margin(&mut self, margin: usize) -> &mut Self {
-        self.margin = margin;
+        self.margin = Margin::uniform(margin);
         self
     }
 
@@ -103,20 +420,86 @@ impl Builder for SvgBuilder {
         self
     }
 
+    fn module_gradient(&mut self, gradient: Gradient) -> &mut Self {
+        self.module_gradient = Some(gradient);
+        self
+    }
+
+    fn finder_color<C: Into<Color>>(&mut self, finder_color: C) -> &mut Self {
+        self.finder_color = Some(finder_color.into());
+        self
+    }
+
+    fn eye_frame_color<C: Into<Color>>(&mut self, color: C) -> &mut Self {
+        self.eye_frame_color = Some(color.into());
+        self
+    }
+
+    fn eye_ball_color<C: Into<Color>>(&mut self, color: C) -> &mut Self {
+        self.eye_ball_color = Some(color.into());
+        self
+    }
+
+    fn eye_frame_shape(&mut self, shape: EyeShape) -> &mut Self {
+        self.eye_frame_shape = Some(shape);
+        self
+    }
+
+    fn eye_ball_shape(&mut self, shape: EyeShape) -> &mut Self {
+        self.eye_ball_shape = Some(shape);
+        self
+    }
+
     fn shape(&mut self, shape: Shape) -> &mut Self {
-        self.commands.push(*shape);
+        self.commands.push(shape.into());
         self.command_colors.push(None);
         self
     }
 
     fn shape_color<C: Into<Color>>(&mut self, shape: Shape, color: C) -> &mut Self {
-        self.commands.push(*shape);
+        self.commands.push(shape.into());
         self.command_colors.push(Some(color.into()));
         self
     }
 
+    fn shape_fn<F>(&mut self, shape: F) -> &mut Self
+    where
+        F: Fn(usize, usize, Module) -> String + Send + Sync + 'static,
+    {
+        self.commands.push(ModuleCommand::Closure(Arc::new(shape)));
+        self.command_colors.push(None);
+        self
+    }
+
+    fn shape_neighbors(&mut self, shape: NeighborFunction) -> &mut Self {
+        self.commands.push(ModuleCommand::Neighbors(shape));
+        self.command_colors.push(None);
+        self
+    }
+
+    fn shape_for(&mut self, module_type: ModuleType, shape: Shape) -> &mut Self {
+        self.role_commands.push((module_type, RoleShape(*shape)));
+        self
+    }
+
+    fn merge_modules(&mut self, corner_radius: f64) -> &mut Self {
+        self.merge_radius = Some(clamp_setting(corner_radius, 0.5));
+        self
+    }
+
+    fn dot_radius(&mut self, radius: f64) -> &mut Self {
+        self.dot_radius = clamp_setting(radius, 0.5);
+        self
+    }
+
+    fn module_gap(&mut self, gap: f64) -> &mut Self {
+        self.module_gap = clamp_setting(gap, 1f64);
+        self
+    }
+
     fn image(&mut self, image: String) -> &mut Self {
         self.image = Some(image);
+        self.center_text = None;
         self
     }
 
@@ -134,21 +517,47 @@ impl Builder for SvgBuilder {
     }
 
     fn image_size(&mut self, image_size: f64) -> &mut Self {
-        self.image_size = Some(image_size);
+        self.image_size = positive_finite(image_size);
         self
     }
 
     fn image_gap(&mut self, gap: f64) -> &mut Self {
-        self.image_gap = Some(gap);
+        self.image_gap = positive_finite(gap);
         self
     }
 
     fn image_position(&mut self, x: f64, y: f64) -> &mut Self {
-        self.image_position = Some((x, y));
+        self.image_position = (x.is_finite() && y.is_finite()).then(|| (x, y));
+        self
+    }
+
+    fn background_image(&mut self, image: String) -> &mut Self {
+        self.background_image = Some(image);
+        self
+    }
+
+    fn background_image_opacity(&mut self, opacity: f64) -> &mut Self {
+        self.background_image_opacity = clamp_setting(opacity, 1f64);
         self
     }
 }
 
+/// Limits `value` to `0..=max`, NaN gives 0
+///
+/// Not `f64::clamp`, which keeps NaN: it would be written to the svg as is.
+fn clamp_setting(value: f64, max: f64) -> f64 {
+    if value > 0f64 {
+        value.min(max)
+    } else {
+        0f64
+    }
+}
+
+/// Returns `value` if it is finite and not negative, settings are left unset otherwise
+pub(crate) fn positive_finite(value: f64) -> Option<f64> {
+    (value.is_finite() && value >= 0f64).then(|| value)
+}
+
 impl SvgBuilder {
     fn image_placement(image_background_shape: ImageBackgroundShape, n: usize) -> (f64, f64) {
         use ImageBackgroundShape::{Circle, RoundedSquare, Square};
@@ -167,8 +576,8 @@ impl SvgBuilder {
         const ROUNDED_SQUARE: [f64; 40] = SQUARE;
         const CIRCLE: [f64; 40] = SQUARE;
 
-        // Using hardcoded values
-        let version = Version::from_n(n) as usize;
+        // Using hardcoded values, Micro `QRCode`s use those of version 1
+        let version = Version::from_size(n).map_or(0, |version| version as usize);
         let border_size = match image_background_shape {
             Square => SQUARE[version],
             RoundedSquare => ROUNDED_SQUARE[version],
@@ -185,14 +594,8 @@ impl SvgBuilder {
         (border_size, (border_size - gap).round())
     }
 
-    fn image(&self, n: usize) -> String {
-        if self.image.is_none() {
-            return String::new();
-        }
-
-        let image = self.image.as_ref().unwrap();
-        let mut out = String::with_capacity(image.len() + 100);
-
+    /// Returns the background square `(x, y, size)` and the image `(x, y, size)`, in modules
+    pub(crate) fn image_layout(&self, n: usize) -> ((f64, f64, f64), (f64, f64, f64)) {
         let (mut border_size, mut image_size) =
             Self::image_placement(self.image_background_shape, n);
 
@@ -206,7 +609,7 @@ impl SvgBuilder {
             border_size = image_size + override_gap * 2f64;
         }
 
-        let mut placed_coord_x = (self.margin * 2 + n) as f64 - border_size;
+        let mut placed_coord_x = n as f64 - border_size;
 
         // Adjust for non-integer initial x coordinates so as not to partially cover bits by rounding down.
         if placed_coord_x % 2f64 != 0f64 {
@@ -214,48 +617,436 @@ impl SvgBuilder {
             border_size -= 1f64;
         }
 
-        placed_coord_x = placed_coord_x / 2f64;
+        placed_coord_x /= 2f64;
 
-        let mut placed_coord = (placed_coord_x, placed_coord_x);
+        let mut placed_coord = (
+            self.margin.left as f64 + placed_coord_x,
+            self.margin.top as f64 + placed_coord_x,
+        );
 
         if let Some((x, y)) = self.image_position {
             placed_coord = (x - border_size / 2f64, y - border_size / 2f64);
         }
 
-        let format = match self.image_background_shape {
-            ImageBackgroundShape::Square => {
-                r#"<rect x="{0}" y="{1}" width="{2}" height="{2}" fill="{3}"/>"#
-            }
-            ImageBackgroundShape::Circle => {
-                r#"<rect x="{0}" y="{1}" width="{2}" height="{2}" fill="{3}" rx="1000px"/>"#
-            }
-            ImageBackgroundShape::RoundedSquare => {
-                r#"<rect x="{0}" y="{1}" width="{2}" height="{2}" fill="{3}" rx="1px"/>"#
-            }
+        (
+            (placed_coord.0, placed_coord.1, border_size),
+            (
+                placed_coord.0 + (border_size - image_size) / 2f64,
+                placed_coord.1 + (border_size - image_size) / 2f64,
+                image_size,
+            ),
+        )
+    }
+
+    /// Whether an image or a text is drawn in the center
+    fn has_center(&self) -> bool {
+        self.image.is_some() || self.center_text.is_some()
+    }
+
+    /// Background of the embedded image, without the image itself
+    fn image_background(&self, n: usize) -> String {
+        if !self.has_center() {
+            return String::new();
+        }
+
+        let ((x, y, border_size), _) = self.image_layout(n);
+
+        let radius = match self.image_background_shape {
+            ImageBackgroundShape::Square => None,
+            ImageBackgroundShape::Circle => Some(1000f64),
+            ImageBackgroundShape::RoundedSquare => Some(1f64),
         };
+        let rx = radius.map_or_else(String::new, |radius| {
+            format!(r#" rx="{}px""#, self.length(radius))
+        });
+        let size = self.length(border_size);
+
+        format!(
+            r#"<rect x="{}" y="{}" width="{size}" height="{size}" fill="{}"{rx}/>"#,
+            self.length(x),
+            self.length(y),
+            self.image_background_color.to_str(),
+        )
+    }
 
-        let format = format
-            .replace("{0}", &placed_coord.0.to_string())
-            .replace("{1}", &placed_coord.1.to_string())
-            .replace("{2}", &border_size.to_string())
-            .replace("{3}", &self.image_background_color.to_str());
+    fn image(&self, n: usize) -> String {
+        if let Some((text, font_size, color)) = &self.center_text {
+            let ((x, y, border_size), _) = self.image_layout(n);
+            let center = border_size / 2f64;
+            // Centers the capital letters, about 0.7em high
+            return format!(
+                r#"{}<text x="{}" y="{}" font-family="sans-serif" font-size="{}" font-weight="bold" text-anchor="middle" fill="{}">{}</text>"#,
+                self.image_background(n),
+                self.length(x + center),
+                self.length(y + center + font_size * 0.35),
+                self.length(*font_size),
+                color.to_str(),
+                escape_xml(text),
+            );
+        }
+        let image = match &self.image {
+            Some(image) => image,
+            None => return String::new(),
+        };
+        let mut out = String::with_capacity(image.len() + 100);
 
-        out.push_str(&format);
+        out.push_str(&self.image_background(n));
 
+        let (_, (x, y, image_size)) = self.image_layout(n);
+        let length = |value: f64| match self.precision {
+            Some(_) => self.length(value),
+            None => format!("{:.2}", value * self.scale as f64),
+        };
         out.push_str(&format!(
-            r#"<image x="{0:.2}" y="{1:.2}" width="{2:.2}" height="{2:.2}" href="{3}" />"#,
-            placed_coord.0 + (border_size - image_size) / 2f64,
-            placed_coord.1 + (border_size - image_size) / 2f64,
-            image_size,
+            r#"<image x="{0}" y="{1}" width="{2}" height="{2}" href="{3}" />"#,
+            length(x),
+            length(y),
+            length(image_size),
             image
         ));
 
         out
     }
 
-    fn path(&self, qr: &QRCode) -> String {
-        const DEFAULT_COMMAND: [ModuleFunction; 1] = [Shape::square];
+    /// Returns the modules to draw: swapped when inverted, then knocked out by the image, `None`
+    /// if `qr` is drawn as is
+    fn drawn(&self, qr: &QRCode) -> Option<QRCode> {
+        if !self.invert {
+            return self.knock_out(qr);
+        }
+
+        let mut inverted = qr.clone();
+        for y in 0..qr.size {
+            for x in 0..qr.size {
+                let value = qr[y][x].value();
+                inverted[y][x].set(!value);
+            }
+        }
+        Some(self.knock_out(&inverted).unwrap_or(inverted))
+    }
+
+    /// Returns a copy of `qr` where the modules fully hidden by the image background are light,
+    /// `None` without image
+    fn knock_out(&self, qr: &QRCode) -> Option<QRCode> {
+        if !self.has_center() {
+            return None;
+        }
+
+        let ((bx, by, size), _) = self.image_layout(qr.size);
+        let radius = match self.image_background_shape {
+            ImageBackgroundShape::Square => 0f64,
+            ImageBackgroundShape::RoundedSquare => 1f64,
+            ImageBackgroundShape::Circle => size / 2f64,
+        };
+        // A point is hidden if it is in the rectangle, outside the corners or close to their center.
+        // `max` then `min` rather than `clamp`, which panics when the bounds cross: a rounded
+        // background smaller than 2 modules has corners larger than half its size
+        let hidden = |x: f64, y: f64| {
+            let cx = x.max(bx + radius).min(bx + size - radius);
+            let cy = y.max(by + radius).min(by + size - radius);
+            (bx..=bx + size).contains(&x)
+                && (by..=by + size).contains(&y)
+                && (x - cx).powi(2) + (y - cy).powi(2) <= radius * radius
+        };
+
+        let mut knocked = qr.clone();
+        for y in 0..qr.size {
+            for x in 0..qr.size {
+                let (top, left) = self.margin.offset(y, x);
+                let (left, top) = (left as f64, top as f64);
+                let corners = [(0f64, 0f64), (1f64, 0f64), (0f64, 1f64), (1f64, 1f64)];
+                if corners.iter().all(|&(dx, dy)| hidden(left + dx, top + dy)) {
+                    knocked[y][x].set(false);
+                }
+            }
+        }
+
+        Some(knocked)
+    }
+
+    /// Checks that `qr` still decodes to the same data once the modules hidden by the image are
+    /// cleared, which [`SvgBuilder::to_str`] does when drawing
+    ///
+    /// Also called by [`SvgBuilder::write_to`] and [`SvgBuilder::to_file`].
+    ///
+    /// # Errors
+    /// - `SvgError::SvgError` if the image hides more modules than the ECL can recover
+    ///
+    /// # Example
+    /// ```rust
+    /// # use fast_qr::convert::{svg::SvgBuilder, Builder};
+    /// # use fast_qr::qr::QRBuilder;
+    /// # use fast_qr::ECL;
+    /// let qrcode = QRBuilder::new("https://example.com/").ecl(ECL::L).build().unwrap();
+    ///
+    /// let mut builder = SvgBuilder::default();
+    /// builder.image(String::from("logo.png"));
+    /// assert!(builder.image_size(5f64).check_image(&qrcode).is_ok());
+    /// assert!(builder.image_size(15f64).check_image(&qrcode).is_err());
+    /// ```
+    pub fn check_image(&self, qr: &QRCode) -> Result<(), SvgError> {
+        let knocked = match self.knock_out(qr) {
+            Some(knocked) => knocked,
+            None => return Ok(()),
+        };
+
+        // Nothing to compare with if the qr code itself does not decode
+        let expected = crate::decode::decode(qr).ok();
+        if expected.is_some() && crate::decode::decode(&knocked).ok() != expected {
+            return Err(SvgError::SvgError(String::from(
+                "The image hides more modules than the error correction can recover",
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Refuses colors with a WCAG 2 contrast ratio below `ratio` between the modules and the
+    /// background, see [`SvgBuilder::check_contrast`] (default: none)
+    ///
+    /// A ratio of 4.5 is advised for printed codes, low contrast styled codes often fail to
+    /// scan.
+    pub fn min_contrast(&mut self, ratio: f64) -> &mut Self {
+        self.min_contrast = Some(clamp_setting(ratio, 21f64));
+        self
+    }
+
+    /// Returns the lowest WCAG 2 contrast ratio (1 to 21) between the background color and the
+    /// colors of the modules: module color or gradient stops, shape colors and finder pattern
+    /// colors
+    ///
+    /// Colors other than `#rrggbb` or `#rrggbbaa` are skipped, as are
+    /// [`SvgBuilder::module_color_fn`] and background pictures, `None` if no color is left.
+    ///
+    /// ```rust
+    /// # use fast_qr::convert::{svg::SvgBuilder, Builder};
+    /// let mut builder = SvgBuilder::default();
+    /// assert_eq!(builder.contrast_ratio(), Some(21f64));
+    ///
+    /// builder.module_color("#7dd3fc");
+    /// assert!(builder.contrast_ratio().unwrap() < 2f64);
+    /// ```
+    #[must_use]
+    pub fn contrast_ratio(&self) -> Option<f64> {
+        let mut colors = Vec::new();
+        match &self.module_gradient {
+            Some(Gradient::Linear { stops, .. } | Gradient::Radial { stops }) => {
+                colors.extend(stops.iter().map(|(_, color)| color));
+            }
+            None => colors.push(&self.dot_color),
+        }
+        colors.extend(self.command_colors.iter().flatten());
+        colors.extend(
+            [
+                &self.finder_color,
+                &self.eye_frame_color,
+                &self.eye_ball_color,
+            ]
+            .into_iter()
+            .flatten(),
+        );
+
+        colors
+            .into_iter()
+            .filter_map(|color| color.contrast_ratio(&self.background_color))
+            .reduce(f64::min)
+    }
+
+    /// Checks the contrast of the colors against [`SvgBuilder::min_contrast`], when set
+    ///
+    /// Also called by [`SvgBuilder::build_qr`], [`SvgBuilder::write_to`] and
+    /// [`SvgBuilder::to_file`].
+    ///
+    /// # Errors
+    /// - `SvgError::SvgError` if [`SvgBuilder::contrast_ratio`] is below the minimum
+    ///
+    /// # Example
+    /// ```rust
+    /// # use fast_qr::convert::{svg::SvgBuilder, Builder};
+    /// let mut builder = SvgBuilder::default();
+    /// builder.min_contrast(4.5).module_color("#1e3a8a");
+    /// assert!(builder.check_contrast().is_ok());
+    ///
+    /// builder.background_color("#3b82f6");
+    /// assert!(builder.check_contrast().is_err());
+    /// ```
+    pub fn check_contrast(&self) -> Result<(), SvgError> {
+        match (self.min_contrast, self.contrast_ratio()) {
+            (Some(min), Some(ratio)) if ratio < min => Err(SvgError::SvgError(format!(
+                "Contrast ratio {ratio:.2} between the modules and the background is below {min}"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Builds the [`QRCode`] of `builder` so that the image fits: with an image or a center
+    /// text, the ECL is `ECL::H` unless forced with [`QRBuilder::ecl`], then
+    /// [`SvgBuilder::check_image`] and [`SvgBuilder::check_contrast`] are run
+    ///
+    /// # Errors
+    /// - `SvgError::SvgError` if `builder` fails to build
+    /// - `SvgError::SvgError` if the image hides more modules than the ECL can recover
+    /// - `SvgError::SvgError` if the colors have too little contrast
+    ///
+    /// # Example
+    /// ```rust
+    /// # use fast_qr::convert::{svg::SvgBuilder, Builder};
+    /// # use fast_qr::qr::QRBuilder;
+    /// # use fast_qr::ECL;
+    /// let mut builder = SvgBuilder::default();
+    /// builder.image(String::from("logo.png")).image_size(10f64);
+    ///
+    /// let qrcode = builder.build_qr(&QRBuilder::new("https://example.com/")).unwrap();
+    /// assert!(matches!(qrcode.ecl(), ECL::H));
+    ///
+    /// // The forced ECL is kept, too low for the image
+    /// assert!(builder
+    ///     .build_qr(QRBuilder::new("https://example.com/").ecl(ECL::L))
+    ///     .is_err());
+    /// ```
+    pub fn build_qr(&self, builder: &QRBuilder) -> Result<QRCode, SvgError> {
+        let mut builder = builder.clone();
+        if self.has_center() {
+            builder.default_ecl(ECL::H);
+        }
+
+        let qr = builder
+            .build()
+            .map_err(|error| SvgError::SvgError(error.to_string()))?;
+        self.check_image(&qr)?;
+        self.check_contrast()?;
+        Ok(qr)
+    }
+
+    /// Top left corners of the finder patterns, 1 for Micro `QRCode`s
+    fn finder_origins(qr: &QRCode) -> Vec<(usize, usize)> {
+        if qr.size < 7 {
+            return Vec::new();
+        }
+
+        let far = qr.size - 7;
+        [(0, 0), (0, far), (far, 0)]
+            .into_iter()
+            .filter(|&(y, x)| {
+                // Micro QRCodes are smaller than two finder patterns side by side
+                ((y, x) == (0, 0) || far >= 7)
+                    && qr[y][x].module_type() == ModuleType::FinderPattern
+            })
+            .collect()
+    }
+
+    /// Part of the finder pattern containing the module at (`y`, `x`), `None` for other modules
+    fn eye_part(qr: &QRCode, y: usize, x: usize) -> Option<EyePart> {
+        if qr[y][x].module_type() != ModuleType::FinderPattern {
+            return None;
+        }
+
+        let local = |i: usize| if i < 7 { i } else { i + 7 - qr.size };
+        match (local(y), local(x)) {
+            (2..=4, 2..=4) => Some(EyePart::Ball),
+            _ => Some(EyePart::Frame),
+        }
+    }
+
+    /// Whether the module is part of an eye drawn with [`Builder::eye_frame_shape`] or
+    /// [`Builder::eye_ball_shape`] instead of the shapes list
+    fn eye_shaped(&self, qr: &QRCode, y: usize, x: usize) -> bool {
+        if self.invert {
+            return false;
+        }
+        match Self::eye_part(qr, y, x) {
+            Some(EyePart::Frame) => self.eye_frame_shape.is_some(),
+            Some(EyePart::Ball) => self.eye_ball_shape.is_some(),
+            None => false,
+        }
+    }
+
+    /// Group of the finder pattern module drawn with its own color, `None` if it is drawn with
+    /// the other modules
+    fn eye_group(&self, qr: &QRCode, y: usize, x: usize) -> Option<EyeGroup> {
+        let group = match Self::eye_part(qr, y, x)? {
+            EyePart::Frame if self.eye_frame_color.is_some() => EyeGroup::Frame,
+            EyePart::Ball if self.eye_ball_color.is_some() => EyeGroup::Ball,
+            _ => EyeGroup::Finder,
+        };
+        self.eye_group_color(group).map(|_| group)
+    }
+
+    fn eye_group_color(&self, group: EyeGroup) -> Option<&Color> {
+        match group {
+            EyeGroup::Finder => self.finder_color.as_ref(),
+            EyeGroup::Frame => self.eye_frame_color.as_ref(),
+            EyeGroup::Ball => self.eye_ball_color.as_ref(),
+        }
+    }
+
+    /// Frames and balls drawn as a whole, with [`Builder::eye_frame_shape`] and
+    /// [`Builder::eye_ball_shape`], unless inverted
+    fn eye_shape_paths(&self, qr: &QRCode) -> Vec<String> {
+        if self.invert {
+            return Vec::new();
+        }
+        let origins = Self::finder_origins(qr);
+        let color = |part_color: &Option<Color>| match (
+            part_color.as_ref().or(self.finder_color.as_ref()),
+            &self.module_gradient,
+        ) {
+            (Some(color), _) => color.to_str().to_string(),
+            (None, Some(_)) => String::from("url(#fast_qr_gradient)"),
+            (None, None) => self.dot_color.to_str().to_string(),
+        };
+
+        let mut paths = Vec::new();
+        if let Some(shape) = self.eye_frame_shape {
+            // The hole is drawn in the other direction, evenodd cuts it out
+            let d = origins
+                .iter()
+                .map(|&(y, x)| {
+                    let (y, x) = self.margin.offset(y, x);
+                    let (x, y) = (x as f64, y as f64);
+                    let (outer, inner) = match shape {
+                        EyeShape::Square => (0f64, 0f64),
+                        EyeShape::RoundedSquare => (2f64, 1f64),
+                        EyeShape::Circle => (3.5, 2.5),
+                    };
+                    [
+                        rounded_rect(x, y, 7f64, outer),
+                        rounded_rect(x + 1f64, y + 1f64, 5f64, inner),
+                    ]
+                    .concat()
+                })
+                .collect::<String>();
+            paths.push(format!(
+                r#"<path d="{d}" fill-rule="evenodd" fill="{}"/>"#,
+                color(&self.eye_frame_color)
+            ));
+        }
+        if let Some(shape) = self.eye_ball_shape {
+            let radius = match shape {
+                EyeShape::Square => 0f64,
+                EyeShape::RoundedSquare => 0.75,
+                EyeShape::Circle => 1.5,
+            };
+            let d = origins
+                .iter()
+                .map(|&(y, x)| {
+                    let (y, x) = self.margin.offset(y + 2, x + 2);
+                    let (x, y) = (x as f64, y as f64);
+                    rounded_rect(x, y, 3f64, radius)
+                })
+                .collect::<String>();
+            paths.push(format!(
+                r#"<path d="{d}" fill="{}"/>"#,
+                color(&self.eye_ball_color)
+            ));
+        }
+
+        paths
+    }
+
+    fn paths(&self, qr: &QRCode) -> Vec<String> {
         const DEFAULT_COMMAND_COLOR: [Option<Color>; 1] = [None];
+        let default_command = [ModuleCommand::Function(Shape::square)];
 
         // TODO: cleanup this basic logic
         let command_colors: &[Option<Color>] = if !self.commands.is_empty() {
@@ -263,15 +1054,45 @@ impl SvgBuilder {
         } else {
             &DEFAULT_COMMAND_COLOR
         };
-        let commands: &[ModuleFunction] = if !self.commands.is_empty() {
+        let commands: &[ModuleCommand] = if !self.commands.is_empty() {
             &self.commands
         } else {
-            &DEFAULT_COMMAND
+            &default_command
         };
 
+        let needs_neighbors = commands.iter().any(ModuleCommand::needs_neighbors);
+        // Square modules are merged into runs once every module is known
+        let runs = commands
+            .iter()
+            .map(|command| {
+                self.minify
+                    && self.module_color_fn.is_none()
+                    && self.module_gap == 0f64
+                    && !self.symbols()
+                    && command.function().map(|command| command as usize)
+                        == Some(Shape::square as ModuleFunction as usize)
+            })
+            .collect::<Vec<_>>();
+
         let mut paths = vec![String::with_capacity(10 * qr.size * qr.size); commands.len()];
-        for path in paths.iter_mut() {
-            path.push_str(r#"<path d=""#);
+        // Finder patterns drawn with their own color, by `EyeGroup`
+        let mut eye_paths = [
+            vec![String::new(); commands.len()],
+            vec![String::new(); commands.len()],
+            vec![String::new(); commands.len()],
+        ];
+        let mut role_paths = vec![String::new(); self.role_commands.len()];
+        // Paths of `module_color_fn`, by command and color
+        let mut colored_paths = std::collections::BTreeMap::<(usize, String), String>::new();
+        // Shapes of the modules drawn with `<use>`
+        let mut symbols = Vec::new();
+        let start = if self.symbols() { "" } else { r#"<path d=""# };
+        for path in paths
+            .iter_mut()
+            .chain(eye_paths.iter_mut().flatten())
+            .chain(&mut role_paths)
+        {
+            path.push_str(start);
         }
 
         for y in 0..qr.size {
@@ -281,63 +1102,1633 @@ impl SvgBuilder {
                     continue;
                 }
 
+                let module_type = cell.module_type();
+                // Drawn as a whole once every module is known
+                if self.eye_shaped(qr, y, x) {
+                    continue;
+                }
+                if self
+                    .role_commands
+                    .iter()
+                    .any(|&(role, _)| role == module_type)
+                {
+                    for (i, &(role, command)) in self.role_commands.iter().enumerate() {
+                        if role == module_type {
+                            let (y, x) = self.margin.offset(y, x);
+                            let d = command(y, x, cell);
+                            role_paths[i].push_str(&self.place_module(y, x, d, &mut symbols));
+                        }
+                    }
+                    continue;
+                }
+
+                // Merged modules are traced once every module is known
+                if self.merge_radius.is_some() {
+                    continue;
+                }
+
+                let group = self.eye_group(qr, y, x);
+                let neighbors = if needs_neighbors {
+                    Neighbors::of(qr, y, x)
+                } else {
+                    Neighbors::default()
+                };
+
+                if let (Some(color_fn), None) = (&self.module_color_fn, group) {
+                    let color = Color::from(color_fn(x, y, cell)).0;
+                    for (i, command) in commands.iter().enumerate() {
+                        colored_paths
+                            .entry((i, color.clone()))
+                            .or_insert_with(|| String::from(start))
+                            .push_str(&self.draw_module(
+                                command,
+                                (y, x),
+                                cell,
+                                neighbors,
+                                &mut symbols,
+                            ));
+                    }
+                    continue;
+                }
+
+                let paths = match group {
+                    Some(group) => &mut eye_paths[group as usize],
+                    None => &mut paths,
+                };
                 for (i, command) in commands.iter().enumerate() {
-                    paths[i].push_str(&command(y + self.margin, x + self.margin, cell));
+                    if runs[i] {
+                        continue;
+                    }
+                    paths[i].push_str(&self.draw_module(
+                        command,
+                        (y, x),
+                        cell,
+                        neighbors,
+                        &mut symbols,
+                    ));
                 }
             }
         }
 
-        for (i, &command) in commands.iter().enumerate() {
-            let command_color = command_colors[i].as_ref().unwrap_or(&self.dot_color);
-            // Allows to compare if two function pointers are the same
-            // This works because there is no notion of Generics for `rounded_square`
-            if command as usize == Shape::rounded_square as usize {
-                paths[i].push_str(&format!(
-                    r##"" stroke-width=".3" stroke-linejoin="round" stroke="{}"##,
-                    command_color.to_str()
-                ));
+        for (i, &(role, command)) in self.role_commands.iter().enumerate() {
+            let color = match (&self.finder_color, &self.module_gradient) {
+                (Some(color), _) if role == ModuleType::FinderPattern => color.to_str(),
+                (_, Some(_)) => "url(#fast_qr_gradient)",
+                _ => self.dot_color.to_str(),
+            };
+            self.close_path(&mut role_paths[i], Some(*command), color);
+        }
+
+        // Whether the module is drawn in the eye `group` or with the others, when merged
+        let drawn = |y: usize, x: usize, group: Option<EyeGroup>| {
+            let cell = qr[y][x];
+            let module_type = cell.module_type();
+            cell.value()
+                && !self
+                    .role_commands
+                    .iter()
+                    .any(|&(role, _)| role == module_type)
+                && !self.eye_shaped(qr, y, x)
+                && self.eye_group(qr, y, x) == group
+        };
+        if let Some(radius) = self.merge_radius {
+            let color = match self.module_gradient {
+                Some(_) => "url(#fast_qr_gradient)",
+                None => self.dot_color.to_str(),
+            };
+            let outline = |group: Option<EyeGroup>| {
+                outline(qr.size, self.margin, radius, |y, x| drawn(y, x, group))
+            };
+
+            paths = vec![format!(r#"<path d="{}" fill="{color}"/>"#, outline(None))];
+            for group in EyeGroup::ALL {
+                if let Some(color) = self.eye_group_color(group) {
+                    paths.push(format!(
+                        r#"<path d="{}" fill="{}"/>"#,
+                        outline(Some(group)),
+                        color.to_str()
+                    ));
+                }
             }
+        } else {
+            for (i, command) in commands.iter().enumerate() {
+                if runs[i] {
+                    paths[i].push_str(&square_runs(qr.size, self.margin, |y, x| drawn(y, x, None)));
+                    for group in EyeGroup::ALL {
+                        eye_paths[group as usize][i].push_str(&square_runs(
+                            qr.size,
+                            self.margin,
+                            |y, x| drawn(y, x, Some(group)),
+                        ));
+                    }
+                }
 
-            paths[i].push_str(&format!(r#"" fill="{}"/>"#, command_color.to_str()));
-        }
+                let command = command.function();
+                let command_color = match (&command_colors[i], &self.module_gradient) {
+                    (Some(color), _) => color.to_str(),
+                    (None, Some(_)) => "url(#fast_qr_gradient)",
+                    (None, None) => self.dot_color.to_str(),
+                };
+                self.close_path(&mut paths[i], command, command_color);
 
-        paths.join("")
-    }
+                for group in EyeGroup::ALL {
+                    if let Some(color) = self.eye_group_color(group) {
+                        self.close_path(&mut eye_paths[group as usize][i], command, color.to_str());
+                    }
+                }
+            }
 
-    /// Return a string containing the svg for a qr code
-    pub fn to_str(&self, qr: &QRCode) -> String {
-        let n = qr.size;
+            if self.module_color_fn.is_some() {
+                paths = colored_paths
+                    .into_iter()
+                    .map(|((i, color), mut path)| {
+                        self.close_path(&mut path, commands[i].function(), &color);
+                        path
+                    })
+                    .collect();
+            }
+            for group in EyeGroup::ALL {
+                if self.eye_group_color(group).is_some() {
+                    // Groups without modules, like finder patterns drawn with eye shapes
+                    paths.extend(eye_paths[group as usize].drain(..).filter(|path| {
+                        !path.starts_with(r#"<path d="""#) && !path.ends_with(r#""></g>"#)
+                    }));
+                }
+            }
+        }
+        paths.extend(self.eye_shape_paths(qr));
+        paths.append(&mut role_paths);
+        if let Some(quiet_zone) = self.quiet_zone_path(qr.size) {
+            paths.insert(0, quiet_zone);
+        }
+        paths.splice(0..0, self.margin_paths(qr));
 
-        let mut out = String::with_capacity(11 * n * n / 2);
-        out.push_str(&format!(
-            r#"<svg viewBox="0 0 {0} {0}" xmlns="http://www.w3.org/2000/svg">"#,
-            self.margin * 2 + n
-        ));
+        for path in &mut paths {
+            // Layers of `<use>` have no path data
+            let start = match path.find(r#" d=""#) {
+                Some(start) => start + 4,
+                None => continue,
+            };
+            let end = start + path[start..].find('"').unwrap_or(0);
+            let d = self.scaled(&path[start..end]);
+            path.replace_range(start..end, &d);
+        }
+        if !symbols.is_empty() {
+            let mut defs = String::from("<defs>");
+            for (id, symbol) in symbols.iter().enumerate() {
+                let d = self.scaled(symbol);
+                defs.push_str(&format!(r#"<path id="fast_qr_shape_{id}" d="{d}"/>"#));
+            }
+            defs.push_str("</defs>");
+            paths.insert(0, defs);
+        }
 
-        out.push_str(&format!(
-            r#"<rect width="{0}px" height="{0}px" fill="{1}"/>"#,
-            self.margin * 2 + n,
-            self.background_color.to_str()
-        ));
+        paths
+    }
 
-        out.push_str(&self.path(qr));
-        out.push_str(&self.image(n));
+    /// Applies `precision` and `scale` to the path data `d`
+    fn scaled(&self, d: &str) -> String {
+        if self.precision.is_none() && self.scale == 1 {
+            return d.to_string();
+        }
 
-        out.push_str("</svg>");
-        out
+        rewrite_path(d, |value, number| match number {
+            PathNumber::Angle => self.number(value),
+            _ => self.length(value),
+        })
+    }
+
+    /// Whether modules are drawn with `<use>`, gradients would fill each module on its own
+    fn symbols(&self) -> bool {
+        self.use_symbols && self.module_gradient.is_none()
+    }
+
+    /// Path of `command` for the module at (`y`, `x`), without the margin, see
+    /// [`SvgBuilder::place_module`]
+    fn draw_module(
+        &self,
+        command: &ModuleCommand,
+        (y, x): (usize, usize),
+        module: Module,
+        neighbors: Neighbors,
+        symbols: &mut Vec<String>,
+    ) -> String {
+        let (y, x) = self.margin.offset(y, x);
+        let d = command.draw(y, x, module, neighbors, self.dot_radius);
+        self.place_module(y, x, d, symbols)
+    }
+
+    /// Path `d` of the module at (`y`, `x`), with the margin, shrunk by the module gap, or a
+    /// `<use>` of its shape, added to `symbols` the first time
+    fn place_module(&self, y: usize, x: usize, d: String, symbols: &mut Vec<String>) -> String {
+        let d = self.gapped(y, x, d);
+        if !self.symbols() {
+            return d;
+        }
+
+        // Moved to the origin, any shape can be reused
+        let (left, top) = (x as f64, y as f64);
+        let shape = rewrite_path(&d, |value, number| {
+            coordinate(match number {
+                PathNumber::X => value - left,
+                PathNumber::Y => value - top,
+                _ => value,
+            })
+        });
+        let id = match symbols.iter().position(|symbol| *symbol == shape) {
+            Some(id) => id,
+            None => {
+                symbols.push(shape);
+                symbols.len() - 1
+            }
+        };
+
+        format!(
+            r##"<use href="#fast_qr_shape_{id}" x="{}" y="{}"/>"##,
+            self.length(left),
+            self.length(top)
+        )
+    }
+
+    /// Shrinks the path `d` of the module at (`y`, `x`), with the margin, around the center of
+    /// the module to leave [`Builder::module_gap`] empty
+    fn gapped(&self, y: usize, x: usize, d: String) -> String {
+        if self.module_gap == 0f64 {
+            return d;
+        }
+
+        let factor = 1f64 - self.module_gap;
+        let (cx, cy) = (x as f64 + 0.5, y as f64 + 0.5);
+        rewrite_path(&d, |value, number| {
+            coordinate(match number {
+                PathNumber::X => cx + (value - cx) * factor,
+                PathNumber::Y => cy + (value - cy) * factor,
+                PathNumber::Length => value * factor,
+                PathNumber::Angle => value,
+            })
+        })
+    }
+
+    /// Margin drawn in the module color when inverted, `None` otherwise
+    fn quiet_zone_path(&self, n: usize) -> Option<String> {
+        if !self.invert || self.margin == Margin::uniform(0) {
+            return None;
+        }
+
+        let (width, height) = (self.margin.width(n) as f64, self.margin.height(n) as f64);
+        let radius = self.background_radius.min(width.min(height) / 2f64);
+        let color = match self.module_gradient {
+            Some(_) => "url(#fast_qr_gradient)",
+            None => self.dot_color.to_str(),
+        };
+        // The qr code is cut out of the background shape
+        Some(format!(
+            r#"<path d="{}{}" fill-rule="evenodd" fill="{color}"/>"#,
+            rounded_box(0f64, 0f64, width, height, radius),
+            rounded_rect(
+                self.margin.left as f64,
+                self.margin.top as f64,
+                n as f64,
+                0f64
+            )
+        ))
+    }
+
+    /// Band and caption of [`MarginStyle::Band`], in the margin outside of the quiet zone
+    fn margin_paths(&self, qr: &QRCode) -> Vec<String> {
+        let (color, caption) = match &self.margin_style {
+            MarginStyle::Band { color, caption } => (color, caption),
+            MarginStyle::Plain => return Vec::new(),
+        };
+        let quiet_zone = if qr.micro_version.is_some() { 2 } else { 4 };
+        let band = |margin: usize| margin.saturating_sub(quiet_zone) as f64;
+        let (top, right, bottom, left) = (
+            band(self.margin.top),
+            band(self.margin.right),
+            band(self.margin.bottom),
+            band(self.margin.left),
+        );
+        if top + right + bottom + left == 0f64 {
+            return Vec::new();
+        }
+
+        let n = qr.size;
+        let (width, height) = (self.margin.width(n) as f64, self.margin.height(n) as f64);
+        let radius = self.background_radius.min(width.min(height) / 2f64);
+        // The inner side of the band is cut out of the background shape
+        let mut paths = vec![format!(
+            r#"<path d="{}{}" fill-rule="evenodd" fill="{}"/>"#,
+            rounded_box(0f64, 0f64, width, height, radius),
+            rounded_box(left, top, width - left - right, height - top - bottom, 0f64),
+            color.to_str()
+        )];
+        if let Some(caption) = caption.as_ref().filter(|_| bottom > 0f64) {
+            let font_size = bottom * CAPTION_FONT_SIZE;
+            // Centers the capital letters, about 0.7em high, on the bottom side of the band
+            let baseline = height - bottom / 2f64 + font_size * 0.35;
+            paths.push(format!(
+                r#"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" text-anchor="middle" fill="{}">{}</text>"#,
+                self.length(self.margin.left as f64 + n as f64 / 2f64),
+                self.length(baseline),
+                self.length(font_size),
+                self.background_color.to_str(),
+                escape_xml(caption),
+            ));
+        }
+
+        paths
+    }
+
+    /// Formats a number with `precision`, without scaling it
+    fn number(&self, value: f64) -> String {
+        match self.precision {
+            Some(_) if !value.is_finite() => String::from("0"),
+            Some(precision) => {
+                let value = format!("{value:.precision$}");
+                let value = match value.contains('.') {
+                    true => value.trim_end_matches('0').trim_end_matches('.'),
+                    false => &value,
+                };
+                match value {
+                    "-0" => String::from("0"),
+                    value => value.to_string(),
+                }
+            }
+            None => coordinate(value),
+        }
+    }
+
+    /// Formats a length in modules, multiplied by `scale`
+    fn length(&self, value: f64) -> String {
+        self.number(value * self.scale as f64)
+    }
+
+    /// Adds the end of a `<path>` element, with its fill (and stroke for `rounded_square`)
+    fn close_path(&self, path: &mut String, command: Option<ModuleFunction>, color: &str) {
+        // Allows to compare if two function pointers are the same
+        // This works because there is no notion of Generics for `rounded_square`
+        let stroke = if command.map(|command| command as usize)
+            == Some(Shape::rounded_square as ModuleFunction as usize)
+        {
+            format!(
+                r#" stroke-width="{}" stroke-linejoin="round" stroke="{}""#,
+                self.length(0.3),
+                color
+            )
+        } else {
+            String::new()
+        };
+
+        if self.symbols() {
+            *path = format!(r#"<g{stroke} fill="{color}">{path}</g>"#);
+        } else {
+            path.push_str(&format!(r#""{stroke} fill="{color}"/>"#));
+        }
+    }
+
+    fn gradient(&self) -> String {
+        let (tag, attributes, stops) = match &self.module_gradient {
+            None => return String::new(),
+            Some(Gradient::Linear { angle, stops }) => (
+                "linearGradient",
+                format!(
+                    r#" gradientTransform="rotate({} .5 .5)""#,
+                    coordinate(*angle)
+                ),
+                stops,
+            ),
+            Some(Gradient::Radial { stops }) => ("radialGradient", String::new(), stops),
+        };
+
+        let mut out = format!(r#"<defs><{tag} id="fast_qr_gradient"{attributes}>"#);
+        for (offset, color) in stops {
+            out.push_str(&format!(
+                r#"<stop offset="{}" stop-color="{}"/>"#,
+                coordinate(*offset),
+                color.to_str()
+            ));
+        }
+        out.push_str(&format!("</{tag}></defs>"));
+
+        out
+    }
+
+    /// Background picture covering the whole svg, `href` replaces the picture when set
+    fn background_image(&self, n: usize, href: Option<&str>) -> String {
+        let image = match href.or(self.background_image.as_deref()) {
+            Some(image) => image,
+            None => return String::new(),
+        };
+
+        let opacity = if self.background_image_opacity < 1f64 {
+            format!(r#" opacity="{:.2}""#, self.background_image_opacity)
+        } else {
+            String::new()
+        };
+
+        format!(
+            r#"<image width="{}" height="{}" href="{}" preserveAspectRatio="xMidYMid slice"{}/>"#,
+            self.length(self.margin.width(n) as f64),
+            self.length(self.margin.height(n) as f64),
+            image,
+            opacity
+        )
+    }
+
+    /// Opening `<svg>` tag and background, `sized` adds the width & height attributes
+    fn header(&self, n: usize, sized: bool) -> String {
+        let (left, top, right, bottom) = self.frame_insets();
+        let width = self.margin.width(n) + left + right;
+        let height = self.margin.height(n) + top + bottom;
+
+        let size = match self.size {
+            Some((size, unit)) if sized => format!(
+                r#" width="{}{2}" height="{}{2}""#,
+                coordinate(size),
+                coordinate(size * height as f64 / width as f64),
+                unit.as_str()
+            ),
+            _ => String::new(),
+        };
+        // The qr code keeps its coordinates, the frame and label take negative ones
+        let (origin, position) = if left + top == 0 {
+            (String::from("0 0"), String::new())
+        } else {
+            // Subtracted from 0, as `-0f64` would be written `-0`
+            let (x, y) = (
+                self.length(0f64 - left as f64),
+                self.length(0f64 - top as f64),
+            );
+            (format!("{x} {y}"), format!(r#" x="{x}" y="{y}""#))
+        };
+        let radius = if self.background_radius > 0f64 {
+            format!(r#" rx="{}""#, self.length(self.background_radius))
+        } else {
+            String::new()
+        };
+
+        // Unitless lengths are user units, i.e. pixels
+        let unit = if self.minify { "" } else { "px" };
+
+        let (width, height) = (self.length(width as f64), self.length(height as f64));
+        format!(
+            r#"<svg viewBox="{origin} {width} {height}"{size} xmlns="http://www.w3.org/2000/svg"><rect{position} width="{width}{unit}" height="{height}{unit}"{radius} fill="{}"/>"#,
+            self.background_color.to_str(),
+        )
+    }
+
+    /// Creates a builder with the configuration of `preset`
+    pub fn from_preset(preset: &Preset) -> Self {
+        preset.svg.clone()
+    }
+
+    /// Sets the width & height of the svg to `size` in `unit`, i.e. the physical size when
+    /// printed (default: none, the svg fills its container)
+    ///
+    /// Only applies to svg output, raster images have their own size.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use fast_qr::convert::svg::{SvgBuilder, SvgUnit};
+    /// # use fast_qr::qr::QRBuilder;
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    ///
+    /// let svg = SvgBuilder::default().size(25.0, SvgUnit::Mm).to_str(&qrcode);
+    /// assert!(svg.starts_with(r#"<svg viewBox="0 0 33 33" width="25mm" height="25mm""#));
+    /// ```
+    pub fn size(&mut self, size: f64, unit: SvgUnit) -> &mut Self {
+        self.size = positive_finite(size).map(|size| (size, unit));
+        self
+    }
+
+    /// Rounds the coordinates to `decimals` decimals, keeping the leading zero of numbers
+    /// below 1 (default: none, 3 decimals without leading zero, e.g. `.5`)
+    ///
+    /// # Example
+    /// ```rust
+    /// # use fast_qr::convert::svg::SvgBuilder;
+    /// # use fast_qr::convert::{Builder, Shape};
+    /// # use fast_qr::qr::QRBuilder;
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    ///
+    /// let svg = SvgBuilder::default()
+    ///     .shape(Shape::Diamond)
+    ///     .precision(2)
+    ///     .to_str(&qrcode);
+    /// assert!(svg.contains("M4.5,4l0.5,0.5l-0.5,0.5l-0.5,-0.5z"));
+    /// ```
+    pub fn precision(&mut self, decimals: usize) -> &mut Self {
+        self.precision = Some(decimals);
+        self
+    }
+
+    /// Multiplies every coordinate by `scale`, one module is `scale` user units wide
+    /// (default: 1)
+    ///
+    /// With a scale of 10, coordinates of the built-in shapes are integers. Raster images without
+    /// `fit_width` nor `fit_height` are `scale` times larger.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use fast_qr::convert::svg::SvgBuilder;
+    /// # use fast_qr::convert::{Builder, Shape};
+    /// # use fast_qr::qr::QRBuilder;
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    ///
+    /// let svg = SvgBuilder::default()
+    ///     .shape(Shape::Diamond)
+    ///     .scale(10)
+    ///     .to_str(&qrcode);
+    /// assert!(svg.starts_with(r#"<svg viewBox="0 0 330 330""#));
+    /// assert!(svg.contains("M45,40l5,5l-5,5l-5,-5z"));
+    /// ```
+    pub fn scale(&mut self, scale: usize) -> &mut Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Merges adjacent square modules of a row into a single rectangle, drawn with relative
+    /// moves, to shrink the svg (default: false)
+    ///
+    /// Only [`Shape::Square`] modules are merged, other shapes are drawn as usual.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use fast_qr::convert::svg::SvgBuilder;
+    /// # use fast_qr::qr::QRBuilder;
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    ///
+    /// let svg = SvgBuilder::default().to_str(&qrcode);
+    /// let minified = SvgBuilder::default().minify(true).to_str(&qrcode);
+    /// assert!(minified.len() < svg.len());
+    /// ```
+    pub fn minify(&mut self, minify: bool) -> &mut Self {
+        self.minify = minify;
+        self
+    }
+
+    /// Swaps dark and light modules, the margin included, for light on dark codes (default:
+    /// false)
+    ///
+    /// The margin and the light modules are drawn with the module color and shapes, the dark
+    /// modules let the background color through: black on white gives white modules on a black
+    /// field. Eye shapes are drawn module by module.
+    ///
+    /// Inverted codes (reflectance reversal) are allowed since ISO/IEC 18004:2015, whatever the
+    /// ECL, and most phone cameras read them. Older and embedded scanners, and some libraries
+    /// unless told to (ZXing's `ALSO_INVERTED` hint), do not: test with the targeted scanners,
+    /// especially for Micro `QRCode`s.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use fast_qr::convert::{svg::SvgBuilder, Builder};
+    /// # use fast_qr::qr::QRBuilder;
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    ///
+    /// // Light modules on a dark field, for dark mode pages
+    /// let svg = SvgBuilder::default()
+    ///     .invert(true)
+    ///     .module_color("#0f172a")
+    ///     .background_color("#f8fafc")
+    ///     .to_str(&qrcode);
+    /// assert!(svg.contains(r##"fill-rule="evenodd" fill="#0f172a""##));
+    /// ```
+    pub fn invert(&mut self, invert: bool) -> &mut Self {
+        self.invert = invert;
+        self
+    }
+
+    /// Draws each distinct module shape once in `<defs>`, and each module as a `<use>` of it
+    /// (default: false)
+    ///
+    /// The svg compresses better, and design tools can recolor single modules. Layers keep
+    /// their color on a `<g>`. Ignored with a gradient, which would fill each module on its
+    /// own, merged modules and eye shapes are still drawn as paths.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use fast_qr::convert::svg::SvgBuilder;
+    /// # use fast_qr::qr::QRBuilder;
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    ///
+    /// let svg = SvgBuilder::default().use_symbols(true).to_str(&qrcode);
+    /// assert!(svg.contains(r#"<defs><path id="fast_qr_shape_0" d="M0,0h1v1h-1"/></defs>"#));
+    /// assert!(svg.contains(r##"<use href="#fast_qr_shape_0" x="4" y="4"/>"##));
+    /// ```
+    pub fn use_symbols(&mut self, use_symbols: bool) -> &mut Self {
+        self.use_symbols = use_symbols;
+        self
+    }
+
+    /// Computes the color of each module from its position in the qr code (`x`, `y`, without
+    /// the margin), for gradients or highlighting regions
+    ///
+    /// Replaces the module color, gradient and [`Builder::shape_color`] colors. The finder
+    /// patterns keep [`Builder::finder_color`] when set, and modules drawn with
+    /// [`Builder::shape_for`] or [`Builder::merge_modules`] keep their colors.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::convert::svg::SvgBuilder;
+    /// use fast_qr::QRBuilder;
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    /// let size = qrcode.size;
+    ///
+    /// // Red on the left, blue on the right
+    /// let svg = SvgBuilder::default()
+    ///     .module_color_fn(move |x, _, _| {
+    ///         let blue = (x * 255 / (size - 1)) as u8;
+    ///         [255 - blue, 0, blue, 255]
+    ///     })
+    ///     .to_str(&qrcode);
+    /// assert!(svg.contains(r##"fill="#ff0000""##));
+    /// ```
+    pub fn module_color_fn<F>(&mut self, color_fn: F) -> &mut Self
+    where
+        F: Fn(usize, usize, Module) -> [u8; 4] + Send + Sync + 'static,
+    {
+        self.module_color_fn = Some(ModuleColorFn(Arc::new(color_fn)));
+        self
+    }
+
+    /// Draws the Swiss cross in the center, as required by the Swiss QR-bill, see
+    /// [`SwissQrBuilder`](crate::data::swissqr::SwissQrBuilder) (default: false)
+    ///
+    /// The cross is 7/46 of the qr code wide, i.e. 7mm for a 46mm qr code.
+    pub fn swiss_cross(&mut self, swiss_cross: bool) -> &mut Self {
+        self.swiss_cross = swiss_cross;
+        self
+    }
+
+    /// Swiss cross centered on the qr code: a black square with a white border and a white
+    /// cross, with the proportions of the Swiss flag
+    fn swiss_cross_paths(&self, n: usize) -> String {
+        if !self.swiss_cross {
+            return String::new();
+        }
+
+        let size = n as f64 * 7f64 / 46f64;
+        let center = (
+            self.margin.left as f64 + n as f64 / 2f64,
+            self.margin.top as f64 + n as f64 / 2f64,
+        );
+        let border = size * 0.7 / 19.8;
+        let square = size - 2f64 * border;
+        let (arm, span) = (square * 6f64 / 32f64, square * 20f64 / 32f64);
+
+        let rect = |width: f64, height: f64, fill: &str| {
+            format!(
+                r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{fill}"/>"#,
+                self.length(center.0 - width / 2f64),
+                self.length(center.1 - height / 2f64),
+                self.length(width),
+                self.length(height),
+            )
+        };
+        [
+            rect(size, size, "#FFFFFF"),
+            rect(square, square, "#000000"),
+            rect(arm, span, "#FFFFFF"),
+            rect(span, arm, "#FFFFFF"),
+        ]
+        .concat()
+    }
+
+    /// Draws `text` in the center instead of an image, `font_size` in module size, on the
+    /// image background (see [`Builder::image_background_shape`])
+    ///
+    /// Replaces the image, and is replaced by [`Builder::image`]. The modules under the
+    /// background are cleared, see [`SvgBuilder::check_image`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::convert::{svg::SvgBuilder, Builder, ImageBackgroundShape};
+    /// use fast_qr::{QRBuilder, ECL};
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").ecl(ECL::H).build().unwrap();
+    ///
+    /// let svg = SvgBuilder::default()
+    ///     .center_text("A1B2", 2.0, "#1e3a8a")
+    ///     .image_background_shape(ImageBackgroundShape::Circle)
+    ///     .to_str(&qrcode);
+    /// assert!(svg.contains(r##"fill="#1e3a8a">A1B2</text>"##));
+    /// ```
+    pub fn center_text<T: Into<String>, C: Into<Color>>(
+        &mut self,
+        text: T,
+        font_size: f64,
+        color: C,
+    ) -> &mut Self {
+        self.center_text = Some((
+            text.into(),
+            positive_finite(font_size).unwrap_or(0f64),
+            color.into(),
+        ));
+        self.image = None;
+        self
+    }
+
+    /// Draws a border around the qr code and its label, in the module color (default: none)
+    ///
+    /// The frame is 1 module wide, outside of the margin: the svg grows by 2 modules.
+    pub fn frame(&mut self, frame: FrameStyle) -> &mut Self {
+        self.frame = Some(frame);
+        self
+    }
+
+    /// Draws `text` in a banner above or below the qr code, with the `font` family
+    /// (default: none)
+    ///
+    /// The banner is 4 modules high, outside of the margin. With a [`SvgBuilder::frame`], it
+    /// is filled with the module color and the text is drawn in the background color.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::convert::svg::{FrameStyle, LabelPosition, SvgBuilder};
+    /// use fast_qr::QRBuilder;
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    ///
+    /// let svg = SvgBuilder::default()
+    ///     .frame(FrameStyle::Rounded)
+    ///     .label("SCAN ME", "Helvetica, sans-serif", LabelPosition::Bottom)
+    ///     .to_str(&qrcode);
+    /// assert!(svg.starts_with(r#"<svg viewBox="-1 -1 35 39""#));
+    /// ```
+    pub fn label<T: Into<String>, F: Into<String>>(
+        &mut self,
+        text: T,
+        font: F,
+        position: LabelPosition,
+    ) -> &mut Self {
+        self.label = Some((text.into(), font.into(), position));
+        self
+    }
+
+    /// Sets the margin of each side in modules, replacing [`Builder::margin`] (default: 4 on
+    /// each side), i.e. to leave room for a caption or to sit against the edge of a card
+    ///
+    /// Scanners need a quiet zone of 4 modules, 2 for Micro `QRCode`s, a smaller side relies on
+    /// what surrounds the code to be light.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::convert::svg::SvgBuilder;
+    /// use fast_qr::{QRBuilder, Version};
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").version(Version::V03).build().unwrap();
+    ///
+    /// // 29 modules, with room for a caption under it
+    /// let svg = SvgBuilder::default().margins(4, 4, 10, 4).to_str(&qrcode);
+    /// assert!(svg.starts_with(r#"<svg viewBox="0 0 37 43""#));
+    /// ```
+    pub fn margins(&mut self, top: usize, right: usize, bottom: usize, left: usize) -> &mut Self {
+        self.margin = Margin {
+            top,
+            right,
+            bottom,
+            left,
+        };
+        self
+    }
+
+    /// Decorates the margin outside of the quiet zone (default: [`MarginStyle::Plain`])
+    ///
+    /// The quiet zone is 4 modules wide, 2 for Micro `QRCode`s, and stays in the background
+    /// color: the band only takes the rest of the margin, raise the margin to make room for it.
+    /// The caption is drawn on the bottom side, when it has room, see [`SvgBuilder::margins`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::convert::svg::{MarginStyle, SvgBuilder};
+    /// use fast_qr::convert::Builder;
+    /// use fast_qr::QRBuilder;
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    ///
+    /// let svg = SvgBuilder::default()
+    ///     .margin(7)
+    ///     .margin_style(MarginStyle::Band {
+    ///         color: "#1e3a8a".into(),
+    ///         caption: Some("example.com".into()),
+    ///     })
+    ///     .to_str(&qrcode);
+    /// assert!(svg.contains(">example.com</text>"));
+    /// ```
+    pub fn margin_style(&mut self, style: MarginStyle) -> &mut Self {
+        self.margin_style = style;
+        self
+    }
+
+    /// Space taken by the frame and the label around the margin, in modules:
+    /// `(left, top, right, bottom)`
+    pub(crate) fn frame_insets(&self) -> (usize, usize, usize, usize) {
+        let frame = if self.frame.is_some() { FRAME_WIDTH } else { 0 };
+        let (top, bottom) = match &self.label {
+            Some((_, _, LabelPosition::Top)) => (LABEL_HEIGHT, 0),
+            Some((_, _, LabelPosition::Bottom)) => (0, LABEL_HEIGHT),
+            None => (0, 0),
+        };
+
+        (frame, frame + top, frame, frame + bottom)
+    }
+
+    /// Frame and label, drawn over everything else
+    fn frame_paths(&self, n: usize) -> String {
+        let (left, top, right, bottom) = self.frame_insets();
+        let (width, height) = (self.margin.width(n) as f64, self.margin.height(n) as f64);
+        let color = self.dot_color.to_str();
+        let mut out = String::new();
+
+        if let Some(frame) = self.frame {
+            // The stroke is centered on the outline
+            let half = FRAME_WIDTH as f64 / 2f64;
+            let radius = match frame {
+                FrameStyle::Square => String::new(),
+                FrameStyle::Rounded => format!(r#" rx="{}""#, self.length(FRAME_RADIUS - half)),
+            };
+            out.push_str(&format!(
+                r#"<rect x="{}" y="{}" width="{}" height="{}"{radius} fill="none" stroke="{color}" stroke-width="{}"/>"#,
+                self.length(half - left as f64),
+                self.length(half - top as f64),
+                self.length(width + (left + right) as f64 - 2f64 * half),
+                self.length(height + (top + bottom) as f64 - 2f64 * half),
+                self.length(FRAME_WIDTH as f64),
+            ));
+        }
+
+        if let Some((text, font, position)) = &self.label {
+            let y = match position {
+                LabelPosition::Top => -(LABEL_HEIGHT as f64),
+                LabelPosition::Bottom => height,
+            };
+            let text_color = if self.frame.is_some() {
+                out.push_str(&format!(
+                    r#"<rect y="{}" width="{}" height="{}" fill="{color}"/>"#,
+                    self.length(y),
+                    self.length(width),
+                    self.length(LABEL_HEIGHT as f64),
+                ));
+                self.background_color.to_str()
+            } else {
+                color
+            };
+            // Centers the capital letters, about 0.7em high, on the banner
+            let baseline = y + LABEL_HEIGHT as f64 / 2f64 + LABEL_FONT_SIZE * 0.35;
+            out.push_str(&format!(
+                r#"<text x="{}" y="{}" font-family="{}" font-size="{}" font-weight="bold" text-anchor="middle" fill="{text_color}">{}</text>"#,
+                self.length(width / 2f64),
+                self.length(baseline),
+                escape_xml(font),
+                self.length(LABEL_FONT_SIZE),
+                escape_xml(text),
+            ));
+        }
+
+        out
+    }
+
+    /// Rounds the corners of the background by `radius` modules, for a card look
+    /// (default: 0)
+    pub fn background_radius(&mut self, radius: f64) -> &mut Self {
+        self.background_radius = positive_finite(radius).unwrap_or(0f64);
+        self
+    }
+
+    /// Return a string containing the svg for a qr code
+    ///
+    /// The modules hidden by the image are left out, see [`SvgBuilder::check_image`] to ensure
+    /// the qr code still scans.
+    pub fn to_str(&self, qr: &QRCode) -> String {
+        self.to_str_sized(qr, true)
+    }
+
+    /// Return a string containing the svg for `qr`, with the modules that differ from `other`
+    /// highlighted: red where `qr` is dark and `other` light, green the other way around
+    ///
+    /// Helps comparing the output of two versions of the crate, or of another generator.
+    /// Modules of `other` outside of `qr` are not drawn, see [`QRCode::diff`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # use fast_qr::convert::svg::SvgBuilder;
+    /// # use fast_qr::{Mask, QRBuilder};
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    /// let other = QRBuilder::new("https://example.com/")
+    ///     .mask(Mask::Checkerboard)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let svg = SvgBuilder::default().to_diff_str(&qrcode, &other);
+    /// assert!(svg.contains(r##"fill="#dc2626""##));
+    /// ```
+    pub fn to_diff_str(&self, qr: &QRCode, other: &QRCode) -> String {
+        let (mut dark, mut light) = (String::new(), String::new());
+        for (x, y) in qr.diff(other) {
+            if x >= qr.size || y >= qr.size {
+                continue;
+            }
+            let d = if qr[y][x].value() {
+                &mut dark
+            } else {
+                &mut light
+            };
+            let (y, x) = self.margin.offset(y, x);
+            d.push_str(&format!("M{x},{y}h1v1h-1"));
+        }
+
+        let mut out = self.to_str(qr);
+        out.truncate(out.len() - "</svg>".len());
+        for (d, color) in [(dark, DIFF_DARK_COLOR), (light, DIFF_LIGHT_COLOR)] {
+            if !d.is_empty() {
+                out.push_str(&format!(
+                    r#"<path d="{}" fill="{color}" fill-opacity=".8"/>"#,
+                    self.scaled(&d)
+                ));
+            }
+        }
+
+        out.push_str("</svg>");
+        out
+    }
+
+    /// Same as [`SvgBuilder::to_str`], without width & height attributes when `sized` is false
+    pub(crate) fn to_str_sized(&self, qr: &QRCode, sized: bool) -> String {
+        let drawn = self.drawn(qr);
+        let qr = drawn.as_ref().unwrap_or(qr);
+        let n = qr.size;
+
+        let mut out = String::with_capacity(11 * n * n / 2);
+        out.push_str(&self.header(n, sized));
+        out.push_str(&self.background_image(n, None));
+        out.push_str(&self.gradient());
+        for path in self.paths(qr) {
+            out.push_str(&path);
+        }
+        out.push_str(&self.image(n));
+        out.push_str(&self.swiss_cross_paths(n));
+        out.push_str(&self.frame_paths(n));
+
+        out.push_str("</svg>");
+        out
+    }
+
+    /// Same as [`SvgBuilder::to_str`], but only draws the background of the embedded image when
+    /// `image` is false, so the image can be composited afterwards, and `background_image`
+    /// replaces the background picture
+    #[cfg(feature = "image")]
+    pub(crate) fn to_str_for_raster(
+        &self,
+        qr: &QRCode,
+        image: bool,
+        background_image: Option<&str>,
+    ) -> String {
+        let drawn = self.drawn(qr);
+        let qr = drawn.as_ref().unwrap_or(qr);
+        let n = qr.size;
+
+        let mut out = String::with_capacity(11 * n * n / 2);
+        out.push_str(&self.header(n, false));
+        out.push_str(&self.background_image(n, background_image));
+        out.push_str(&self.gradient());
+        for path in self.paths(qr) {
+            out.push_str(&path);
+        }
+        if image {
+            out.push_str(&self.image(n));
+        } else {
+            out.push_str(&self.image_background(n));
+        }
+        out.push_str(&self.swiss_cross_paths(n));
+        out.push_str(&self.frame_paths(n));
+
+        out.push_str("</svg>");
+        out
+    }
+
+    /// Writes the svg for a qr code to `writer`, one element at a time, instead of building the
+    /// whole string first
+    ///
+    /// Fails if the image hides too many modules or the colors have too little contrast, see
+    /// [`SvgBuilder::check_image`] and [`SvgBuilder::check_contrast`]
+    /// # Example
+    /// ```rust
+    /// # use fast_qr::convert::{svg::SvgBuilder, Builder};
+    /// # use fast_qr::qr::QRBuilder;
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    ///
+    /// let mut body = Vec::new();
+    /// SvgBuilder::default().write_to(&qrcode, &mut body).unwrap();
+    /// assert!(body.starts_with(b"<svg"));
+    /// ```
+    #[cfg(not(feature = "wasm-bindgen"))]
+    pub fn write_to<W: std::io::Write>(&self, qr: &QRCode, mut writer: W) -> Result<(), SvgError> {
+        self.check_image(qr)?;
+        self.check_contrast()?;
+        let drawn = self.drawn(qr);
+        let qr = drawn.as_ref().unwrap_or(qr);
+        let n = qr.size;
+        let mut write = |part: &str| writer.write_all(part.as_bytes()).map_err(SvgError::IoError);
+
+        write(&self.header(n, true))?;
+        write(&self.background_image(n, None))?;
+        write(&self.gradient())?;
+        for path in self.paths(qr) {
+            write(&path)?;
+        }
+        write(&self.image(n))?;
+        write(&self.swiss_cross_paths(n))?;
+        write(&self.frame_paths(n))?;
+        write("</svg>")?;
+
+        writer.flush().map_err(SvgError::IoError)
+    }
+
+    /// Writes the gzipped svg (`.svgz`) for a qr code to `writer`
+    ///
+    /// Fails if the image hides too many modules or the colors have too little contrast, see
+    /// [`SvgBuilder::check_image`] and [`SvgBuilder::check_contrast`]
+    #[cfg(all(feature = "svgz", not(feature = "wasm-bindgen")))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "svgz")))]
+    pub fn write_svgz<W: std::io::Write>(&self, qr: &QRCode, writer: W) -> Result<(), SvgError> {
+        use flate2::{write::GzEncoder, Compression};
+
+        let mut encoder = GzEncoder::new(writer, Compression::best());
+        self.write_to(qr, &mut encoder)?;
+        encoder.finish().map_err(SvgError::IoError)?;
+        Ok(())
+    }
+
+    /// Return the gzipped svg (`.svgz`) for a qr code
+    ///
+    /// Fails if the image hides too many modules or the colors have too little contrast, see
+    /// [`SvgBuilder::check_image`] and [`SvgBuilder::check_contrast`]
+    #[cfg(all(feature = "svgz", not(feature = "wasm-bindgen")))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "svgz")))]
+    pub fn to_svgz(&self, qr: &QRCode) -> Result<Vec<u8>, SvgError> {
+        let mut out = Vec::new();
+        self.write_svgz(qr, &mut out)?;
+        Ok(out)
     }
 
     /// Saves the svg for a qr code to a file
     #[cfg(not(feature = "wasm-bindgen"))]
     pub fn to_file(&self, qr: &QRCode, file: &str) -> Result<(), SvgError> {
         use std::fs::File;
-        use std::io::Write;
+        use std::io::BufWriter;
 
-        let out = self.to_str(qr);
+        let f = File::create(file).map_err(SvgError::IoError)?;
+        self.write_to(qr, BufWriter::new(f))
+    }
+}
+
+/// Serializable settings of [`SvgBuilder`], shapes are stored by name
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "SvgBuilder", default)]
+struct SvgConfig {
+    shapes: Vec<(Shape, Option<Color>)>,
+    shapes_for: Vec<(ModuleType, Shape)>,
+    merge_radius: Option<f64>,
+    dot_radius: f64,
+    module_gap: f64,
+    margin: usize,
+    /// Top, right, bottom and left margins, when they differ
+    margins: Option<[usize; 4]>,
+    background_color: Color,
+    module_color: Color,
+    module_gradient: Option<Gradient>,
+    finder_color: Option<Color>,
+    eye_frame_color: Option<Color>,
+    eye_ball_color: Option<Color>,
+    eye_frame_shape: Option<EyeShape>,
+    eye_ball_shape: Option<EyeShape>,
+    image: Option<String>,
+    image_background_color: Color,
+    image_background_shape: ImageBackgroundShape,
+    image_size: Option<f64>,
+    image_gap: Option<f64>,
+    image_position: Option<(f64, f64)>,
+    center_text: Option<(String, f64, Color)>,
+    background_image: Option<String>,
+    background_image_opacity: f64,
+    size: Option<(f64, SvgUnit)>,
+    background_radius: f64,
+    minify: bool,
+    use_symbols: bool,
+    swiss_cross: bool,
+    frame: Option<FrameStyle>,
+    label: Option<(String, String, LabelPosition)>,
+    margin_style: MarginStyle,
+    precision: Option<usize>,
+    scale: usize,
+    min_contrast: Option<f64>,
+    invert: bool,
+}
 
-        let mut f = File::create(file).map_err(SvgError::IoError)?;
-        f.write_all(out.as_bytes()).map_err(SvgError::IoError)?;
+#[cfg(feature = "serde")]
+impl Default for SvgConfig {
+    fn default() -> Self {
+        // The default builder only has built-in shapes
+        let builder = SvgBuilder::default();
+        let shapes = builder
+            .commands
+            .iter()
+            .zip(&builder.command_colors)
+            .filter_map(|(command, color)| Some((command.shape()?, color.clone())))
+            .collect();
+        builder.config_with(shapes, Vec::new())
+    }
+}
 
-        Ok(())
+#[cfg(feature = "serde")]
+impl SvgBuilder {
+    /// Returns the settings, `None` if a custom shape is used
+    fn config(&self) -> Option<SvgConfig> {
+        if self.module_color_fn.is_some() {
+            return None;
+        }
+        let shapes = self
+            .commands
+            .iter()
+            .zip(&self.command_colors)
+            .map(|(command, color)| Some((command.shape()?, color.clone())))
+            .collect::<Option<_>>()?;
+        let shapes_for = self
+            .role_commands
+            .iter()
+            .map(|&(module_type, function)| Some((module_type, Shape::from_function(*function)?)))
+            .collect::<Option<_>>()?;
+
+        Some(self.config_with(shapes, shapes_for))
+    }
+
+    /// Returns the settings with the given built-in shapes
+    fn config_with(
+        &self,
+        shapes: Vec<(Shape, Option<Color>)>,
+        shapes_for: Vec<(ModuleType, Shape)>,
+    ) -> SvgConfig {
+        SvgConfig {
+            shapes,
+            shapes_for,
+            merge_radius: self.merge_radius,
+            dot_radius: self.dot_radius,
+            module_gap: self.module_gap,
+            margin: self.margin.top,
+            margins: (self.margin != Margin::uniform(self.margin.top)).then(|| {
+                let Margin {
+                    top,
+                    right,
+                    bottom,
+                    left,
+                } = self.margin;
+                [top, right, bottom, left]
+            }),
+            background_color: self.background_color.clone(),
+            module_color: self.dot_color.clone(),
+            module_gradient: self.module_gradient.clone(),
+            finder_color: self.finder_color.clone(),
+            eye_frame_color: self.eye_frame_color.clone(),
+            eye_ball_color: self.eye_ball_color.clone(),
+            eye_frame_shape: self.eye_frame_shape,
+            eye_ball_shape: self.eye_ball_shape,
+            image: self.image.clone(),
+            image_background_color: self.image_background_color.clone(),
+            image_background_shape: self.image_background_shape,
+            image_size: self.image_size,
+            image_gap: self.image_gap,
+            image_position: self.image_position,
+            center_text: self.center_text.clone(),
+            background_image: self.background_image.clone(),
+            background_image_opacity: self.background_image_opacity,
+            size: self.size,
+            background_radius: self.background_radius,
+            minify: self.minify,
+            use_symbols: self.use_symbols,
+            swiss_cross: self.swiss_cross,
+            frame: self.frame,
+            label: self.label.clone(),
+            margin_style: self.margin_style.clone(),
+            precision: self.precision,
+            scale: self.scale,
+            min_contrast: self.min_contrast,
+            invert: self.invert,
+        }
+    }
+}
+
+/// Serializes the settings, fails if a custom shape ([`Shape::Command`] or
+/// [`Builder::shape_fn`]) or [`SvgBuilder::module_color_fn`] is used
+#[cfg(feature = "serde")]
+impl serde::Serialize for SvgBuilder {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.config()
+            .ok_or_else(|| {
+                serde::ser::Error::custom("custom shapes and colors can't be serialized")
+            })?
+            .serialize(serializer)
+    }
+}
+
+/// Missing settings keep their default value
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SvgBuilder {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = SvgConfig::deserialize(deserializer)?;
+
+        let mut builder = SvgBuilder::default();
+        for (shape, color) in config.shapes {
+            builder.commands.push(shape.into());
+            builder.command_colors.push(color);
+        }
+        builder.role_commands = config
+            .shapes_for
+            .into_iter()
+            .map(|(module_type, shape)| (module_type, RoleShape(*shape)))
+            .collect();
+
+        Ok(SvgBuilder {
+            merge_radius: config.merge_radius,
+            dot_radius: config.dot_radius,
+            module_gap: config.module_gap,
+            margin: match config.margins {
+                Some([top, right, bottom, left]) => Margin {
+                    top,
+                    right,
+                    bottom,
+                    left,
+                },
+                None => Margin::uniform(config.margin),
+            },
+            background_color: config.background_color,
+            dot_color: config.module_color,
+            module_gradient: config.module_gradient,
+            finder_color: config.finder_color,
+            eye_frame_color: config.eye_frame_color,
+            eye_ball_color: config.eye_ball_color,
+            eye_frame_shape: config.eye_frame_shape,
+            eye_ball_shape: config.eye_ball_shape,
+            image: config.image,
+            image_background_color: config.image_background_color,
+            image_background_shape: config.image_background_shape,
+            image_size: config.image_size,
+            image_gap: config.image_gap,
+            image_position: config.image_position,
+            center_text: config.center_text,
+            background_image: config.background_image,
+            background_image_opacity: config.background_image_opacity,
+            size: config.size,
+            background_radius: config.background_radius,
+            minify: config.minify,
+            use_symbols: config.use_symbols,
+            swiss_cross: config.swiss_cross,
+            frame: config.frame,
+            label: config.label,
+            margin_style: config.margin_style,
+            precision: config.precision,
+            scale: config.scale.max(1),
+            min_contrast: config.min_contrast,
+            invert: config.invert,
+            ..builder
+        })
+    }
+}
+
+/// Path of a `size` wide square at (`x`, `y`) with corners rounded by `radius`
+fn rounded_rect(x: f64, y: f64, size: f64, radius: f64) -> String {
+    rounded_box(x, y, size, size, radius)
+}
+
+/// Path of a `width` x `height` rectangle at (`x`, `y`) with corners rounded by `radius`
+fn rounded_box(x: f64, y: f64, width: f64, height: f64, radius: f64) -> String {
+    let r = coordinate(radius);
+    // Straight side then corner, clockwise from the top side
+    let edge = |line: char, sign_x: f64, sign_y: f64| {
+        let (sign, size) = if line == 'h' {
+            (sign_x, width)
+        } else {
+            (sign_y, height)
+        };
+        let side = size - 2f64 * radius;
+        let mut edge = String::new();
+        if side > 0f64 {
+            edge.push_str(&format!("{line}{}", coordinate(sign * side)));
+        }
+        if radius > 0f64 {
+            edge.push_str(&format!(
+                "a{r},{r},0,0,1,{},{}",
+                coordinate(sign_x * radius),
+                coordinate(sign_y * radius)
+            ));
+        }
+        edge
+    };
+
+    format!(
+        "M{},{}{}{}{}{}z",
+        coordinate(x + radius),
+        coordinate(y),
+        edge('h', 1f64, 1f64),
+        edge('v', -1f64, 1f64),
+        edge('h', -1f64, -1f64),
+        edge('v', 1f64, -1f64),
+    )
+}
+
+/// Escapes the characters with a meaning in xml text and attributes
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Formats a coordinate, removing floating point noise and leading zeros
+///
+/// Rounding to 3 decimals before formatting keeps the output identical across platforms, `-0`
+/// and non-finite values are written `0`.
+pub(crate) fn coordinate(value: f64) -> String {
+    let value = (value * 1000f64).round() / 1000f64;
+    if value == 0f64 || !value.is_finite() {
+        return String::from("0");
     }
+
+    let value = value.to_string();
+    match value.strip_prefix("0.") {
+        Some(decimals) => format!(".{decimals}"),
+        None => value.replacen("-0.", "-.", 1),
+    }
+}
+
+/// Kind of a number of path data, see [`rewrite_path`]
+#[derive(Clone, Copy)]
+enum PathNumber {
+    /// Absolute x coordinate
+    X,
+    /// Absolute y coordinate
+    Y,
+    /// Relative coordinate or radius
+    Length,
+    /// Rotation of an arc
+    Angle,
+}
+
+/// Rewrites every number of the path data `d` with `format`, given the kind of the number
+///
+/// The flags of arcs are kept, they are not numbers.
+fn rewrite_path(d: &str, format: impl Fn(f64, PathNumber) -> String) -> String {
+    let bytes = d.as_bytes();
+    let mut out = String::with_capacity(d.len());
+    // Index of the argument in the current command and the command
+    let (mut argument, mut command) = (0, b'M');
+    let mut previous_number = false;
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c.is_ascii_alphabetic() && c != b'e' && c != b'E' {
+            out.push(char::from(c));
+            (argument, command) = (0, c);
+            previous_number = false;
+            i += 1;
+            continue;
+        }
+        if c.is_ascii_whitespace() || c == b',' {
+            i += 1;
+            continue;
+        }
+
+        let arc = command.eq_ignore_ascii_case(&b'a');
+        let flag = arc && matches!(argument % 7, 3 | 4);
+        let start = i;
+        if flag {
+            i += 1;
+        } else {
+            // Sign, integer part, decimals and exponent, a second dot starts a new number
+            if matches!(bytes[i], b'+' | b'-') {
+                i += 1;
+            }
+            let mut dot = false;
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'0'..=b'9' => i += 1,
+                    b'.' if !dot => {
+                        dot = true;
+                        i += 1;
+                    }
+                    b'e' | b'E' => {
+                        i += 1;
+                        if i < bytes.len() && matches!(bytes[i], b'+' | b'-') {
+                            i += 1;
+                        }
+                    }
+                    _ => break,
+                }
+            }
+        }
+        if i == start {
+            // Not a number, kept as is
+            out.push(char::from(c));
+            i += 1;
+            continue;
+        }
+
+        let number = &d[start..i];
+        // Axis of the coordinate, `None` for a length
+        let axis = match command.to_ascii_uppercase() {
+            b'A' => [None, None, None, None, None, Some(0), Some(1)][argument % 7],
+            b'H' => Some(0),
+            b'V' => Some(1),
+            _ => Some(argument % 2),
+        };
+        let kind = match axis {
+            _ if arc && argument % 7 == 2 => PathNumber::Angle,
+            Some(0) if command.is_ascii_uppercase() => PathNumber::X,
+            Some(_) if command.is_ascii_uppercase() => PathNumber::Y,
+            _ => PathNumber::Length,
+        };
+        let number = match number.parse::<f64>() {
+            Ok(_) if flag => number.to_string(),
+            Ok(value) => format(value, kind),
+            Err(_) => number.to_string(),
+        };
+        if previous_number {
+            out.push(',');
+        }
+        out.push_str(&number);
+        previous_number = true;
+        argument += 1;
+    }
+
+    out
+}
+
+/// Draws the modules where `dark(y, x)` is true as one rectangle per horizontal run, each
+/// starting with a move relative to the end of the previous one
+fn square_runs(size: usize, margin: Margin, dark: impl Fn(usize, usize) -> bool) -> String {
+    let mut out = String::new();
+    // End of the previous run, i.e. its bottom left corner
+    let mut pen: Option<(usize, usize)> = None;
+    for y in 0..size {
+        let mut x = 0;
+        while x < size {
+            if !dark(y, x) {
+                x += 1;
+                continue;
+            }
+
+            let start = x;
+            while x < size && dark(y, x) {
+                x += 1;
+            }
+
+            let (top, left) = margin.offset(y, start);
+            match pen {
+                None => out.push_str(&format!("M{left},{top}")),
+                Some((px, py)) => out.push_str(&format!(
+                    "m{},{}",
+                    left as isize - px as isize,
+                    top as isize - py as isize
+                )),
+            }
+            let len = x - start;
+            out.push_str(&format!("h{len}v1h-{len}"));
+            pen = Some((left, top + 1));
+        }
+    }
+
+    out
+}
+
+/// Traces the outlines of the modules where `dark(y, x)` is true as a single path, rounding
+/// the outer corners by `radius`.
+///
+/// Outlines are clockwise, so holes are counter clockwise and stay empty with the default fill
+/// rule. Modules touching by a corner are traced separately.
+fn outline(
+    size: usize,
+    margin: Margin,
+    radius: f64,
+    dark: impl Fn(usize, usize) -> bool,
+) -> String {
+    // Right, down, left, up: turning right is the next direction
+    const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+    let side = size + 1;
+    let is_dark = |y: isize, x: isize| {
+        (0..size as isize).contains(&y)
+            && (0..size as isize).contains(&x)
+            && dark(y as usize, x as usize)
+    };
+
+    // Outgoing edges of each vertex, one bit per direction, dark modules are on the right
+    let mut edges = vec![0u8; side * side];
+    for y in 0..size {
+        for x in 0..size {
+            let (yi, xi) = (y as isize, x as isize);
+            if !is_dark(yi, xi) {
+                continue;
+            }
+
+            if !is_dark(yi - 1, xi) {
+                edges[y * side + x] |= 1 << 0;
+            }
+            if !is_dark(yi, xi + 1) {
+                edges[y * side + x + 1] |= 1 << 1;
+            }
+            if !is_dark(yi + 1, xi) {
+                edges[(y + 1) * side + x + 1] |= 1 << 2;
+            }
+            if !is_dark(yi, xi - 1) {
+                edges[(y + 1) * side + x] |= 1 << 3;
+            }
+        }
+    }
+
+    let mut out = String::new();
+    for start in 0..edges.len() {
+        while edges[start] != 0 {
+            let first = edges[start].trailing_zeros() as usize;
+
+            // Corners of the outline: vertex, incoming and outgoing directions
+            let mut corners = Vec::new();
+            let (mut vertex, mut direction) = (start, first);
+            loop {
+                edges[vertex] &= !(1 << direction);
+                let (dx, dy) = DIRECTIONS[direction];
+                let y = (vertex / side) as isize + dy;
+                let x = (vertex % side) as isize + dx;
+                vertex = y as usize * side + x as usize;
+
+                // Prefers turning right, separating modules touching by a corner
+                // Every vertex has as many incoming as outgoing edges, so an edge is found
+                let next = match [(direction + 1) % 4, direction, (direction + 3) % 4]
+                    .into_iter()
+                    .find(|&next| {
+                        edges[vertex] & (1 << next) != 0 || (vertex == start && next == first)
+                    }) {
+                    Some(next) => next,
+                    None => break,
+                };
+
+                if next != direction {
+                    corners.push((vertex, direction, next));
+                }
+                if vertex == start && next == first {
+                    break;
+                }
+                direction = next;
+            }
+
+            let point = |vertex: usize, direction: usize, distance: f64| {
+                let (dx, dy) = DIRECTIONS[direction];
+                (
+                    (vertex % side + margin.left) as f64 + dx as f64 * distance,
+                    (vertex / side + margin.top) as f64 + dy as f64 * distance,
+                )
+            };
+            let is_convex = |incoming: usize, outgoing: usize| outgoing == (incoming + 1) % 4;
+            let rounding = |incoming: usize, outgoing: usize| {
+                if is_convex(incoming, outgoing) {
+                    radius
+                } else {
+                    0f64
+                }
+            };
+
+            // A closed outline turns at least 4 times
+            let (vertex, incoming, outgoing) = match corners.last() {
+                Some(&corner) => corner,
+                None => continue,
+            };
+            let (x, y) = point(vertex, outgoing, rounding(incoming, outgoing));
+            out.push_str(&format!("M{},{}", coordinate(x), coordinate(y)));
+
+            for &(vertex, incoming, outgoing) in &corners {
+                let rounding = rounding(incoming, outgoing);
+                let (x, y) = point(vertex, incoming, -rounding);
+                if incoming % 2 == 0 {
+                    out.push_str(&format!("H{}", coordinate(x)));
+                } else {
+                    out.push_str(&format!("V{}", coordinate(y)));
+                }
+
+                if rounding > 0f64 {
+                    let (ix, iy) = DIRECTIONS[incoming];
+                    let (ox, oy) = DIRECTIONS[outgoing];
+                    out.push_str(&format!(
+                        "a{r},{r} 0 0 1 {},{}",
+                        coordinate((ix + ox) as f64 * rounding),
+                        coordinate((iy + oy) as f64 * rounding),
+                        r = coordinate(rounding),
+                    ));
+                }
+            }
+            out.push('z');
+        }
+    }
+
+    out
 }
@@ -0,0 +1,190 @@
+//! Converts [`QRCode`] to text for terminals, with Unicode blocks and ANSI colors
+//!
+//! ```rust
+//! use fast_qr::convert::term::{TermBuilder, TermColor};
+//! use fast_qr::qr::QRBuilder;
+//!
+//! // QRBuilde::new can fail if content is too big for version,
+//! // please check before unwrapping.
+//! let qrcode = QRBuilder::new("https://example.com/")
+//!     .build()
+//!     .unwrap();
+//!
+//! let text = TermBuilder::default()
+//!     .module_color(TermColor::TrueColor([30, 58, 138]))
+//!     .to_str(&qrcode);
+//! println!("{text}");
+//! ```
+
+use crate::QRCode;
+
+/// Used to print a ` ` (space)
+const EMPTY: char = ' ';
+/// Used to print a `█`
+const BLOCK: char = '█';
+/// Used to print a `▀`
+const TOP: char = '▀';
+/// Used to print a `▄`
+const BOTTOM: char = '▄';
+
+/// Color of a terminal cell, using ANSI escape codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TermColor {
+    /// One of the 256 colors palette
+    Ansi256(u8),
+    /// 24-bit RGB color, not supported by every terminal
+    TrueColor([u8; 3]),
+}
+
+impl TermColor {
+    /// Returns the escape code setting the foreground color
+    fn foreground(self) -> String {
+        match self {
+            TermColor::Ansi256(n) => format!("\x1b[38;5;{n}m"),
+            TermColor::TrueColor([r, g, b]) => format!("\x1b[38;2;{r};{g};{b}m"),
+        }
+    }
+
+    /// Returns the escape code setting the background color
+    fn background(self) -> String {
+        match self {
+            TermColor::Ansi256(n) => format!("\x1b[48;5;{n}m"),
+            TermColor::TrueColor([r, g, b]) => format!("\x1b[48;2;{r};{g};{b}m"),
+        }
+    }
+}
+
+/// Builder for terminal output, can set margin, colors, half blocks and inversion
+pub struct TermBuilder {
+    /// The margin around the [`QRCode`], default is 2
+    margin: usize,
+    /// Prints two rows per line using half blocks, default is true
+    half_blocks: bool,
+    /// Swaps dark and light modules, default is false
+    invert: bool,
+    /// The color for each module, printed with ANSI codes when a color is set
+    module_color: Option<TermColor>,
+    /// The background color, printed with ANSI codes when a color is set
+    background_color: Option<TermColor>,
+}
+
+/// Creates a `TermBuilder` instance
+impl Default for TermBuilder {
+    fn default() -> Self {
+        TermBuilder {
+            margin: 2,
+            half_blocks: true,
+            invert: false,
+            module_color: None,
+            background_color: None,
+        }
+    }
+}
+
+impl TermBuilder {
+    /// Updates margin (default: 2)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Prints two rows of modules per line using `▀` and `▄` (default: true),
+    /// otherwise each module is two characters wide
+    pub fn half_blocks(&mut self, half_blocks: bool) -> &mut Self {
+        self.half_blocks = half_blocks;
+        self
+    }
+
+    /// Swaps dark and light modules (default: false)
+    ///
+    /// Without colors, dark modules are printed with the terminal text color, which is light
+    /// on dark terminals: inverting makes the [`QRCode`] readable there.
+    pub fn invert(&mut self, invert: bool) -> &mut Self {
+        self.invert = invert;
+        self
+    }
+
+    /// Updates module color (default: terminal text color, or black when a color is set)
+    pub fn module_color(&mut self, module_color: TermColor) -> &mut Self {
+        self.module_color = Some(module_color);
+        self
+    }
+
+    /// Updates background color (default: terminal background, or white when a color is set)
+    pub fn background_color(&mut self, background_color: TermColor) -> &mut Self {
+        self.background_color = Some(background_color);
+        self
+    }
+
+    /// Returns whether the module at (`y`, `x`), margin included, is printed as dark
+    fn is_dark(&self, qr: &QRCode, y: usize, x: usize) -> bool {
+        let dark = match (y.checked_sub(self.margin), x.checked_sub(self.margin)) {
+            (Some(y), Some(x)) if y < qr.size && x < qr.size => qr[y][x].value(),
+            _ => false,
+        };
+
+        dark != self.invert
+    }
+
+    /// Return a string containing the [`QRCode`] for a terminal
+    pub fn to_str(&self, qr: &QRCode) -> String {
+        let n = qr.size + self.margin * 2;
+        let colored = self.module_color.is_some() || self.background_color.is_some();
+        let dark_color = self.module_color.unwrap_or(TermColor::Ansi256(16));
+        let light_color = self.background_color.unwrap_or(TermColor::Ansi256(231));
+        let color = |dark: bool| if dark { dark_color } else { light_color };
+
+        let step = if self.half_blocks { 2 } else { 1 };
+        let mut out = String::with_capacity(n * n * 2);
+
+        for y in (0..n).step_by(step) {
+            // Current (foreground, background), only escaped when it changes
+            let mut current = None;
+
+            for x in 0..n {
+                let top = self.is_dark(qr, y, x);
+                // The row past the end (odd number of rows) is left empty
+                let bottom = self.half_blocks && y + 1 < n && self.is_dark(qr, y + 1, x);
+
+                match (colored, self.half_blocks) {
+                    (true, true) => {
+                        let colors = (color(top), color(bottom));
+                        if current != Some(colors) {
+                            out.push_str(&colors.0.foreground());
+                            out.push_str(&colors.1.background());
+                            current = Some(colors);
+                        }
+                        out.push(TOP);
+                    }
+                    (true, false) => {
+                        let colors = (color(top), color(top));
+                        if current != Some(colors) {
+                            out.push_str(&colors.1.background());
+                            current = Some(colors);
+                        }
+                        out.push_str("  ");
+                    }
+                    (false, true) => out.push(match (top, bottom) {
+                        (true, true) => BLOCK,
+                        (true, false) => TOP,
+                        (false, true) => BOTTOM,
+                        (false, false) => EMPTY,
+                    }),
+                    (false, false) => out.push_str(if top { "██" } else { "  " }),
+                }
+            }
+
+            if colored {
+                out.push_str("\x1b[0m");
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// Prints the [`QRCode`] to the terminal
+    pub fn print(&self, qr: &QRCode) {
+        print!("{}", self.to_str(qr));
+    }
+}
@@ -0,0 +1,222 @@
+//! Converts [`QRCode`] to plain text: Unicode blocks, ASCII, Braille patterns or an HTML grid
+//!
+//! Useful where images can't be used, i.e. command-line tools or emails.
+//!
+//! ```rust
+//! use fast_qr::convert::text::{TextBuilder, TextFormat};
+//! use fast_qr::qr::QRBuilder;
+//!
+//! // QRBuilde::new can fail if content is too big for version,
+//! // please check before unwrapping.
+//! let qrcode = QRBuilder::new("https://example.com/")
+//!     .build()
+//!     .unwrap();
+//!
+//! let html = TextBuilder::default()
+//!     .format(TextFormat::HtmlTable)
+//!     .module_size(3)
+//!     .to_str(&qrcode);
+//! assert!(html.starts_with("<table"));
+//! ```
+
+use super::term::TermBuilder;
+use super::Color;
+use crate::QRCode;
+
+/// First Braille pattern, without any raised dot
+const BRAILLE: u32 = 0x2800;
+
+/// Output of [`TextBuilder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextFormat {
+    /// Unicode half blocks, two rows of modules per line, the default
+    Blocks,
+    /// ASCII only, each module is two characters wide: `##` when dark, spaces otherwise
+    Ascii,
+    /// Braille patterns, 2x4 modules per character, dark modules are raised dots
+    Braille,
+    /// HTML `<table>` with one cell per module, styled inline for emails
+    HtmlTable,
+    /// HTML `<div>` grid with one `<div>` per module
+    HtmlDiv,
+}
+
+/// Builder for text output, can set the format, margin, inversion and HTML colors
+pub struct TextBuilder {
+    /// The output format, default is [`TextFormat::Blocks`]
+    format: TextFormat,
+    /// The margin around the [`QRCode`], default is 2
+    margin: usize,
+    /// Swaps dark and light modules, default is false
+    invert: bool,
+    /// Side of a module in pixels for HTML, default is 4
+    module_size: usize,
+    /// The color for each module in HTML, default is #000000
+    module_color: Color,
+    /// The background color in HTML, default is #FFFFFF
+    background_color: Color,
+}
+
+/// Creates a `TextBuilder` instance
+impl Default for TextBuilder {
+    fn default() -> Self {
+        TextBuilder {
+            format: TextFormat::Blocks,
+            margin: 2,
+            invert: false,
+            module_size: 4,
+            module_color: [0, 0, 0, 255].into(),
+            background_color: [255; 4].into(),
+        }
+    }
+}
+
+impl TextBuilder {
+    /// Changes the output format (default: [`TextFormat::Blocks`])
+    pub fn format(&mut self, format: TextFormat) -> &mut Self {
+        self.format = format;
+        self
+    }
+
+    /// Updates margin (default: 2)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Swaps dark and light modules (default: false)
+    ///
+    /// Blocks and Braille dots are printed with the text color, which is light on dark
+    /// terminals: inverting makes the [`QRCode`] readable there.
+    pub fn invert(&mut self, invert: bool) -> &mut Self {
+        self.invert = invert;
+        self
+    }
+
+    /// Updates the side of a module in pixels for HTML (default: 4)
+    pub fn module_size(&mut self, module_size: usize) -> &mut Self {
+        self.module_size = module_size;
+        self
+    }
+
+    /// Updates module color for HTML (default: #000000)
+    pub fn module_color<C: Into<Color>>(&mut self, module_color: C) -> &mut Self {
+        self.module_color = module_color.into();
+        self
+    }
+
+    /// Updates background color for HTML (default: #FFFFFF)
+    pub fn background_color<C: Into<Color>>(&mut self, background_color: C) -> &mut Self {
+        self.background_color = background_color.into();
+        self
+    }
+
+    /// Returns whether the module at (`y`, `x`), margin included, is dark
+    fn is_dark(&self, qr: &QRCode, y: usize, x: usize) -> bool {
+        let dark = match (y.checked_sub(self.margin), x.checked_sub(self.margin)) {
+            (Some(y), Some(x)) if y < qr.size && x < qr.size => qr[y][x].value(),
+            _ => false,
+        };
+
+        dark != self.invert
+    }
+
+    /// `#` and spaces, for printers without Unicode support
+    fn ascii(&self, qr: &QRCode) -> String {
+        let n = qr.size + self.margin * 2;
+        let mut out = String::with_capacity(n * (n * 2 + 1));
+
+        for y in 0..n {
+            for x in 0..n {
+                out.push_str(if self.is_dark(qr, y, x) { "##" } else { "  " });
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// Braille patterns, rows and columns past the end are left empty
+    fn braille(&self, qr: &QRCode) -> String {
+        // Bit of each dot, by row then column, referring to the Unicode Braille patterns block
+        const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+        let n = qr.size + self.margin * 2;
+        let mut out = String::with_capacity(n * n / 8 * 3 + n / 4);
+
+        for y in (0..n).step_by(4) {
+            for x in (0..n).step_by(2) {
+                let mut pattern = BRAILLE;
+                for (dy, bits) in DOTS.iter().enumerate() {
+                    for (dx, &bit) in bits.iter().enumerate() {
+                        if y + dy < n && x + dx < n && self.is_dark(qr, y + dy, x + dx) {
+                            pattern |= bit;
+                        }
+                    }
+                }
+                out.push(char::from_u32(pattern).unwrap_or(' '));
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// HTML table or div grid, one cell per module
+    fn html(&self, qr: &QRCode, table: bool) -> String {
+        let n = qr.size + self.margin * 2;
+        let size = self.module_size;
+        let cell = |dark: bool| {
+            let color = if dark {
+                &self.module_color
+            } else {
+                &self.background_color
+            };
+            let tag = if table { "td" } else { "div" };
+            format!(
+                r#"<{tag} style="width:{size}px;height:{size}px;padding:0;background:{}"></{tag}>"#,
+                color.to_str()
+            )
+        };
+
+        let mut out = String::with_capacity(n * n * 80);
+        if table {
+            out.push_str(
+                r#"<table cellpadding="0" cellspacing="0" style="border-collapse:collapse;border:0">"#,
+            );
+        } else {
+            out.push_str(&format!(
+                r#"<div style="display:grid;grid-template-columns:repeat({n},{size}px)">"#
+            ));
+        }
+
+        for y in 0..n {
+            if table {
+                out.push_str("<tr>");
+            }
+            for x in 0..n {
+                out.push_str(&cell(self.is_dark(qr, y, x)));
+            }
+            if table {
+                out.push_str("</tr>");
+            }
+        }
+
+        out.push_str(if table { "</table>" } else { "</div>" });
+        out
+    }
+
+    /// Return a string containing the [`QRCode`] in the chosen format
+    pub fn to_str(&self, qr: &QRCode) -> String {
+        match self.format {
+            TextFormat::Blocks => TermBuilder::default()
+                .margin(self.margin)
+                .invert(self.invert)
+                .to_str(qr),
+            TextFormat::Ascii => self.ascii(qr),
+            TextFormat::Braille => self.braille(qr),
+            TextFormat::HtmlTable => self.html(qr, true),
+            TextFormat::HtmlDiv => self.html(qr, false),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Converts [`QRCode`] to XBM, a C source declaring the bitmap, without any graphics dependency
+//!
+//! Firmware can `#include` the generated file and draw the bitmap with its display library.
+//!
+//! ```rust
+//! use fast_qr::convert::xbm::XbmBuilder;
+//! use fast_qr::qr::QRBuilder;
+//!
+//! // QRBuilde::new can fail if content is too big for version,
+//! // please check before unwrapping.
+//! let qrcode = QRBuilder::new("https://example.com/")
+//!     .build()
+//!     .unwrap();
+//!
+//! let xbm = XbmBuilder::default().name("logo_qr").to_str(&qrcode);
+//! assert!(xbm.starts_with("#define logo_qr_width 33\n#define logo_qr_height 33\n"));
+//! assert!(xbm.contains("static unsigned char logo_qr_bits[] = {\n"));
+//! ```
+
+use std::io;
+
+use crate::QRCode;
+
+/// Bytes written on each line of the array
+const BYTES_PER_LINE: usize = 12;
+
+/// Builder for XBM bitmaps, can set the name, margin and module size
+pub struct XbmBuilder {
+    /// Prefix of the C identifiers, default is `qr`
+    name: String,
+    /// The margin around the [`QRCode`] in modules, default is 4
+    margin: usize,
+    /// Side of a module in pixels, default is 1
+    module_size: usize,
+}
+
+/// Creates a `XbmBuilder` instance
+impl Default for XbmBuilder {
+    fn default() -> Self {
+        XbmBuilder {
+            name: String::from("qr"),
+            margin: 4,
+            module_size: 1,
+        }
+    }
+}
+
+impl XbmBuilder {
+    /// Changes the prefix of the C identifiers: `{name}_width`, `{name}_height` and
+    /// `{name}_bits` (default: `qr`)
+    ///
+    /// Characters that are not allowed in a C identifier are replaced by `_`.
+    pub fn name<S: Into<String>>(&mut self, name: S) -> &mut Self {
+        let name = name
+            .into()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        self.name = match name.chars().next() {
+            Some(c) if c.is_ascii_digit() => format!("_{name}"),
+            Some(_) => name,
+            None => String::from("qr"),
+        };
+        self
+    }
+
+    /// Updates margin in modules (default: 4)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Updates the side of a module in pixels (default: 1)
+    pub fn module_size(&mut self, module_size: usize) -> &mut Self {
+        self.module_size = module_size.max(1);
+        self
+    }
+
+    /// Returns whether the pixel at (`y`, `x`) is dark
+    fn is_dark(&self, qr: &QRCode, y: usize, x: usize) -> bool {
+        let (y, x) = (y / self.module_size, x / self.module_size);
+        match (y.checked_sub(self.margin), x.checked_sub(self.margin)) {
+            (Some(y), Some(x)) if y < qr.size && x < qr.size => qr[y][x].value(),
+            _ => false,
+        }
+    }
+
+    /// Returns the C source of the bitmap
+    ///
+    /// Rows are padded to a whole byte, the leftmost pixel is the least significant bit and
+    /// dark pixels are set, as the XBM format defines.
+    pub fn to_str(&self, qr: &QRCode) -> String {
+        let side = (qr.size + self.margin * 2) * self.module_size;
+        let name = &self.name;
+
+        let bytes = (0..side).flat_map(|y| {
+            (0..side).step_by(8).map(move |start| {
+                (start..side.min(start + 8)).fold(0u8, |byte, x| {
+                    byte | u8::from(self.is_dark(qr, y, x)) << (x - start)
+                })
+            })
+        });
+
+        let mut out = format!(
+            "#define {name}_width {side}\n#define {name}_height {side}\nstatic unsigned char {name}_bits[] = {{\n"
+        );
+        for (i, byte) in bytes.enumerate() {
+            if i > 0 {
+                out.push_str(if i % BYTES_PER_LINE == 0 { ",\n" } else { ", " });
+            }
+            if i % BYTES_PER_LINE == 0 {
+                out.push_str("   ");
+            }
+            out.push_str(&format!("0x{byte:02x}"));
+        }
+        out.push_str(" };\n");
+
+        out
+    }
+
+    /// Saves the C source of the bitmap to a file, i.e. a `.xbm` or `.h` file
+    ///
+    /// # Errors
+    /// - `io::Error` if the file cannot be written
+    pub fn to_file(&self, qr: &QRCode, file: &str) -> io::Result<()> {
+        std::fs::write(file, self.to_str(qr))
+    }
+}
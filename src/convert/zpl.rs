@@ -0,0 +1,144 @@
+//! Converts [`QRCode`] to ZPL, the language of Zebra label printers
+//!
+//! The matrix is sent as a `^GFA` graphic field, so the printed code is exactly the one built
+//! here. [`ZplBuilder::to_native`] lets the printer encode the data itself with `^BQN`.
+//!
+//! ```rust
+//! use fast_qr::convert::zpl::ZplBuilder;
+//! use fast_qr::qr::QRBuilder;
+//!
+//! // QRBuilde::new can fail if content is too big for version,
+//! // please check before unwrapping.
+//! let qrcode = QRBuilder::new("https://example.com/")
+//!     .build()
+//!     .unwrap();
+//!
+//! // 20mm wide on a 203 dpi printer
+//! let zpl = ZplBuilder::default()
+//!     .fit_width_mm(20.0, 203)
+//!     .position(50, 50)
+//!     .to_str(&qrcode);
+//! assert!(zpl.starts_with("^XA^FO50,50^GFA,"));
+//! ```
+
+use crate::QRCode;
+
+/// Builder for ZPL labels, can set the module size, margin and position on the label
+pub struct ZplBuilder {
+    /// Side of a module in dots, default is 4
+    module_size: usize,
+    /// Width of the [`QRCode`] with its margin in dots, replaces `module_size` when set
+    width: Option<usize>,
+    /// The margin around the [`QRCode`] in modules, default is 4
+    margin: usize,
+    /// Top left corner on the label in dots, `^FO`, default is (0, 0)
+    position: (usize, usize),
+}
+
+/// Creates a `ZplBuilder` instance
+impl Default for ZplBuilder {
+    fn default() -> Self {
+        ZplBuilder {
+            module_size: 4,
+            width: None,
+            margin: 4,
+            position: (0, 0),
+        }
+    }
+}
+
+impl ZplBuilder {
+    /// Updates the side of a module in dots (default: 4)
+    pub fn module_size(&mut self, module_size: usize) -> &mut Self {
+        self.module_size = module_size.max(1);
+        self.width = None;
+        self
+    }
+
+    /// Uses the largest module size keeping the [`QRCode`] and its margin within `width_mm`,
+    /// printed at `dpi` dots per inch, usually 203 or 300
+    ///
+    /// Modules are a whole number of dots and at least one dot wide, so the printed code can
+    /// be smaller than `width_mm`, or larger when it does not fit.
+    pub fn fit_width_mm(&mut self, width_mm: f64, dpi: u32) -> &mut Self {
+        self.width = Some((width_mm.max(0.0) / 25.4 * f64::from(dpi)) as usize);
+        self
+    }
+
+    /// Updates margin in modules (default: 4)
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Updates the top left corner on the label in dots (default: (0, 0))
+    pub fn position(&mut self, x: usize, y: usize) -> &mut Self {
+        self.position = (x, y);
+        self
+    }
+
+    /// Returns the side of a module in dots for `qr`
+    fn module_dots(&self, qr: &QRCode) -> usize {
+        match self.width {
+            Some(width) => (width / (qr.size + self.margin * 2)).max(1),
+            None => self.module_size,
+        }
+    }
+
+    /// Returns the label with the [`QRCode`] as a `^GFA` graphic field
+    pub fn to_str(&self, qr: &QRCode) -> String {
+        let module_size = self.module_dots(qr);
+        let side = (qr.size + self.margin * 2) * module_size;
+        let width = (side + 7) / 8;
+        let total = width * side;
+
+        let is_dark = |y: usize, x: usize| {
+            let (y, x) = (y / module_size, x / module_size);
+            match (y.checked_sub(self.margin), x.checked_sub(self.margin)) {
+                (Some(y), Some(x)) if y < qr.size && x < qr.size => qr[y][x].value(),
+                _ => false,
+            }
+        };
+
+        let (x, y) = self.position;
+        let mut out = format!("^XA^FO{x},{y}^GFA,{total},{total},{width},");
+        out.reserve(total * 2 + 6);
+
+        // One bit per dot, most significant bit first, rows padded to a whole byte
+        for y in 0..side {
+            for byte in 0..width {
+                let bits = (0..8)
+                    .filter(|&bit| byte * 8 + bit < side && is_dark(y, byte * 8 + bit))
+                    .fold(0u8, |acc, bit| acc | 0x80 >> bit);
+                out.push_str(&format!("{bits:02X}"));
+            }
+        }
+
+        out.push_str("^FS^XZ");
+        out
+    }
+
+    /// Returns a label where the printer encodes `data` itself, with `^BQN`
+    ///
+    /// The error correction level of `qr` is kept, but the printer picks the version and mask,
+    /// so the result can differ from `qr`. The magnification is the module size, from 1 to 10,
+    /// and the printer adds its own margin.
+    pub fn to_native(&self, qr: &QRCode, data: &str) -> String {
+        let ecl = qr.ecl;
+        let magnification = self.module_dots(qr).min(10);
+
+        // `^FH` escapes field data as `_` and two hex digits, `^` and `~` would be commands
+        let mut field = String::with_capacity(data.len());
+        for byte in data.bytes() {
+            match byte {
+                b'_' | b'^' | b'~' | 0..=0x1F | 0x7F..=0xFF => {
+                    field.push_str(&format!("_{byte:02X}"));
+                }
+                _ => field.push(byte as char),
+            }
+        }
+
+        let (x, y) = self.position;
+        format!("^XA^FO{x},{y}^BQN,2,{magnification}^FH^FD{ecl}A,{field}^FS^XZ")
+    }
+}
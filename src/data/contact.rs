@@ -0,0 +1,227 @@
+//! Contact cards, `vCard` 3.0 and `MECARD`
+
+use core::fmt::{Display, Formatter};
+
+use alloc::{format, string::String, vec::Vec};
+
+use super::escape;
+
+/// Contact card following `vCard` 3.0 (RFC 2426)
+///
+/// # Example
+/// ```rust
+/// use fast_qr::data::VCard;
+///
+/// let mut card = VCard::new("Jane", "Doe");
+/// card.phone("+33 1 23 45 67 89").email("jane@example.com");
+///
+/// assert_eq!(
+///     card.to_string(),
+///     "BEGIN:VCARD\r\nVERSION:3.0\r\nN:Doe;Jane\r\nFN:Jane Doe\r\n\
+///      TEL:+33 1 23 45 67 89\r\nEMAIL:jane@example.com\r\nEND:VCARD"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct VCard {
+    first_name: String,
+    last_name: String,
+    organization: Option<String>,
+    title: Option<String>,
+    phones: Vec<String>,
+    emails: Vec<String>,
+    url: Option<String>,
+    address: Option<String>,
+    note: Option<String>,
+}
+
+impl VCard {
+    /// Creates a new card with the given name
+    #[must_use]
+    pub fn new<F: Into<String>, L: Into<String>>(first_name: F, last_name: L) -> Self {
+        VCard {
+            first_name: first_name.into(),
+            last_name: last_name.into(),
+            organization: None,
+            title: None,
+            phones: Vec::new(),
+            emails: Vec::new(),
+            url: None,
+            address: None,
+            note: None,
+        }
+    }
+
+    /// Sets the organization
+    pub fn organization<S: Into<String>>(&mut self, organization: S) -> &mut Self {
+        self.organization = Some(organization.into());
+        self
+    }
+
+    /// Sets the job title
+    pub fn title<S: Into<String>>(&mut self, title: S) -> &mut Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Adds a phone number
+    pub fn phone<S: Into<String>>(&mut self, phone: S) -> &mut Self {
+        self.phones.push(phone.into());
+        self
+    }
+
+    /// Adds an email address
+    pub fn email<S: Into<String>>(&mut self, email: S) -> &mut Self {
+        self.emails.push(email.into());
+        self
+    }
+
+    /// Sets the website
+    pub fn url<S: Into<String>>(&mut self, url: S) -> &mut Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Sets the street address, written as a single line
+    pub fn address<S: Into<String>>(&mut self, address: S) -> &mut Self {
+        self.address = Some(address.into());
+        self
+    }
+
+    /// Sets a free text note
+    pub fn note<S: Into<String>>(&mut self, note: S) -> &mut Self {
+        self.note = Some(note.into());
+        self
+    }
+
+    /// Escapes text values, referring to RFC 2426 section 4
+    fn escape(value: &str) -> String {
+        escape(value, &[';', ',']).replace('\n', "\\n")
+    }
+}
+
+impl Display for VCard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let first_name = Self::escape(&self.first_name);
+        let last_name = Self::escape(&self.last_name);
+        let full_name = format!("{first_name} {last_name}");
+
+        f.write_str("BEGIN:VCARD\r\nVERSION:3.0\r\n")?;
+        write!(f, "N:{last_name};{first_name}\r\n")?;
+        write!(f, "FN:{}\r\n", full_name.trim())?;
+        if let Some(organization) = &self.organization {
+            write!(f, "ORG:{}\r\n", Self::escape(organization))?;
+        }
+        if let Some(title) = &self.title {
+            write!(f, "TITLE:{}\r\n", Self::escape(title))?;
+        }
+        for phone in &self.phones {
+            write!(f, "TEL:{}\r\n", Self::escape(phone))?;
+        }
+        for email in &self.emails {
+            write!(f, "EMAIL:{}\r\n", Self::escape(email))?;
+        }
+        if let Some(url) = &self.url {
+            write!(f, "URL:{}\r\n", Self::escape(url))?;
+        }
+        if let Some(address) = &self.address {
+            write!(f, "ADR:;;{};;;;\r\n", Self::escape(address))?;
+        }
+        if let Some(note) = &self.note {
+            write!(f, "NOTE:{}\r\n", Self::escape(note))?;
+        }
+        f.write_str("END:VCARD")
+    }
+}
+
+/// Contact card following the compact NTT Docomo `MECARD` format
+///
+/// # Example
+/// ```rust
+/// use fast_qr::data::MeCard;
+///
+/// let mut card = MeCard::new("Doe,Jane");
+/// card.phone("0123456789");
+///
+/// assert_eq!(card.to_string(), r"MECARD:N:Doe\,Jane;TEL:0123456789;;");
+/// ```
+#[derive(Debug, Clone)]
+pub struct MeCard {
+    name: String,
+    phones: Vec<String>,
+    emails: Vec<String>,
+    url: Option<String>,
+    address: Option<String>,
+    note: Option<String>,
+}
+
+impl MeCard {
+    /// Characters that need to be escaped in a `MECARD:` payload
+    const SPECIAL: [char; 3] = [';', ',', ':'];
+
+    /// Creates a new card with the given name
+    #[must_use]
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        MeCard {
+            name: name.into(),
+            phones: Vec::new(),
+            emails: Vec::new(),
+            url: None,
+            address: None,
+            note: None,
+        }
+    }
+
+    /// Adds a phone number
+    pub fn phone<S: Into<String>>(&mut self, phone: S) -> &mut Self {
+        self.phones.push(phone.into());
+        self
+    }
+
+    /// Adds an email address
+    pub fn email<S: Into<String>>(&mut self, email: S) -> &mut Self {
+        self.emails.push(email.into());
+        self
+    }
+
+    /// Sets the website
+    pub fn url<S: Into<String>>(&mut self, url: S) -> &mut Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Sets the address
+    pub fn address<S: Into<String>>(&mut self, address: S) -> &mut Self {
+        self.address = Some(address.into());
+        self
+    }
+
+    /// Sets a free text note
+    pub fn note<S: Into<String>>(&mut self, note: S) -> &mut Self {
+        self.note = Some(note.into());
+        self
+    }
+}
+
+impl Display for MeCard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let escape = |value: &str| escape(value, &Self::SPECIAL);
+
+        write!(f, "MECARD:N:{};", escape(&self.name))?;
+        for phone in &self.phones {
+            write!(f, "TEL:{};", escape(phone))?;
+        }
+        for email in &self.emails {
+            write!(f, "EMAIL:{};", escape(email))?;
+        }
+        if let Some(url) = &self.url {
+            write!(f, "URL:{};", escape(url))?;
+        }
+        if let Some(address) = &self.address {
+            write!(f, "ADR:{};", escape(address))?;
+        }
+        if let Some(note) = &self.note {
+            write!(f, "NOTE:{};", escape(note))?;
+        }
+        f.write_str(";")
+    }
+}
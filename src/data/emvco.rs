@@ -0,0 +1,200 @@
+//! EMVCo merchant-presented payment payloads, `000201...6304ABCD`
+//!
+//! Each field is written as `ID` (2 digits), `length` (2 digits) and value, sorted by ID, and the
+//! payload ends with a CRC-16 of everything before it, referring to the EMV QR Code
+//! Specification for Payment Systems, Merchant-Presented Mode.
+
+use core::fmt::{Display, Formatter, Write};
+
+use alloc::{collections::BTreeMap, format, string::String};
+
+/// Longest value a 2 digits length can describe
+const MAX_LEN: usize = 99;
+
+/// Whether the QRCode is reused for every payment or shown for a single one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointOfInitiation {
+    /// Same QRCode for every payment, `11`
+    Static,
+    /// New QRCode for each payment, `12`
+    Dynamic,
+}
+
+/// EMVCo merchant-presented payment payload builder
+///
+/// The merchant name, city, country and currency are mandatory, the merchant category
+/// defaults to `0000`. Values longer than 99 characters are cut.
+///
+/// # Example
+/// ```rust
+/// use fast_qr::data::emvco::EmvcoBuilder;
+///
+/// let mut pix = EmvcoBuilder::new("Fulano de Tal", "BRASILIA", "BR", 986);
+/// pix.merchant_account_template(
+///     26,
+///     "br.gov.bcb.pix",
+///     &[(1, "123e4567-e12b-12d1-a456-426655440000")],
+/// )
+/// .reference_label("***");
+///
+/// assert_eq!(
+///     pix.to_string(),
+///     "00020126580014br.gov.bcb.pix0136123e4567-e12b-12d1-a456-426655440000\
+///      5204000053039865802BR5913Fulano de Tal6008BRASILIA62070503***63041D3D"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct EmvcoBuilder {
+    /// Fields by ID, the additional data (62) is kept apart
+    fields: BTreeMap<u8, String>,
+    /// Sub-fields of the additional data template (62) by ID
+    additional_data: BTreeMap<u8, String>,
+}
+
+impl EmvcoBuilder {
+    /// Creates a payload for a merchant, `country` is an ISO 3166-1 alpha-2 code (`"FR"`) and
+    /// `currency` an ISO 4217 numeric code (`978` for euros)
+    #[must_use]
+    pub fn new<N: Into<String>, C: Into<String>>(
+        merchant_name: N,
+        merchant_city: C,
+        country: &str,
+        currency: u16,
+    ) -> Self {
+        let mut fields = BTreeMap::new();
+        fields.insert(52, String::from("0000"));
+        fields.insert(53, format!("{currency:03}"));
+        fields.insert(58, String::from(country));
+        fields.insert(59, merchant_name.into());
+        fields.insert(60, merchant_city.into());
+
+        EmvcoBuilder {
+            fields,
+            additional_data: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the point of initiation method (ID `01`), omitted by default
+    pub fn point_of_initiation(&mut self, point: PointOfInitiation) -> &mut Self {
+        let value = match point {
+            PointOfInitiation::Static => "11",
+            PointOfInitiation::Dynamic => "12",
+        };
+        self.fields.insert(1, String::from(value));
+        self
+    }
+
+    /// Sets a merchant account assigned by a payment network (IDs `02` to `25`)
+    pub fn merchant_account<S: Into<String>>(&mut self, id: u8, value: S) -> &mut Self {
+        self.fields.insert(id, value.into());
+        self
+    }
+
+    /// Sets a merchant account template (IDs `26` to `51`): the globally unique identifier of
+    /// the payment system (`00`), followed by its own fields
+    pub fn merchant_account_template<G: Into<String>>(
+        &mut self,
+        id: u8,
+        guid: G,
+        fields: &[(u8, &str)],
+    ) -> &mut Self {
+        let mut template = String::new();
+        push_field(&mut template, 0, &guid.into());
+        for &(sub_id, value) in fields {
+            push_field(&mut template, sub_id, value);
+        }
+
+        self.fields.insert(id, template);
+        self
+    }
+
+    /// Sets the merchant category code, ISO 18245 (default: `0000`)
+    pub fn merchant_category(&mut self, code: u16) -> &mut Self {
+        self.fields.insert(52, format!("{code:04}"));
+        self
+    }
+
+    /// Sets the amount, with a `.` as decimal separator (`"12.50"`), omitted by default so
+    /// the payer enters it
+    pub fn amount<S: Into<String>>(&mut self, amount: S) -> &mut Self {
+        self.fields.insert(54, amount.into());
+        self
+    }
+
+    /// Sets the postal code of the merchant
+    pub fn postal_code<S: Into<String>>(&mut self, postal_code: S) -> &mut Self {
+        self.fields.insert(61, postal_code.into());
+        self
+    }
+
+    /// Sets the bill or invoice number, additional data `01`
+    pub fn bill_number<S: Into<String>>(&mut self, bill_number: S) -> &mut Self {
+        self.additional_data(1, bill_number)
+    }
+
+    /// Sets the reference label identifying the transaction, additional data `05`
+    pub fn reference_label<S: Into<String>>(&mut self, reference_label: S) -> &mut Self {
+        self.additional_data(5, reference_label)
+    }
+
+    /// Sets the terminal label, additional data `07`
+    pub fn terminal_label<S: Into<String>>(&mut self, terminal_label: S) -> &mut Self {
+        self.additional_data(7, terminal_label)
+    }
+
+    /// Sets any field of the additional data template (ID `62`)
+    pub fn additional_data<S: Into<String>>(&mut self, id: u8, value: S) -> &mut Self {
+        self.additional_data.insert(id, value.into());
+        self
+    }
+}
+
+/// Appends `id`, the length and the value, cut to [`MAX_LEN`] characters
+fn push_field(out: &mut String, id: u8, value: &str) {
+    let value = match value.char_indices().nth(MAX_LEN) {
+        Some((end, _)) => &value[..end],
+        None => value,
+    };
+    // Writing to a String never fails
+    let _ = write!(out, "{id:02}{:02}{value}", value.chars().count());
+}
+
+/// CRC-16/CCITT-FALSE: polynomial `0x1021`, initial value `0xFFFF`
+pub(crate) fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFF_u16;
+    for &byte in data {
+        crc ^= u16::from(byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+impl Display for EmvcoBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut payload = String::new();
+        push_field(&mut payload, 0, "01");
+        for (&id, value) in self.fields.range(1..62) {
+            push_field(&mut payload, id, value);
+        }
+        if !self.additional_data.is_empty() {
+            let mut template = String::new();
+            for (&id, value) in &self.additional_data {
+                push_field(&mut template, id, value);
+            }
+            push_field(&mut payload, 62, &template);
+        }
+        for (&id, value) in self.fields.range(64..) {
+            push_field(&mut payload, id, value);
+        }
+
+        // The CRC covers its own ID and length
+        payload.push_str("6304");
+        write!(f, "{payload}{:04X}", crc16(payload.as_bytes()))
+    }
+}
@@ -0,0 +1,246 @@
+//! EPC SEPA credit transfers ("GiroCode"), one field per line starting with `BCD`
+//!
+//! Referring to EPC069-12, Quick Response Code: Guidelines to Enable Data Capture for the
+//! Initiation of a SEPA Credit Transfer.
+
+use core::fmt::{Display, Formatter};
+
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
+use crate::{QRBuilder, Version, ECL};
+
+/// Longest payload in bytes
+const MAX_PAYLOAD: usize = 331;
+
+/// Remittance information, either a creditor reference or free text
+#[derive(Debug, Clone)]
+enum Remittance {
+    Reference(String),
+    Text(String),
+}
+
+/// Field of an [`EpcBuilder`] that does not follow EPC069-12
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpcError {
+    /// Beneficiary name is empty or longer than 70 characters
+    Name,
+    /// IBAN is empty, longer than 34 characters or not alphanumeric
+    Iban,
+    /// BIC is not 8 or 11 alphanumeric characters
+    Bic,
+    /// Amount is not between EUR 0.01 and EUR 999999999.99
+    Amount,
+    /// Purpose is not 4 alphanumeric characters
+    Purpose,
+    /// Creditor reference is longer than 35 characters, or text longer than 140
+    Remittance,
+    /// Beneficiary to originator information is longer than 70 characters
+    Information,
+    /// Payload is longer than 331 bytes
+    TooLong,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EpcError {}
+
+impl Display for EpcError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EpcError::Name => f.write_str("Name must be 1 to 70 characters"),
+            EpcError::Iban => f.write_str("IBAN must be 1 to 34 alphanumeric characters"),
+            EpcError::Bic => f.write_str("BIC must be 8 or 11 alphanumeric characters"),
+            EpcError::Amount => f.write_str("Amount must be between EUR 0.01 and 999999999.99"),
+            EpcError::Purpose => f.write_str("Purpose must be 4 alphanumeric characters"),
+            EpcError::Remittance => {
+                f.write_str("Reference must be up to 35 characters, text up to 140")
+            }
+            EpcError::Information => f.write_str("Information must be up to 70 characters"),
+            EpcError::TooLong => f.write_str("Payload is longer than 331 bytes"),
+        }
+    }
+}
+
+/// EPC069-12 SEPA credit transfer builder, version `002` in UTF-8
+///
+/// [`Display`] writes the fields as given, use [`EpcBuilder::validate`] or
+/// [`EpcBuilder::qr_builder`] to check their lengths.
+///
+/// # Example
+/// ```rust
+/// use fast_qr::data::epc::EpcBuilder;
+///
+/// let mut transfer = EpcBuilder::new("Red Cross", "BE72 0000 0001 6116");
+/// transfer
+///     .bic("BPOTBEB1")
+///     .amount_cents(1050)
+///     .text("Donation");
+///
+/// assert_eq!(
+///     transfer.to_string(),
+///     "BCD\n002\n1\nSCT\nBPOTBEB1\nRed Cross\nBE72000000016116\nEUR10.50\n\n\nDonation"
+/// );
+///
+/// let qrcode = transfer.qr_builder().unwrap().build().unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct EpcBuilder {
+    name: String,
+    iban: String,
+    bic: Option<String>,
+    amount_cents: Option<u64>,
+    purpose: Option<String>,
+    remittance: Option<Remittance>,
+    information: Option<String>,
+}
+
+impl EpcBuilder {
+    /// Creates a transfer to the beneficiary `name`, spaces in `iban` are removed
+    #[must_use]
+    pub fn new<N: Into<String>>(name: N, iban: &str) -> Self {
+        EpcBuilder {
+            name: name.into(),
+            iban: iban.chars().filter(|c| !c.is_whitespace()).collect(),
+            bic: None,
+            amount_cents: None,
+            purpose: None,
+            remittance: None,
+            information: None,
+        }
+    }
+
+    /// Sets the BIC of the beneficiary bank, optional inside the EEA
+    pub fn bic<S: Into<String>>(&mut self, bic: S) -> &mut Self {
+        self.bic = Some(bic.into());
+        self
+    }
+
+    /// Sets the amount in euro cents, the payer enters it when omitted
+    pub fn amount_cents(&mut self, amount_cents: u64) -> &mut Self {
+        self.amount_cents = Some(amount_cents);
+        self
+    }
+
+    /// Sets the purpose code, ISO 20022 `ExternalPurpose1Code` (`"CHAR"`, `"GDDS"`...)
+    pub fn purpose<S: Into<String>>(&mut self, purpose: S) -> &mut Self {
+        self.purpose = Some(purpose.into());
+        self
+    }
+
+    /// Sets a structured creditor reference (ISO 11649), replaces [`EpcBuilder::text`]
+    pub fn reference<S: Into<String>>(&mut self, reference: S) -> &mut Self {
+        self.remittance = Some(Remittance::Reference(reference.into()));
+        self
+    }
+
+    /// Sets the unstructured remittance text, replaces [`EpcBuilder::reference`]
+    pub fn text<S: Into<String>>(&mut self, text: S) -> &mut Self {
+        self.remittance = Some(Remittance::Text(text.into()));
+        self
+    }
+
+    /// Sets a message shown to the payer
+    pub fn information<S: Into<String>>(&mut self, information: S) -> &mut Self {
+        self.information = Some(information.into());
+        self
+    }
+
+    /// Checks every field length, and the payload length
+    ///
+    /// # Errors
+    /// - The first [`EpcError`] found, in the order of the fields
+    pub fn validate(&self) -> Result<(), EpcError> {
+        let chars = |value: &str| value.chars().count();
+        let alphanumeric = |value: &str| value.chars().all(|c| c.is_ascii_alphanumeric());
+
+        if !(1..=70).contains(&chars(&self.name)) {
+            return Err(EpcError::Name);
+        }
+        if !(1..=34).contains(&self.iban.len()) || !alphanumeric(&self.iban) {
+            return Err(EpcError::Iban);
+        }
+        if let Some(bic) = &self.bic {
+            if !matches!(bic.len(), 8 | 11) || !alphanumeric(bic) {
+                return Err(EpcError::Bic);
+            }
+        }
+        if let Some(amount) = self.amount_cents {
+            if !(1..=99_999_999_999).contains(&amount) {
+                return Err(EpcError::Amount);
+            }
+        }
+        if let Some(purpose) = &self.purpose {
+            if purpose.len() != 4 || !alphanumeric(purpose) {
+                return Err(EpcError::Purpose);
+            }
+        }
+        match &self.remittance {
+            Some(Remittance::Reference(reference)) if chars(reference) > 35 => {
+                return Err(EpcError::Remittance)
+            }
+            Some(Remittance::Text(text)) if chars(text) > 140 => return Err(EpcError::Remittance),
+            _ => {}
+        }
+        if matches!(&self.information, Some(information) if chars(information) > 70) {
+            return Err(EpcError::Information);
+        }
+
+        if self.to_string().len() > MAX_PAYLOAD {
+            return Err(EpcError::TooLong);
+        }
+        Ok(())
+    }
+
+    /// Returns a [`QRBuilder`] with the payload, at ECL M and up to version 13 as required
+    ///
+    /// # Errors
+    /// - Same as [`EpcBuilder::validate`]
+    pub fn qr_builder(&self) -> Result<QRBuilder, EpcError> {
+        self.validate()?;
+
+        let mut builder = QRBuilder::new(self);
+        builder
+            .ecl(ECL::M)
+            .disable_ecl_boost()
+            .version_range(Version::V01, Version::V13);
+        Ok(builder)
+    }
+}
+
+impl Display for EpcBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let amount = self
+            .amount_cents
+            .map(|cents| format!("EUR{}.{:02}", cents / 100, cents % 100))
+            .unwrap_or_default();
+        let (reference, text) = match &self.remittance {
+            Some(Remittance::Reference(reference)) => (reference.as_str(), ""),
+            Some(Remittance::Text(text)) => ("", text.as_str()),
+            None => ("", ""),
+        };
+
+        let fields = [
+            "BCD",
+            "002",
+            "1",
+            "SCT",
+            self.bic.as_deref().unwrap_or_default(),
+            &self.name,
+            &self.iban,
+            &amount,
+            self.purpose.as_deref().unwrap_or_default(),
+            reference,
+            text,
+            self.information.as_deref().unwrap_or_default(),
+        ];
+        // Trailing empty fields can be left out
+        let used = fields
+            .iter()
+            .rposition(|field| !field.is_empty())
+            .map_or(0, |last| last + 1);
+
+        f.write_str(&fields[..used].join("\n"))
+    }
+}
@@ -0,0 +1,105 @@
+//! Calendar events, iCalendar `VEVENT`
+
+use core::fmt::{Display, Formatter};
+
+use alloc::string::String;
+
+use super::escape;
+
+/// Longest line in octets, longer lines are folded, referring to RFC 5545 section 3.1
+const MAX_LINE: usize = 75;
+
+/// Calendar event following iCalendar (RFC 5545)
+///
+/// Dates are written as is, `YYYYMMDD` for a whole day or `YYYYMMDDTHHMMSS`, ending with `Z`
+/// for UTC.
+///
+/// # Example
+/// ```rust
+/// use fast_qr::data::Event;
+///
+/// let mut event = Event::new("Meetup; Rust", "20250612T180000Z");
+/// event.end("20250612T210000Z").location("Paris");
+///
+/// assert_eq!(
+///     event.to_string(),
+///     "BEGIN:VEVENT\r\nSUMMARY:Meetup\\; Rust\r\nDTSTART:20250612T180000Z\r\n\
+///      DTEND:20250612T210000Z\r\nLOCATION:Paris\r\nEND:VEVENT"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Event {
+    summary: String,
+    start: String,
+    end: Option<String>,
+    location: Option<String>,
+    description: Option<String>,
+}
+
+impl Event {
+    /// Creates an event starting at `start`
+    #[must_use]
+    pub fn new<S: Into<String>, D: Into<String>>(summary: S, start: D) -> Self {
+        Event {
+            summary: summary.into(),
+            start: start.into(),
+            end: None,
+            location: None,
+            description: None,
+        }
+    }
+
+    /// Sets when the event ends
+    pub fn end<S: Into<String>>(&mut self, end: S) -> &mut Self {
+        self.end = Some(end.into());
+        self
+    }
+
+    /// Sets where the event takes place
+    pub fn location<S: Into<String>>(&mut self, location: S) -> &mut Self {
+        self.location = Some(location.into());
+        self
+    }
+
+    /// Sets a free text description
+    pub fn description<S: Into<String>>(&mut self, description: S) -> &mut Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Writes `name:value` with the value escaped, folded every 75 octets
+    fn line(f: &mut Formatter<'_>, name: &str, value: &str) -> core::fmt::Result {
+        let value = escape(value, &[';', ',']).replace('\n', "\\n");
+        let line = [name, ":", &value].concat();
+
+        // Continuation lines start with a space, which counts in their length
+        let mut start = 0;
+        let mut width = MAX_LINE;
+        for (i, c) in line.char_indices() {
+            if i + c.len_utf8() - start > width {
+                write!(f, "{}\r\n ", &line[start..i])?;
+                start = i;
+                width = MAX_LINE - 1;
+            }
+        }
+        write!(f, "{}\r\n", &line[start..])
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("BEGIN:VEVENT\r\n")?;
+        Self::line(f, "SUMMARY", &self.summary)?;
+        Self::line(f, "DTSTART", &self.start)?;
+        if let Some(end) = &self.end {
+            Self::line(f, "DTEND", end)?;
+        }
+        if let Some(location) = &self.location {
+            Self::line(f, "LOCATION", location)?;
+        }
+        if let Some(description) = &self.description {
+            Self::line(f, "DESCRIPTION", description)?;
+        }
+        f.write_str("END:VEVENT")
+    }
+}
@@ -0,0 +1,64 @@
+//! Geographic location, `geo:48.8584,2.2945`
+
+use core::fmt::{Display, Formatter};
+
+use alloc::string::String;
+
+use super::percent_encode;
+
+/// Location following the `geo` URI scheme (RFC 5870)
+///
+/// # Example
+/// ```rust
+/// use fast_qr::data::Geo;
+///
+/// let mut geo = Geo::new(48.8584, 2.2945);
+/// geo.label("Eiffel Tower");
+///
+/// assert_eq!(geo.to_string(), "geo:48.8584,2.2945?q=Eiffel%20Tower");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Geo {
+    latitude: f64,
+    longitude: f64,
+    altitude: Option<f64>,
+    label: Option<String>,
+}
+
+impl Geo {
+    /// Creates a location from WGS 84 coordinates, in degrees
+    #[must_use]
+    pub const fn new(latitude: f64, longitude: f64) -> Self {
+        Geo {
+            latitude,
+            longitude,
+            altitude: None,
+            label: None,
+        }
+    }
+
+    /// Sets the altitude in meters
+    pub fn altitude(&mut self, altitude: f64) -> &mut Self {
+        self.altitude = Some(altitude);
+        self
+    }
+
+    /// Sets a name or search query for the place, understood by most map applications
+    pub fn label<S: Into<String>>(&mut self, label: S) -> &mut Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
+impl Display for Geo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "geo:{},{}", self.latitude, self.longitude)?;
+        if let Some(altitude) = self.altitude {
+            write!(f, ",{altitude}")?;
+        }
+        if let Some(label) = &self.label {
+            write!(f, "?q={}", percent_encode(label))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+//! GS1 element strings, `(01)09501101020917(10)ABC123` with `GS` separators
+
+use core::fmt::{Display, Formatter};
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{Fnc1, QRBuilder};
+
+/// Separates a variable length value from the next application identifier
+const GS: char = '\x1d';
+
+/// Application identifiers starting with these two digits have a predefined length and
+/// don't need a separator, referring to the GS1 General Specifications (7.8.5).
+const FIXED_LENGTH: [&str; 22] = [
+    "00", "01", "02", "03", "04", "11", "12", "13", "14", "15", "16", "17", "18", "19", "20", "31",
+    "32", "33", "34", "35", "36", "41",
+];
+
+/// GS1 element string builder, joins application identifiers (AI) and their values
+///
+/// The [`QRCode`](crate::QRCode) needs an FNC1 in first position to be read as GS1 data,
+/// use [`Gs1Builder::qr_builder`] rather than [`QRBuilder::new`].
+///
+/// # Example
+/// ```rust
+/// use fast_qr::data::Gs1Builder;
+///
+/// let mut gs1 = Gs1Builder::new();
+/// gs1.ai("01", "09501101020917").ai("10", "ABC123").ai("17", "251231");
+///
+/// // Batch number (10) has a variable length, a `GS` ends it
+/// assert_eq!(gs1.to_string(), "010950110102091710ABC123\x1d17251231");
+///
+/// let qrcode = gs1.qr_builder().build().unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Gs1Builder {
+    elements: Vec<(String, String)>,
+}
+
+impl Gs1Builder {
+    /// Creates an empty element string
+    #[must_use]
+    pub fn new() -> Self {
+        Gs1Builder::default()
+    }
+
+    /// Appends an application identifier (`"01"`, `"3103"`...) and its value
+    pub fn ai<A: Into<String>, V: Into<String>>(&mut self, ai: A, value: V) -> &mut Self {
+        self.elements.push((ai.into(), value.into()));
+        self
+    }
+
+    /// Returns a [`QRBuilder`] with the element string and an FNC1 in first position
+    #[must_use]
+    pub fn qr_builder(&self) -> QRBuilder {
+        let mut builder = QRBuilder::new(self);
+        builder.fnc1(Fnc1::First);
+        builder
+    }
+}
+
+impl Display for Gs1Builder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for (i, (ai, value)) in self.elements.iter().enumerate() {
+            let last = i + 1 == self.elements.len();
+            let fixed = FIXED_LENGTH.iter().any(|prefix| ai.starts_with(prefix));
+
+            write!(f, "{ai}{value}")?;
+            if !last && !fixed {
+                write!(f, "{GS}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
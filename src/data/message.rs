@@ -0,0 +1,126 @@
+//! Prefilled messages, `mailto:` email and `SMSTO:` text message
+
+use core::fmt::{Display, Formatter};
+
+use alloc::string::String;
+
+use super::percent_encode;
+
+/// Email draft following the `mailto` URI scheme (RFC 6068)
+///
+/// # Example
+/// ```rust
+/// use fast_qr::data::Email;
+///
+/// let mut email = Email::new("contact@example.com");
+/// email.subject("Hello & welcome").body("First line\nSecond line");
+///
+/// assert_eq!(
+///     email.to_string(),
+///     "mailto:contact@example.com?subject=Hello%20%26%20welcome&body=First%20line%0ASecond%20line"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Email {
+    to: String,
+    cc: Option<String>,
+    subject: Option<String>,
+    body: Option<String>,
+}
+
+impl Email {
+    /// Creates an email to `to`, several addresses are separated by commas
+    #[must_use]
+    pub fn new<S: Into<String>>(to: S) -> Self {
+        Email {
+            to: to.into(),
+            cc: None,
+            subject: None,
+            body: None,
+        }
+    }
+
+    /// Sets the carbon copy recipients, separated by commas
+    pub fn cc<S: Into<String>>(&mut self, cc: S) -> &mut Self {
+        self.cc = Some(cc.into());
+        self
+    }
+
+    /// Sets the subject
+    pub fn subject<S: Into<String>>(&mut self, subject: S) -> &mut Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    /// Sets the body
+    pub fn body<S: Into<String>>(&mut self, body: S) -> &mut Self {
+        self.body = Some(body.into());
+        self
+    }
+}
+
+impl Display for Email {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        // Addresses keep their `@` and `,`
+        write!(f, "mailto:{}", self.to)?;
+
+        let mut separator = '?';
+        let fields = [
+            ("cc", &self.cc),
+            ("subject", &self.subject),
+            ("body", &self.body),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value {
+                write!(f, "{separator}{name}={}", percent_encode(value))?;
+                separator = '&';
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Text message, in the `SMSTO:number:message` format read by most scanners
+///
+/// # Example
+/// ```rust
+/// use fast_qr::data::Sms;
+///
+/// let mut sms = Sms::new("+33123456789");
+/// sms.message("STOP: unsubscribe");
+///
+/// assert_eq!(sms.to_string(), "SMSTO:+33123456789:STOP: unsubscribe");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sms {
+    number: String,
+    message: Option<String>,
+}
+
+impl Sms {
+    /// Creates a text message to `number`
+    #[must_use]
+    pub fn new<S: Into<String>>(number: S) -> Self {
+        Sms {
+            number: number.into(),
+            message: None,
+        }
+    }
+
+    /// Sets the message, everything after the number is part of it so it needs no escaping
+    pub fn message<S: Into<String>>(&mut self, message: S) -> &mut Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+impl Display for Sms {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "SMSTO:{}", self.number)?;
+        if let Some(message) = &self.message {
+            write!(f, ":{message}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+//! Typed payloads that serialize to the strings QR scanners expect
+//!
+//! Every payload implements [`core::fmt::Display`] and can be given directly to
+//! [`crate::QRBuilder::new`].
+//!
+//! ```rust
+//! use fast_qr::data::{WifiCredentials, WifiSecurity};
+//! use fast_qr::QRBuilder;
+//!
+//! let mut wifi = WifiCredentials::new("My Network");
+//! wifi.security(WifiSecurity::Wpa).password("p4ss;word");
+//!
+//! assert_eq!(wifi.to_string(), r"WIFI:T:WPA;S:My Network;P:p4ss\;word;;");
+//!
+//! let qrcode = QRBuilder::new(&wifi).build().unwrap();
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+mod contact;
+pub mod emvco;
+pub mod epc;
+mod event;
+mod geo;
+mod gs1;
+mod message;
+pub mod otp;
+pub mod swissqr;
+mod url;
+mod wifi;
+
+pub use contact::{MeCard, VCard};
+pub use emvco::EmvcoBuilder;
+pub use epc::EpcBuilder;
+pub use event::Event;
+pub use geo::Geo;
+pub use gs1::Gs1Builder;
+pub use message::{Email, Sms};
+pub use otp::OtpAuth;
+pub use swissqr::SwissQrBuilder;
+pub use url::Url;
+pub use wifi::{WifiCredentials, WifiSecurity};
+
+/// Implements the conversions needed to give a payload to [`crate::QRBuilder::new`]
+macro_rules! impl_payload {
+    ($($payload:ty),* $(,)?) => {
+        $(
+            impl From<&$payload> for Vec<u8> {
+                fn from(payload: &$payload) -> Self {
+                    payload.to_string().into_bytes()
+                }
+            }
+
+            impl From<&mut $payload> for Vec<u8> {
+                fn from(payload: &mut $payload) -> Self {
+                    payload.to_string().into_bytes()
+                }
+            }
+
+            impl From<$payload> for Vec<u8> {
+                fn from(payload: $payload) -> Self {
+                    payload.to_string().into_bytes()
+                }
+            }
+        )*
+    };
+}
+
+impl_payload!(
+    WifiCredentials,
+    VCard,
+    MeCard,
+    Url,
+    Gs1Builder,
+    EmvcoBuilder,
+    OtpAuth,
+    Event,
+    Geo,
+    Email,
+    Sms,
+    EpcBuilder,
+    SwissQrBuilder,
+);
+
+/// Escapes every character of `special` (and `\`) with a backslash
+pub(crate) fn escape(input: &str, special: &[char]) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        if c == '\\' || special.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Percent-encodes everything but unreserved characters, referring to RFC 3986 section 2.3
+pub(crate) fn percent_encode(input: &str) -> String {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+    let mut out = String::with_capacity(input.len());
+    for &b in input.as_bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                out.push(b as char);
+            }
+            _ => {
+                out.push('%');
+                out.push(HEX[(b >> 4) as usize] as char);
+                out.push(HEX[(b & 0xF) as usize] as char);
+            }
+        }
+    }
+    out
+}
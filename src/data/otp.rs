@@ -0,0 +1,187 @@
+//! One-time password enrollment, `otpauth://totp/Issuer:account?secret=...&issuer=Issuer`
+//!
+//! Referring to the Key Uri Format of Google Authenticator, understood by most authenticator
+//! apps.
+
+use core::fmt::{Display, Formatter};
+
+use alloc::string::String;
+
+use super::percent_encode;
+
+/// RFC 4648 base32 alphabet
+const BASE32: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Hash function used to compute the codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtpAlgorithm {
+    /// HMAC-SHA1, the default
+    Sha1,
+    /// HMAC-SHA256
+    Sha256,
+    /// HMAC-SHA512
+    Sha512,
+}
+
+impl OtpAlgorithm {
+    const fn as_str(self) -> &'static str {
+        match self {
+            OtpAlgorithm::Sha1 => "SHA1",
+            OtpAlgorithm::Sha256 => "SHA256",
+            OtpAlgorithm::Sha512 => "SHA512",
+        }
+    }
+}
+
+/// Time-based (TOTP) or counter-based (HOTP) codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OtpKind {
+    Totp { period: u32 },
+    Hotp { counter: u64 },
+}
+
+/// `otpauth://` URI builder, parameters left to their default are omitted
+///
+/// # Example
+/// ```rust
+/// use fast_qr::data::otp::{OtpAlgorithm, OtpAuth};
+/// use fast_qr::QRBuilder;
+///
+/// let mut otp = OtpAuth::totp("alice@example.com", "jbsw y3dp ehpk 3pxp");
+/// otp.issuer("ACME Co").digits(8).algorithm(OtpAlgorithm::Sha256);
+///
+/// assert_eq!(
+///     otp.to_string(),
+///     "otpauth://totp/ACME%20Co:alice%40example.com?secret=JBSWY3DPEHPK3PXP\
+///      &issuer=ACME%20Co&algorithm=SHA256&digits=8"
+/// );
+///
+/// let qrcode = QRBuilder::new(&otp).build().unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct OtpAuth {
+    kind: OtpKind,
+    account: String,
+    secret: String,
+    issuer: Option<String>,
+    algorithm: OtpAlgorithm,
+    digits: u8,
+}
+
+impl OtpAuth {
+    /// Creates a time-based URI, `secret` is base32 (spaces, padding and case are ignored)
+    #[must_use]
+    pub fn totp<A: Into<String>>(account: A, secret: &str) -> Self {
+        OtpAuth::new(OtpKind::Totp { period: 30 }, account.into(), secret)
+    }
+
+    /// Creates a counter-based URI, `secret` is base32 (spaces, padding and case are ignored)
+    #[must_use]
+    pub fn hotp<A: Into<String>>(account: A, secret: &str, counter: u64) -> Self {
+        OtpAuth::new(OtpKind::Hotp { counter }, account.into(), secret)
+    }
+
+    fn new(kind: OtpKind, account: String, secret: &str) -> Self {
+        let secret = secret
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '=')
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+
+        OtpAuth {
+            kind,
+            account,
+            secret,
+            issuer: None,
+            algorithm: OtpAlgorithm::Sha1,
+            digits: 6,
+        }
+    }
+
+    /// Replaces the secret with raw bytes, encoded to base32
+    pub fn secret_bytes(&mut self, secret: &[u8]) -> &mut Self {
+        self.secret = base32(secret);
+        self
+    }
+
+    /// Sets the provider or service, shown next to the account
+    pub fn issuer<S: Into<String>>(&mut self, issuer: S) -> &mut Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    /// Sets the hash function (default: [`OtpAlgorithm::Sha1`])
+    pub fn algorithm(&mut self, algorithm: OtpAlgorithm) -> &mut Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Sets the length of the codes, 6 to 8 (default: 6)
+    pub fn digits(&mut self, digits: u8) -> &mut Self {
+        self.digits = digits.clamp(6, 8);
+        self
+    }
+
+    /// Sets how many seconds a TOTP code is valid (default: 30), ignored for HOTP
+    pub fn period(&mut self, period: u32) -> &mut Self {
+        if let OtpKind::Totp { period: current } = &mut self.kind {
+            *current = period.max(1);
+        }
+        self
+    }
+}
+
+/// Encodes `bytes` to base32, without padding
+pub(crate) fn base32(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity((bytes.len() * 8 + 4) / 5);
+    let mut buffer = 0u16;
+    let mut bits = 0;
+
+    for &byte in bytes {
+        buffer = (buffer << 8) | u16::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32[usize::from((buffer >> bits) & 0x1F)] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32[usize::from((buffer << (5 - bits)) & 0x1F)] as char);
+    }
+
+    out
+}
+
+impl Display for OtpAuth {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let kind = match self.kind {
+            OtpKind::Totp { .. } => "totp",
+            OtpKind::Hotp { .. } => "hotp",
+        };
+        write!(f, "otpauth://{kind}/")?;
+        if let Some(issuer) = &self.issuer {
+            write!(f, "{}:", percent_encode(issuer))?;
+        }
+        write!(
+            f,
+            "{}?secret={}",
+            percent_encode(&self.account),
+            self.secret
+        )?;
+
+        if let Some(issuer) = &self.issuer {
+            write!(f, "&issuer={}", percent_encode(issuer))?;
+        }
+        if self.algorithm != OtpAlgorithm::Sha1 {
+            write!(f, "&algorithm={}", self.algorithm.as_str())?;
+        }
+        if self.digits != 6 {
+            write!(f, "&digits={}", self.digits)?;
+        }
+        match self.kind {
+            OtpKind::Totp { period: 30 } => Ok(()),
+            OtpKind::Totp { period } => write!(f, "&period={period}"),
+            OtpKind::Hotp { counter } => write!(f, "&counter={counter}"),
+        }
+    }
+}
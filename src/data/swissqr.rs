@@ -0,0 +1,341 @@
+//! Swiss QR-bill payment part, one field per line starting with `SPC`
+//!
+//! Referring to the Swiss Implementation Guidelines for the QR-bill, version 2.2. The qr code
+//! itself is drawn with [`SvgBuilder::swiss_cross`](crate::convert::svg::SvgBuilder::swiss_cross).
+
+use core::fmt::{Display, Formatter};
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{QRBuilder, Version, ECL};
+
+/// Longest payload in characters
+const MAX_PAYLOAD: usize = 997;
+
+/// Currency of the bill
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwissCurrency {
+    /// Swiss franc, the default
+    Chf,
+    /// Euro
+    Eur,
+}
+
+/// Reference identifying the payment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SwissReference {
+    /// QR reference, 27 digits, required with a QR-IBAN
+    Qrr(String),
+    /// Creditor reference (ISO 11649), `RF` and up to 23 characters
+    Scor(String),
+    /// No reference, the default
+    Non,
+}
+
+/// Structured address of the creditor or the debtor
+///
+/// # Example
+/// ```rust
+/// use fast_qr::data::swissqr::SwissAddress;
+///
+/// let mut address = SwissAddress::new("Robert Schneider AG", "2501", "Biel", "CH");
+/// address.street("Rue du Lac").building_number("1268");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SwissAddress {
+    name: String,
+    street: Option<String>,
+    building_number: Option<String>,
+    postal_code: String,
+    town: String,
+    country: String,
+}
+
+impl SwissAddress {
+    /// Creates an address, `country` is an ISO 3166-1 alpha-2 code (`"CH"`)
+    #[must_use]
+    pub fn new<N: Into<String>, P: Into<String>, T: Into<String>, C: Into<String>>(
+        name: N,
+        postal_code: P,
+        town: T,
+        country: C,
+    ) -> Self {
+        SwissAddress {
+            name: name.into(),
+            street: None,
+            building_number: None,
+            postal_code: postal_code.into(),
+            town: town.into(),
+            country: country.into(),
+        }
+    }
+
+    /// Sets the street
+    pub fn street<S: Into<String>>(&mut self, street: S) -> &mut Self {
+        self.street = Some(street.into());
+        self
+    }
+
+    /// Sets the building number
+    pub fn building_number<S: Into<String>>(&mut self, building_number: S) -> &mut Self {
+        self.building_number = Some(building_number.into());
+        self
+    }
+
+    /// Returns the 7 lines of the address, starting with the address type
+    fn fields(&self) -> [&str; 7] {
+        [
+            "S",
+            &self.name,
+            self.street.as_deref().unwrap_or_default(),
+            self.building_number.as_deref().unwrap_or_default(),
+            &self.postal_code,
+            &self.town,
+            &self.country,
+        ]
+    }
+
+    fn is_valid(&self) -> bool {
+        let chars = |value: &str| value.chars().count();
+        let optional = |value: &Option<String>, max: usize| {
+            value.as_deref().map_or(true, |value| chars(value) <= max)
+        };
+
+        (1..=70).contains(&chars(&self.name))
+            && optional(&self.street, 70)
+            && optional(&self.building_number, 16)
+            && (1..=16).contains(&chars(&self.postal_code))
+            && (1..=35).contains(&chars(&self.town))
+            && self.country.len() == 2
+            && self.country.chars().all(|c| c.is_ascii_uppercase())
+    }
+}
+
+/// Field of a [`SwissQrBuilder`] that does not follow the QR-bill guidelines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwissQrError {
+    /// IBAN is not 21 alphanumeric characters starting with `CH` or `LI`
+    Iban,
+    /// Creditor address has an empty or too long field, or its country is not 2 letters
+    Creditor,
+    /// Debtor address has an empty or too long field, or its country is not 2 letters
+    Debtor,
+    /// Amount is not between 0.01 and 999999999.99
+    Amount,
+    /// Reference does not match its type, or the IBAN (a QR-IBAN needs a QR reference)
+    Reference,
+    /// Message is longer than 140 characters
+    Message,
+    /// Payload is longer than 997 characters
+    TooLong,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SwissQrError {}
+
+impl Display for SwissQrError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SwissQrError::Iban => f.write_str("IBAN must be a CH or LI IBAN"),
+            SwissQrError::Creditor => f.write_str("Invalid creditor address"),
+            SwissQrError::Debtor => f.write_str("Invalid debtor address"),
+            SwissQrError::Amount => f.write_str("Amount must be between 0.01 and 999999999.99"),
+            SwissQrError::Reference => f.write_str("Reference does not match its type or IBAN"),
+            SwissQrError::Message => f.write_str("Message must be up to 140 characters"),
+            SwissQrError::TooLong => f.write_str("Payload is longer than 997 characters"),
+        }
+    }
+}
+
+/// Swiss QR-bill payload builder, version `0200` with structured addresses
+///
+/// [`Display`] writes the fields as given, use [`SwissQrBuilder::validate`] or
+/// [`SwissQrBuilder::qr_builder`] to check them.
+///
+/// # Example
+/// ```rust
+/// use fast_qr::data::swissqr::{SwissAddress, SwissQrBuilder, SwissReference};
+///
+/// let creditor = SwissAddress::new("Robert Schneider AG", "2501", "Biel", "CH");
+/// let mut bill = SwissQrBuilder::new("CH44 3199 9123 0008 8901 2", creditor);
+/// bill.amount_cents(194_975)
+///     .reference(SwissReference::Qrr("210000000003139471430009017".into()))
+///     .message("Order of 15 June 2020");
+///
+/// // Draw it with `SvgBuilder::swiss_cross`
+/// let qrcode = bill.qr_builder().unwrap().build().unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SwissQrBuilder {
+    iban: String,
+    creditor: SwissAddress,
+    amount_cents: Option<u64>,
+    currency: SwissCurrency,
+    debtor: Option<SwissAddress>,
+    reference: SwissReference,
+    message: Option<String>,
+    billing_information: Option<String>,
+}
+
+impl SwissQrBuilder {
+    /// Creates a bill paid to `creditor` on `iban`, spaces in `iban` are removed
+    #[must_use]
+    pub fn new(iban: &str, creditor: SwissAddress) -> Self {
+        SwissQrBuilder {
+            iban: iban.chars().filter(|c| !c.is_whitespace()).collect(),
+            creditor,
+            amount_cents: None,
+            currency: SwissCurrency::Chf,
+            debtor: None,
+            reference: SwissReference::Non,
+            message: None,
+            billing_information: None,
+        }
+    }
+
+    /// Sets the amount in cents, the payer enters it when omitted
+    pub fn amount_cents(&mut self, amount_cents: u64) -> &mut Self {
+        self.amount_cents = Some(amount_cents);
+        self
+    }
+
+    /// Sets the currency (default: [`SwissCurrency::Chf`])
+    pub fn currency(&mut self, currency: SwissCurrency) -> &mut Self {
+        self.currency = currency;
+        self
+    }
+
+    /// Sets who pays the bill
+    pub fn debtor(&mut self, debtor: SwissAddress) -> &mut Self {
+        self.debtor = Some(debtor);
+        self
+    }
+
+    /// Sets the payment reference (default: [`SwissReference::Non`])
+    pub fn reference(&mut self, reference: SwissReference) -> &mut Self {
+        self.reference = reference;
+        self
+    }
+
+    /// Sets the unstructured message shown to the payer
+    pub fn message<S: Into<String>>(&mut self, message: S) -> &mut Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Sets the bill information for automated booking (`//S1/10/...`)
+    pub fn billing_information<S: Into<String>>(&mut self, billing_information: S) -> &mut Self {
+        self.billing_information = Some(billing_information.into());
+        self
+    }
+
+    /// Whether the IBAN is a QR-IBAN, its institution identifier being 30000 to 31999
+    fn is_qr_iban(&self) -> bool {
+        self.iban
+            .get(4..9)
+            .and_then(|iid| iid.parse::<u32>().ok())
+            .map_or(false, |iid| (30000..=31999).contains(&iid))
+    }
+
+    /// Checks every field, and the payload length
+    ///
+    /// # Errors
+    /// - The first [`SwissQrError`] found, in the order of the fields
+    pub fn validate(&self) -> Result<(), SwissQrError> {
+        let iban = self.iban.as_bytes();
+        if iban.len() != 21
+            || !(iban.starts_with(b"CH") || iban.starts_with(b"LI"))
+            || !iban.iter().all(u8::is_ascii_alphanumeric)
+        {
+            return Err(SwissQrError::Iban);
+        }
+        if !self.creditor.is_valid() {
+            return Err(SwissQrError::Creditor);
+        }
+        if matches!(self.amount_cents, Some(amount) if !(1..=99_999_999_999).contains(&amount)) {
+            return Err(SwissQrError::Amount);
+        }
+        if matches!(&self.debtor, Some(debtor) if !debtor.is_valid()) {
+            return Err(SwissQrError::Debtor);
+        }
+
+        let reference = match &self.reference {
+            SwissReference::Qrr(reference) => {
+                reference.len() == 27 && reference.bytes().all(|c| c.is_ascii_digit())
+            }
+            SwissReference::Scor(reference) => {
+                (5..=25).contains(&reference.len())
+                    && reference.starts_with("RF")
+                    && reference.bytes().all(|c| c.is_ascii_alphanumeric())
+            }
+            SwissReference::Non => true,
+        };
+        let qrr = matches!(self.reference, SwissReference::Qrr(_));
+        if !reference || qrr != self.is_qr_iban() {
+            return Err(SwissQrError::Reference);
+        }
+
+        if matches!(&self.message, Some(message) if message.chars().count() > 140) {
+            return Err(SwissQrError::Message);
+        }
+        if self.to_string().chars().count() > MAX_PAYLOAD {
+            return Err(SwissQrError::TooLong);
+        }
+        Ok(())
+    }
+
+    /// Returns a [`QRBuilder`] with the payload, at ECL M and up to version 25 as required
+    ///
+    /// # Errors
+    /// - Same as [`SwissQrBuilder::validate`]
+    pub fn qr_builder(&self) -> Result<QRBuilder, SwissQrError> {
+        self.validate()?;
+
+        let mut builder = QRBuilder::new(self);
+        builder
+            .ecl(ECL::M)
+            .disable_ecl_boost()
+            .version_range(Version::V01, Version::V25);
+        Ok(builder)
+    }
+}
+
+impl Display for SwissQrBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let amount = self
+            .amount_cents
+            .map(|cents| format!("{}.{:02}", cents / 100, cents % 100))
+            .unwrap_or_default();
+        let currency = match self.currency {
+            SwissCurrency::Chf => "CHF",
+            SwissCurrency::Eur => "EUR",
+        };
+        let (reference_type, reference) = match &self.reference {
+            SwissReference::Qrr(reference) => ("QRR", reference.as_str()),
+            SwissReference::Scor(reference) => ("SCOR", reference.as_str()),
+            SwissReference::Non => ("NON", ""),
+        };
+
+        let mut fields = Vec::with_capacity(32);
+        fields.extend(["SPC", "0200", "1", &self.iban]);
+        fields.extend(self.creditor.fields());
+        // Ultimate creditor, reserved for future use
+        fields.extend([""; 7]);
+        fields.extend([amount.as_str(), currency]);
+        match &self.debtor {
+            Some(debtor) => fields.extend(debtor.fields()),
+            None => fields.extend([""; 7]),
+        }
+        fields.extend([reference_type, reference]);
+        fields.extend([self.message.as_deref().unwrap_or_default(), "EPD"]);
+        if let Some(billing_information) = &self.billing_information {
+            fields.push(billing_information);
+        }
+
+        f.write_str(&fields.join("\n"))
+    }
+}
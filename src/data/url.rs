@@ -0,0 +1,60 @@
+//! URL with percent-encoded query parameters
+
+use core::fmt::{Display, Formatter};
+
+use alloc::{string::String, vec::Vec};
+
+use super::percent_encode;
+
+/// URL builder, appends query parameters with the right encoding
+///
+/// # Example
+/// ```rust
+/// use fast_qr::data::Url;
+///
+/// let mut url = Url::new("https://example.com/search");
+/// url.param("q", "fast qr").param("lang", "en&fr");
+///
+/// assert_eq!(url.to_string(), "https://example.com/search?q=fast%20qr&lang=en%26fr");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Url {
+    base: String,
+    params: Vec<(String, String)>,
+}
+
+impl Url {
+    /// Creates a new URL, `base` is written as is
+    #[must_use]
+    pub fn new<S: Into<String>>(base: S) -> Self {
+        Url {
+            base: base.into(),
+            params: Vec::new(),
+        }
+    }
+
+    /// Appends a query parameter, both `key` and `value` are percent-encoded
+    pub fn param<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) -> &mut Self {
+        self.params.push((key.into(), value.into()));
+        self
+    }
+}
+
+impl Display for Url {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.base)?;
+
+        let mut separator = if self.base.contains('?') { '&' } else { '?' };
+        for (key, value) in &self.params {
+            write!(
+                f,
+                "{separator}{}={}",
+                percent_encode(key),
+                percent_encode(value)
+            )?;
+            separator = '&';
+        }
+
+        Ok(())
+    }
+}
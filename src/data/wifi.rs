@@ -0,0 +1,101 @@
+//! WiFi network credentials, `WIFI:T:WPA;S:ssid;P:password;;`
+
+use core::fmt::{Display, Formatter};
+
+use alloc::string::String;
+
+use super::escape;
+
+/// Characters that need to be escaped in a `WIFI:` payload
+const SPECIAL: [char; 4] = [';', ',', ':', '"'];
+
+/// Authentication type of a WiFi network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WifiSecurity {
+    /// Open network, no password
+    None,
+    /// WEP
+    Wep,
+    /// WPA / WPA2 / WPA3 personal
+    Wpa,
+}
+
+impl WifiSecurity {
+    const fn as_str(self) -> &'static str {
+        match self {
+            WifiSecurity::None => "nopass",
+            WifiSecurity::Wep => "WEP",
+            WifiSecurity::Wpa => "WPA",
+        }
+    }
+}
+
+/// Credentials to join a WiFi network
+///
+/// # Example
+/// ```rust
+/// use fast_qr::data::{WifiCredentials, WifiSecurity};
+///
+/// let mut wifi = WifiCredentials::new("Home");
+/// wifi.password("secret").hidden(true);
+///
+/// assert_eq!(wifi.to_string(), "WIFI:T:WPA;S:Home;P:secret;H:true;;");
+/// ```
+#[derive(Debug, Clone)]
+pub struct WifiCredentials {
+    ssid: String,
+    password: Option<String>,
+    security: Option<WifiSecurity>,
+    hidden: bool,
+}
+
+impl WifiCredentials {
+    /// Creates credentials for network `ssid`, open until a password is given
+    #[must_use]
+    pub fn new<S: Into<String>>(ssid: S) -> Self {
+        WifiCredentials {
+            ssid: ssid.into(),
+            password: None,
+            security: None,
+            hidden: false,
+        }
+    }
+
+    /// Sets the password, security defaults to [`WifiSecurity::Wpa`]
+    pub fn password<S: Into<String>>(&mut self, password: S) -> &mut Self {
+        self.password = Some(password.into());
+        self
+    }
+
+    /// Forces the security type
+    pub fn security(&mut self, security: WifiSecurity) -> &mut Self {
+        self.security = Some(security);
+        self
+    }
+
+    /// Marks the network as hidden (does not broadcast its SSID)
+    pub fn hidden(&mut self, hidden: bool) -> &mut Self {
+        self.hidden = hidden;
+        self
+    }
+}
+
+impl Display for WifiCredentials {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let security = match (self.security, &self.password) {
+            (Some(security), _) => security,
+            (None, Some(_)) => WifiSecurity::Wpa,
+            (None, None) => WifiSecurity::None,
+        };
+
+        write!(f, "WIFI:T:{};", security.as_str())?;
+        write!(f, "S:{};", escape(&self.ssid, &SPECIAL))?;
+        if let (Some(password), false) = (&self.password, security == WifiSecurity::None) {
+            write!(f, "P:{};", escape(password, &SPECIAL))?;
+        }
+        if self.hidden {
+            f.write_str("H:true;")?;
+        }
+        f.write_str(";")
+    }
+}
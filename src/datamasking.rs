@@ -8,6 +8,7 @@ use crate::QRCode;
 /// The different mask patterns. The mask pattern should only be applied to
 /// the data and error correction portion of the QR code.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Mask {
     /// QR code pattern n°0: `(x + y) % 2 == 0`.
     Checkerboard = 0,
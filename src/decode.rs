@@ -0,0 +1,447 @@
+//! Decodes a [`QRCode`] (or a matrix of booleans) back to its content, correcting errors
+//! with Reed-Solomon.
+//!
+//! Regular and Micro `QRCode`s are supported, in Numeric, Alphanumeric and Byte modes.
+//! ECI, Structured Append and FNC1 headers are skipped, the raw bytes are returned.
+//!
+//! ```rust
+//! use fast_qr::{decode, QRBuilder};
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//! let data = decode::decode(&qrcode).unwrap();
+//!
+//! assert_eq!(data, b"https://example.com/");
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use core::fmt::Formatter;
+
+use alloc::vec::Vec;
+
+use crate::datamasking::{self, Mask};
+use crate::micro::{self, MicroVersion};
+use crate::module::ModuleType;
+use crate::{default, hardcode, polynomials, Mode, QRCode, Version, ECL};
+
+const ALPHANUMERIC: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+const ECLS: [ECL; 4] = [ECL::L, ECL::M, ECL::Q, ECL::H];
+
+const MASKS: [Mask; 8] = [
+    Mask::Checkerboard,
+    Mask::HorizontalLines,
+    Mask::VerticalLines,
+    Mask::DiagonalLines,
+    Mask::LargeCheckerboard,
+    Mask::Fields,
+    Mask::Diamonds,
+    Mask::Meadow,
+];
+
+/// Format information with more differing bits can't be recovered
+const MAX_FORMAT_ERRORS: u32 = 3;
+
+/// Contains different error when a `QRCode` could not be decoded
+#[derive(Debug)]
+pub enum DecodeError {
+    /// Matrix is not square, or its size does not match any version
+    InvalidSize,
+    /// Format information is too damaged to be read
+    FormatInformation,
+    /// Too many errors to be corrected
+    TooManyErrors,
+    /// Data is not a valid bit stream (unsupported mode, truncated segment...)
+    InvalidData,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DecodeError::InvalidSize => f.write_str("Matrix size does not match any version"),
+            DecodeError::FormatInformation => f.write_str("Format information is unreadable"),
+            DecodeError::TooManyErrors => f.write_str("Too many errors to be corrected"),
+            DecodeError::InvalidData => f.write_str("Invalid data bit stream"),
+        }
+    }
+}
+
+/// Decodes a [`QRCode`], returns the encoded bytes
+///
+/// # Errors
+/// - `DecodeError::InvalidSize` if `qr.size` does not match any version
+/// - `DecodeError::FormatInformation` if the format information is unreadable
+/// - `DecodeError::TooManyErrors` if too many modules are wrong
+/// - `DecodeError::InvalidData` if the content uses an unsupported mode (Kanji)
+pub fn decode(qr: &QRCode) -> Result<Vec<u8>, DecodeError> {
+    decode_with(qr.size, |row, column| qr[row][column].value())
+}
+
+/// Decodes a square matrix of booleans (`true` being dark), without quiet zone
+///
+/// # Errors
+/// Same as [`decode`], `DecodeError::InvalidSize` if the matrix is not square
+pub fn decode_matrix<R: AsRef<[bool]>>(matrix: &[R]) -> Result<Vec<u8>, DecodeError> {
+    let size = matrix.len();
+    if matrix.iter().any(|row| row.as_ref().len() != size) {
+        return Err(DecodeError::InvalidSize);
+    }
+
+    decode_with(size, |row, column| matrix[row].as_ref()[column])
+}
+
+fn decode_with(size: usize, get: impl Fn(usize, usize) -> bool) -> Result<Vec<u8>, DecodeError> {
+    if let Some(&version) = Version::ALL.iter().find(|version| version.size() == size) {
+        return decode_regular(version, get);
+    }
+    if let Some(&version) = MicroVersion::ALL.iter().find(|v| v.size() == size) {
+        return decode_micro(version, get);
+    }
+
+    Err(DecodeError::InvalidSize)
+}
+
+/// Returns the candidate closest to `format_information`, if close enough
+fn closest_format<T: Copy>(
+    format_information: &[usize],
+    candidates: impl Iterator<Item = (usize, T)>,
+) -> Result<T, DecodeError> {
+    candidates
+        .map(|(candidate, value)| {
+            let distance = format_information
+                .iter()
+                .map(|&read| (read ^ candidate).count_ones())
+                .min()
+                .unwrap_or(u32::MAX);
+            (distance, value)
+        })
+        .min_by_key(|&(distance, _)| distance)
+        .filter(|&(distance, _)| distance <= MAX_FORMAT_ERRORS)
+        .map(|(_, value)| value)
+        .ok_or(DecodeError::FormatInformation)
+}
+
+/// Reads the data modules in placement order, `columns` gives the right column of each pair
+fn read_bits(qr: &QRCode, columns: impl Iterator<Item = usize>) -> Vec<bool> {
+    let mut bits = Vec::with_capacity(qr.size * qr.size);
+    let mut upward = true;
+
+    for x in columns {
+        for i in 0..qr.size {
+            let y = if upward { qr.size - 1 - i } else { i };
+
+            for x in [x, x - 1] {
+                if qr[y][x].module_type() == ModuleType::Data {
+                    bits.push(qr[y][x].value());
+                }
+            }
+        }
+
+        upward = !upward;
+    }
+
+    bits
+}
+
+/// Groups bits by 8, the last codeword being padded with zeroes
+fn to_codewords(bits: &[bool]) -> Vec<u8> {
+    bits.chunks(8)
+        .map(|chunk| {
+            let codeword = chunk
+                .iter()
+                .fold(0, |codeword, &bit| codeword << 1 | u8::from(bit));
+            codeword << (8 - chunk.len())
+        })
+        .collect()
+}
+
+/// Copies the data modules into `template` then removes the `mask`
+fn unmask(template: &mut QRCode, mask: Mask, get: &impl Fn(usize, usize) -> bool) {
+    for row in 0..template.size {
+        for column in 0..template.size {
+            if template[row][column].module_type() == ModuleType::Data {
+                template[row][column].set(get(row, column));
+            }
+        }
+    }
+
+    datamasking::mask(template, mask);
+}
+
+fn decode_regular(
+    version: Version,
+    get: impl Fn(usize, usize) -> bool,
+) -> Result<Vec<u8>, DecodeError> {
+    let n = version.size();
+
+    // Coordinates (row, column) of the bits 0 to 14 of both format information copies
+    let mut coordinates = [[(0, 0); 15]; 2];
+    for i in 0..=5 {
+        coordinates[0][i] = (i, 8);
+        coordinates[1][i] = (8, n - 1 - i);
+        coordinates[0][i + 9] = (8, 5 - i);
+        coordinates[1][i + 9] = (n - 6 + i, 8);
+    }
+    coordinates[0][6..=8].copy_from_slice(&[(7, 8), (8, 8), (8, 7)]);
+    coordinates[1][6..=8].copy_from_slice(&[(8, n - 7), (8, n - 8), (n - 7, 8)]);
+
+    let format_information = coordinates.map(|copy| {
+        copy.iter().enumerate().fold(0, |acc, (i, &(row, column))| {
+            acc | usize::from(get(row, column)) << i
+        })
+    });
+
+    let candidates = ECLS.iter().flat_map(|&ecl| {
+        MASKS.iter().map(move |&mask| {
+            let format = hardcode::ecm_to_format_information(ecl, mask) as usize;
+            (format, (ecl, mask))
+        })
+    });
+    let (ecl, mask) = closest_format(&format_information, candidates)?;
+
+    let mut qr = default::create_matrix(version);
+    unmask(&mut qr, mask, &get);
+
+    let columns = (0..6).chain(7..n).rev().step_by(2);
+    let bits = read_bits(&qr, columns);
+    let codewords = to_codewords(&bits[..version.max_bytes() * 8]);
+
+    // De-interleaves the blocks, referring to 7.6 of the spec
+    let [(g1_count, g1_size), (g2_count, g2_size)] = hardcode::ecc_to_groups(ecl, version);
+    let error_len = hardcode::get_polynomial(version, ecl).len() - 1;
+    let sizes = core::iter::repeat(g1_size)
+        .take(g1_count)
+        .chain(core::iter::repeat(g2_size).take(g2_count))
+        .collect::<Vec<usize>>();
+
+    let mut blocks = sizes
+        .iter()
+        .map(|size| Vec::with_capacity(size + error_len))
+        .collect::<Vec<Vec<u8>>>();
+    let mut codewords = codewords.into_iter();
+
+    for i in 0..core::cmp::max(g1_size, g2_size) {
+        for (block, &size) in blocks.iter_mut().zip(&sizes) {
+            if i < size {
+                block.extend(codewords.next());
+            }
+        }
+    }
+    for _ in 0..error_len {
+        for block in &mut blocks {
+            block.extend(codewords.next());
+        }
+    }
+
+    let mut data = Vec::with_capacity(hardcode::data_codewords(version, ecl));
+    for block in &mut blocks {
+        polynomials::correct(block, error_len).ok_or(DecodeError::TooManyErrors)?;
+        data.extend_from_slice(&block[..block.len() - error_len]);
+    }
+
+    let mut reader = BitReader::new(&data, data.len() * 8);
+    let mut out = Vec::new();
+
+    while reader.remaining() >= 4 {
+        let mode = match reader.read(4)? {
+            0b0000 => break,
+            0b0001 => Mode::Numeric,
+            0b0010 => Mode::Alphanumeric,
+            0b0100 => Mode::Byte,
+            // ECI designator, 8, 16 or 24 bits depending on its first bits
+            0b0111 => {
+                let remaining = match reader.read(8)? {
+                    0b0000_0000..=0b0111_1111 => 0,
+                    0b1000_0000..=0b1011_1111 => 8,
+                    _ => 16,
+                };
+                reader.read(remaining)?;
+                continue;
+            }
+            // Structured Append header
+            0b0011 => {
+                reader.read(16)?;
+                continue;
+            }
+            // FNC1 in first position
+            0b0101 => continue,
+            // FNC1 in second position, followed by the application indicator
+            0b1001 => {
+                reader.read(8)?;
+                continue;
+            }
+            _ => return Err(DecodeError::InvalidData),
+        };
+
+        let count = reader.read(hardcode::cci_bits(version, mode))?;
+        decode_characters(&mut reader, mode, count, &mut out)?;
+    }
+
+    Ok(out)
+}
+
+fn decode_micro(
+    version: MicroVersion,
+    get: impl Fn(usize, usize) -> bool,
+) -> Result<Vec<u8>, DecodeError> {
+    // Bit 14 is the closest to the bottom left
+    let mut format_information = 0;
+    for i in 0..8 {
+        format_information |= usize::from(get(8, i + 1)) << (14 - i);
+    }
+    for i in 0..7 {
+        format_information |= usize::from(get(i + 1, 8)) << i;
+    }
+
+    let candidates = MicroVersion::ALL.iter().flat_map(|&version| {
+        ECLS.iter()
+            .filter(move |&&ecl| version.data_bits(ecl).is_some())
+            .flat_map(move |&ecl| {
+                micro::MASKS.iter().map(move |&mask| {
+                    let mask_number = micro::mask_number(mask).unwrap();
+                    let format = micro::format_information(version, ecl, mask_number);
+                    (format, (version, ecl, mask))
+                })
+            })
+    });
+    let (read_version, ecl, mask) = closest_format(&[format_information], candidates)?;
+    if read_version as usize != version as usize {
+        return Err(DecodeError::FormatInformation);
+    }
+
+    let mut qr = micro::create_matrix(version);
+    unmask(&mut qr, mask, &get);
+
+    let bits = read_bits(&qr, (1..qr.size).rev().step_by(2));
+
+    let data_bits = version.data_bits(ecl).unwrap();
+    let data_len = (data_bits + 7) / 8;
+    let error_len = version.codewords() - data_len;
+
+    // The final data codeword of M1 and M3 only holds 4 bits
+    let mut codewords = to_codewords(&bits[..data_bits]);
+    codewords.extend(to_codewords(&bits[data_bits..data_bits + error_len * 8]));
+
+    polynomials::correct(&mut codewords, error_len).ok_or(DecodeError::TooManyErrors)?;
+
+    let mut reader = BitReader::new(&codewords, data_bits);
+    let mut out = Vec::new();
+
+    while reader.remaining() > 0 {
+        let terminator = core::cmp::min(version.terminator_bits(), reader.remaining());
+        if reader.peek(terminator)? == 0 {
+            break;
+        }
+
+        let mode = match reader.read(version.mode_bits())? {
+            0b00 => Mode::Numeric,
+            0b01 => Mode::Alphanumeric,
+            0b10 => Mode::Byte,
+            _ => return Err(DecodeError::InvalidData),
+        };
+
+        let cci_bits = version.cci_bits(mode).ok_or(DecodeError::InvalidData)?;
+        let count = reader.read(cci_bits)?;
+        decode_characters(&mut reader, mode, count, &mut out)?;
+    }
+
+    Ok(out)
+}
+
+/// Decodes `count` characters encoded in `mode`, referring to 8.4.2 to 8.4.4 of the spec
+fn decode_characters(
+    reader: &mut BitReader,
+    mode: Mode,
+    count: usize,
+    out: &mut Vec<u8>,
+) -> Result<(), DecodeError> {
+    fn push_digits(out: &mut Vec<u8>, value: usize, digits: usize) -> Result<(), DecodeError> {
+        if value >= 10usize.pow(digits as u32) {
+            return Err(DecodeError::InvalidData);
+        }
+
+        for i in (0..digits).rev() {
+            out.push(b'0' + (value / 10usize.pow(i as u32) % 10) as u8);
+        }
+        Ok(())
+    }
+
+    fn push_alphanumeric(out: &mut Vec<u8>, value: usize) -> Result<(), DecodeError> {
+        let c = ALPHANUMERIC.get(value).ok_or(DecodeError::InvalidData)?;
+        out.push(*c);
+        Ok(())
+    }
+
+    match mode {
+        Mode::Numeric => {
+            for _ in 0..count / 3 {
+                push_digits(out, reader.read(10)?, 3)?;
+            }
+            match count % 3 {
+                2 => push_digits(out, reader.read(7)?, 2)?,
+                1 => push_digits(out, reader.read(4)?, 1)?,
+                _ => {}
+            }
+        }
+        Mode::Alphanumeric => {
+            for _ in 0..count / 2 {
+                let value = reader.read(11)?;
+                push_alphanumeric(out, value / 45)?;
+                push_alphanumeric(out, value % 45)?;
+            }
+            if count % 2 == 1 {
+                push_alphanumeric(out, reader.read(6)?)?;
+            }
+        }
+        Mode::Byte => {
+            for _ in 0..count {
+                out.push(reader.read(8)? as u8);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads bits one after the other, most significant bit first
+struct BitReader<'a> {
+    data: &'a [u8],
+    len: usize,
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8], len: usize) -> Self {
+        BitReader {
+            data,
+            len,
+            position: 0,
+        }
+    }
+
+    const fn remaining(&self) -> usize {
+        self.len - self.position
+    }
+
+    fn peek(&self, bits: usize) -> Result<usize, DecodeError> {
+        if bits > self.remaining() {
+            return Err(DecodeError::InvalidData);
+        }
+
+        let value = (self.position..self.position + bits).fold(0, |value, i| {
+            let bit = self.data[i / 8] >> (7 - i % 8) & 1;
+            value << 1 | bit as usize
+        });
+        Ok(value)
+    }
+
+    fn read(&mut self, bits: usize) -> Result<usize, DecodeError> {
+        let value = self.peek(bits)?;
+        self.position += bits;
+        Ok(value)
+    }
+}
@@ -4,10 +4,11 @@
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
-use std::fmt::Write;
+use core::fmt::Write;
 
 /// Error Correction Coding has 4 levels
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(dead_code)]
 #[cfg_attr(feature = "wasm-bindgen", wasm_bindgen::prelude::wasm_bindgen)]
 pub enum ECL {
@@ -1,8 +1,31 @@
 //! Contains all functions required to encode any string as a `QRCode`
+//!
+//! The segment-level API computes encoded lengths and writes the bit stream of regular
+//! `QRCode`s, referring to 7.4 and 8.4 of the spec, for capacity planning without the spec
+//! tables:
+//!
+//! ```rust
+//! use fast_qr::encode::{self, BitWriter};
+//! use fast_qr::{Version, ECL};
+//!
+//! let segments = encode::optimal_segments(b"ORDER 0123456789", Version::V01);
+//! let bits: usize = segments.iter().map(|segment| segment.bits(Version::V01)).sum();
+//! assert_eq!(bits, 94);
+//! assert!(bits <= encode::data_bits(Version::V01, ECL::M));
+//!
+//! let mut writer = BitWriter::new();
+//! for segment in &segments {
+//!     encode::encode_segment(&mut writer, segment, Version::V01);
+//! }
+//! assert_eq!(writer.len(), bits);
+//! ```
 
 #![deny(unsafe_code)]
+#![deny(clippy::unwrap_used)]
 #![warn(missing_docs)]
 
+use alloc::{vec, vec::Vec};
+
 use crate::compact::CompactQR;
 use crate::ecl::ECL;
 use crate::hardcode;
@@ -10,6 +33,7 @@ use crate::version::Version;
 
 /// Enum for the 3 encoding mode
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Mode {
     /// Numeric mode (0-9 only)
     Numeric,
@@ -19,25 +43,524 @@ pub enum Mode {
     Byte,
 }
 
-/// Encodes the string according the mode and version
-pub fn encode(input: &[u8], ecl: ECL, mode: Mode, version: Version) -> CompactQR {
-    let cci_bits = hardcode::cci_bits(version, mode);
+/// Extended Channel Interpretation designators, tells the scanner which charset the data uses,
+/// referring to 8.4.1 of the spec.
+///
+/// The input is not transcoded, it should already be encoded in the given charset.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EciCode {
+    /// Code page 437
+    Cp437 = 2,
+    /// ISO/IEC 8859-1, Latin-1
+    Iso8859_1 = 3,
+    /// ISO/IEC 8859-2, Latin-2
+    Iso8859_2 = 4,
+    /// ISO/IEC 8859-5, Latin/Cyrillic
+    Iso8859_5 = 7,
+    /// ISO/IEC 8859-7, Latin/Greek
+    Iso8859_7 = 9,
+    /// ISO/IEC 8859-15, Latin-9
+    Iso8859_15 = 17,
+    /// Shift JIS
+    ShiftJis = 20,
+    /// Windows-1252
+    Cp1252 = 23,
+    /// UTF-8
+    Utf8 = 26,
+    /// US-ASCII
+    Ascii = 27,
+    /// Big5
+    Big5 = 28,
+    /// GB 18030
+    Gb18030 = 29,
+    /// EUC-KR
+    EucKr = 30,
+}
+
+impl EciCode {
+    /// Returns the ECI assignment number
+    #[must_use]
+    pub const fn value(self) -> u32 {
+        self as u32
+    }
+}
 
-    let mut compact = CompactQR::from_version(version);
+/// FNC1 indicator, flags the data as formatted according to an industry standard,
+/// referring to 7.4.8 of the spec.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Fnc1 {
+    /// The data follows the GS1 General Specifications, see [`crate::data::Gs1Builder`]
+    First,
+    /// The data follows an industry application specification identified by the application
+    /// indicator: `0` to `99`, or the ASCII value of a letter plus `100` (`b'a' + 100`)
+    Second(u8),
+}
+
+/// Structured Append header, links up to 16 `QRCode`s together, referring to 8.3 of the spec.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct StructuredAppend {
+    /// Position of the symbol, starting at 0
+    pub(crate) index: u8,
+    /// Total number of symbols
+    pub(crate) total: u8,
+    /// XOR of every byte of the complete (unsplit) data
+    pub(crate) parity: u8,
+}
+
+/// Headers written before the data segments
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub(crate) struct Headers {
+    pub(crate) structured_append: Option<StructuredAppend>,
+    pub(crate) eci: Option<EciCode>,
+    pub(crate) fnc1: Option<Fnc1>,
+}
+
+impl Headers {
+    /// Returns the number of bits used by the headers
+    pub(crate) const fn bits(&self) -> usize {
+        let structured_append = match self.structured_append {
+            Some(_) => 4 + 4 + 4 + 8,
+            None => 0,
+        };
+
+        let fnc1 = match self.fnc1 {
+            Some(Fnc1::First) => 4,
+            Some(Fnc1::Second(_)) => 4 + 8,
+            None => 0,
+        };
+
+        structured_append + eci_bits(self.eci) + fnc1
+    }
+}
+
+/// Returns the number of bits used by the ECI header, referring to 8.4.1.1 of the spec
+pub(crate) const fn eci_bits(eci: Option<EciCode>) -> usize {
+    match eci {
+        None => 0,
+        Some(eci) => match eci.value() {
+            0..=127 => 4 + 8,
+            128..=16383 => 4 + 16,
+            _ => 4 + 24,
+        },
+    }
+}
 
+/// Returns the number of bits used by `len` characters encoded in `mode`, without mode indicator
+/// nor character count indicator
+#[must_use]
+pub const fn character_bits(mode: Mode, len: usize) -> usize {
     match mode {
-        Mode::Numeric => encode_numeric(&mut compact, input, cci_bits),
-        Mode::Alphanumeric => encode_alphanumeric(&mut compact, input, cci_bits),
-        Mode::Byte => encode_byte(&mut compact, input, cci_bits),
-    };
+        Mode::Numeric => len / 3 * 10 + [0, 4, 7][len % 3],
+        Mode::Alphanumeric => len / 2 * 11 + (len % 2) * 6,
+        Mode::Byte => len * 8,
+    }
+}
+
+/// Returns the number of bits of a segment of `len` characters, mode indicator and
+/// character count indicator included
+#[must_use]
+pub const fn segment_bits(mode: Mode, len: usize, version: Version) -> usize {
+    4 + hardcode::cci_bits(version, mode) + character_bits(mode, len)
+}
+
+/// Returns the length of the character count indicator of `mode` in `version`, referring to
+/// Table 3 of the spec
+#[must_use]
+pub const fn cci_bits(version: Version, mode: Mode) -> usize {
+    hardcode::cci_bits(version, mode)
+}
+
+/// Returns the number of data bits of `version` and `ecl`, headers and segments included,
+/// referring to Table 7 of the spec
+#[must_use]
+pub const fn data_bits(version: Version, ecl: ECL) -> usize {
+    hardcode::data_bits(version, ecl)
+}
+
+/// Bits written most significant first, like the bit stream of a `QRCode`
+///
+/// # Example
+/// ```rust
+/// use fast_qr::encode::BitWriter;
+///
+/// let mut writer = BitWriter::new();
+/// writer.push_bits(0b0100, 4);
+/// writer.push_bytes(b"A");
+/// assert_eq!(writer.to_string(), "010001000001");
+/// assert_eq!(writer.as_bytes(), [0b0100_0100, 0b0001_0000]);
+/// ```
+#[derive(Clone)]
+pub struct BitWriter {
+    compact: CompactQR,
+}
+
+impl BitWriter {
+    /// Creates an empty writer
+    #[must_use]
+    pub const fn new() -> Self {
+        BitWriter {
+            compact: CompactQR::new(),
+        }
+    }
+
+    /// Writes the `len` lowest bits of `bits`, most significant first
+    ///
+    /// # Panics
+    /// If `len` is greater than 32
+    pub fn push_bits(&mut self, bits: usize, len: usize) {
+        assert!(len <= 32, "Cannot push more than 32 bits at once");
+        self.compact.push_bits(bits, len);
+    }
+
+    /// Writes every bit of `bytes`
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        self.compact.push_u8_slice(bytes);
+    }
+
+    /// Returns the number of bits written
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.compact.len()
+    }
+
+    /// Returns whether no bit was written
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.compact.len() == 0
+    }
+
+    /// Returns the bits packed most significant first, the last byte padded with zeros
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.compact.get_data()[..(self.len() + 7) / 8]
+    }
+}
+
+impl Default for BitWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Debug for BitWriter {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "BitWriter({self})")
+    }
+}
+
+/// Writes the bits as `0`s and `1`s
+impl core::fmt::Display for BitWriter {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let bytes = self.as_bytes();
+        for i in 0..self.len() {
+            let bit = bytes[i / 8] & (0x80 >> (i % 8)) != 0;
+            f.write_str(if bit { "1" } else { "0" })?;
+        }
+        Ok(())
+    }
+}
+
+/// A part of the input encoded with a single [`Mode`], referring to 8.4 of the spec.
+///
+/// # Example
+/// ```rust
+/// use fast_qr::{Mode, QRBuilder, Segment};
+///
+/// let segments = vec![
+///     Segment::new(Mode::Alphanumeric, "ORDER-").unwrap(),
+///     Segment::new(Mode::Numeric, "0123456789").unwrap(),
+/// ];
+///
+/// let qrcode = QRBuilder::new("").segments(segments).build();
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Segment {
+    mode: Mode,
+    data: Vec<u8>,
+    /// Number of pre-encoded bits packed in `data`, `None` for regular segments
+    raw_bits: Option<usize>,
+}
+
+impl Segment {
+    /// Creates a segment, returns `None` if `data` cannot be represented in `mode`
+    #[must_use]
+    pub fn new<D: Into<Vec<u8>>>(mode: Mode, data: D) -> Option<Self> {
+        let data = data.into();
+        if !data.iter().all(|&c| is_encodable(mode, c)) {
+            return None;
+        }
+
+        Some(Segment {
+            mode,
+            data,
+            raw_bits: None,
+        })
+    }
+
+    /// Creates a pre-encoded segment, the mode indicator of `mode` is written before `bits`,
+    /// which are placed verbatim into the bit stream.
+    ///
+    /// `bits` must contain the character count indicator, whose length depends on the version
+    /// (see 8.4.1 of the spec): forcing the version with [`crate::QRBuilder::version`] is advised.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::{Mode, QRBuilder, Version};
+    ///
+    /// // "hi" in Byte mode: 8 bits character count, then the bytes
+    /// let bits = [2u8, b'h', b'i']
+    ///     .iter()
+    ///     .flat_map(|byte| (0..8).rev().map(move |i| byte & (1 << i) != 0))
+    ///     .collect::<Vec<bool>>();
+    ///
+    /// let qrcode = QRBuilder::new("")
+    ///     .raw_segment(Mode::Byte, &bits)
+    ///     .version(Version::V01)
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn raw(mode: Mode, bits: &[bool]) -> Self {
+        let mut data = vec![0; (bits.len() + 7) / 8];
+        for (i, _) in bits.iter().enumerate().filter(|(_, &bit)| bit) {
+            data[i / 8] |= 1 << (7 - i % 8);
+        }
+
+        Segment {
+            mode,
+            data,
+            raw_bits: Some(bits.len()),
+        }
+    }
+
+    /// Creates a segment without checking `data` is representable in `mode`
+    pub(crate) fn new_unchecked(mode: Mode, data: Vec<u8>) -> Self {
+        Segment {
+            mode,
+            data,
+            raw_bits: None,
+        }
+    }
+
+    /// Returns the `Mode` of the segment
+    #[must_use]
+    pub const fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns the data of the segment, the bits packed most significant first for
+    /// pre-encoded segments
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns whether the segment was created with [`Segment::raw`]
+    #[must_use]
+    pub const fn is_raw(&self) -> bool {
+        self.raw_bits.is_some()
+    }
+
+    /// Returns the number of bits of the segment for a given `version`, mode indicator and
+    /// character count indicator included
+    #[must_use]
+    pub fn bits(&self, version: Version) -> usize {
+        match self.raw_bits {
+            Some(len) => 4 + len,
+            None => segment_bits(self.mode, self.data.len(), version),
+        }
+    }
+}
+
+/// Encodes the segments according to the version in `compact`, reusing its allocation
+pub(crate) fn encode_into(
+    compact: &mut CompactQR,
+    segments: &[Segment],
+    ecl: ECL,
+    version: Version,
+    headers: Headers,
+) {
+    compact.reset(version);
+
+    if let Some(structured_append) = headers.structured_append {
+        encode_structured_append(compact, structured_append);
+    }
+    if let Some(eci) = headers.eci {
+        encode_eci(compact, eci);
+    }
+    if let Some(fnc1) = headers.fnc1 {
+        encode_fnc1(compact, fnc1);
+    }
+
+    for segment in segments {
+        encode_segment_into(compact, segment, version);
+    }
 
     let data_bits = hardcode::data_bits(version, ecl);
 
-    add_terminator(&mut compact, data_bits);
-    pad_to_8(&mut compact);
+    add_terminator(compact, data_bits);
+    pad_to_8(compact);
     compact.fill();
+}
 
-    compact
+/// Writes `segment` for `version`: mode indicator, character count indicator and characters,
+/// [`Segment::bits`] long
+pub fn encode_segment(writer: &mut BitWriter, segment: &Segment, version: Version) {
+    encode_segment_into(&mut writer.compact, segment, version);
+}
+
+fn encode_segment_into(compact: &mut CompactQR, segment: &Segment, version: Version) {
+    if let Some(len) = segment.raw_bits {
+        encode_raw(compact, segment.mode, &segment.data, len);
+        return;
+    }
+
+    let cci_bits = hardcode::cci_bits(version, segment.mode);
+    let input = &segment.data[..];
+
+    match segment.mode {
+        Mode::Numeric => encode_numeric(compact, input, cci_bits),
+        Mode::Alphanumeric => encode_alphanumeric(compact, input, cci_bits),
+        Mode::Byte => encode_byte(compact, input, cci_bits),
+    };
+}
+
+/// Moves Alphanumeric segments containing `%` to Byte mode: with FNC1, `%` stands for the `GS`
+/// separator in Alphanumeric mode, referring to 7.4.8.1 of the spec.
+pub(crate) fn escape_fnc1(segments: &mut [Segment]) {
+    for segment in segments {
+        if segment.mode == Mode::Alphanumeric && !segment.is_raw() && segment.data.contains(&b'%') {
+            segment.mode = Mode::Byte;
+        }
+    }
+}
+
+/// Returns the smallest version, starting at `from`, that can contain `segments` and
+/// `extra_bits` (i.e. an ECI header) at `ecl`
+#[must_use]
+pub fn smallest_version(
+    segments: &[Segment],
+    ecl: ECL,
+    extra_bits: usize,
+    from: Version,
+) -> Option<Version> {
+    Version::ALL[from as usize..]
+        .iter()
+        .copied()
+        .find(|&version| {
+            let bits: usize = segments.iter().map(|segment| segment.bits(version)).sum();
+            bits + extra_bits <= hardcode::data_bits(version, ecl)
+        })
+}
+
+/// Splits `input` in segments using as few bits as possible for `version`,
+/// referring to Annex J of the spec.
+///
+/// Costs are counted in sixths of a bit so that Numeric (10 bits / 3 chars) and
+/// Alphanumeric (11 bits / 2 chars) characters have integer costs.
+#[must_use]
+pub fn optimal_segments(input: &[u8], version: Version) -> Vec<Segment> {
+    const MODES: [Mode; 3] = [Mode::Byte, Mode::Alphanumeric, Mode::Numeric];
+    const CHAR_COSTS: [usize; 3] = [8 * 6, 33, 20];
+
+    if input.is_empty() {
+        return vec![Segment::new_unchecked(best_encoding(input), Vec::new())];
+    }
+
+    let head_costs = MODES.map(|mode| (4 + hardcode::cci_bits(version, mode)) * 6);
+
+    // `modes[i][m]` is the mode of character `i` in the cheapest encoding of
+    // `input[..=i]` after which the next character is encoded in `MODES[m]`
+    let mut modes: Vec<[Option<Mode>; 3]> = Vec::with_capacity(input.len());
+    let mut costs = head_costs;
+
+    for &c in input {
+        let mut current_costs = [usize::MAX; 3];
+        let mut current_modes = [None; 3];
+
+        // Extends the current segment
+        for (m, &mode) in MODES.iter().enumerate() {
+            if is_encodable(mode, c) && costs[m] != usize::MAX {
+                current_costs[m] = costs[m] + CHAR_COSTS[m];
+                current_modes[m] = Some(mode);
+            }
+        }
+
+        // Or ends it to start a new segment after this character
+        let extended_costs = current_costs;
+        for (to, &head_cost) in head_costs.iter().enumerate() {
+            for (from, &cost) in extended_costs.iter().enumerate() {
+                if cost == usize::MAX {
+                    continue;
+                }
+
+                let switch_cost = (cost + 5) / 6 * 6 + head_cost;
+                if switch_cost < current_costs[to] {
+                    current_costs[to] = switch_cost;
+                    current_modes[to] = Some(MODES[from]);
+                }
+            }
+        }
+
+        modes.push(current_modes);
+        costs = current_costs;
+    }
+
+    // Traces back the cheapest path, starting from the cheapest final mode
+    let mut best = 0;
+    for m in 1..MODES.len() {
+        if modes[input.len() - 1][m].is_some() && costs[m] < costs[best] {
+            best = m;
+        }
+    }
+
+    let mut mode = MODES[best];
+    let mut char_modes = vec![Mode::Byte; input.len()];
+    for i in (0..input.len()).rev() {
+        let index = MODES.iter().position(|&m| m == mode).unwrap_or(0);
+        mode = modes[i][index].unwrap_or(Mode::Byte);
+        char_modes[i] = mode;
+    }
+
+    let mut segments: Vec<Segment> = Vec::new();
+    for (&c, &mode) in input.iter().zip(char_modes.iter()) {
+        match segments.last_mut() {
+            Some(segment) if segment.mode == mode => segment.data.push(c),
+            _ => segments.push(Segment::new_unchecked(mode, vec![c])),
+        }
+    }
+
+    segments
+}
+
+/// Returns the best segmentation of `input` along with the smallest version containing it and
+/// `extra_bits` at `ecl`, `None` if it does not fit in a `QRCode`
+#[must_use]
+pub fn best_segments(input: &[u8], ecl: ECL, extra_bits: usize) -> Option<(Vec<Segment>, Version)> {
+    // Character count indicators only change size between these version ranges
+    const RANGES: [(Version, Version); 3] = [
+        (Version::V01, Version::V09),
+        (Version::V10, Version::V26),
+        (Version::V27, Version::V40),
+    ];
+
+    for (first, last) in RANGES {
+        let segments = optimal_segments(input, first);
+        match smallest_version(&segments, ecl, extra_bits, first) {
+            Some(version) if version as usize <= last as usize => return Some((segments, version)),
+            _ => continue,
+        }
+    }
+
+    None
+}
+
+/// Returns whether `c` can be encoded in `mode`
+const fn is_encodable(mode: Mode, c: u8) -> bool {
+    match mode {
+        Mode::Numeric => c.is_ascii_digit(),
+        Mode::Alphanumeric => is_qr_alphanumeric(c),
+        Mode::Byte => true,
+    }
 }
 
 /// Find the best encoding (Numeric -> Alnum -> Byte)
@@ -63,8 +586,71 @@ pub fn best_encoding(input: &[u8]) -> Mode {
     try_encode_numeric(input, 0)
 }
 
+/// Encodes the Structured Append header, referring to 8.3 of the spec.
+pub(crate) fn encode_structured_append(compact: &mut CompactQR, header: StructuredAppend) {
+    compact.push_bits(0b0011, 4);
+    compact.push_bits(header.index as usize, 4);
+    compact.push_bits(header.total as usize - 1, 4);
+    compact.push_bits(header.parity as usize, 8);
+}
+
+/// Encodes the ECI header, referring to 8.4.1.1 of the spec.
+pub(crate) fn encode_eci(compact: &mut CompactQR, eci: EciCode) {
+    let value = eci.value() as usize;
+
+    compact.push_bits(0b0111, 4);
+    match value {
+        0..=127 => compact.push_bits(value, 8),
+        128..=16383 => compact.push_bits(0b10 << 14 | value, 16),
+        _ => compact.push_bits(0b110 << 21 | value, 24),
+    }
+}
+
+/// Encodes the FNC1 indicator, referring to 7.4.8 of the spec.
+pub(crate) fn encode_fnc1(compact: &mut CompactQR, fnc1: Fnc1) {
+    match fnc1 {
+        Fnc1::First => compact.push_bits(0b0101, 4),
+        Fnc1::Second(indicator) => {
+            compact.push_bits(0b1001, 4);
+            compact.push_bits(indicator as usize, 8);
+        }
+    }
+}
+
+/// Encodes a pre-encoded segment: the mode indicator followed by the first `len` bits of `data`
+fn encode_raw(compact: &mut CompactQR, mode: Mode, data: &[u8], len: usize) {
+    let indicator = match mode {
+        Mode::Numeric => 0b0001,
+        Mode::Alphanumeric => 0b0010,
+        Mode::Byte => 0b0100,
+    };
+    compact.push_bits(indicator, 4);
+
+    compact.push_u8_slice(&data[..len / 8]);
+    if len % 8 != 0 {
+        compact.push_bits(data[len / 8] as usize >> (8 - len % 8), len % 8);
+    }
+}
+
 /// Encodes numeric strings (i.e. "123456789"), referring to 8.4.2 of the spec.
 pub(crate) fn encode_numeric(compact: &mut CompactQR, input: &[u8], cci_bits: usize) {
+    compact.push_bits(0b0001, 4);
+    compact.push_bits(input.len(), cci_bits);
+    encode_numeric_characters(compact, input);
+}
+
+/// Encodes the characters of `input` in the given `mode`, without mode indicator nor character
+/// count indicator.
+pub(crate) fn encode_characters(compact: &mut CompactQR, mode: Mode, input: &[u8]) {
+    match mode {
+        Mode::Numeric => encode_numeric_characters(compact, input),
+        Mode::Alphanumeric => encode_alphanumeric_characters(compact, input),
+        Mode::Byte => compact.push_u8_slice(input),
+    }
+}
+
+/// Encodes digits by groups of three, referring to 8.4.2 of the spec.
+fn encode_numeric_characters(compact: &mut CompactQR, input: &[u8]) {
     #[derive(Clone, Copy)]
     enum NumericEncoding {
         Single,
@@ -80,9 +666,6 @@ pub(crate) fn encode_numeric(compact: &mut CompactQR, input: &[u8], cci_bits: us
         }
     }
 
-    compact.push_bits(0b0001, 4);
-    compact.push_bits(input.len(), cci_bits);
-
     let mut i = 0;
     let len = input.len() - input.len() % 3;
 
@@ -120,15 +703,19 @@ pub(crate) fn encode_numeric(compact: &mut CompactQR, input: &[u8], cci_bits: us
 pub(crate) fn encode_alphanumeric(compact: &mut CompactQR, input: &[u8], cci_bits: usize) {
     compact.push_bits(0b0010, 4);
     compact.push_bits(input.len(), cci_bits);
+    encode_alphanumeric_characters(compact, input);
+}
 
-    let even_size = input.len() - input.len() % 2;
-    for chunk in input.chunks_exact(2) {
-        let a = ascii_to_alphanumeric(chunk[0]);
-        let b = ascii_to_alphanumeric(chunk[1]);
-        compact.push_bits(a * 45 + b, 11);
-    }
-    if even_size != input.len() {
-        compact.push_bits(ascii_to_alphanumeric(*input.last().unwrap()), 6);
+/// Encodes alphanumeric characters by pairs, referring to 8.4.3 of the spec.
+fn encode_alphanumeric_characters(compact: &mut CompactQR, input: &[u8]) {
+    for chunk in input.chunks(2) {
+        match *chunk {
+            [a, b] => {
+                compact.push_bits(ascii_to_alphanumeric(a) * 45 + ascii_to_alphanumeric(b), 11)
+            }
+            [a] => compact.push_bits(ascii_to_alphanumeric(a), 6),
+            _ => {}
+        }
     }
 }
 
@@ -0,0 +1,186 @@
+//! C bindings (`ffi` feature), the header is `include/fast_qr.h`.
+//!
+//! Outputs are written to caller-provided buffers: functions return the needed length, and only
+//! write when the buffer is large enough, so a first call with a null buffer gives the size.
+//!
+//! ```c
+//! #include "fast_qr.h"
+//!
+//! const char *url = "https://example.com/";
+//! FastQrCode *qr = fast_qr_build((const uint8_t *)url, strlen(url), FAST_QR_ECL_AUTO, 0);
+//!
+//! size_t len = fast_qr_to_svg(qr, NULL, 0);
+//! char *svg = malloc(len);
+//! fast_qr_to_svg(qr, (uint8_t *)svg, len);
+//!
+//! free(svg);
+//! fast_qr_free(qr);
+//! ```
+
+#![warn(missing_docs)]
+
+use crate::convert::svg::SvgBuilder;
+use crate::{QRBuilder, QRCode, Version, ECL};
+
+/// Lets `fast_qr_build` choose the error correction level
+pub const FAST_QR_ECL_AUTO: i32 = -1;
+/// Low error correction level, 7%
+pub const FAST_QR_ECL_L: i32 = 0;
+/// Medium error correction level, 15%
+pub const FAST_QR_ECL_M: i32 = 1;
+/// Quartile error correction level, 25%
+pub const FAST_QR_ECL_Q: i32 = 2;
+/// High error correction level, 30%
+pub const FAST_QR_ECL_H: i32 = 3;
+
+/// Opaque [`QRCode`], created by [`fast_qr_build`] and released by [`fast_qr_free`]
+pub struct FastQrCode(QRCode);
+
+/// Copies `bytes` to `buffer` when `len` is large enough, returns the length of `bytes`
+///
+/// # Safety
+/// `buffer` must be null or valid for `len` bytes
+unsafe fn write_buffer(bytes: &[u8], buffer: *mut u8, len: usize) -> usize {
+    if !buffer.is_null() && len >= bytes.len() {
+        core::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, bytes.len());
+    }
+
+    bytes.len()
+}
+
+/// Builds a QR code of `data`, returns null if the data is too long for `version`.
+///
+/// `ecl` is one of the `FAST_QR_ECL_*` constants, `version` goes from 1 to 40 or is 0 to use
+/// the smallest fitting version.
+///
+/// # Safety
+/// `data` must be valid for `len` bytes, it can be null when `len` is 0
+#[no_mangle]
+pub unsafe extern "C" fn fast_qr_build(
+    data: *const u8,
+    len: usize,
+    ecl: i32,
+    version: i32,
+) -> *mut FastQrCode {
+    let data = if len == 0 {
+        &[]
+    } else {
+        core::slice::from_raw_parts(data, len)
+    };
+
+    let ecl = match ecl {
+        FAST_QR_ECL_AUTO => None,
+        FAST_QR_ECL_L => Some(ECL::L),
+        FAST_QR_ECL_M => Some(ECL::M),
+        FAST_QR_ECL_Q => Some(ECL::Q),
+        FAST_QR_ECL_H => Some(ECL::H),
+        _ => return core::ptr::null_mut(),
+    };
+    let version = match version {
+        0 => None,
+        _ => match Version::try_from(version as usize) {
+            Ok(version) => Some(version),
+            Err(_) => return core::ptr::null_mut(),
+        },
+    };
+
+    let mut builder = QRBuilder::new(data);
+    if let Some(ecl) = ecl {
+        builder.ecl(ecl);
+    }
+    if let Some(version) = version {
+        builder.version(version);
+    }
+
+    match builder.build() {
+        Ok(qr) => Box::into_raw(Box::new(FastQrCode(qr))),
+        Err(_) => core::ptr::null_mut(),
+    }
+}
+
+/// Releases a QR code returned by [`fast_qr_build`], does nothing if `qr` is null
+///
+/// # Safety
+/// `qr` must come from [`fast_qr_build`] and not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn fast_qr_free(qr: *mut FastQrCode) {
+    if !qr.is_null() {
+        drop(Box::from_raw(qr));
+    }
+}
+
+/// Returns the width & height of the QR code in modules, without margin
+///
+/// # Safety
+/// `qr` must come from [`fast_qr_build`]
+#[no_mangle]
+pub unsafe extern "C" fn fast_qr_size(qr: *const FastQrCode) -> usize {
+    (*qr).0.size
+}
+
+/// Returns whether the module at row `y`, column `x` is dark, false when out of bounds
+///
+/// # Safety
+/// `qr` must come from [`fast_qr_build`]
+#[no_mangle]
+pub unsafe extern "C" fn fast_qr_module(qr: *const FastQrCode, y: usize, x: usize) -> bool {
+    let qr = &(*qr).0;
+    y < qr.size && x < qr.size && qr[y][x].value()
+}
+
+/// Writes the modules row by row, 1 for dark and 0 for light, returns `size * size`
+///
+/// # Safety
+/// `qr` must come from [`fast_qr_build`], `buffer` must be null or valid for `len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn fast_qr_modules(
+    qr: *const FastQrCode,
+    buffer: *mut u8,
+    len: usize,
+) -> usize {
+    let qr = &(*qr).0;
+    let modules = qr.data[..qr.size * qr.size]
+        .iter()
+        .map(|module| u8::from(module.value()))
+        .collect::<Vec<_>>();
+
+    write_buffer(&modules, buffer, len)
+}
+
+/// Writes the SVG as a null-terminated string, returns its length including the terminator
+///
+/// # Safety
+/// `qr` must come from [`fast_qr_build`], `buffer` must be null or valid for `len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn fast_qr_to_svg(
+    qr: *const FastQrCode,
+    buffer: *mut u8,
+    len: usize,
+) -> usize {
+    let mut svg = SvgBuilder::default().to_str(&(*qr).0).into_bytes();
+    svg.push(0);
+
+    write_buffer(&svg, buffer, len)
+}
+
+/// Writes a PNG fitting in `width` pixels, returns its length, or 0 if encoding failed
+///
+/// # Safety
+/// `qr` must come from [`fast_qr_build`], `buffer` must be null or valid for `len` bytes
+#[cfg(feature = "image")]
+#[no_mangle]
+pub unsafe extern "C" fn fast_qr_to_png(
+    qr: *const FastQrCode,
+    width: u32,
+    buffer: *mut u8,
+    len: usize,
+) -> usize {
+    let png = crate::convert::image::ImageBuilder::default()
+        .fit_width(width)
+        .to_bytes(&(*qr).0);
+
+    match png {
+        Ok(png) => write_buffer(&png, buffer, len),
+        Err(_) => 0,
+    }
+}
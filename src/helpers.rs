@@ -2,6 +2,8 @@
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
+use alloc::{format, string::String, vec::Vec};
+
 use crate::module::Module;
 use crate::QRCode;
 
@@ -30,12 +32,18 @@ fn print_line(line1: &[Module], line2: &[Module], size: usize) -> String {
 
 /// Prints a matrix with margins
 pub fn print_matrix_with_margin(qr: &QRCode) -> String {
+    let rows = (0..qr.size).map(|i| &qr[i]).collect::<Vec<_>>();
+    print_rows_with_margin(&rows, qr.size)
+}
+
+/// Prints `width` modules wide rows with margins, there has to be an odd number of rows
+pub(crate) fn print_rows_with_margin(rows: &[&[Module]], width: usize) -> String {
     let mut out = String::new();
 
     let line = print_line(
         &[Module::empty(true); 177],
         &[Module::empty(false); 177],
-        qr.size,
+        width,
     );
 
     out.push(BOTTOM);
@@ -43,14 +51,14 @@ pub fn print_matrix_with_margin(qr: &QRCode) -> String {
     out.push_str(&format!("{BOTTOM}\n"));
 
     // Black background
-    for i in (0..qr.size - 1).step_by(2) {
-        let line = print_line(&qr[i], &qr[i + 1], qr.size);
+    for pair in rows[..rows.len() - 1].chunks_exact(2) {
+        let line = print_line(pair[0], pair[1], width);
         out.push(BLOCK);
         out.push_str(&line);
         out.push_str(&format!("{BLOCK}\n"));
     }
 
-    let line = print_line(&qr[qr.size - 1], &[Module::empty(false); 177], qr.size);
+    let line = print_line(rows[rows.len() - 1], &[Module::empty(false); 177], width);
     out.push(BLOCK);
     out.push_str(&line);
     out.push(BLOCK);
@@ -1,4 +1,5 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 //! # Easy to use fast QRCode generator
 //!
@@ -27,10 +28,13 @@
 //! ## Converts [`QRCode`] to SVG
 //!
 //! ```rust
+//! # #[cfg(feature = "svg")]
 //! # use fast_qr::convert::ConvertError;
+//! # #[cfg(feature = "svg")]
 //! use fast_qr::convert::{svg::SvgBuilder, Builder, Shape};
 //! use fast_qr::qr::QRBuilder;
 //!
+//! # #[cfg(feature = "svg")]
 //! # fn main() -> Result<(), ConvertError> {
 //! // QRBuilder::new can fail if content is too big for version,
 //! // please check before unwrapping.
@@ -45,15 +49,20 @@
 //!
 //! #     Ok(())
 //! # }
+//! # #[cfg(not(feature = "svg"))]
+//! # fn main() {}
 //! ```
 //!
 //! ## Converts [`QRCode`] to an image
 //!
 //! ```rust
+//! # #[cfg(feature = "image")]
 //! # use fast_qr::convert::ConvertError;
+//! # #[cfg(feature = "image")]
 //! use fast_qr::convert::{image::ImageBuilder, Builder, Shape};
 //! use fast_qr::qr::QRBuilder;
 //!
+//! # #[cfg(feature = "image")]
 //! # fn main() -> Result<(), ConvertError> {
 //! // QRBuilder::new can fail if content is too big for version,
 //! // please check before unwrapping.
@@ -70,32 +79,56 @@
 //!
 //! #     Ok(())
 //! # }
+//! # #[cfg(not(feature = "image"))]
+//! # fn main() {}
 //! ```
 
+extern crate alloc;
+
 pub use crate::datamasking::Mask;
 pub use crate::ecl::ECL;
-pub use crate::encode::Mode;
+pub use crate::encode::{EciCode, Fnc1, Mode, Segment};
+pub use crate::micro::MicroVersion;
 pub use crate::module::{Module, ModuleType};
-pub use crate::qr::{QRBuilder, QRCode};
-pub use crate::version::Version;
+pub use crate::qr::{QRBuilder, QRCode, QRCodeWithQuietZone, QREncoder};
+pub use crate::score::{PenaltyScore, Scannability};
+pub use crate::version::{Version, VersionError};
 
+pub mod art;
 mod compact;
 #[doc(hidden)]
 pub mod datamasking;
 
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub mod convert;
+pub mod data;
+pub mod decode;
 mod default;
 mod ecl;
-mod encode;
+pub mod encode;
+#[cfg(feature = "ffi")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ffi")))]
+pub mod ffi;
 mod hardcode;
 #[cfg(not(feature = "wasm-bindgen"))]
 mod helpers;
+mod micro;
 mod module;
 mod placement;
 mod polynomials;
 #[macro_use]
 pub mod qr;
+pub mod rmqr;
+#[cfg(feature = "low-level")]
+#[cfg_attr(docsrs, doc(cfg(feature = "low-level")))]
+pub mod rs;
 mod score;
+#[cfg(feature = "simd")]
+mod simd;
+#[cfg(feature = "test-utils")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-utils")))]
+pub mod test_utils;
 mod version;
 
 #[cfg(test)]
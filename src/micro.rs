@@ -0,0 +1,386 @@
+//! Micro QR codes, smaller symbols (11x11 to 17x17) with a single finder pattern,
+//! referring to the Micro QR sections of the spec.
+//!
+//! They are created with [`crate::QRBuilder::micro`] and share the [`QRCode`] struct.
+
+#![deny(unsafe_code)]
+#![deny(clippy::unwrap_used)]
+#![warn(missing_docs)]
+
+use alloc::vec::Vec;
+
+use crate::compact::CompactQR;
+use crate::datamasking::{self, Mask};
+use crate::encode::{self, Mode, Segment};
+use crate::module::Module;
+use crate::{polynomials, QRCode, ECL};
+
+/// Enum containing all possible Micro `QRCode` versions
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MicroVersion {
+    /// Version M1 (11x11), numeric only and error detection only
+    M1 = 1,
+    /// Version M2 (13x13)
+    M2 = 2,
+    /// Version M3 (15x15)
+    M3 = 3,
+    /// Version M4 (17x17)
+    M4 = 4,
+}
+
+impl MicroVersion {
+    pub(crate) const ALL: [MicroVersion; 4] = [
+        MicroVersion::M1,
+        MicroVersion::M2,
+        MicroVersion::M3,
+        MicroVersion::M4,
+    ];
+
+    /// Returns the size of the matrix, width & height
+    #[must_use]
+    pub const fn size(self) -> usize {
+        self as usize * 2 + 9
+    }
+
+    /// Returns the number of data bits, `None` if `ecl` is not available for this version.
+    ///
+    /// M1 only detects errors, it is considered as `ECL::L`.
+    pub(crate) const fn data_bits(self, ecl: ECL) -> Option<usize> {
+        match (self, ecl) {
+            (MicroVersion::M1, ECL::L) => Some(20),
+            (MicroVersion::M2, ECL::L) => Some(40),
+            (MicroVersion::M2, ECL::M) => Some(32),
+            (MicroVersion::M3, ECL::L) => Some(84),
+            (MicroVersion::M3, ECL::M) => Some(68),
+            (MicroVersion::M4, ECL::L) => Some(128),
+            (MicroVersion::M4, ECL::M) => Some(112),
+            (MicroVersion::M4, ECL::Q) => Some(80),
+            _ => None,
+        }
+    }
+
+    /// Returns the total number of codewords, data and error correction
+    pub(crate) const fn codewords(self) -> usize {
+        match self {
+            MicroVersion::M1 => 5,
+            MicroVersion::M2 => 10,
+            MicroVersion::M3 => 17,
+            MicroVersion::M4 => 24,
+        }
+    }
+
+    /// Returns the symbol number used in the format information, expects `ecl` to be available
+    const fn symbol_number(self, ecl: ECL) -> usize {
+        match (self, ecl) {
+            (MicroVersion::M1, _) => 0,
+            (MicroVersion::M2, ECL::L) => 1,
+            (MicroVersion::M2, _) => 2,
+            (MicroVersion::M3, ECL::L) => 3,
+            (MicroVersion::M3, _) => 4,
+            (MicroVersion::M4, ECL::L) => 5,
+            (MicroVersion::M4, ECL::M) => 6,
+            (MicroVersion::M4, _) => 7,
+        }
+    }
+
+    /// Returns the length of the mode indicator
+    pub(crate) const fn mode_bits(self) -> usize {
+        self as usize - 1
+    }
+
+    /// Returns the length of the character count indicator, `None` if `mode` is not available
+    /// for this version
+    pub(crate) const fn cci_bits(self, mode: Mode) -> Option<usize> {
+        match (mode, self) {
+            (Mode::Numeric, _) => Some(self as usize + 2),
+            (Mode::Alphanumeric, MicroVersion::M1)
+            | (Mode::Byte, MicroVersion::M1 | MicroVersion::M2) => None,
+            (Mode::Alphanumeric | Mode::Byte, _) => Some(self as usize + 1),
+        }
+    }
+
+    /// Returns the length of the terminator
+    pub(crate) const fn terminator_bits(self) -> usize {
+        self as usize * 2 + 1
+    }
+}
+
+/// Returns the mask number used in the format information, `None` if `mask` is not
+/// available for Micro `QRCode`s
+pub(crate) const fn mask_number(mask: Mask) -> Option<usize> {
+    match mask {
+        Mask::HorizontalLines => Some(0),
+        Mask::LargeCheckerboard => Some(1),
+        Mask::Diamonds => Some(2),
+        Mask::Meadow => Some(3),
+        _ => None,
+    }
+}
+
+pub(crate) const MASKS: [Mask; 4] = [
+    Mask::HorizontalLines,
+    Mask::LargeCheckerboard,
+    Mask::Diamonds,
+    Mask::Meadow,
+];
+
+/// Returns the number of bits needed by `segments`, `None` if one of them can't be encoded
+/// in `version`
+fn segments_bits(segments: &[Segment], version: MicroVersion) -> Option<usize> {
+    let mut bits = 0;
+    for segment in segments {
+        let cci_bits = version.cci_bits(segment.mode())?;
+        if segment.data().len() >> cci_bits != 0 {
+            return None;
+        }
+
+        bits += version.mode_bits()
+            + cci_bits
+            + encode::character_bits(segment.mode(), segment.data().len());
+    }
+
+    Some(bits)
+}
+
+/// Returns the smallest version that can contain `segments`
+pub(crate) fn smallest_version(segments: &[Segment], ecl: ECL) -> Option<MicroVersion> {
+    MicroVersion::ALL.iter().copied().find(|&version| {
+        match (version.data_bits(ecl), segments_bits(segments, version)) {
+            (Some(data_bits), Some(bits)) => bits <= data_bits,
+            _ => false,
+        }
+    })
+}
+
+/// Encodes the segments, returns the data codewords.
+///
+/// The final data codeword of M1 and M3 only holds 4 bits, stored in its high nibble.
+fn encode(segments: &[Segment], ecl: ECL, version: MicroVersion) -> Vec<u8> {
+    const PAD_BYTES: [u8; 2] = [0b1110_1100, 0b0001_0001];
+
+    let data_bits = version
+        .data_bits(ecl)
+        .expect("Micro versions are only chosen for the ECLs they support");
+    let mut compact = CompactQR::new();
+
+    for segment in segments {
+        let indicator = match segment.mode() {
+            Mode::Numeric => 0b00,
+            Mode::Alphanumeric => 0b01,
+            Mode::Byte => 0b10,
+        };
+        let cci_bits = version
+            .cci_bits(segment.mode())
+            .expect("Micro versions are only chosen for the modes they support");
+
+        if version.mode_bits() != 0 {
+            compact.push_bits(indicator, version.mode_bits());
+        }
+        compact.push_bits(segment.data().len(), cci_bits);
+        encode::encode_characters(&mut compact, segment.mode(), segment.data());
+    }
+
+    let terminator = core::cmp::min(version.terminator_bits(), data_bits - compact.len());
+    compact.push_bits(0, terminator);
+    compact.push_bits(0, (8 - compact.len() % 8) % 8);
+
+    for &pad in PAD_BYTES.iter().cycle() {
+        if compact.len() + 8 > data_bits {
+            break;
+        }
+        compact.push_u8(pad);
+    }
+
+    // Final 4 bits codeword is `0000`
+    let codewords = (data_bits + 7) / 8;
+    while compact.len() < codewords * 8 {
+        compact.push_u8(0);
+    }
+
+    compact.get_data()[..codewords].to_vec()
+}
+
+/// Returns the format information, referring to the Micro QR format information section of the
+/// spec: BCH(15, 5) code masked with `0x4445`
+pub(crate) const fn format_information(version: MicroVersion, ecl: ECL, mask: usize) -> usize {
+    const GENERATOR: usize = 0b101_0011_0111;
+
+    let data = version.symbol_number(ecl) << 2 | mask;
+
+    let mut remainder = data << 10;
+    let mut i = 14;
+    while i >= 10 {
+        if remainder & (1 << i) != 0 {
+            remainder ^= GENERATOR << (i - 10);
+        }
+        i -= 1;
+    }
+
+    (data << 10 | remainder) ^ 0b100_0100_0100_0101
+}
+
+/// Creates the matrix with the finder pattern, separator, timing patterns and reserved format
+/// information
+pub(crate) fn create_matrix(version: MicroVersion) -> QRCode {
+    let size = version.size();
+    let mut qr = QRCode::default(size);
+
+    for y in 0..7 {
+        for x in 0..7 {
+            let border = x == 0 || x == 6 || y == 0 || y == 6;
+            let center = (2..=4).contains(&x) && (2..=4).contains(&y);
+            qr[y][x] = Module::finder_pattern(border || center);
+        }
+    }
+
+    for i in 0..8 {
+        qr[7][i] = Module::empty(Module::LIGHT);
+        qr[i][7] = Module::empty(Module::LIGHT);
+    }
+
+    for i in 8..size {
+        let value = i % 2 == 0;
+        qr[0][i] = Module::timing(value);
+        qr[i][0] = Module::timing(value);
+    }
+
+    for i in 1..=8 {
+        qr[8][i] = Module::format(Module::LIGHT);
+        qr[i][8] = Module::format(Module::LIGHT);
+    }
+
+    qr
+}
+
+/// Places the format information, bit 14 is the closest to the bottom left
+fn place_format_information(qr: &mut QRCode, format_information: usize) {
+    for i in 0..8 {
+        let value = format_information & (1 << (14 - i)) != 0;
+        qr[8][i + 1] = Module::format(value);
+    }
+
+    for i in 0..7 {
+        let value = format_information & (1 << i) != 0;
+        qr[i + 1][8] = Module::format(value);
+    }
+}
+
+/// Places the codewords in upward and downward columns of width 2, starting at the bottom right
+fn place_data(qr: &mut QRCode, codewords: &CompactQR) {
+    let data = codewords.get_data();
+    let mut idx = 0;
+    let mut upward = true;
+
+    for x in (1..qr.size).rev().step_by(2) {
+        for i in 0..qr.size {
+            let y = if upward { qr.size - 1 - i } else { i };
+
+            for x in [x, x - 1] {
+                if qr[y][x].module_type() == crate::ModuleType::Data {
+                    let value = data[idx / 8] & (1 << (7 - idx % 8)) != 0;
+                    qr[y][x].set(value);
+                    idx += 1;
+                }
+            }
+        }
+
+        upward = !upward;
+    }
+
+    #[cfg(debug_assertions)]
+    assert_eq!(idx, codewords.len());
+}
+
+/// Scores a masked matrix using the dark modules of the right and bottom edges, the higher the
+/// better.
+fn score(qr: &QRCode) -> usize {
+    let last = qr.size - 1;
+    let right = (1..qr.size).filter(|&y| qr[y][last].value()).count();
+    let bottom = (1..qr.size).filter(|&x| qr[last][x].value()).count();
+
+    if right <= bottom {
+        right * 16 + bottom
+    } else {
+        bottom * 16 + right
+    }
+}
+
+/// Generates the whole Micro `QRCode`, `mask` has to be available (see [`mask_number`]).
+///
+/// The mask is searched among the available `candidates`, or all masks when none is available
+pub(crate) fn create(
+    segments: &[Segment],
+    ecl: ECL,
+    version: MicroVersion,
+    mask: Option<Mask>,
+    candidates: &[Mask],
+) -> QRCode {
+    let data = encode(segments, ecl, version);
+    let error_len = version.codewords() - data.len();
+    let generator = polynomials::generator(error_len);
+    let division = polynomials::division(&data, &generator);
+
+    let mut codewords = CompactQR::new();
+    let (last, data) = data
+        .split_last()
+        .expect("Micro versions have data codewords");
+    codewords.push_u8_slice(data);
+    match version {
+        MicroVersion::M1 | MicroVersion::M3 => codewords.push_bits(*last as usize >> 4, 4),
+        MicroVersion::M2 | MicroVersion::M4 => codewords.push_u8(*last),
+    }
+    codewords.push_u8_slice(&division[255 - error_len..]);
+
+    let mut qr = create_matrix(version);
+    place_data(&mut qr, &codewords);
+
+    let mask = mask.unwrap_or_else(|| {
+        let mut masks = MASKS
+            .into_iter()
+            .filter(|&mask| {
+                candidates
+                    .iter()
+                    .any(|&candidate| candidate as usize == mask as usize)
+            })
+            .collect::<Vec<_>>();
+        if masks.is_empty() {
+            masks = MASKS.to_vec();
+        }
+
+        let mut best_mask = masks[0];
+        let mut best_score = 0;
+
+        for mask in masks {
+            let mut copy = qr.clone();
+            datamasking::mask(&mut copy, mask);
+
+            let score = score(&copy);
+            if score > best_score {
+                best_score = score;
+                best_mask = mask;
+            }
+        }
+
+        best_mask
+    });
+
+    datamasking::mask(&mut qr, mask);
+    let mask_number = mask_number(mask).expect("Masks are checked to be Micro masks");
+    place_format_information(&mut qr, format_information(version, ecl, mask_number));
+
+    // Mixed segments report the most general mode used, no segment is Numeric like empty input
+    let mode = segments
+        .iter()
+        .map(Segment::mode)
+        .max_by_key(|&mode| mode as u8)
+        .or(Some(Mode::Numeric));
+
+    QRCode {
+        ecl,
+        mask,
+        mode,
+        micro_version: Some(version),
+        ..qr
+    }
+}
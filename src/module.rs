@@ -1,5 +1,6 @@
 /// Module is a single pixel in the QR code.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum ModuleType {
     /// The module is part of the data              (Encoded data)
@@ -39,6 +40,7 @@ impl From<u8> for ModuleType {
 /// Module is a single pixel in the QR code.
 /// Module uses u8 to store value and type.
 #[derive(Copy, Clone, Debug)]
+#[repr(transparent)]
 pub struct Module(pub u8);
 
 impl Module {
@@ -114,6 +116,65 @@ impl Module {
         ModuleType::from(self.0 >> 1)
     }
 
+    /// Returns whether the module has type `module_type`, usable in const contexts
+    const fn is(self, module_type: ModuleType) -> bool {
+        self.0 & 0b1110 == module_type as u8
+    }
+
+    /// Returns whether the module is part of the encoded data (or error correction).
+    #[must_use]
+    pub const fn is_data(self) -> bool {
+        self.is(ModuleType::Data)
+    }
+
+    /// Returns whether the module is part of a finder pattern.
+    #[must_use]
+    pub const fn is_finder(self) -> bool {
+        self.is(ModuleType::FinderPattern)
+    }
+
+    /// Returns whether the module is part of an alignment pattern.
+    #[must_use]
+    pub const fn is_alignment(self) -> bool {
+        self.is(ModuleType::Alignment)
+    }
+
+    /// Returns whether the module is part of a timing pattern.
+    #[must_use]
+    pub const fn is_timing(self) -> bool {
+        self.is(ModuleType::Timing)
+    }
+
+    /// Returns whether the module is part of the format information.
+    #[must_use]
+    pub const fn is_format(self) -> bool {
+        self.is(ModuleType::Format)
+    }
+
+    /// Returns whether the module is part of the version information.
+    #[must_use]
+    pub const fn is_version(self) -> bool {
+        self.is(ModuleType::Version)
+    }
+
+    /// Returns whether the module is the dark module, next to the bottom-left finder pattern.
+    #[must_use]
+    pub const fn is_dark_module(self) -> bool {
+        self.is(ModuleType::DarkModule)
+    }
+
+    /// Returns whether the module is a separator around the finder patterns.
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.is(ModuleType::Empty)
+    }
+
+    /// Returns whether the module is part of a function pattern, i.e. anything but data.
+    #[must_use]
+    pub const fn is_function(self) -> bool {
+        !self.is_data()
+    }
+
     /// Sets the boolean value of the module.
     pub fn set(&mut self, value: bool) {
         self.0 = if value { self.0 | 1 } else { self.0 & !1 };
@@ -149,6 +210,21 @@ impl Eq for Module {}
 mod test {
     use super::*;
 
+    #[test]
+    fn role_queries() {
+        let dark_finder = Module::finder_pattern(Module::DARK);
+        assert!(dark_finder.is_finder() && dark_finder.is_function());
+        assert!(!dark_finder.is_data() && !dark_finder.is_timing());
+
+        assert!(Module::data(Module::DARK).is_data());
+        assert!(Module::alignment(Module::LIGHT).is_alignment());
+        assert!(Module::timing(Module::DARK).is_timing());
+        assert!(Module::format(Module::LIGHT).is_format());
+        assert!(Module::version(Module::DARK).is_version());
+        assert!(Module::dark(Module::DARK).is_dark_module());
+        assert!(Module::empty(Module::LIGHT).is_empty());
+    }
+
     #[test]
     fn byte_size() {
         assert_eq!(std::mem::size_of::<Module>(), 1);
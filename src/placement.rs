@@ -1,12 +1,14 @@
 //! Places data on a matrix
 #![deny(unsafe_code)]
+#![deny(clippy::unwrap_used)]
 #![warn(missing_docs)]
 
 use crate::compact::CompactQR;
 use crate::datamasking::Mask;
-use crate::encode::Mode;
+use crate::encode::{Headers, Mode, Segment};
 
 use crate::module::ModuleType;
+use crate::score::PenaltyScore;
 use crate::{datamasking, default, encode, polynomials, score, QRCode};
 use crate::{Version, ECL};
 use core::iter::Rev;
@@ -70,7 +72,7 @@ pub fn place_on_matrix_data(qr: &mut QRCode, structure_as_binarystring: &Compact
     }
 }
 
-const MASKS: [Mask; 8] = [
+pub(crate) const MASKS: [Mask; 8] = [
     Mask::Checkerboard,
     Mask::HorizontalLines,
     Mask::VerticalLines,
@@ -81,61 +83,163 @@ const MASKS: [Mask; 8] = [
     Mask::Meadow,
 ];
 
+/// Penalty of the unmasked `qr` once masked with `mask`, as scored to select the mask.
+///
+/// Columns are read from `transpose`, the transposed matrix before masking.
+pub(crate) fn mask_penalty(qr: &QRCode, transpose: &QRCode, mask: Mask) -> PenaltyScore {
+    let mut copy = qr.clone();
+    datamasking::mask(&mut copy, mask);
+    score::penalty(&copy, transpose)
+}
+
+/// Smallest version whose masks are scored in parallel, below it threads cost more than they save
+#[cfg(feature = "rayon")]
+const PARALLEL_MASKS_VERSION: Version = Version::V30;
+
+/// Mask of `masks` giving the lowest penalty, the first one on ties, `MASKS[0]` if empty
+///
+/// With the `rayon` feature, masks of large versions are scored in parallel.
+fn lowest_penalty(qr: &QRCode, transpose: &QRCode, masks: &[Mask]) -> Mask {
+    let penalty = |&mask: &Mask| mask_penalty(qr, transpose, mask).total();
+
+    #[cfg(feature = "rayon")]
+    if qr.size >= PARALLEL_MASKS_VERSION.size() {
+        use rayon::prelude::*;
+
+        return masks
+            .par_iter()
+            .copied()
+            .min_by_key(penalty)
+            .unwrap_or(MASKS[0]);
+    }
+
+    masks
+        .iter()
+        .copied()
+        .min_by_key(penalty)
+        .unwrap_or(MASKS[0])
+}
+
 /// Main function to place everything in the `QRCode`, returns a valid matrix
+///
+/// The data is placed on a copy of `template`, the function patterns of the version. The mask
+/// is searched among `candidates`, or all masks when empty, unless `mask` is set
 pub fn place_on_matrix(
     structure_as_binarystring: &CompactQR,
     quality: ECL,
-    version: Version,
+    template: &QRCode,
     mask: &mut Option<Mask>,
+    candidates: &[Mask],
 ) -> QRCode {
-    let mut best_score = u32::MAX;
-    let mut best_mask = MASKS[0];
-
-    let mut qr = default::create_matrix(version);
+    let mut qr = template.clone();
     place_on_matrix_data(&mut qr, structure_as_binarystring);
 
     let transpose = default::transpose(&qr);
 
-    for mask in MASKS {
-        let mut copy = qr.clone();
-        let copy_transpose = transpose.clone();
-
-        datamasking::mask(&mut copy, mask);
-        let matrix_score = score::score(&copy, &copy_transpose);
-        if matrix_score < best_score {
-            best_score = matrix_score;
-            best_mask = mask;
-        }
+    let allowed = |mask: Mask| {
+        candidates.is_empty()
+            || candidates
+                .iter()
+                .any(|&candidate| candidate as usize == mask as usize)
+    };
+    // Allowed masks at the front, without allocating
+    let (mut masks, mut count) = (MASKS, 0);
+    for candidate in MASKS.into_iter().filter(|&mask| allowed(mask)) {
+        masks[count] = candidate;
+        count += 1;
     }
-
-    best_mask = mask.unwrap_or(best_mask);
+    let best_mask = mask.unwrap_or_else(|| lowest_penalty(&qr, &transpose, &masks[..count]));
     *mask = Some(best_mask);
 
     default::create_matrix_format_info(&mut qr, quality, best_mask);
     datamasking::mask(&mut qr, best_mask);
 
-    qr.mask = *mask;
+    qr.mask = best_mask;
     qr
 }
 
-/// Generate the whole matrix
-pub fn create_matrix(
-    input: &[u8],
+/// Bit buffers of the data codewords and of the final structure, and the function patterns of
+/// the last version, reused between builds
+pub(crate) struct Buffers {
+    data: CompactQR,
+    structure: CompactQR,
+    /// Matrix with the function patterns of the last version built, before any data
+    template: Option<QRCode>,
+}
+
+impl Buffers {
+    /// Creates empty buffers, they grow on the first build
+    pub(crate) const fn new() -> Self {
+        Buffers {
+            data: CompactQR::new(),
+            structure: CompactQR::new(),
+            template: None,
+        }
+    }
+}
+
+/// Encodes `segments` and interleaves the data and error correction codewords, the first
+/// `version.max_bytes()` bytes are used
+pub(crate) fn interleaved_codewords(
+    buffers: &mut Buffers,
+    segments: &[Segment],
+    ecl: ECL,
+    version: Version,
+    headers: Headers,
+) -> [u8; 5430] {
+    encode::encode_into(&mut buffers.data, segments, ecl, version, headers);
+    polynomials::structure(buffers.data.get_data(), ecl, version)
+}
+
+/// Most general mode of `segments`, no segment is Numeric like empty input
+pub(crate) fn segments_mode(segments: &[Segment]) -> Mode {
+    segments
+        .iter()
+        .map(Segment::mode)
+        .max_by_key(|&mode| mode as u8)
+        .unwrap_or(Mode::Numeric)
+}
+
+/// Generate the whole matrix, using `buffers` for the intermediate bit strings
+pub(crate) fn create_matrix(
+    buffers: &mut Buffers,
+    segments: &[Segment],
     ecl: ECL,
-    mode: Mode,
     version: Version,
+    headers: Headers,
     mask: &mut Option<Mask>,
+    candidates: &[Mask],
 ) -> QRCode {
-    let data_codewords = encode::encode(input, ecl, mode, version);
-    let structure = polynomials::structure(data_codewords.get_data(), ecl, version);
+    let structure = interleaved_codewords(buffers, segments, ecl, version, headers);
+    let mode = Some(segments_mode(segments));
 
+    place_codewords(buffers, &structure, ecl, version, mode, mask, candidates)
+}
+
+/// Places the interleaved `codewords` of `version` on the matrix, see [`place_on_matrix`]
+pub(crate) fn place_codewords(
+    buffers: &mut Buffers,
+    codewords: &[u8],
+    ecl: ECL,
+    version: Version,
+    mode: Option<Mode>,
+    mask: &mut Option<Mask>,
+    candidates: &[Mask],
+) -> QRCode {
     let max = version.max_bytes() * 8;
-    let structure_binstring = CompactQR::from_array(&structure, max + version.missing_bits());
+    let structure_binstring = &mut buffers.structure;
+    structure_binstring.set_array(codewords, max + version.missing_bits());
+
+    // Same size means same version, the function patterns are only placed on a version change
+    let template = match &mut buffers.template {
+        Some(template) if template.size == version.size() => template,
+        template => template.insert(default::create_matrix(version)),
+    };
 
     QRCode {
-        mode: Some(mode),
-        ecl: Some(ecl),
+        mode,
+        ecl,
         version: Some(version),
-        ..place_on_matrix(&structure_binstring, ecl, version, mask)
+        ..place_on_matrix(structure_binstring, ecl, template, mask, candidates)
     }
 }
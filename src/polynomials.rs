@@ -3,12 +3,16 @@
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
+#[cfg(test)]
+use alloc::{format, string::String};
+use alloc::{vec, vec::Vec};
+
 use crate::hardcode;
 use crate::polynomials;
 use crate::{Version, ECL};
 
 /// Used in the ring, convert a^x using `LOG[x % 255]` to it's decimal Galois-Field value
-const LOG: [u8; 256] = [
+pub(crate) const LOG: [u8; 256] = [
     1, 2, 4, 8, 16, 32, 64, 128, 29, 58, 116, 232, 205, 135, 19, 38, 76, 152, 45, 90, 180, 117,
     234, 201, 143, 3, 6, 12, 24, 48, 96, 192, 157, 39, 78, 156, 37, 74, 148, 53, 106, 212, 181,
     119, 238, 193, 159, 35, 70, 140, 5, 10, 20, 40, 80, 160, 93, 186, 105, 210, 185, 111, 222, 161,
@@ -25,7 +29,7 @@ const LOG: [u8; 256] = [
 ];
 
 /// Reverses a ring value, converts decimal value x using `ANTILOG[x % 255]` to it's alpha power value
-const ANTILOG: [u8; 256] = [
+pub(crate) const ANTILOG: [u8; 256] = [
     175, 0, 1, 25, 2, 50, 26, 198, 3, 223, 51, 238, 27, 104, 199, 75, 4, 100, 224, 14, 52, 141,
     239, 129, 28, 193, 105, 248, 200, 8, 76, 113, 5, 138, 101, 47, 225, 36, 15, 33, 53, 147, 142,
     218, 240, 18, 130, 69, 29, 181, 194, 125, 106, 39, 249, 185, 201, 154, 9, 120, 77, 228, 114,
@@ -81,6 +85,16 @@ pub fn generated_to_string(poly: &[u8]) -> String {
 /// Then the actual division takes place
 /// We convert `from` from INTEGER to ALPHA
 pub fn division(from: &[u8], by: &[u8]) -> [u8; 255] {
+    #[cfg(feature = "simd")]
+    if let Some(division) = crate::simd::division(from, by) {
+        return division;
+    }
+
+    scalar_division(from, by)
+}
+
+/// Same as [`division`], without SIMD
+pub(crate) fn scalar_division(from: &[u8], by: &[u8]) -> [u8; 255] {
     let mut from_mut = [0; 255];
     let start = 256 - from.len() - by.len();
 
@@ -101,6 +115,152 @@ pub fn division(from: &[u8], by: &[u8]) -> [u8; 255] {
     from_mut
 }
 
+/// Multiplies two integers in the Galois Field (256)
+pub(crate) fn multiply(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+
+    LOG[(ANTILOG[a as usize] as usize + ANTILOG[b as usize] as usize) % 255]
+}
+
+/// Computes the generator polynomial for `degree` error correction codewords, using the
+/// same alpha notation as [`hardcode::get_polynomial`], referring to 7.5.2 of the spec.
+///
+/// `(x - α0)(x - α1)...(x - α(degree - 1))`
+pub fn generator(degree: usize) -> Vec<u8> {
+    // Integer notation, highest degree first
+    let mut poly = vec![1];
+
+    for &root in &LOG[..degree] {
+        let mut next = vec![0; poly.len() + 1];
+        for (j, &coefficient) in poly.iter().enumerate() {
+            next[j] ^= coefficient;
+            next[j + 1] ^= multiply(coefficient, root);
+        }
+        poly = next;
+    }
+
+    poly.iter().map(|&c| ANTILOG[c as usize]).collect()
+}
+
+/// Divides two integers in the Galois Field (256), `b` should not be 0
+pub(crate) fn divide(a: u8, b: u8) -> u8 {
+    if a == 0 {
+        return 0;
+    }
+
+    LOG[(ANTILOG[a as usize] as usize + 255 - ANTILOG[b as usize] as usize) % 255]
+}
+
+/// Evaluates a polynomial (integer notation, lowest degree first) at `x`
+fn evaluate(poly: &[u8], x: u8) -> u8 {
+    poly.iter()
+        .rev()
+        .fold(0, |acc, &coefficient| multiply(acc, x) ^ coefficient)
+}
+
+/// Corrects `codewords` (data followed by `error_len` error correction codewords) in place,
+/// using Berlekamp-Massey, Chien search and Forney algorithms.
+///
+/// Returns the number of corrected codewords, `None` if there are too many errors.
+pub fn correct(codewords: &mut [u8], error_len: usize) -> Option<usize> {
+    let n = codewords.len();
+
+    // Syndromes: codewords evaluated at α0, α1, ..., α(error_len - 1)
+    let syndromes = (0..error_len)
+        .map(|j| {
+            codewords
+                .iter()
+                .fold(0, |acc, &codeword| multiply(acc, LOG[j % 255]) ^ codeword)
+        })
+        .collect::<Vec<u8>>();
+
+    if syndromes.iter().all(|&syndrome| syndrome == 0) {
+        return Some(0);
+    }
+
+    // Berlekamp-Massey, polynomials are lowest degree first
+    let mut locator = vec![1u8];
+    let mut previous = vec![1u8];
+    let mut errors = 0;
+    let mut shift = 1;
+    let mut previous_discrepancy = 1;
+
+    for i in 0..error_len {
+        let mut discrepancy = syndromes[i];
+        for j in 1..=errors {
+            discrepancy ^= multiply(locator[j], syndromes[i - j]);
+        }
+
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+
+        let coefficient = divide(discrepancy, previous_discrepancy);
+        let mut next = locator.clone();
+        next.resize(core::cmp::max(locator.len(), previous.len() + shift), 0);
+        for (j, &value) in previous.iter().enumerate() {
+            next[j + shift] ^= multiply(coefficient, value);
+        }
+
+        if 2 * errors <= i {
+            previous = core::mem::replace(&mut locator, next);
+            errors = i + 1 - errors;
+            previous_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            locator = next;
+            shift += 1;
+        }
+    }
+
+    if 2 * errors > error_len {
+        return None;
+    }
+
+    // Chien search, an error at degree `p` is a root α(-p) of the locator
+    let positions = (0..n)
+        .filter(|&p| evaluate(&locator, LOG[(255 - p % 255) % 255]) == 0)
+        .collect::<Vec<usize>>();
+
+    if positions.len() != errors {
+        return None;
+    }
+
+    // Forney, evaluator = syndromes * locator mod x^error_len
+    let mut evaluator = vec![0u8; error_len];
+    for (i, &syndrome) in syndromes.iter().enumerate() {
+        for (j, &value) in locator.iter().enumerate().take(error_len - i) {
+            evaluator[i + j] ^= multiply(syndrome, value);
+        }
+    }
+
+    // Formal derivative, only odd powers remain in GF(2^8)
+    let derivative = locator
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, &value)| if i % 2 == 1 { value } else { 0 })
+        .collect::<Vec<u8>>();
+
+    for &p in &positions {
+        let x = LOG[p % 255];
+        let x_inverse = LOG[(255 - p % 255) % 255];
+
+        let denominator = evaluate(&derivative, x_inverse);
+        if denominator == 0 {
+            return None;
+        }
+
+        let magnitude = multiply(x, divide(evaluate(&evaluator, x_inverse), denominator));
+        codewords[n - 1 - p] ^= magnitude;
+    }
+
+    Some(errors)
+}
+
 /// Uses the data and error(generator polynomial) to compute the divisions
 /// for each block.
 pub fn structure(data: &[u8], quality: ECL, version: Version) -> [u8; 5430] {
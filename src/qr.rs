@@ -1,16 +1,24 @@
 //! Module `qr` is the entrypoint to start making `QRCodes`
 
-use crate::module::Module;
+#![deny(clippy::unwrap_used)]
+
+use crate::module::{Module, ModuleType};
+use alloc::{borrow::Cow, string::String, vec, vec::Vec};
 use core::fmt::{Debug, Formatter};
 use core::ops::{Index, IndexMut};
 
 use crate::datamasking::Mask;
-use crate::encode::Mode;
+use crate::encode::{EciCode, Fnc1, Headers, Mode, Segment, StructuredAppend};
 #[cfg(not(feature = "wasm-bindgen"))]
 use crate::helpers;
-use crate::{encode, Version, ECL};
+use crate::micro::{self, MicroVersion};
+use crate::placement::{self, Buffers};
+use crate::score::{self, PenaltyScore, Scannability};
+use crate::{datamasking, default, encode, hardcode, Version, ECL};
 
+#[cfg(not(feature = "boxed"))]
 const QR_MAX_WIDTH: usize = 177;
+#[cfg(not(feature = "boxed"))]
 const QR_MAX_MODULES: usize = QR_MAX_WIDTH * QR_MAX_WIDTH;
 
 /// A `QRCode` can be created using [`QRBuilder`]. Simple API for simple usage.
@@ -29,7 +37,12 @@ pub struct QRCode {
     /// # Other data type possible:
     /// - Templated Matrix was faster but crate size was huge.
     /// - Vector using `with_capacity`, really bad.
+    #[cfg(not(feature = "boxed"))]
     pub data: [Module; QR_MAX_MODULES],
+    /// Matrix of length `size x size`, allocated on the heap (`boxed` feature) so small versions
+    /// take less memory.
+    #[cfg(feature = "boxed")]
+    pub data: alloc::boxed::Box<[Module]>,
     /// Width & Height of QRCode. If manually set, should be `version * 4 + 17`, `version` going
     /// from 1 to 40 both included.
     pub size: usize,
@@ -46,13 +59,11 @@ pub struct QRCode {
     /// - `ECL::Q`: 25%
     /// - `ELC::H`: 30%
     ///
-    /// `None` will set ECL to Quartile (`ELC::Q`)
-    pub ecl: Option<ECL>,
+    /// Level used to build the `QRCode`, boost included
+    pub ecl: ECL,
 
-    /// Changes the final pattern used.
-    ///
-    /// None will find the best suited mask.
-    pub mask: Option<Mask>,
+    /// Pattern applied to the data modules, the one of lowest penalty unless forced
+    pub mask: Mask,
     /// Mode defines which data is being parsed, between Numeric, AlphaNumeric & Byte.
     ///
     /// `None` will optimize Mode according to user input.
@@ -60,6 +71,8 @@ pub struct QRCode {
     /// ## Note
     /// Kanji mode is not supported (yet).
     pub mode: Option<Mode>,
+    /// Version of a Micro `QRCode`, `None` for regular `QRCode`s.
+    pub micro_version: Option<MicroVersion>,
 }
 
 impl Debug for QRCode {
@@ -70,21 +83,41 @@ impl Debug for QRCode {
             .field("ecl", &self.ecl)
             .field("mask", &self.mask)
             .field("mode", &self.mode)
+            .field("micro_version", &self.micro_version)
             .finish_non_exhaustive()
     }
 }
 
 impl QRCode {
-    /// A default `QRCode` will have all it's fields as `None` and a default Matrix filled with `Module::LIGHT`.
+    /// A default `QRCode` will have its optional fields as `None`, `ECL::Q`, `Mask::Checkerboard`
+    /// and a default Matrix filled with `Module::LIGHT`.
     #[must_use]
+    #[cfg(not(feature = "boxed"))]
     pub const fn default(size: usize) -> Self {
         QRCode {
             data: [Module::data(Module::LIGHT); QR_MAX_MODULES],
             size,
             version: None,
-            ecl: None,
-            mask: None,
+            ecl: ECL::Q,
+            mask: Mask::Checkerboard,
+            mode: None,
+            micro_version: None,
+        }
+    }
+
+    /// A default `QRCode` will have its optional fields as `None`, `ECL::Q`, `Mask::Checkerboard`
+    /// and a default Matrix filled with `Module::LIGHT`.
+    #[must_use]
+    #[cfg(feature = "boxed")]
+    pub fn default(size: usize) -> Self {
+        QRCode {
+            data: vec![Module::data(Module::LIGHT); size * size].into_boxed_slice(),
+            size,
+            version: None,
+            ecl: ECL::Q,
+            mask: Mask::Checkerboard,
             mode: None,
+            micro_version: None,
         }
     }
 }
@@ -103,24 +136,203 @@ impl IndexMut<usize> for QRCode {
     }
 }
 
+/// Matrix of a [`QRCode`] with its quiet zone, see [`QRCode::with_quiet_zone`].
+///
+/// Indexing works like [`QRCode`], quiet zone modules are light and of type
+/// `ModuleType::Empty`.
+#[derive(Clone, Debug)]
+pub struct QRCodeWithQuietZone {
+    /// Modules row by row, `size x size`
+    pub data: Vec<Module>,
+    /// Width & Height, quiet zone included
+    pub size: usize,
+    /// Width of the quiet zone on each side
+    pub margin: usize,
+}
+
+impl QRCodeWithQuietZone {
+    /// Returns an iterator over the rows of the matrix, from top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[Module]> {
+        self.data.chunks_exact(self.size)
+    }
+
+    /// Returns the matrix as rows of booleans, `true` being a dark module
+    #[must_use]
+    pub fn to_bool_matrix(&self) -> Vec<Vec<bool>> {
+        self.rows()
+            .map(|row| row.iter().map(|module| module.value()).collect())
+            .collect()
+    }
+
+    /// Returns the modules packed 8 per byte in `layout`, quiet zone included, see
+    /// [`QRCode::to_bits`]
+    #[must_use]
+    pub fn to_bits(&self, layout: BitLayout) -> Vec<u8> {
+        pack_bits(&self.data, self.size, layout)
+    }
+}
+
+impl Index<usize> for QRCodeWithQuietZone {
+    type Output = [Module];
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[index * self.size..(index + 1) * self.size]
+    }
+}
+
+/// Order of the modules packed by [`QRCode::to_bits`], a set bit is a dark module
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitLayout {
+    /// Rows from top to bottom, 8 modules per byte from left to right, most significant bit
+    /// first, each row padded with light modules to a whole number of bytes
+    RowMajor,
+    /// Pages of 8 rows from top to bottom, 1 byte per column from left to right, least
+    /// significant bit on top, like the page addressing of SSD1306 displays. The last page is
+    /// padded with light modules.
+    ColumnMajor,
+}
+
+/// Packs the `size` x `size` modules of `data`, row by row, in `layout`
+fn pack_bits(data: &[Module], size: usize, layout: BitLayout) -> Vec<u8> {
+    let stride = (size + 7) / 8;
+    let mut bits = vec![0u8; stride * size];
+    for (i, module) in data[..size * size].iter().enumerate() {
+        if !module.value() {
+            continue;
+        }
+
+        let (y, x) = (i / size, i % size);
+        match layout {
+            BitLayout::RowMajor => bits[y * stride + x / 8] |= 0x80 >> (x % 8),
+            BitLayout::ColumnMajor => bits[y / 8 * size + x] |= 1 << (y % 8),
+        }
+    }
+
+    bits
+}
+
+/// Splits `input` in exactly `total` parts, whose lengths differ by one at most
+///
+/// Every part is non-empty if `total <= input.len()`: each Structured Append header announces
+/// `total` symbols.
+pub(crate) fn split_parts(input: &[u8], total: usize) -> impl Iterator<Item = &[u8]> {
+    let (size, longer) = (input.len() / total, input.len() % total);
+    (0..total).map(move |index| {
+        let start = index * size + index.min(longer);
+        let end = start + size + usize::from(index < longer);
+        &input[start..end]
+    })
+}
+
+/// Serializable form of [`QRCode`], modules are packed 8 per byte, row by row, most significant
+/// bit first
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "QRCode")]
+struct QRCodeRepr {
+    size: usize,
+    version: Option<Version>,
+    ecl: Option<ECL>,
+    mask: Option<Mask>,
+    mode: Option<Mode>,
+    micro_version: Option<MicroVersion>,
+    modules: Vec<u8>,
+}
+
+/// Serializes the value of each module and the parameters, module types are not stored
+#[cfg(feature = "serde")]
+impl serde::Serialize for QRCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let n = self.size;
+        let mut modules = vec![0u8; (n * n + 7) / 8];
+        for (i, module) in self.data[..n * n].iter().enumerate() {
+            modules[i / 8] |= u8::from(module.value()) << (7 - i % 8);
+        }
+
+        QRCodeRepr {
+            size: n,
+            version: self.version,
+            ecl: Some(self.ecl),
+            mask: Some(self.mask),
+            mode: self.mode,
+            micro_version: self.micro_version,
+            modules,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Module types are restored from `version` (or `micro_version`), every module is
+/// `ModuleType::Data` when both are `None`
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for QRCode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let repr = QRCodeRepr::deserialize(deserializer)?;
+        let n = repr.size;
+
+        let mut qr = match (repr.micro_version, repr.version) {
+            (Some(micro_version), _) => micro::create_matrix(micro_version),
+            (None, Some(version)) => default::create_matrix(version),
+            (None, None) if n <= 177 => QRCode::default(n),
+            (None, None) => return Err(D::Error::custom("size is larger than 177")),
+        };
+
+        if qr.size != n {
+            return Err(D::Error::custom("size does not match the version"));
+        }
+        if repr.modules.len() != (n * n + 7) / 8 {
+            return Err(D::Error::invalid_length(
+                repr.modules.len(),
+                &"one bit per module",
+            ));
+        }
+
+        for (i, module) in qr.data[..n * n].iter_mut().enumerate() {
+            module.set(repr.modules[i / 8] & (1 << (7 - i % 8)) != 0);
+        }
+
+        Ok(QRCode {
+            version: repr.version,
+            ecl: repr.ecl.unwrap_or(ECL::Q),
+            mask: repr.mask.unwrap_or(Mask::Checkerboard),
+            mode: repr.mode,
+            micro_version: repr.micro_version,
+            ..qr
+        })
+    }
+}
+
 /// Contains different error when [`QRCode`] could not be created
 pub enum QRCodeError {
     /// If data if too large to be encoded (refer to Table 7-11 of the spec or [an online table](https://fast-qr.com/blog/tables/ecl))
     EncodedData,
     /// Specified version too small to contain data
     SpecifiedVersion,
+    /// Option not available for Micro `QRCode`s (`ECL::H`, ECI, FNC1, pre-encoded segments,
+    /// masks other than `HorizontalLines`, `LargeCheckerboard`, `Diamonds` and `Meadow`)
+    MicroUnsupported,
+    /// Data too large for the maximum version of [`QRBuilder::version_range`]
+    VersionRange,
+    /// Input contains characters that the mode forced with [`QRBuilder::mode`] cannot represent
+    InvalidMode,
 }
 
 // We don't want to use `std::error::Error` on wasm32
+#[cfg(feature = "std")]
 impl std::error::Error for QRCodeError {}
 
-impl std::fmt::Display for QRCodeError {
+impl core::fmt::Display for QRCodeError {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             QRCodeError::EncodedData => f.write_str("Data too big to be encoded"),
             QRCodeError::SpecifiedVersion => {
                 f.write_str("Specified version too low to contain data")
             }
+            QRCodeError::MicroUnsupported => f.write_str("Option not supported by Micro QR codes"),
+            QRCodeError::VersionRange => f.write_str("Data too big for the maximum version"),
+            QRCodeError::InvalidMode => f.write_str("Input not representable in the forced mode"),
         }
     }
 }
@@ -132,40 +344,380 @@ impl Debug for QRCodeError {
             QRCodeError::SpecifiedVersion => {
                 f.write_str("Specified version too low to contain data")
             }
+            QRCodeError::MicroUnsupported => f.write_str("Option not supported by Micro QR codes"),
+            QRCodeError::VersionRange => f.write_str("Data too big for the maximum version"),
+            QRCodeError::InvalidMode => f.write_str("Input not representable in the forced mode"),
+        }
+    }
+}
+
+/// Reason why [`QRBuilder::validate`] rejects its parameters, more detailed than [`QRCodeError`]
+#[derive(Debug, Clone, Copy)]
+pub enum ValidationError {
+    /// The byte at `index` of the input cannot be represented in the forced `mode`
+    InvalidCharacter {
+        /// Mode forced with [`QRBuilder::mode`]
+        mode: Mode,
+        /// Position of the first invalid byte in the input
+        index: usize,
+    },
+    /// Data does not fit in the largest version, which holds `capacity` data bits at this ECL
+    TooLong {
+        /// Data bits of Version 40 (or M4 for Micro `QRCode`s)
+        capacity: usize,
+    },
+    /// Data needs a larger version than the one forced with [`QRBuilder::version`]
+    VersionTooSmall {
+        /// Smallest version containing the data
+        needed: Version,
+        /// Forced version
+        version: Version,
+    },
+    /// Data needs a larger version than the maximum of [`QRBuilder::version_range`]
+    VersionOutOfRange {
+        /// Smallest version containing the data
+        needed: Version,
+        /// Maximum version of the range
+        max: Version,
+    },
+    /// Data needs a larger version than the one forced with [`QRBuilder::micro_version`]
+    MicroVersionTooSmall {
+        /// Smallest Micro version containing the data
+        needed: MicroVersion,
+        /// Forced Micro version
+        version: MicroVersion,
+    },
+    /// Option not available for Micro `QRCode`s, see `QRCodeError::MicroUnsupported`
+    MicroUnsupported,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ValidationError {}
+
+impl core::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ValidationError::InvalidCharacter { mode, index } => {
+                write!(f, "Character at {index} cannot be encoded in {mode:?} mode")
+            }
+            ValidationError::TooLong { capacity } => {
+                write!(f, "Data too big to be encoded, at most {capacity} bits fit")
+            }
+            ValidationError::VersionTooSmall { needed, version } => write!(
+                f,
+                "Specified version {} too low to contain data, {} needed",
+                *version as usize, *needed as usize
+            ),
+            ValidationError::VersionOutOfRange { needed, max } => write!(
+                f,
+                "Data needs version {}, above the maximum version {}",
+                *needed as usize, *max as usize
+            ),
+            ValidationError::MicroVersionTooSmall { needed, version } => write!(
+                f,
+                "Specified version M{} too low to contain data, M{} needed",
+                *version as usize, *needed as usize
+            ),
+            ValidationError::MicroUnsupported => {
+                f.write_str("Option not supported by Micro QR codes")
+            }
         }
     }
 }
 
 impl QRCode {
-    /// Creates a new `QRCode` from a ECL / version
+    /// Creates a new `QRCode` from a ECL / version, `ecl_boost` raises the ECL if the data
+    /// still fits (see [`QRBuilder::disable_ecl_boost`])
     ///
     /// # Errors
     /// - `QRCodeError::EncodedData` if `input` is too large to be encoded
     /// - `QRCodeError::SpecifiedVersion` if specified `version` is too small to contain data
+    #[cfg(any(test, target_arch = "wasm32"))]
     pub(crate) fn new(
         input: &[u8],
         ecl: Option<ECL>,
         v: Option<Version>,
         mode: Option<Mode>,
-        mut mask: Option<Mask>,
+        mask: Option<Mask>,
+        ecl_boost: bool,
     ) -> Result<Self, QRCodeError> {
-        use crate::placement::create_matrix;
+        QRBuilder {
+            input: input.to_vec(),
+            ecl,
+            mode,
+            version: v,
+            mask,
+            eci: None,
+            fnc1: None,
+            segments: None,
+            micro: false,
+            micro_version: None,
+            ecl_boost,
+            version_range: None,
+            mask_candidates: Vec::new(),
+        }
+        .build()
+    }
 
-        let mode = mode.unwrap_or_else(|| encode::best_encoding(input));
-        let level = ecl.unwrap_or(ECL::Q);
+    /// Returns the version used by [`QRBuilder::build`], selected automatically unless forced
+    ///
+    /// `None` for Micro `QRCode`s, see `micro_version`, and for `QRCode`s not built with
+    /// [`QRBuilder`] (i.e. [`QRCode::default`] or deserialized without version).
+    #[must_use]
+    pub const fn version(&self) -> Option<Version> {
+        self.version
+    }
 
-        let version = match Version::get(mode, level, input.len()) {
-            Some(version) => version,
-            None => return Err(QRCodeError::EncodedData),
-        };
-        let version = match v {
-            Some(user_version) if user_version as usize >= version as usize => user_version,
-            None => version,
-            Some(_) => return Err(QRCodeError::SpecifiedVersion),
+    /// Returns the error correction level used by [`QRBuilder::build`], boost included
+    #[must_use]
+    pub const fn ecl(&self) -> ECL {
+        self.ecl
+    }
+
+    /// Returns the mask applied by [`QRBuilder::build`], selected automatically unless forced
+    #[must_use]
+    pub const fn mask(&self) -> Mask {
+        self.mask
+    }
+
+    /// Returns the mode used by [`QRBuilder::build`], the most general one for mixed segments
+    ///
+    /// `None` for `QRCode`s not built with [`QRBuilder`] and for raw codewords placed with
+    /// [`QRCode::from_codewords`], whose mode is unknown.
+    #[must_use]
+    pub const fn mode(&self) -> Option<Mode> {
+        self.mode
+    }
+
+    /// Places interleaved `codewords` on the matrix of their version, with `mask` or the mask
+    /// of lowest penalty when `None`
+    ///
+    /// See [`QRBuilder::build_codewords`].
+    #[must_use]
+    pub fn from_codewords(codewords: &Codewords, mask: Option<Mask>) -> QRCode {
+        let mut mask = mask;
+        placement::place_codewords(
+            &mut Buffers::new(),
+            &codewords.bytes,
+            codewords.ecl,
+            codewords.version,
+            codewords.mode,
+            &mut mask,
+            &[],
+        )
+    }
+
+    /// Returns the penalty points of the matrix for each rule of the spec, the lower the better
+    ///
+    /// ```rust
+    /// use fast_qr::QRBuilder;
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    /// let penalty = qrcode.penalty_score();
+    /// println!("{} points, {} for 2x2 blocks", penalty.total(), penalty.blocks);
+    /// ```
+    #[must_use]
+    pub fn penalty_score(&self) -> PenaltyScore {
+        score::penalty(self, &default::transpose(self))
+    }
+
+    /// Returns the penalty points the matrix would have with `mask` instead of its own
+    #[must_use]
+    pub fn penalty_score_for(&self, mask: Mask) -> PenaltyScore {
+        let mut qr = self.clone();
+        datamasking::mask(&mut qr, self.mask);
+        datamasking::mask(&mut qr, mask);
+        if self.micro_version.is_none() {
+            default::create_matrix_format_info(&mut qr, self.ecl, mask);
+        }
+
+        qr.penalty_score()
+    }
+
+    /// Returns the penalty points of the matrix with each mask, the way [`QRBuilder::build`]
+    /// scored them: the mask with the lowest total, the first one on ties, is selected unless
+    /// forced or excluded by [`QRBuilder::mask_candidates`].
+    ///
+    /// Unlike [`QRCode::penalty_score_for`], which scores the final matrix, the format
+    /// information is left out and columns are scored before masking.
+    ///
+    /// ```rust
+    /// use fast_qr::QRBuilder;
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    /// let scores = qrcode.mask_scores().unwrap();
+    ///
+    /// let best = scores.iter().min_by_key(|(_, score)| score.total()).unwrap();
+    /// assert_eq!(qrcode.mask() as usize, best.0 as usize);
+    /// ```
+    ///
+    /// `None` for Micro `QRCode`s, whose masks are selected differently.
+    #[must_use]
+    pub fn mask_scores(&self) -> Option<[(Mask, PenaltyScore); 8]> {
+        if self.micro_version.is_some() {
+            return None;
+        }
+
+        let mut unmasked = self.clone();
+        datamasking::mask(&mut unmasked, self.mask);
+        for module in unmasked.data[..self.size * self.size].iter_mut() {
+            if module.is_format() {
+                module.set(Module::LIGHT);
+            }
+        }
+
+        let transpose = default::transpose(&unmasked);
+        Some(
+            placement::MASKS
+                .map(|mask| (mask, placement::mask_penalty(&unmasked, &transpose, mask))),
+        )
+    }
+
+    /// Suggests how easily the `QRCode` scans once rendered, with the contrast ratio between
+    /// module and background colors (1 to 21, see WCAG 2) and the margin in modules.
+    ///
+    /// The spec asks for a margin of 4 modules (2 for Micro `QRCode`s), a contrast of 4.5 is
+    /// advised and 3 is the bare minimum. Proportions of dark modules far from 50% also
+    /// lower the rating.
+    ///
+    /// ```rust
+    /// use fast_qr::{QRBuilder, Scannability};
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    ///
+    /// // Black on white with the default margin
+    /// assert_eq!(qrcode.scannability(21.0, 4), Scannability::Good);
+    /// assert_eq!(qrcode.scannability(21.0, 0), Scannability::Poor);
+    /// ```
+    #[must_use]
+    pub fn scannability(&self, contrast_ratio: f64, quiet_zone: usize) -> Scannability {
+        let min_quiet_zone = if self.micro_version.is_some() { 2 } else { 4 };
+        let balance = self.penalty_score().balance;
+
+        if contrast_ratio < 3f64 || quiet_zone < min_quiet_zone / 2 || balance >= 40 {
+            Scannability::Poor
+        } else if contrast_ratio < 4.5 || quiet_zone < min_quiet_zone || balance >= 20 {
+            Scannability::Fair
+        } else {
+            Scannability::Good
+        }
+    }
+
+    /// Returns an iterator over the rows of the matrix, from top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[Module]> {
+        self.data[..self.size * self.size].chunks_exact(self.size)
+    }
+
+    /// Returns an iterator over every module as `(x, y, module)`, row by row
+    pub fn iter_modules(&self) -> impl Iterator<Item = (usize, usize, Module)> + '_ {
+        self.rows().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, &module)| (x, y, module))
+        })
+    }
+
+    /// Returns the matrix as rows of booleans, `true` being a dark module
+    #[must_use]
+    pub fn to_bool_matrix(&self) -> Vec<Vec<bool>> {
+        self.rows()
+            .map(|row| row.iter().map(|module| module.value()).collect())
+            .collect()
+    }
+
+    /// Returns the modules packed 8 per byte, row by row, most significant bit first, each row
+    /// padded to a whole number of bytes, i.e. a monochrome framebuffer with dark pixels set
+    ///
+    /// See [`QRCode::to_bits_with_quiet_zone`] to include the quiet zone and
+    /// [`QRCodeWithQuietZone::to_bits`] for other layouts.
+    ///
+    /// ```rust
+    /// use fast_qr::{QRBuilder, Version};
+    ///
+    /// let qrcode = QRBuilder::new("fast_qr").version(Version::V01).build().unwrap();
+    /// let bits = qrcode.to_bits();
+    ///
+    /// // 21 modules, 3 bytes per row
+    /// assert_eq!(bits.len(), 21 * 3);
+    /// // Top row of the finder pattern, then its light separator
+    /// assert_eq!(bits[0], 0b1111_1110);
+    /// ```
+    #[must_use]
+    pub fn to_bits(&self) -> Vec<u8> {
+        self.to_bits_with_layout(BitLayout::RowMajor)
+    }
+
+    /// Returns the modules packed 8 per byte in `layout`, see [`BitLayout`]
+    #[must_use]
+    pub fn to_bits_with_layout(&self, layout: BitLayout) -> Vec<u8> {
+        pack_bits(&self.data, self.size, layout)
+    }
+
+    /// Returns the modules surrounded by the quiet zone, see [`QRCode::with_quiet_zone`], packed
+    /// like [`QRCode::to_bits`]
+    #[must_use]
+    pub fn to_bits_with_quiet_zone(&self) -> Vec<u8> {
+        self.with_quiet_zone().to_bits(BitLayout::RowMajor)
+    }
+
+    /// Returns the `(x, y)` of the modules whose value differs from `other`, row by row
+    ///
+    /// `QRCode`s of different sizes differ on every module outside of the smaller one. Render
+    /// the differences with [`SvgBuilder::to_diff_str`](crate::convert::svg::SvgBuilder::to_diff_str).
+    ///
+    /// ```rust
+    /// use fast_qr::QRBuilder;
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    /// assert!(qrcode.diff(&qrcode).is_empty());
+    ///
+    /// let mut other = qrcode.clone();
+    /// other[qrcode.size - 1][0].set(!qrcode[qrcode.size - 1][0].value());
+    /// assert_eq!(qrcode.diff(&other), [(0, qrcode.size - 1)]);
+    /// ```
+    #[must_use]
+    pub fn diff(&self, other: &QRCode) -> Vec<(usize, usize)> {
+        let size = core::cmp::max(self.size, other.size);
+        let value = |qr: &QRCode, x: usize, y: usize| {
+            (x < qr.size && y < qr.size).then(|| qr[y][x].value())
         };
 
-        let out = create_matrix(input, level, mode, version, &mut mask);
-        Ok(out)
+        (0..size * size)
+            .map(|i| (i % size, i / size))
+            .filter(|&(x, y)| value(self, x, y) != value(other, x, y))
+            .collect()
+    }
+
+    /// Returns a copy of the matrix surrounded by the quiet zone the spec asks for, 4 modules
+    /// (2 for Micro `QRCode`s), for consumers reading the modules directly
+    ///
+    /// ```rust
+    /// use fast_qr::QRBuilder;
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    /// let padded = qrcode.with_quiet_zone();
+    ///
+    /// assert_eq!(padded.size, qrcode.size + 8);
+    /// assert_eq!(padded[4][4].value(), qrcode[0][0].value());
+    /// assert!(!padded[0][0].value());
+    /// ```
+    #[must_use]
+    pub fn with_quiet_zone(&self) -> QRCodeWithQuietZone {
+        let margin = if self.micro_version.is_some() { 2 } else { 4 };
+        self.with_margin(margin)
+    }
+
+    /// Returns a copy of the matrix surrounded by `margin` light modules on each side
+    #[must_use]
+    pub fn with_margin(&self, margin: usize) -> QRCodeWithQuietZone {
+        let size = self.size + margin * 2;
+        let mut data = vec![Module::new(Module::LIGHT, ModuleType::Empty); size * size];
+        for (y, row) in self.rows().enumerate() {
+            let start = (y + margin) * size + margin;
+            data[start..start + self.size].copy_from_slice(row);
+        }
+
+        QRCodeWithQuietZone { data, size, margin }
     }
 
     /// Prints the `QRCode` to the terminal
@@ -176,12 +728,66 @@ impl QRCode {
     }
 
     /// Prints the `QRCode` to the terminal
-    #[cfg(not(feature = "wasm-bindgen"))]
+    #[cfg(all(feature = "std", not(feature = "wasm-bindgen")))]
     pub fn print(&self) {
         println!("{}", helpers::print_matrix_with_margin(self));
     }
 }
 
+/// Data and error correction codewords of a [`QRCode`], interleaved in placement order, see
+/// [`QRBuilder::build_codewords`]
+#[derive(Debug, Clone)]
+pub struct Codewords {
+    bytes: Vec<u8>,
+    version: Version,
+    ecl: ECL,
+    mode: Option<Mode>,
+}
+
+impl Codewords {
+    /// Creates codewords from raw `bytes`, e.g. to place a hand-made stream
+    ///
+    /// Returns `None` if `bytes` is not as long as the total number of codewords of `version`.
+    /// The `QRCode` placed from them has no mode.
+    #[must_use]
+    pub fn new(bytes: Vec<u8>, version: Version, ecl: ECL) -> Option<Self> {
+        if bytes.len() != version.max_bytes() {
+            return None;
+        }
+
+        Some(Codewords {
+            bytes,
+            version,
+            ecl,
+            mode: None,
+        })
+    }
+
+    /// Returns the codewords, data and error correction interleaved
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the codewords, to alter them before placing them again with [`Codewords::new`]
+    #[must_use]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Returns the version of the matrix they fill
+    #[must_use]
+    pub const fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns the error correction level, boost included
+    #[must_use]
+    pub const fn ecl(&self) -> ECL {
+        self.ecl
+    }
+}
+
 /// Builder struct, makes it easier to create a [`QRCode`].
 ///
 /// # Example
@@ -197,12 +803,21 @@ impl QRCode {
 ///     // .mask(Mask::Checkerboard)
 ///     .build();
 /// ```
+#[derive(Clone)]
 pub struct QRBuilder {
     input: Vec<u8>,
     ecl: Option<ECL>,
     mode: Option<Mode>,
     version: Option<Version>,
     mask: Option<Mask>,
+    eci: Option<EciCode>,
+    fnc1: Option<Fnc1>,
+    segments: Option<Vec<Segment>>,
+    micro: bool,
+    micro_version: Option<MicroVersion>,
+    ecl_boost: bool,
+    version_range: Option<(Version, Version)>,
+    mask_candidates: Vec<Mask>,
 }
 
 impl QRBuilder {
@@ -215,39 +830,646 @@ impl QRBuilder {
             mode: None,
             version: None,
             ecl: None,
+            eci: None,
+            fnc1: None,
+            segments: None,
+            micro: false,
+            micro_version: None,
+            ecl_boost: true,
+            version_range: None,
+            mask_candidates: Vec::new(),
         }
     }
 
-    /// Forces the Mode
+    /// Forces the Mode of the whole input, for scanners only handling some modes.
+    /// By default, the best mode is chosen and modes are mixed when it allows a smaller version.
+    ///
+    /// Building fails with `QRCodeError::InvalidMode` if `input` cannot be represented in
+    /// `mode`, i.e. letters in `Mode::Numeric` or lowercase letters in `Mode::Alphanumeric`.
+    ///
+    /// ```rust
+    /// use fast_qr::{Mode, QRBuilder};
+    ///
+    /// let qrcode = QRBuilder::new("HELLO WORLD").mode(Mode::Byte).build().unwrap();
+    /// assert_eq!(qrcode.mode(), Some(Mode::Byte));
+    ///
+    /// assert!(QRBuilder::new("hello").mode(Mode::Alphanumeric).build().is_err());
+    /// ```
     pub fn mode(&mut self, mode: Mode) -> &mut Self {
         self.mode = Some(mode);
         self
     }
 
-    /// Forces the Encoding Level
+    /// Forces the Encoding Level, it can still be raised if the data fits
+    /// (see [`QRBuilder::disable_ecl_boost`])
     pub fn ecl(&mut self, ecl: ECL) -> &mut Self {
         self.ecl = Some(ecl);
         self
     }
 
+    /// Sets the Encoding Level unless it was forced with [`QRBuilder::ecl`]
+    #[cfg(feature = "svg")]
+    pub(crate) fn default_ecl(&mut self, ecl: ECL) -> &mut Self {
+        self.ecl.get_or_insert(ecl);
+        self
+    }
+
+    /// Keeps the Encoding Level as is, by default it is raised as long as the data still fits
+    /// in the version (i.e. `ECL::Q` becomes `ECL::H` when there is enough spare room)
+    pub fn disable_ecl_boost(&mut self) -> &mut Self {
+        self.ecl_boost = false;
+        self
+    }
+
     /// Forces the version
     pub fn version(&mut self, version: Version) -> &mut Self {
         self.version = Some(version);
         self
     }
 
+    /// Restricts the automatically selected version to `min..=max`, i.e. to fit a label.
+    ///
+    /// Smaller data still uses `min`, so every [`QRCode`] has the same size, and building fails
+    /// with `QRCodeError::VersionRange` when the data needs more than `max`.
+    /// A version forced with [`QRBuilder::version`] is used as is.
+    ///
+    /// ```rust
+    /// use fast_qr::{QRBuilder, Version};
+    ///
+    /// let qrcode = QRBuilder::new("Hi")
+    ///     .version_range(Version::V03, Version::V05)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(qrcode.version().map(|version| version as usize), Some(Version::V03 as usize));
+    /// ```
+    pub fn version_range(&mut self, min: Version, max: Version) -> &mut Self {
+        self.version_range = if (min as usize) <= (max as usize) {
+            Some((min, max))
+        } else {
+            Some((max, min))
+        };
+        self
+    }
+
     /// Forces the mask, should very rarely be used
     pub fn mask(&mut self, mask: Mask) -> &mut Self {
         self.mask = Some(mask);
         self
     }
 
+    /// Restricts the automatic mask selection to `masks`, an empty slice allows every mask.
+    ///
+    /// A mask forced with [`QRBuilder::mask`] is used as is. Micro `QRCode`s only use the
+    /// masks they support among `masks`, or all of theirs when there is none.
+    ///
+    /// ```rust
+    /// use fast_qr::{Mask, QRBuilder};
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/")
+    ///     .mask_candidates(&[Mask::Checkerboard, Mask::Diamonds])
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(matches!(qrcode.mask(), Mask::Checkerboard | Mask::Diamonds));
+    /// ```
+    pub fn mask_candidates(&mut self, masks: &[Mask]) -> &mut Self {
+        self.mask_candidates = masks.to_vec();
+        self
+    }
+
+    /// Adds an ECI header telling scanners which charset `input` is encoded in
+    pub fn eci(&mut self, eci: EciCode) -> &mut Self {
+        self.eci = Some(eci);
+        self
+    }
+
+    /// Adds an FNC1 indicator, flagging `input` as GS1 or industry formatted data.
+    ///
+    /// `GS` (`0x1D`) separates the data fields. With FNC1, `%` stands for `GS` in
+    /// Alphanumeric mode: segments containing `%` are encoded in Byte mode instead.
+    pub fn fnc1(&mut self, fnc1: Fnc1) -> &mut Self {
+        self.fnc1 = Some(fnc1);
+        self
+    }
+
+    /// Forces the segments, `input` and `mode` are then ignored.
+    /// By default, modes are mixed automatically when it allows a smaller version.
+    pub fn segments(&mut self, segments: Vec<Segment>) -> &mut Self {
+        self.segments = Some(segments);
+        self
+    }
+
+    /// Appends a pre-encoded segment (see [`Segment::raw`]) to the forced segments,
+    /// `input` and `mode` are then ignored.
+    ///
+    /// The bits are checked to fit in the version when building, but are not validated otherwise.
+    pub fn raw_segment(&mut self, mode: Mode, bits: &[bool]) -> &mut Self {
+        self.segments
+            .get_or_insert_with(Vec::new)
+            .push(Segment::raw(mode, bits));
+        self
+    }
+
+    /// Creates a Micro `QRCode` (M1 to M4) instead of a regular one.
+    ///
+    /// Micro `QRCode`s default to `ECL::L`, do not support `ECL::H`, ECI nor FNC1,
+    /// and only use 4 masks.
+    /// They need a margin of 2 modules instead of 4.
+    pub fn micro(&mut self) -> &mut Self {
+        self.micro = true;
+        self
+    }
+
+    /// Forces the Micro `QRCode` version, implies [`QRBuilder::micro`]
+    pub fn micro_version(&mut self, version: MicroVersion) -> &mut Self {
+        self.micro = true;
+        self.micro_version = Some(version);
+        self
+    }
+
     /// Computes a [`QRCode`] with given parameters
     ///
     /// # Errors
     /// - `QRCodeError::EncodedData` if `input` is too large to be encoded. See [an online table](https://fast-qr.com/blog/tables/ecl) for more info.
     /// - `QRCodeError::SpecifiedVersion` if specified `version` is too small to contain data
+    /// - `QRCodeError::VersionRange` if the data needs a version above [`QRBuilder::version_range`]
+    /// - `QRCodeError::InvalidMode` if `input` cannot be represented in the forced `mode`
     pub fn build(&self) -> Result<QRCode, QRCodeError> {
-        QRCode::new(&self.input, self.ecl, self.version, self.mode, self.mask)
+        self.build_input(&mut Buffers::new(), &self.input)
+    }
+
+    /// Checks that [`QRBuilder::build`] would succeed and tells why otherwise, without
+    /// building the matrix, e.g. to validate a form field on each keystroke
+    ///
+    /// # Errors
+    /// - `ValidationError::InvalidCharacter` if `input` cannot be represented in the forced `mode`
+    /// - `ValidationError::TooLong` if `input` is too large to be encoded
+    /// - `ValidationError::VersionTooSmall`, `ValidationError::VersionOutOfRange` and
+    ///   `ValidationError::MicroVersionTooSmall` if the version constraints are too small
+    /// - `ValidationError::MicroUnsupported` for options Micro `QRCode`s do not support
+    ///
+    /// ```rust
+    /// use fast_qr::qr::ValidationError;
+    /// use fast_qr::{QRBuilder, Version};
+    ///
+    /// let error = QRBuilder::new("https://example.com/")
+    ///     .version(Version::V01)
+    ///     .validate();
+    /// assert!(matches!(
+    ///     error,
+    ///     Err(ValidationError::VersionTooSmall { needed: Version::V02, .. })
+    /// ));
+    /// ```
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if let (Some(mode), None) = (self.mode, &self.segments) {
+            let invalid = self
+                .input
+                .iter()
+                .position(|&c| Segment::new(mode, [c]).is_none());
+            if let Some(index) = invalid {
+                return Err(ValidationError::InvalidCharacter { mode, index });
+            }
+        }
+
+        if self.micro {
+            let ecl = self.ecl.unwrap_or(ECL::L);
+            let (_, needed) = self.micro_plan(&self.input).map_err(|err| match err {
+                QRCodeError::MicroUnsupported => ValidationError::MicroUnsupported,
+                _ => ValidationError::TooLong {
+                    capacity: MicroVersion::M4.data_bits(ecl).unwrap_or(0),
+                },
+            })?;
+
+            return match self.micro_version {
+                Some(version) if (version as usize) < needed as usize => {
+                    Err(ValidationError::MicroVersionTooSmall { needed, version })
+                }
+                _ => Ok(()),
+            };
+        }
+
+        let level = self.ecl.unwrap_or(ECL::Q);
+        let (_, needed) =
+            self.plan(&self.input, self.headers())
+                .map_err(|_| ValidationError::TooLong {
+                    capacity: hardcode::data_bits(Version::V40, level),
+                })?;
+
+        match (self.version, self.version_range) {
+            (Some(version), _) if (version as usize) < needed as usize => {
+                Err(ValidationError::VersionTooSmall { needed, version })
+            }
+            (None, Some((_, max))) if needed as usize > max as usize => {
+                Err(ValidationError::VersionOutOfRange { needed, max })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Computes a [`QRCode`] for each input, with the parameters of this builder (`ecl`,
+    /// `version`, `mask`...). Forced `segments` replace every input, like with
+    /// [`QRBuilder::build`].
+    ///
+    /// With the `rayon` feature, [`QRCode`]s are built in parallel.
+    ///
+    /// ```rust
+    /// use fast_qr::{QRBuilder, ECL};
+    ///
+    /// let tickets = (0..100).map(|id| format!("TICKET-{id:05}"));
+    /// let qrcodes = QRBuilder::new("").ecl(ECL::M).build_many(tickets);
+    ///
+    /// assert_eq!(qrcodes.len(), 100);
+    /// assert!(qrcodes.iter().all(Result::is_ok));
+    /// ```
+    pub fn build_many<I, D>(&self, inputs: I) -> Vec<Result<QRCode, QRCodeError>>
+    where
+        I: IntoIterator<Item = D>,
+        D: Into<Vec<u8>>,
+    {
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+
+            let inputs = inputs.into_iter().map(Into::into).collect::<Vec<Vec<u8>>>();
+            inputs
+                .par_iter()
+                .map_init(Buffers::new, |buffers, input| {
+                    self.build_input(buffers, input)
+                })
+                .collect()
+        }
+
+        #[cfg(not(feature = "rayon"))]
+        {
+            let mut buffers = Buffers::new();
+            inputs
+                .into_iter()
+                .map(|input| self.build_input(&mut buffers, &input.into()))
+                .collect()
+        }
+    }
+
+    /// Computes a [`QRCode`] of `input` with given parameters, using `buffers` while encoding
+    fn build_input(&self, buffers: &mut Buffers, input: &[u8]) -> Result<QRCode, QRCodeError> {
+        if self.micro {
+            return self.build_micro(input);
+        }
+
+        let headers = self.headers();
+        let (segments, version) = self.plan(input, headers)?;
+        let version = self.user_version(version)?;
+
+        Ok(self.create_matrix(buffers, &segments, version, headers))
+    }
+
+    /// Encodes the input like [`QRBuilder::build`], but stops before placing it on the matrix:
+    /// returns the data and error correction codewords, interleaved
+    ///
+    /// Place them with [`QRCode::from_codewords`], possibly after altering them.
+    ///
+    /// # Errors
+    /// - Same as [`QRBuilder::build`]
+    /// - `QRCodeError::MicroUnsupported` for Micro `QRCode`s
+    ///
+    /// ```rust
+    /// use fast_qr::{QRBuilder, QRCode};
+    ///
+    /// let builder = QRBuilder::new("https://example.com/");
+    /// let codewords = builder.build_codewords().unwrap();
+    /// assert_eq!(codewords.as_bytes().len(), 44);
+    ///
+    /// let qrcode = QRCode::from_codewords(&codewords, None);
+    /// assert_eq!(qrcode.to_str(), builder.build().unwrap().to_str());
+    /// ```
+    pub fn build_codewords(&self) -> Result<Codewords, QRCodeError> {
+        if self.micro {
+            return Err(QRCodeError::MicroUnsupported);
+        }
+
+        let headers = self.headers();
+        let (segments, version) = self.plan(&self.input, headers)?;
+        let version = self.user_version(version)?;
+        let ecl = self.level(&segments, version, headers);
+
+        let structure =
+            placement::interleaved_codewords(&mut Buffers::new(), &segments, ecl, version, headers);
+        Ok(Codewords {
+            bytes: structure[..version.max_bytes()].to_vec(),
+            version,
+            ecl,
+            mode: Some(placement::segments_mode(&segments)),
+        })
+    }
+
+    /// Returns the version [`QRBuilder::build`] would use, without building the [`QRCode`].
+    ///
+    /// Returns `None` if building would fail, or for Micro `QRCode`s.
+    ///
+    /// ```rust
+    /// use fast_qr::{QRBuilder, Version};
+    ///
+    /// let version = QRBuilder::new("https://example.com/").estimate_version();
+    /// assert!(matches!(version, Some(Version::V02)));
+    /// ```
+    #[must_use]
+    pub fn estimate_version(&self) -> Option<Version> {
+        if self.micro {
+            return None;
+        }
+
+        let (_, version) = self.plan(&self.input, self.headers()).ok()?;
+        self.user_version(version).ok()
+    }
+
+    /// Returns the headers written before the data segments
+    fn headers(&self) -> Headers {
+        Headers {
+            eci: self.eci,
+            fnc1: self.fnc1,
+            ..Headers::default()
+        }
+    }
+
+    /// Returns the forced version if it can contain the data, `version` otherwise, kept in
+    /// the version range
+    fn user_version(&self, version: Version) -> Result<Version, QRCodeError> {
+        match (self.version, self.version_range) {
+            (Some(user_version), _) if user_version as usize >= version as usize => {
+                Ok(user_version)
+            }
+            (Some(_), _) => Err(QRCodeError::SpecifiedVersion),
+            (None, Some((_, max))) if version as usize > max as usize => {
+                Err(QRCodeError::VersionRange)
+            }
+            (None, Some((min, _))) if (version as usize) < (min as usize) => Ok(min),
+            (None, _) => Ok(version),
+        }
+    }
+
+    /// Splits `input` across up to 16 [`QRCode`]s linked with Structured Append,
+    /// none of them being bigger than `max_version`.
+    ///
+    /// Returns a single [`QRCode`] (without Structured Append header) if `input` fits in one.
+    /// `segments` and `version` are ignored, `input` is split in parts whose lengths differ by
+    /// one byte at most.
+    ///
+    /// # Errors
+    /// - `QRCodeError::EncodedData` if `input` does not fit in 16 `QRCode`s of `max_version`
+    pub fn build_multi(&self, max_version: Version) -> Result<Vec<QRCode>, QRCodeError> {
+        const MAX_SYMBOLS: usize = 16;
+
+        let fits = |version: Version| version as usize <= max_version as usize;
+        let input = &self.input[..];
+        let buffers = &mut Buffers::new();
+
+        let headers = self.headers();
+        if let Ok((segments, version)) = self.plan(input, headers) {
+            if fits(version) {
+                return Ok(vec![
+                    self.create_matrix(buffers, &segments, version, headers)
+                ]);
+            }
+        }
+
+        let parity = input.iter().fold(0, |parity, &byte| parity ^ byte);
+
+        for total in 2..=MAX_SYMBOLS.min(input.len()) {
+            let plans = split_parts(input, total)
+                .enumerate()
+                .map(|(index, chunk)| {
+                    let headers = Headers {
+                        structured_append: Some(StructuredAppend {
+                            index: index as u8,
+                            total: total as u8,
+                            parity,
+                        }),
+                        eci: self.eci,
+                        fnc1: self.fnc1,
+                    };
+                    let (segments, version) = self.plan(chunk, headers).ok()?;
+                    fits(version).then(|| (segments, version, headers))
+                })
+                .collect::<Option<Vec<_>>>();
+
+            if let Some(plans) = plans {
+                return Ok(plans
+                    .iter()
+                    .map(|(segments, version, headers)| {
+                        self.create_matrix(buffers, segments, *version, *headers)
+                    })
+                    .collect());
+            }
+        }
+
+        Err(QRCodeError::EncodedData)
+    }
+
+    /// Computes a Micro [`QRCode`], `version` is ignored in favor of `micro_version`
+    fn build_micro(&self, input: &[u8]) -> Result<QRCode, QRCodeError> {
+        let (segments, version) = self.micro_plan(input)?;
+        let version = match self.micro_version {
+            Some(user_version) if user_version as usize >= version as usize => user_version,
+            None => version,
+            Some(_) => return Err(QRCodeError::SpecifiedVersion),
+        };
+
+        let ecl = self.ecl.unwrap_or(ECL::L);
+        Ok(micro::create(
+            &segments,
+            ecl,
+            version,
+            self.mask,
+            &self.mask_candidates,
+        ))
+    }
+
+    /// Chooses the segments and the smallest Micro version containing them
+    fn micro_plan(&self, input: &[u8]) -> Result<(Cow<'_, [Segment]>, MicroVersion), QRCodeError> {
+        let ecl = self.ecl.unwrap_or(ECL::L);
+        let mask_supported = self
+            .mask
+            .map_or(true, |mask| micro::mask_number(mask).is_some());
+        let raw = self
+            .segments
+            .as_ref()
+            .map_or(false, |segments| segments.iter().any(Segment::is_raw));
+        let headers = self.eci.is_some() || self.fnc1.is_some();
+        if headers || matches!(ecl, ECL::H) || !mask_supported || raw {
+            return Err(QRCodeError::MicroUnsupported);
+        }
+
+        let segments = match &self.segments {
+            Some(segments) => Cow::Borrowed(&segments[..]),
+            None => Cow::Owned(vec![self.input_segment(input)?]),
+        };
+
+        let version = micro::smallest_version(&segments, ecl).ok_or(QRCodeError::EncodedData)?;
+        Ok((segments, version))
+    }
+
+    /// Chooses the segments and the smallest version containing them and the headers
+    fn plan(
+        &self,
+        input: &[u8],
+        headers: Headers,
+    ) -> Result<(Cow<'_, [Segment]>, Version), QRCodeError> {
+        let level = self.ecl.unwrap_or(ECL::Q);
+        let extra_bits = headers.bits();
+
+        let (segments, version) = match &self.segments {
+            Some(segments) if headers.structured_append.is_none() => {
+                let version = encode::smallest_version(segments, level, extra_bits, Version::V01)
+                    .ok_or(QRCodeError::EncodedData)?;
+                (Cow::Borrowed(&segments[..]), version)
+            }
+            _ => {
+                let (segments, version) = self.input_segments(input, level, extra_bits)?;
+                (Cow::Owned(segments), version)
+            }
+        };
+
+        if headers.fnc1.is_none() {
+            return Ok((segments, version));
+        }
+
+        // Escaping can only make segments bigger, the version is searched from the current one
+        let mut segments = segments.into_owned();
+        encode::escape_fnc1(&mut segments);
+        let version = encode::smallest_version(&segments, level, extra_bits, version)
+            .ok_or(QRCodeError::EncodedData)?;
+        Ok((Cow::Owned(segments), version))
+    }
+
+    /// Places `segments` in a matrix of `version`
+    fn create_matrix(
+        &self,
+        buffers: &mut Buffers,
+        segments: &[Segment],
+        version: Version,
+        headers: Headers,
+    ) -> QRCode {
+        let level = self.level(segments, version, headers);
+        let mut mask = self.mask;
+        placement::create_matrix(
+            buffers,
+            segments,
+            level,
+            version,
+            headers,
+            &mut mask,
+            &self.mask_candidates,
+        )
+    }
+
+    /// Returns the ECL of `segments` in `version`, boosted unless disabled
+    fn level(&self, segments: &[Segment], version: Version, headers: Headers) -> ECL {
+        let mut level = self.ecl.unwrap_or(ECL::Q);
+
+        // Boosts the level while the data still fits in `version`, referring to 7.4.10 of the spec
+        if self.ecl_boost {
+            let bits = headers.bits()
+                + segments
+                    .iter()
+                    .map(|segment| segment.bits(version))
+                    .sum::<usize>();
+            for higher in [ECL::M, ECL::Q, ECL::H] {
+                if higher as usize > level as usize && bits <= hardcode::data_bits(version, higher)
+                {
+                    level = higher;
+                }
+            }
+        }
+
+        level
+    }
+
+    /// Returns `input` as a single segment, in the forced mode or the best one
+    fn input_segment(&self, input: &[u8]) -> Result<Segment, QRCodeError> {
+        match self.mode {
+            Some(mode) => Segment::new(mode, input).ok_or(QRCodeError::InvalidMode),
+            None => Ok(Segment::new_unchecked(
+                encode::best_encoding(input),
+                input.to_vec(),
+            )),
+        }
+    }
+
+    /// Splits `input` in segments, using a single mode unless mixing modes
+    /// allows a smaller version
+    fn input_segments(
+        &self,
+        input: &[u8],
+        level: ECL,
+        extra_bits: usize,
+    ) -> Result<(Vec<Segment>, Version), QRCodeError> {
+        let segment = self.input_segment(input)?;
+        let mode = segment.mode();
+        let segments = vec![segment];
+
+        // `Version::get` does not account for headers
+        let single = Version::get(mode, level, input.len())
+            .and_then(|version| encode::smallest_version(&segments, level, extra_bits, version));
+
+        // A single Numeric segment is already optimal
+        let mixed = match (self.mode, mode) {
+            (None, Mode::Alphanumeric | Mode::Byte) => {
+                encode::best_segments(input, level, extra_bits)
+            }
+            _ => None,
+        };
+
+        match (single, mixed) {
+            (Some(single), Some((mixed, version))) if (version as usize) < (single as usize) => {
+                Ok((mixed, version))
+            }
+            (Some(single), _) => Ok((segments, single)),
+            (None, Some(mixed)) => Ok(mixed),
+            (None, None) => Err(QRCodeError::EncodedData),
+        }
+    }
+}
+
+/// Reusable encoder, keeps the scratch space of a [`QRBuilder`] between builds.
+///
+/// Encoding many inputs with the same parameters (e.g. on a server) then avoids allocating the
+/// intermediate bit strings for every [`QRCode`]. The function patterns (finders, timing,
+/// alignment, version information) of the last version are kept too, inputs of the same
+/// version only get their data and format information placed.
+///
+/// # Example
+/// ```rust
+/// use fast_qr::{QRBuilder, QREncoder, Version, ECL};
+///
+/// let mut builder = QRBuilder::new("");
+/// builder.ecl(ECL::Q);
+///
+/// let mut encoder = QREncoder::new(builder);
+/// for id in 0..10 {
+///     let qrcode = encoder.encode_into(format!("https://example.com/{id}")).unwrap();
+///     assert_eq!(qrcode.version().map(|version| version as usize), Some(Version::V03 as usize));
+/// }
+/// ```
+pub struct QREncoder {
+    builder: QRBuilder,
+    buffers: Buffers,
+}
+
+impl QREncoder {
+    /// Creates a `QREncoder` using the parameters of `builder`, its input is ignored
+    #[must_use]
+    pub const fn new(builder: QRBuilder) -> QREncoder {
+        QREncoder {
+            builder,
+            buffers: Buffers::new(),
+        }
+    }
+
+    /// Computes a [`QRCode`] of `input` with the parameters of the builder, reusing the scratch
+    /// space of previous calls
+    ///
+    /// # Errors
+    /// - `QRCodeError::EncodedData` if `input` is too large to be encoded
+    /// - `QRCodeError::SpecifiedVersion` if specified `version` is too small to contain data
+    pub fn encode_into<I: AsRef<[u8]>>(&mut self, input: I) -> Result<QRCode, QRCodeError> {
+        self.builder.build_input(&mut self.buffers, input.as_ref())
     }
 }
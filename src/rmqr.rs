@@ -0,0 +1,623 @@
+//! Rectangular Micro QR codes (rMQR), from R7x43 to R17x139, referring to ISO/IEC 23941.
+//!
+//! Their matrix is not square, so they have their own [`RMQRCode`] struct, created with
+//! [`RMQRBuilder`].
+//!
+//! ```rust
+//! use fast_qr::rmqr::{RMQRBuilder, RMQRVersion};
+//!
+//! let rmqr = RMQRBuilder::new("https://example.com/")
+//!     .max_height(9)
+//!     .build()
+//!     .unwrap();
+//!
+//! assert!(matches!(rmqr.version, RMQRVersion::R9x59));
+//! assert_eq!((rmqr.width, rmqr.height), (59, 9));
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use alloc::{string::String, vec, vec::Vec};
+use core::ops::{Index, IndexMut};
+
+use crate::compact::CompactQR;
+use crate::encode::{self, Mode, Segment};
+use crate::module::{Module, ModuleType};
+use crate::qr::QRCodeError;
+use crate::{polynomials, ECL};
+
+/// Enum containing all rMQR versions, named after their height & width
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum RMQRVersion {
+    R7x43 = 0,
+    R7x59,
+    R7x77,
+    R7x99,
+    R7x139,
+    R9x43,
+    R9x59,
+    R9x77,
+    R9x99,
+    R9x139,
+    R11x27,
+    R11x43,
+    R11x59,
+    R11x77,
+    R11x99,
+    R11x139,
+    R13x27,
+    R13x43,
+    R13x59,
+    R13x77,
+    R13x99,
+    R13x139,
+    R15x43,
+    R15x59,
+    R15x77,
+    R15x99,
+    R15x139,
+    R17x43,
+    R17x59,
+    R17x77,
+    R17x99,
+    R17x139,
+}
+
+/// Error correction of a version: codewords per block, then the number of blocks and their data
+/// codewords, the second group having one more data codeword
+struct Blocks {
+    error: usize,
+    groups: [(usize, usize); 2],
+}
+
+/// `(M, H)` blocks of each version, referring to Table 8 of ISO/IEC 23941
+#[rustfmt::skip]
+const BLOCKS: [(Blocks, Blocks); 32] = [
+    (Blocks { error: 7, groups: [(1, 6), (0, 0)] }, Blocks { error: 10, groups: [(1, 3), (0, 0)] }),
+    (Blocks { error: 9, groups: [(1, 12), (0, 0)] }, Blocks { error: 14, groups: [(1, 7), (0, 0)] }),
+    (Blocks { error: 12, groups: [(1, 20), (0, 0)] }, Blocks { error: 22, groups: [(1, 10), (0, 0)] }),
+    (Blocks { error: 16, groups: [(1, 28), (0, 0)] }, Blocks { error: 30, groups: [(1, 14), (0, 0)] }),
+    (Blocks { error: 24, groups: [(1, 44), (0, 0)] }, Blocks { error: 22, groups: [(2, 12), (0, 0)] }),
+    (Blocks { error: 9, groups: [(1, 12), (0, 0)] }, Blocks { error: 14, groups: [(1, 7), (0, 0)] }),
+    (Blocks { error: 12, groups: [(1, 21), (0, 0)] }, Blocks { error: 22, groups: [(1, 11), (0, 0)] }),
+    (Blocks { error: 18, groups: [(1, 31), (0, 0)] }, Blocks { error: 16, groups: [(1, 8), (1, 9)] }),
+    (Blocks { error: 24, groups: [(1, 42), (0, 0)] }, Blocks { error: 22, groups: [(2, 11), (0, 0)] }),
+    (Blocks { error: 18, groups: [(1, 31), (1, 32)] }, Blocks { error: 22, groups: [(3, 11), (0, 0)] }),
+    (Blocks { error: 8, groups: [(1, 7), (0, 0)] }, Blocks { error: 10, groups: [(1, 5), (0, 0)] }),
+    (Blocks { error: 12, groups: [(1, 19), (0, 0)] }, Blocks { error: 20, groups: [(1, 11), (0, 0)] }),
+    (Blocks { error: 16, groups: [(1, 31), (0, 0)] }, Blocks { error: 16, groups: [(1, 7), (1, 8)] }),
+    (Blocks { error: 24, groups: [(1, 43), (0, 0)] }, Blocks { error: 22, groups: [(1, 11), (1, 12)] }),
+    (Blocks { error: 16, groups: [(1, 28), (1, 29)] }, Blocks { error: 30, groups: [(1, 14), (1, 15)] }),
+    (Blocks { error: 24, groups: [(2, 42), (0, 0)] }, Blocks { error: 30, groups: [(3, 14), (0, 0)] }),
+    (Blocks { error: 9, groups: [(1, 12), (0, 0)] }, Blocks { error: 14, groups: [(1, 7), (0, 0)] }),
+    (Blocks { error: 14, groups: [(1, 27), (0, 0)] }, Blocks { error: 28, groups: [(1, 13), (0, 0)] }),
+    (Blocks { error: 22, groups: [(1, 38), (0, 0)] }, Blocks { error: 20, groups: [(2, 10), (0, 0)] }),
+    (Blocks { error: 16, groups: [(1, 26), (1, 27)] }, Blocks { error: 28, groups: [(1, 14), (1, 15)] }),
+    (Blocks { error: 20, groups: [(1, 36), (1, 37)] }, Blocks { error: 26, groups: [(1, 11), (2, 12)] }),
+    (Blocks { error: 20, groups: [(2, 35), (1, 36)] }, Blocks { error: 28, groups: [(2, 13), (2, 14)] }),
+    (Blocks { error: 18, groups: [(1, 33), (0, 0)] }, Blocks { error: 18, groups: [(1, 7), (1, 8)] }),
+    (Blocks { error: 26, groups: [(1, 48), (0, 0)] }, Blocks { error: 24, groups: [(2, 13), (0, 0)] }),
+    (Blocks { error: 18, groups: [(1, 33), (1, 34)] }, Blocks { error: 24, groups: [(2, 10), (1, 11)] }),
+    (Blocks { error: 24, groups: [(2, 44), (0, 0)] }, Blocks { error: 22, groups: [(4, 12), (0, 0)] }),
+    (Blocks { error: 24, groups: [(2, 42), (1, 43)] }, Blocks { error: 26, groups: [(1, 13), (4, 14)] }),
+    (Blocks { error: 22, groups: [(1, 39), (0, 0)] }, Blocks { error: 20, groups: [(1, 10), (1, 11)] }),
+    (Blocks { error: 16, groups: [(2, 28), (0, 0)] }, Blocks { error: 30, groups: [(2, 14), (0, 0)] }),
+    (Blocks { error: 22, groups: [(2, 39), (0, 0)] }, Blocks { error: 28, groups: [(1, 12), (2, 13)] }),
+    (Blocks { error: 20, groups: [(2, 33), (1, 34)] }, Blocks { error: 26, groups: [(4, 14), (0, 0)] }),
+    (Blocks { error: 20, groups: [(4, 38), (0, 0)] }, Blocks { error: 26, groups: [(2, 12), (4, 13)] }),
+];
+
+/// Character count indicator lengths (Numeric, Alphanumeric, Byte) of each version, referring
+/// to Table 3 of ISO/IEC 23941
+#[rustfmt::skip]
+const CCI_BITS: [[u8; 3]; 32] = [
+    [4, 3, 3], [5, 5, 4], [6, 5, 5], [7, 6, 5], [7, 6, 6],
+    [5, 5, 4], [6, 5, 5], [7, 6, 5], [7, 6, 6], [8, 7, 6],
+    [4, 4, 3], [6, 5, 5], [7, 6, 5], [7, 6, 6], [8, 7, 6], [8, 7, 7],
+    [5, 5, 4], [6, 6, 5], [7, 6, 6], [7, 7, 6], [8, 7, 7], [8, 8, 7],
+    [7, 6, 6], [7, 7, 6], [8, 7, 7], [8, 7, 7], [9, 8, 7],
+    [7, 6, 6], [8, 7, 6], [8, 7, 7], [8, 8, 7], [9, 8, 8],
+];
+
+impl RMQRVersion {
+    pub(crate) const ALL: [RMQRVersion; 32] = [
+        RMQRVersion::R7x43,
+        RMQRVersion::R7x59,
+        RMQRVersion::R7x77,
+        RMQRVersion::R7x99,
+        RMQRVersion::R7x139,
+        RMQRVersion::R9x43,
+        RMQRVersion::R9x59,
+        RMQRVersion::R9x77,
+        RMQRVersion::R9x99,
+        RMQRVersion::R9x139,
+        RMQRVersion::R11x27,
+        RMQRVersion::R11x43,
+        RMQRVersion::R11x59,
+        RMQRVersion::R11x77,
+        RMQRVersion::R11x99,
+        RMQRVersion::R11x139,
+        RMQRVersion::R13x27,
+        RMQRVersion::R13x43,
+        RMQRVersion::R13x59,
+        RMQRVersion::R13x77,
+        RMQRVersion::R13x99,
+        RMQRVersion::R13x139,
+        RMQRVersion::R15x43,
+        RMQRVersion::R15x59,
+        RMQRVersion::R15x77,
+        RMQRVersion::R15x99,
+        RMQRVersion::R15x139,
+        RMQRVersion::R17x43,
+        RMQRVersion::R17x59,
+        RMQRVersion::R17x77,
+        RMQRVersion::R17x99,
+        RMQRVersion::R17x139,
+    ];
+
+    /// Returns the height of the matrix, 7 to 17
+    #[must_use]
+    pub const fn height(self) -> usize {
+        match self as usize {
+            0..=4 => 7,
+            5..=9 => 9,
+            10..=15 => 11,
+            16..=21 => 13,
+            22..=26 => 15,
+            _ => 17,
+        }
+    }
+
+    /// Returns the width of the matrix, 27 to 139
+    #[must_use]
+    pub const fn width(self) -> usize {
+        const WIDTHS: [usize; 6] = [27, 43, 59, 77, 99, 139];
+
+        // Heights 11 and 13 start at width 27
+        let index = match self.height() {
+            7 => self as usize + 1,
+            9 => self as usize - 4,
+            11 => self as usize - 10,
+            13 => self as usize - 16,
+            15 => self as usize - 21,
+            _ => self as usize - 26,
+        };
+        WIDTHS[index]
+    }
+
+    /// Returns the columns of the alignment patterns, also holding vertical timing patterns
+    const fn alignment_columns(self) -> &'static [usize] {
+        match self.width() {
+            27 => &[],
+            43 => &[21],
+            59 => &[19, 39],
+            77 => &[25, 51],
+            99 => &[23, 49, 75],
+            _ => &[27, 55, 83, 111],
+        }
+    }
+
+    /// Returns the blocks for `ecl`, `ECL::M` or `ECL::H`
+    const fn blocks(self, ecl: ECL) -> &'static Blocks {
+        match ecl {
+            ECL::H => &BLOCKS[self as usize].1,
+            _ => &BLOCKS[self as usize].0,
+        }
+    }
+
+    /// Returns the number of data codewords
+    pub(crate) const fn data_codewords(self, ecl: ECL) -> usize {
+        let [(count1, data1), (count2, data2)] = self.blocks(ecl).groups;
+        count1 * data1 + count2 * data2
+    }
+
+    /// Returns the length of the character count indicator
+    pub(crate) const fn cci_bits(self, mode: Mode) -> usize {
+        let bits = CCI_BITS[self as usize];
+        match mode {
+            Mode::Numeric => bits[0] as usize,
+            Mode::Alphanumeric => bits[1] as usize,
+            Mode::Byte => bits[2] as usize,
+        }
+    }
+}
+
+/// rMQR matrix, `width x height` modules stored row by row
+///
+/// Like Micro `QRCode`s, they need a quiet zone of 2 modules.
+#[derive(Clone, Debug)]
+pub struct RMQRCode {
+    /// Modules row by row, `width x height`
+    pub data: Vec<Module>,
+    /// Width in modules
+    pub width: usize,
+    /// Height in modules
+    pub height: usize,
+    /// Version of the symbol
+    pub version: RMQRVersion,
+    /// `ECL::M` or `ECL::H`
+    pub ecl: ECL,
+}
+
+impl Index<usize> for RMQRCode {
+    type Output = [Module];
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[index * self.width..(index + 1) * self.width]
+    }
+}
+
+impl IndexMut<usize> for RMQRCode {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.data[index * self.width..(index + 1) * self.width]
+    }
+}
+
+impl RMQRCode {
+    /// Returns an iterator over the rows of the matrix, from top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[Module]> {
+        self.data.chunks_exact(self.width)
+    }
+
+    /// Returns the matrix as rows of booleans, `true` being a dark module
+    #[must_use]
+    pub fn to_bool_matrix(&self) -> Vec<Vec<bool>> {
+        self.rows()
+            .map(|row| row.iter().map(|module| module.value()).collect())
+            .collect()
+    }
+
+    /// Returns the rMQR as a string of Unicode half blocks, same as [`crate::QRCode::to_str`]
+    #[cfg(not(feature = "wasm-bindgen"))]
+    #[must_use]
+    pub fn to_str(&self) -> String {
+        let rows = self.rows().collect::<Vec<_>>();
+        crate::helpers::print_rows_with_margin(&rows, self.width)
+    }
+}
+
+/// Builder struct, makes it easier to create a [`RMQRCode`]
+///
+/// # Example
+/// ```rust
+/// use fast_qr::rmqr::{RMQRBuilder, RMQRVersion};
+/// use fast_qr::ECL;
+///
+/// let rmqr = RMQRBuilder::new("LOT 4711")
+///     .ecl(ECL::H)
+///     .version(RMQRVersion::R7x59)
+///     .build()
+///     .unwrap();
+/// assert_eq!(rmqr.to_bool_matrix().len(), 7);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RMQRBuilder {
+    input: Vec<u8>,
+    ecl: ECL,
+    mode: Option<Mode>,
+    version: Option<RMQRVersion>,
+    max_height: usize,
+}
+
+impl RMQRBuilder {
+    /// Creates an instance of `RMQRBuilder` with default parameters
+    #[must_use]
+    pub fn new<I: Into<Vec<u8>>>(input: I) -> Self {
+        RMQRBuilder {
+            input: input.into(),
+            ecl: ECL::M,
+            mode: None,
+            version: None,
+            max_height: 17,
+        }
+    }
+
+    /// Sets the error correction level, rMQR only has `ECL::M` (default) and `ECL::H`:
+    /// `ECL::L` is raised to `ECL::M` and `ECL::Q` to `ECL::H`
+    pub fn ecl(&mut self, ecl: ECL) -> &mut Self {
+        self.ecl = match ecl {
+            ECL::L | ECL::M => ECL::M,
+            ECL::Q | ECL::H => ECL::H,
+        };
+        self
+    }
+
+    /// Forces the encoding mode, the input has to be representable in it
+    pub fn mode(&mut self, mode: Mode) -> &mut Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Forces the version
+    pub fn version(&mut self, version: RMQRVersion) -> &mut Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Limits the height of the symbol to `max_height` modules, 7 to 17 (default: 17)
+    ///
+    /// The smallest symbol (in area) holding the data is chosen, see [`RMQRBuilder::version`]
+    /// to force the width too.
+    pub fn max_height(&mut self, max_height: usize) -> &mut Self {
+        self.max_height = max_height.clamp(7, 17);
+        self
+    }
+
+    /// Computes a [`RMQRCode`] with given parameters
+    ///
+    /// # Errors
+    /// - `QRCodeError::EncodedData` if `input` is too large for every allowed version
+    /// - `QRCodeError::SpecifiedVersion` if the forced `version` is too small to contain data
+    /// - `QRCodeError::InvalidMode` if `input` cannot be represented in the forced `mode`
+    pub fn build(&self) -> Result<RMQRCode, QRCodeError> {
+        let segment = match self.mode {
+            Some(mode) => Segment::new(mode, &self.input[..]).ok_or(QRCodeError::InvalidMode)?,
+            None => Segment::new_unchecked(encode::best_encoding(&self.input), self.input.clone()),
+        };
+
+        let version = match self.version {
+            Some(version) if fits(&segment, version, self.ecl) => version,
+            Some(_) => return Err(QRCodeError::SpecifiedVersion),
+            None => RMQRVersion::ALL
+                .iter()
+                .copied()
+                .filter(|version| version.height() <= self.max_height)
+                .filter(|&version| fits(&segment, version, self.ecl))
+                .min_by_key(|version| (version.width() * version.height(), version.height()))
+                .ok_or(QRCodeError::EncodedData)?,
+        };
+
+        Ok(create(&segment, self.ecl, version))
+    }
+}
+
+/// Whether `segment` fits in the data codewords of `version`
+fn fits(segment: &Segment, version: RMQRVersion, ecl: ECL) -> bool {
+    let cci_bits = version.cci_bits(segment.mode());
+    let bits = 3 + cci_bits + encode::character_bits(segment.mode(), segment.data().len());
+
+    segment.data().len() >> cci_bits == 0 && bits <= version.data_codewords(ecl) * 8
+}
+
+/// Encodes the segment, returns the data codewords
+fn encode(segment: &Segment, ecl: ECL, version: RMQRVersion) -> Vec<u8> {
+    const PAD_BYTES: [u8; 2] = [0b1110_1100, 0b0001_0001];
+
+    let data_bits = version.data_codewords(ecl) * 8;
+    let mut compact = CompactQR::new();
+
+    let indicator = match segment.mode() {
+        Mode::Numeric => 0b001,
+        Mode::Alphanumeric => 0b010,
+        Mode::Byte => 0b011,
+    };
+    compact.push_bits(indicator, 3);
+    compact.push_bits(segment.data().len(), version.cci_bits(segment.mode()));
+    encode::encode_characters(&mut compact, segment.mode(), segment.data());
+
+    let terminator = core::cmp::min(3, data_bits - compact.len());
+    compact.push_bits(0, terminator);
+    compact.push_bits(0, (8 - compact.len() % 8) % 8);
+
+    for &pad in PAD_BYTES.iter().cycle() {
+        if compact.len() >= data_bits {
+            break;
+        }
+        compact.push_u8(pad);
+    }
+
+    compact.get_data()[..data_bits / 8].to_vec()
+}
+
+/// Splits the data in blocks, computes their error correction and interleaves them
+pub(crate) fn interleave(data: &[u8], ecl: ECL, version: RMQRVersion) -> Vec<u8> {
+    let blocks = version.blocks(ecl);
+    let generator = polynomials::generator(blocks.error);
+
+    let mut data_blocks = Vec::new();
+    let mut rest = data;
+    for &(count, len) in &blocks.groups {
+        for _ in 0..count {
+            let (block, next) = rest.split_at(len);
+            data_blocks.push(block);
+            rest = next;
+        }
+    }
+    let error_blocks = data_blocks
+        .iter()
+        .map(|block| polynomials::division(block, &generator))
+        .collect::<Vec<_>>();
+
+    let longest = data_blocks
+        .iter()
+        .map(|block| block.len())
+        .max()
+        .unwrap_or(0);
+    let mut codewords = Vec::with_capacity(data.len() + blocks.error * data_blocks.len());
+    for i in 0..longest {
+        codewords.extend(data_blocks.iter().filter_map(|block| block.get(i)));
+    }
+    for i in 0..blocks.error {
+        codewords.extend(
+            error_blocks
+                .iter()
+                .map(|block| block[255 - blocks.error + i]),
+        );
+    }
+
+    codewords
+}
+
+/// Returns the 18 bits format information: the ECL bit and the version, followed by a
+/// BCH(18, 6) code
+pub(crate) const fn format_information(version: RMQRVersion, ecl: ECL) -> usize {
+    const GENERATOR: usize = 0b1_1111_0010_0101;
+
+    let data = (matches!(ecl, ECL::H) as usize) << 5 | version as usize;
+
+    let mut remainder = data << 12;
+    let mut i = 17;
+    while i >= 12 {
+        if remainder & (1 << i) != 0 {
+            remainder ^= GENERATOR << (i - 12);
+        }
+        i -= 1;
+    }
+
+    data << 12 | remainder
+}
+
+/// Creates the matrix with the finder patterns, alignment & timing patterns and reserved
+/// format information
+pub(crate) fn create_matrix(version: RMQRVersion) -> RMQRCode {
+    let (width, height) = (version.width(), version.height());
+    let mut rmqr = RMQRCode {
+        data: vec![Module::data(Module::LIGHT); width * height],
+        width,
+        height,
+        version,
+        ecl: ECL::M,
+    };
+
+    // Timing patterns on the edges and in the alignment columns
+    for x in 0..width {
+        rmqr[0][x] = Module::timing(x % 2 == 0);
+        rmqr[height - 1][x] = Module::timing(x % 2 == 0);
+    }
+    for y in 1..height - 1 {
+        rmqr[y][0] = Module::timing(y % 2 == 0);
+        rmqr[y][width - 1] = Module::timing(y % 2 == 0);
+    }
+    for &x in version.alignment_columns() {
+        for y in 3..height - 3 {
+            rmqr[y][x] = Module::timing((x + y) % 2 == 0);
+        }
+        for dy in 0..3 {
+            for dx in 0..3 {
+                let value = dx != 1 || dy != 1;
+                rmqr[dy][x + dx - 1] = Module::alignment(value);
+                rmqr[height - 3 + dy][x + dx - 1] = Module::alignment(value);
+            }
+        }
+    }
+
+    // Finder pattern and its separator
+    for y in 0..7 {
+        for x in 0..7 {
+            let border = x == 0 || x == 6 || y == 0 || y == 6;
+            let center = (2..=4).contains(&x) && (2..=4).contains(&y);
+            rmqr[y][x] = Module::finder_pattern(border || center);
+        }
+    }
+    for i in 0..core::cmp::min(8, height) {
+        rmqr[i][7] = Module::empty(Module::LIGHT);
+    }
+    if height > 7 {
+        for i in 0..8 {
+            rmqr[7][i] = Module::empty(Module::LIGHT);
+        }
+    }
+
+    // Finder sub-pattern, bottom right
+    for y in height - 5..height {
+        for x in width - 5..width {
+            let border = x == width - 5 || x == width - 1 || y == height - 5 || y == height - 1;
+            let center = x == width - 3 && y == height - 3;
+            rmqr[y][x] = Module::finder_pattern(border || center);
+        }
+    }
+
+    // Corner finder patterns, top right and bottom left
+    for x in width - 5..width {
+        rmqr[0][x] = Module::finder_pattern(Module::DARK);
+    }
+    rmqr[1][width - 1] = Module::finder_pattern(Module::DARK);
+    rmqr[1][width - 2] = Module::finder_pattern(Module::LIGHT);
+    rmqr[2][width - 1] = Module::finder_pattern(Module::DARK);
+    if height > 9 {
+        for x in 0..3 {
+            rmqr[height - 1][x] = Module::finder_pattern(Module::DARK);
+        }
+        rmqr[height - 2][0] = Module::finder_pattern(Module::DARK);
+        rmqr[height - 2][1] = Module::finder_pattern(Module::LIGHT);
+        rmqr[height - 3][0] = Module::finder_pattern(Module::DARK);
+    }
+
+    place_format_information(&mut rmqr, 0);
+    rmqr
+}
+
+/// Places the format information next to both finder patterns, masked differently,
+/// bit 0 first
+fn place_format_information(rmqr: &mut RMQRCode, format_information: usize) {
+    const FINDER_MASK: usize = 0b01_1111_1010_1011_0010;
+    const SUB_PATTERN_MASK: usize = 0b10_0000_1010_0111_1011;
+
+    let (width, height) = (rmqr.width, rmqr.height);
+    let finder = format_information ^ FINDER_MASK;
+    let sub_pattern = format_information ^ SUB_PATTERN_MASK;
+
+    for i in 0..18 {
+        rmqr[1 + i % 5][8 + i / 5] = Module::format(finder & (1 << i) != 0);
+
+        let value = sub_pattern & (1 << i) != 0;
+        if i < 15 {
+            rmqr[height - 6 + i % 5][width - 8 + i / 5] = Module::format(value);
+        } else {
+            rmqr[height - 6][width - 5 + i - 15] = Module::format(value);
+        }
+    }
+}
+
+/// Places the codewords in upward and downward columns of width 2, starting at the bottom right,
+/// the remaining modules are left light
+fn place_data(rmqr: &mut RMQRCode, codewords: &[u8]) {
+    let mut idx = 0;
+    let mut upward = true;
+
+    for x in (1..rmqr.width - 1).rev().step_by(2) {
+        for i in 0..rmqr.height {
+            let y = if upward { rmqr.height - 1 - i } else { i };
+
+            for x in [x, x - 1] {
+                if rmqr[y][x].module_type() == ModuleType::Data && idx < codewords.len() * 8 {
+                    let value = codewords[idx / 8] & (1 << (7 - idx % 8)) != 0;
+                    rmqr[y][x].set(value);
+                    idx += 1;
+                }
+            }
+        }
+
+        upward = !upward;
+    }
+
+    #[cfg(debug_assertions)]
+    assert_eq!(idx, codewords.len() * 8);
+}
+
+/// Applies the only rMQR mask, `(y / 2 + x / 3) % 2 == 0`, to the data modules
+fn mask(rmqr: &mut RMQRCode) {
+    for y in 0..rmqr.height {
+        for x in 0..rmqr.width {
+            if rmqr[y][x].module_type() == ModuleType::Data && (y / 2 + x / 3) % 2 == 0 {
+                rmqr[y][x].toggle();
+            }
+        }
+    }
+}
+
+/// Generates the whole rMQR
+fn create(segment: &Segment, ecl: ECL, version: RMQRVersion) -> RMQRCode {
+    let data = encode(segment, ecl, version);
+    let codewords = interleave(&data, ecl, version);
+
+    let mut rmqr = create_matrix(version);
+    place_data(&mut rmqr, &codewords);
+    mask(&mut rmqr);
+    place_format_information(&mut rmqr, format_information(version, ecl));
+
+    RMQRCode { ecl, ..rmqr }
+}
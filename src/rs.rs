@@ -0,0 +1,144 @@
+//! Reed-Solomon error correction over GF(256) and the interleaving of `QRCode` blocks
+//! (`low-level` feature)
+//!
+//! The field uses the primitive polynomial `x^8 + x^4 + x^3 + x^2 + 1` (`0x11D`) and `α = 2`,
+//! like QR codes, Data Matrix and Aztec codes, referring to 7.5.2 of the spec. Polynomials are
+//! slices of codewords, highest degree first.
+//!
+//! ```rust
+//! use fast_qr::rs;
+//!
+//! let data = b"Hello, GF(256)";
+//! let ecc = rs::encode(data, 10).unwrap();
+//!
+//! let mut codewords = [&data[..], &ecc].concat();
+//! codewords[0] ^= 0xFF;
+//! codewords[7] ^= 0x0F;
+//! assert_eq!(rs::correct(&mut codewords, 10), Some(2));
+//! assert_eq!(&codewords[..data.len()], data);
+//! ```
+
+#![deny(unsafe_code)]
+#![deny(clippy::unwrap_used)]
+#![warn(missing_docs)]
+
+use alloc::vec::Vec;
+
+use crate::polynomials::{self, ANTILOG, LOG};
+use crate::{hardcode, Version, ECL};
+
+/// Longest Reed-Solomon block, data and error correction codewords
+pub const MAX_BLOCK_LEN: usize = 255;
+
+/// Returns `α^power`
+#[must_use]
+pub const fn exp(power: usize) -> u8 {
+    LOG[power % 255]
+}
+
+/// Returns the power of `α` equal to `value`, `None` for 0
+#[must_use]
+pub const fn log(value: u8) -> Option<u8> {
+    match value {
+        0 => None,
+        _ => Some(ANTILOG[value as usize]),
+    }
+}
+
+/// Adds (and subtracts) two elements, a XOR
+#[must_use]
+pub const fn add(a: u8, b: u8) -> u8 {
+    a ^ b
+}
+
+/// Multiplies two elements
+#[must_use]
+pub fn multiply(a: u8, b: u8) -> u8 {
+    polynomials::multiply(a, b)
+}
+
+/// Divides `a` by `b`, `None` when `b` is 0
+#[must_use]
+pub fn divide(a: u8, b: u8) -> Option<u8> {
+    match b {
+        0 => None,
+        _ => Some(polynomials::divide(a, b)),
+    }
+}
+
+/// Returns the generator polynomial of `degree` error correction codewords,
+/// `(x - α^0)(x - α^1)...(x - α^(degree - 1))`, as elements (not powers of `α`)
+///
+/// ```rust
+/// use fast_qr::rs;
+///
+/// // x^2 + 3x + 2
+/// assert_eq!(rs::generator(2), [1, 3, 2]);
+/// ```
+#[must_use]
+pub fn generator(degree: usize) -> Vec<u8> {
+    polynomials::generator(degree)
+        .into_iter()
+        .map(|power| exp(power as usize))
+        .collect()
+}
+
+/// Returns the `ecc_len` error correction codewords of `data`, the remainder of its division
+/// by [`generator`]
+///
+/// `None` if the block, `data` and error correction codewords, is longer than
+/// [`MAX_BLOCK_LEN`].
+#[must_use]
+pub fn encode(data: &[u8], ecc_len: usize) -> Option<Vec<u8>> {
+    if data.len() + ecc_len > MAX_BLOCK_LEN {
+        return None;
+    }
+
+    let generator = polynomials::generator(ecc_len);
+    let division = polynomials::division(data, &generator);
+    Some(division[MAX_BLOCK_LEN - ecc_len..].to_vec())
+}
+
+/// Corrects `codewords`, data followed by `ecc_len` error correction codewords, in place
+///
+/// Returns the number of corrected codewords, up to `ecc_len / 2`, `None` if there are too
+/// many errors (`codewords` is then left as is) or the block is longer than
+/// [`MAX_BLOCK_LEN`].
+#[must_use]
+pub fn correct(codewords: &mut [u8], ecc_len: usize) -> Option<usize> {
+    if codewords.len() > MAX_BLOCK_LEN || ecc_len > codewords.len() {
+        return None;
+    }
+
+    // Works on a copy, the correction may fail halfway
+    let mut corrected = codewords.to_vec();
+    let errors = polynomials::correct(&mut corrected, ecc_len)?;
+    codewords.copy_from_slice(&corrected);
+    Some(errors)
+}
+
+/// Splits the data codewords of `version` and `ecl` in blocks, computes their error correction
+/// codewords and interleaves them, referring to 7.6 of the spec
+///
+/// Returns the final sequence of codewords placed in the matrix, `None` if `data` does not
+/// have exactly the number of data codewords of `version` and `ecl`.
+///
+/// ```rust
+/// use fast_qr::{rs, Version, ECL};
+///
+/// // Version 5-Q: 2 blocks of 15 and 2 blocks of 16 data codewords
+/// let data = (0..62).collect::<Vec<u8>>();
+/// let codewords = rs::interleave(&data, Version::V05, ECL::Q).unwrap();
+///
+/// assert_eq!(codewords.len(), 134);
+/// assert_eq!(codewords[..4], [0, 15, 30, 46]);
+/// ```
+#[must_use]
+pub fn interleave(data: &[u8], version: Version, ecl: ECL) -> Option<Vec<u8>> {
+    if data.len() != hardcode::data_codewords(version, ecl) {
+        return None;
+    }
+
+    let codewords = polynomials::structure(data, ecl, version);
+    Some(codewords[..version.max_bytes()].to_vec())
+}
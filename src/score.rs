@@ -36,13 +36,33 @@ pub fn test_matrix_score_squares(qr: &QRCode) -> u32 {
     matrix_score_squares(qr)
 }
 
+#[cfg(all(test, feature = "simd"))]
+pub fn test_scalar_score_squares(qr: &QRCode) -> u32 {
+    scalar_score_squares(qr)
+}
+
+#[cfg(all(test, feature = "simd"))]
+pub fn test_scalar_dark_modules(qr: &QRCode) -> usize {
+    scalar_dark_modules(qr)
+}
+
+/// Computes scores for squares, using SIMD when available
+fn matrix_score_squares(qr: &QRCode) -> u32 {
+    #[cfg(feature = "simd")]
+    if let Some(square_score) = crate::simd::squares(qr) {
+        return square_score;
+    }
+
+    scalar_score_squares(qr)
+}
+
 /// Computes scores for squares, any 2x2 square (black or white)
 /// add 3 to the score
 ///
 /// ### Opti:
 /// We don't want to access the 4 squares each time, so we score the left most
 /// ones and only fetch the next right ones
-fn matrix_score_squares(qr: &QRCode) -> u32 {
+fn scalar_score_squares(qr: &QRCode) -> u32 {
     let mut square_score = 0;
 
     for i in 0..qr.size - 1 {
@@ -154,25 +174,67 @@ fn matrix_pattern_and_line(qr: &QRCode, qr_transpose: &QRCode) -> (u32, u32, u32
 /// Computes the number of `ModuleType::Dark` modules
 fn dark_module_score(qr: &QRCode) -> u32 {
     let n = qr.size;
-    let dark_modules = qr.data[..n * n]
-        .iter()
-        .filter(|m| m.value() == Module::DARK)
-        .count();
+
+    #[cfg(feature = "simd")]
+    let dark_modules = crate::simd::dark_modules(qr).unwrap_or_else(|| scalar_dark_modules(qr));
+    #[cfg(not(feature = "simd"))]
+    let dark_modules = scalar_dark_modules(qr);
 
     let percent = (dark_modules * 100) / (n * n);
     u32::from(hardcode::PERCENT_SCORE[percent])
 }
 
-/// Computes the score for the matrix
-/// - `matrix_pattern_and_line`:
-///   - 40 points for each [TFTTTFT] pattern (T: true / F: false)
-///   - N - 2 points for each line with N consecutive modules of the same color (N >= 5)
-/// - `matrix_score_squares`: 3 points for each 2x2 square (black or white)
-/// - `dark_module_score`: 10 points for each 5% of dark modules away from 50%
-pub fn score(qr: &QRCode, qr_transpose: &QRCode) -> u32 {
-    let dark_score = dark_module_score(qr);
-    let square_score = matrix_score_squares(qr);
+/// Counts dark modules, without SIMD
+fn scalar_dark_modules(qr: &QRCode) -> usize {
+    let n = qr.size;
+    qr.data[..n * n]
+        .iter()
+        .filter(|m| m.value() == Module::DARK)
+        .count()
+}
+
+/// Penalty points of a [`QRCode`] for each rule of the spec (7.8.3), the lower the better.
+///
+/// Rules 1 to 3 only count data modules, function patterns are always the same.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PenaltyScore {
+    /// Rule 1: N - 2 points for each run of N >= 5 modules of the same color, rows and columns
+    pub runs: u32,
+    /// Rule 2: 3 points for each 2x2 block of the same color
+    pub blocks: u32,
+    /// Rule 3: 40 points for each finder-like pattern `1011101`
+    pub finder_like: u32,
+    /// Rule 4: 10 points for each 5% of dark modules away from 50%
+    pub balance: u32,
+}
+
+impl PenaltyScore {
+    /// Returns the sum of all rules, used to select the mask
+    #[must_use]
+    pub const fn total(&self) -> u32 {
+        self.runs + self.blocks + self.finder_like + self.balance
+    }
+}
+
+/// Suggested rating of how easily a rendered [`QRCode`] scans, see [`QRCode::scannability`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Scannability {
+    /// Likely to fail on some scanners
+    Poor,
+    /// Scans in good conditions
+    Fair,
+    /// Follows the recommendations of the spec
+    Good,
+}
+
+/// Computes the penalty of each rule for the matrix
+pub fn penalty(qr: &QRCode, qr_transpose: &QRCode) -> PenaltyScore {
     let (line_score, col_score, patt_score) = matrix_pattern_and_line(qr, qr_transpose);
 
-    line_score + patt_score + col_score + dark_score + square_score
+    PenaltyScore {
+        runs: line_score + col_score,
+        blocks: matrix_score_squares(qr),
+        finder_like: patt_score,
+        balance: dark_module_score(qr),
+    }
 }
@@ -0,0 +1,380 @@
+//! SIMD versions of the loops dominating build time for large versions (`simd` feature): the
+//! GF(256) polynomial division of error correction, and the 2x2 blocks & balance rules of mask
+//! scoring.
+//!
+//! AVX2 is detected at runtime on `x86_64`, NEON is always available on `aarch64`. Functions
+//! return `None` when no instruction set is available, callers then use the scalar code.
+
+#![warn(missing_docs)]
+
+use crate::module::Module;
+use crate::polynomials::LOG;
+use crate::QRCode;
+
+/// Longest generator polynomial handled, 30 error correction codewords + 1 fit in one vector
+const MAX_GENERATOR: usize = 32;
+
+/// Zeroes after the 255 terms of the division, so vector loads and stores stay in bounds
+const PADDING: usize = 32;
+
+/// Lanes of `NIBBLE_BITS[k]` are `0xFF` when bit `k` of the lane index (modulo 16) is set
+const NIBBLE_BITS: [[u8; 32]; 4] = nibble_bits();
+
+const fn nibble_bits() -> [[u8; 32]; 4] {
+    let mut bits = [[0; 32]; 4];
+
+    let mut k = 0;
+    while k < 4 {
+        let mut lane = 0;
+        while lane < 32 {
+            if (lane % 16) & (1 << k) != 0 {
+                bits[k][lane] = 0xFF;
+            }
+            lane += 1;
+        }
+        k += 1;
+    }
+
+    bits
+}
+
+/// Instruction set available on the running CPU
+#[derive(Clone, Copy)]
+enum Kernel {
+    #[cfg(target_arch = "x86_64")]
+    Avx2,
+    #[cfg(target_arch = "aarch64")]
+    Neon,
+}
+
+#[cfg(target_arch = "x86_64")]
+fn kernel() -> Option<Kernel> {
+    is_x86_feature_detected!("avx2").then(|| Kernel::Avx2)
+}
+
+#[cfg(target_arch = "aarch64")]
+#[allow(clippy::unnecessary_wraps)]
+const fn kernel() -> Option<Kernel> {
+    Some(Kernel::Neon)
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+const fn kernel() -> Option<Kernel> {
+    None
+}
+
+/// Generator polynomial in integer notation, split in nibbles to index the product tables
+struct Generator {
+    low: [u8; MAX_GENERATOR],
+    high: [u8; MAX_GENERATOR],
+}
+
+/// Returns `factor * 2^k` for k in `0..8`, the products with each bit of a byte
+const fn powers(factor: u8) -> [u8; 8] {
+    let mut powers = [factor; 8];
+
+    let mut k = 1;
+    while k < 8 {
+        let previous = powers[k - 1];
+        powers[k] = (previous << 1) ^ if previous & 0x80 != 0 { 0x1d } else { 0 };
+        k += 1;
+    }
+
+    powers
+}
+
+/// Views modules as their `u8` representation
+fn as_bytes(modules: &[Module]) -> &[u8] {
+    // SAFETY: `Module` is `repr(transparent)` over `u8`
+    unsafe { core::slice::from_raw_parts(modules.as_ptr().cast::<u8>(), modules.len()) }
+}
+
+/// Returns whether the 2x2 block at column `j` of rows `a` and `b` is scored, like
+/// `score::matrix_score_squares` does
+fn square(a: &[u8], b: &[u8], j: usize) -> bool {
+    // `ModuleType::Data` is 0
+    let is_data = |module: u8| module & 0b1110 == 0;
+    let same = a[j] & 1 == a[j + 1] & 1 && a[j] & 1 == b[j] & 1 && b[j] & 1 == b[j + 1] & 1;
+
+    // The first column is not checked for data modules
+    same && (j == 0 || is_data(a[j]) && is_data(b[j])) && is_data(a[j + 1]) && is_data(b[j + 1])
+}
+
+/// Same as [`crate::polynomials::division`], `None` when no instruction set is available or
+/// `by` is too long
+pub(crate) fn division(from: &[u8], by: &[u8]) -> Option<[u8; 255]> {
+    if by.len() > MAX_GENERATOR {
+        return None;
+    }
+    let kernel = kernel()?;
+
+    let mut generator = Generator {
+        low: [0; MAX_GENERATOR],
+        high: [0; MAX_GENERATOR],
+    };
+    for (j, &alpha) in by.iter().enumerate() {
+        let coefficient = LOG[alpha as usize % 255];
+        generator.low[j] = coefficient & 0x0F;
+        generator.high[j] = coefficient >> 4;
+    }
+
+    let mut buffer = [0; 255 + PADDING];
+    let start = 256 - from.len() - by.len();
+    buffer[start..start + from.len()].copy_from_slice(from);
+
+    match kernel {
+        #[cfg(target_arch = "x86_64")]
+        // SAFETY: AVX2 was detected
+        Kernel::Avx2 => unsafe { avx2::division(&mut buffer, start, from.len(), &generator) },
+        #[cfg(target_arch = "aarch64")]
+        // SAFETY: NEON is part of the aarch64 baseline
+        Kernel::Neon => unsafe { neon::division(&mut buffer, start, from.len(), &generator) },
+    }
+
+    let mut division = [0; 255];
+    division.copy_from_slice(&buffer[..255]);
+    Some(division)
+}
+
+/// Same as `score::matrix_score_squares`, `None` when no instruction set is available
+pub(crate) fn squares(qr: &QRCode) -> Option<u32> {
+    let kernel = kernel()?;
+    let n = qr.size;
+
+    let mut count = 0;
+    for i in 0..n - 1 {
+        let (a, b) = (as_bytes(&qr[i]), as_bytes(&qr[i + 1]));
+
+        // First column is scalar, its type is not checked
+        count += u32::from(square(a, b, 0));
+
+        let vectorized = match kernel {
+            #[cfg(target_arch = "x86_64")]
+            // SAFETY: AVX2 was detected
+            Kernel::Avx2 => unsafe { avx2::squares(a, b) },
+            #[cfg(target_arch = "aarch64")]
+            // SAFETY: NEON is part of the aarch64 baseline
+            Kernel::Neon => unsafe { neon::squares(a, b) },
+        };
+        count += vectorized.0;
+
+        count += (vectorized.1..n - 1).filter(|&j| square(a, b, j)).count() as u32;
+    }
+
+    Some(count * 3)
+}
+
+/// Returns the number of dark modules, `None` when no instruction set is available
+pub(crate) fn dark_modules(qr: &QRCode) -> Option<usize> {
+    let kernel = kernel()?;
+    let modules = as_bytes(&qr.data[..qr.size * qr.size]);
+
+    let (count, end) = match kernel {
+        #[cfg(target_arch = "x86_64")]
+        // SAFETY: AVX2 was detected
+        Kernel::Avx2 => unsafe { avx2::dark_modules(modules) },
+        #[cfg(target_arch = "aarch64")]
+        // SAFETY: NEON is part of the aarch64 baseline
+        Kernel::Neon => unsafe { neon::dark_modules(modules) },
+    };
+
+    Some(count + modules[end..].iter().filter(|&&m| m & 1 != 0).count())
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    use core::arch::x86_64::*;
+
+    use super::{powers, Generator, NIBBLE_BITS};
+
+    const LANES: usize = 32;
+
+    /// Divides `buffer[start..start + len]` in place, the remainder ends at index 255
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn division(
+        buffer: &mut [u8; 255 + super::PADDING],
+        start: usize,
+        len: usize,
+        generator: &Generator,
+    ) {
+        let low = _mm256_loadu_si256(generator.low.as_ptr().cast());
+        let high = _mm256_loadu_si256(generator.high.as_ptr().cast());
+        let bits = NIBBLE_BITS.map(|bits| _mm256_loadu_si256(bits.as_ptr().cast()));
+
+        for i in start..start + len {
+            let factor = buffer[i];
+            if factor == 0 {
+                continue;
+            }
+
+            // Products of `factor` with every low and high nibble
+            let powers = powers(factor);
+            let mut low_table = _mm256_setzero_si256();
+            let mut high_table = _mm256_setzero_si256();
+            for k in 0..4 {
+                let low_power = _mm256_set1_epi8(powers[k] as i8);
+                let high_power = _mm256_set1_epi8(powers[k + 4] as i8);
+                low_table = _mm256_xor_si256(low_table, _mm256_and_si256(bits[k], low_power));
+                high_table = _mm256_xor_si256(high_table, _mm256_and_si256(bits[k], high_power));
+            }
+
+            let product = _mm256_xor_si256(
+                _mm256_shuffle_epi8(low_table, low),
+                _mm256_shuffle_epi8(high_table, high),
+            );
+
+            // `i + LANES` is at most `255 + PADDING`
+            let row = buffer.as_mut_ptr().add(i).cast::<__m256i>();
+            _mm256_storeu_si256(row, _mm256_xor_si256(_mm256_loadu_si256(row), product));
+        }
+    }
+
+    /// Counts the blocks of rows `a` and `b` from column 1, returns the count and the first
+    /// column left to the scalar code
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn squares(a: &[u8], b: &[u8]) -> (u32, usize) {
+        let type_bits = _mm256_set1_epi8(0b1110);
+        let zero = _mm256_setzero_si256();
+
+        let mut count = 0;
+        let mut j = 1;
+        while j + LANES < a.len() {
+            let a0 = _mm256_loadu_si256(a.as_ptr().add(j).cast());
+            let a1 = _mm256_loadu_si256(a.as_ptr().add(j + 1).cast());
+            let b0 = _mm256_loadu_si256(b.as_ptr().add(j).cast());
+            let b1 = _mm256_loadu_si256(b.as_ptr().add(j + 1).cast());
+
+            // Same bytes have the same type, only one of them has to be data
+            let same = _mm256_and_si256(
+                _mm256_and_si256(_mm256_cmpeq_epi8(a0, a1), _mm256_cmpeq_epi8(a0, b0)),
+                _mm256_cmpeq_epi8(b0, b1),
+            );
+            let data = _mm256_cmpeq_epi8(_mm256_and_si256(a0, type_bits), zero);
+
+            count += (_mm256_movemask_epi8(_mm256_and_si256(same, data)) as u32).count_ones();
+            j += LANES;
+        }
+
+        (count, j)
+    }
+
+    /// Counts dark modules, returns the count and the first module left to the scalar code
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn dark_modules(modules: &[u8]) -> (usize, usize) {
+        let value_bit = _mm256_set1_epi8(1);
+
+        let mut count = 0;
+        let mut i = 0;
+        while i + LANES <= modules.len() {
+            let chunk = _mm256_loadu_si256(modules.as_ptr().add(i).cast());
+            let dark = _mm256_cmpeq_epi8(_mm256_and_si256(chunk, value_bit), value_bit);
+
+            count += (_mm256_movemask_epi8(dark) as u32).count_ones() as usize;
+            i += LANES;
+        }
+
+        (count, i)
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod neon {
+    use core::arch::aarch64::*;
+
+    use super::{powers, Generator, NIBBLE_BITS};
+
+    const LANES: usize = 16;
+
+    /// Divides `buffer[start..start + len]` in place, the remainder ends at index 255
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn division(
+        buffer: &mut [u8; 255 + super::PADDING],
+        start: usize,
+        len: usize,
+        generator: &Generator,
+    ) {
+        let low = [
+            vld1q_u8(generator.low.as_ptr()),
+            vld1q_u8(generator.low.as_ptr().add(LANES)),
+        ];
+        let high = [
+            vld1q_u8(generator.high.as_ptr()),
+            vld1q_u8(generator.high.as_ptr().add(LANES)),
+        ];
+        let bits = NIBBLE_BITS.map(|bits| vld1q_u8(bits.as_ptr()));
+
+        for i in start..start + len {
+            let factor = buffer[i];
+            if factor == 0 {
+                continue;
+            }
+
+            // Products of `factor` with every low and high nibble
+            let powers = powers(factor);
+            let mut low_table = vdupq_n_u8(0);
+            let mut high_table = vdupq_n_u8(0);
+            for k in 0..4 {
+                low_table = veorq_u8(low_table, vandq_u8(bits[k], vdupq_n_u8(powers[k])));
+                high_table = veorq_u8(high_table, vandq_u8(bits[k], vdupq_n_u8(powers[k + 4])));
+            }
+
+            // `i + 2 * LANES` is at most `255 + PADDING`
+            for half in 0..2 {
+                let product = veorq_u8(
+                    vqtbl1q_u8(low_table, low[half]),
+                    vqtbl1q_u8(high_table, high[half]),
+                );
+
+                let row = buffer.as_mut_ptr().add(i + half * LANES);
+                vst1q_u8(row, veorq_u8(vld1q_u8(row), product));
+            }
+        }
+    }
+
+    /// Counts the blocks of rows `a` and `b` from column 1, returns the count and the first
+    /// column left to the scalar code
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn squares(a: &[u8], b: &[u8]) -> (u32, usize) {
+        let type_bits = vdupq_n_u8(0b1110);
+        let value_bit = vdupq_n_u8(1);
+        let zero = vdupq_n_u8(0);
+
+        let mut count = 0;
+        let mut j = 1;
+        while j + LANES < a.len() {
+            let a0 = vld1q_u8(a.as_ptr().add(j));
+            let a1 = vld1q_u8(a.as_ptr().add(j + 1));
+            let b0 = vld1q_u8(b.as_ptr().add(j));
+            let b1 = vld1q_u8(b.as_ptr().add(j + 1));
+
+            // Same bytes have the same type, only one of them has to be data
+            let same = vandq_u8(
+                vandq_u8(vceqq_u8(a0, a1), vceqq_u8(a0, b0)),
+                vceqq_u8(b0, b1),
+            );
+            let data = vceqq_u8(vandq_u8(a0, type_bits), zero);
+
+            count += u32::from(vaddvq_u8(vandq_u8(vandq_u8(same, data), value_bit)));
+            j += LANES;
+        }
+
+        (count, j)
+    }
+
+    /// Counts dark modules, returns the count and the first module left to the scalar code
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn dark_modules(modules: &[u8]) -> (usize, usize) {
+        let value_bit = vdupq_n_u8(1);
+
+        let mut count = 0;
+        let mut i = 0;
+        while i + LANES <= modules.len() {
+            let chunk = vld1q_u8(modules.as_ptr().add(i));
+
+            count += usize::from(vaddvq_u8(vandq_u8(chunk, value_bit)));
+            i += LANES;
+        }
+
+        (count, i)
+    }
+}
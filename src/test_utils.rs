@@ -0,0 +1,142 @@
+//! Reference vectors of the spec and helpers to check a matrix against a reference
+//! (`test-utils` feature)
+//!
+//! Renderers can sample their output back into modules and assert they did not corrupt the
+//! matrix:
+//!
+//! ```rust
+//! use fast_qr::test_utils::compare_matrix;
+//! use fast_qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//!
+//! // e.g. the pixels of a rendered image, one sample per module, with a quiet zone of 4
+//! let mut rendered = qrcode.with_quiet_zone().to_bool_matrix();
+//! assert!(compare_matrix(&qrcode, &rendered, 4).is_empty());
+//!
+//! rendered[4][4] = false;
+//! assert_eq!(compare_matrix(&qrcode, &rendered, 4).len(), 1);
+//! ```
+
+use alloc::{format, string::String, vec::Vec};
+
+use crate::{hardcode, Mask, QRCode, Version, ECL};
+
+/// Module that differs from the reference, see [`compare_matrix`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Row in the `QRCode`, without quiet zone
+    pub row: usize,
+    /// Column in the `QRCode`, without quiet zone
+    pub column: usize,
+    /// Whether the module of the `QRCode` is dark
+    pub expected: bool,
+}
+
+/// Returns the 15 bits of format information of `ecl` and `mask`, already masked with
+/// `101010000010010`, referring to Table C.1 of the spec
+///
+/// ```rust
+/// use fast_qr::test_utils::format_information;
+/// use fast_qr::{Mask, ECL};
+///
+/// assert_eq!(format_information(ECL::M, Mask::Checkerboard), "101010000010010");
+/// ```
+#[must_use]
+pub fn format_information(ecl: ECL, mask: Mask) -> String {
+    format!("{:015b}", hardcode::ecm_to_format_information(ecl, mask))
+}
+
+/// Returns the generator polynomial of the error correction codewords of `version` and `ecl`,
+/// as exponents of alpha from the highest degree, referring to Annex A of the spec
+///
+/// ```rust
+/// use fast_qr::test_utils::generator_polynomial;
+/// use fast_qr::{Version, ECL};
+///
+/// // 7 error correction codewords
+/// assert_eq!(
+///     generator_polynomial(Version::V01, ECL::L),
+///     [0, 87, 229, 146, 149, 238, 102, 21]
+/// );
+/// ```
+#[must_use]
+pub const fn generator_polynomial(version: Version, ecl: ECL) -> &'static [u8] {
+    hardcode::get_polynomial(version, ecl)
+}
+
+/// Returns the number of data codewords of `version` and `ecl`, referring to Table 7 of the
+/// spec
+#[must_use]
+pub const fn data_codewords(version: Version, ecl: ECL) -> usize {
+    hardcode::data_codewords(version, ecl)
+}
+
+/// Returns the number of codewords, data and error correction, of `version`, referring to
+/// Table 1 of the spec
+#[must_use]
+pub const fn total_codewords(version: Version) -> usize {
+    version.max_bytes()
+}
+
+/// Returns the error correction blocks of `version` and `ecl` as `(count, data codewords)` for
+/// each of the 2 groups, referring to Table 9 of the spec
+#[must_use]
+pub const fn error_correction_blocks(version: Version, ecl: ECL) -> [(usize, usize); 2] {
+    hardcode::ecc_to_groups(ecl, version)
+}
+
+/// Parses a matrix drawn as text, one row per line: `#`, `1` and `█` are dark, any other
+/// character is light. Empty lines are skipped.
+#[must_use]
+pub fn parse_matrix(text: &str) -> Vec<Vec<bool>> {
+    text.lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.chars().map(|c| matches!(c, '#' | '1' | '█')).collect())
+        .collect()
+}
+
+/// Compares the modules of `qr` with `reference`, which has `margin` light modules around
+/// the `QRCode`, and returns the modules that differ
+///
+/// A reference with the wrong size reports every module whose reference is missing.
+#[must_use]
+pub fn compare_matrix<R: AsRef<[bool]>>(
+    qr: &QRCode,
+    reference: &[R],
+    margin: usize,
+) -> Vec<Mismatch> {
+    let reference_module = |y: usize, x: usize| {
+        reference
+            .get(y + margin)
+            .and_then(|row| row.as_ref().get(x + margin).copied())
+    };
+
+    qr.iter_modules()
+        .filter(|&(x, y, module)| reference_module(y, x) != Some(module.value()))
+        .map(|(column, row, module)| Mismatch {
+            row,
+            column,
+            expected: module.value(),
+        })
+        .collect()
+}
+
+/// Panics with the differing modules if `reference` does not match `qr`, see
+/// [`compare_matrix`]
+///
+/// # Panics
+/// If a module of `reference` differs from `qr`
+pub fn assert_matrix_eq<R: AsRef<[bool]>>(qr: &QRCode, reference: &[R], margin: usize) {
+    let mismatches = compare_matrix(qr, reference, margin);
+    assert!(
+        mismatches.is_empty(),
+        "{} modules differ from the reference, first at (row, column) {:?}",
+        mismatches.len(),
+        mismatches
+            .iter()
+            .take(8)
+            .map(|mismatch| (mismatch.row, mismatch.column))
+            .collect::<Vec<_>>()
+    );
+}
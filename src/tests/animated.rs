@@ -0,0 +1,69 @@
+#[cfg(feature = "svg")]
+#[test]
+fn frames_are_shown_in_turn() {
+    use crate::convert::animated::AnimatedBuilder;
+    use crate::QRBuilder;
+
+    let svg = AnimatedBuilder::default()
+        .duration(500)
+        .frame(QRBuilder::new("first").build().unwrap())
+        .frame_with_duration(QRBuilder::new("second").build().unwrap(), 1000)
+        .frame(QRBuilder::new("third").build().unwrap())
+        .to_svg();
+
+    assert_eq!(svg.matches("<svg").count(), 4);
+    assert!(svg.contains("@keyframes fast_qr_0{0%{visibility:visible}25%,100%{visibility:hidden}}"));
+    assert!(svg.contains(
+        "@keyframes fast_qr_1{0%{visibility:hidden}25%{visibility:visible}75%,100%{visibility:hidden}}"
+    ));
+    assert!(svg.contains(
+        "@keyframes fast_qr_2{0%{visibility:hidden}75%{visibility:visible}100%{visibility:visible}}"
+    ));
+    assert!(
+        svg.contains(".fast_qr_2{visibility:hidden;animation:fast_qr_2 2000ms step-end infinite}")
+    );
+}
+
+#[cfg(feature = "gif")]
+#[test]
+fn gif_has_a_frame_per_qrcode() {
+    use crate::convert::animated::AnimatedBuilder;
+    use crate::convert::Builder;
+    use crate::{QRBuilder, Version};
+
+    let small = QRBuilder::new("small")
+        .version(Version::V01)
+        .build()
+        .unwrap();
+    let large = QRBuilder::new("large")
+        .version(Version::V02)
+        .build()
+        .unwrap();
+    let gif = AnimatedBuilder::default()
+        .margin(1)
+        .module_size(2)
+        .frame_with_duration(small.clone(), 250)
+        .frame(large)
+        .to_gif()
+        .unwrap();
+
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::Indexed);
+    let mut decoder = options.read_info(gif.as_slice()).unwrap();
+    // Version 2 with its margin
+    assert_eq!((decoder.width(), decoder.height()), (54, 54));
+
+    let frame = decoder.read_next_frame().unwrap().unwrap();
+    assert_eq!(frame.delay, 25);
+    // Version 1 is centered: 2 modules of offset, 1 of margin
+    for y in 0..small.size {
+        for x in 0..small.size {
+            let pixel = frame.buffer[(y + 3) * 2 * 54 + (x + 3) * 2];
+            assert_eq!(pixel == 1, small[y][x].value());
+        }
+    }
+
+    let frame = decoder.read_next_frame().unwrap().unwrap();
+    assert_eq!(frame.delay, 100);
+    assert!(decoder.read_next_frame().unwrap().is_none());
+}
@@ -0,0 +1,46 @@
+use crate::art::{ArtCanvas, ArtError};
+use crate::{decode, QRBuilder, ECL};
+
+#[test]
+fn art_stays_within_the_budget() {
+    let qrcode = QRBuilder::new("https://example.com/")
+        .ecl(ECL::H)
+        .build()
+        .unwrap();
+    let n = qrcode.size;
+
+    let mut canvas = ArtCanvas::new(&qrcode).unwrap();
+    assert_eq!(canvas.flip(0, 0), Err(ArtError::FunctionModule));
+    assert_eq!(canvas.flip(n, 0), Err(ArtError::OutOfBounds));
+
+    // Darkens the whole matrix, as far as the budget goes
+    let refused = canvas.draw(0, 0, &vec![vec![true; n]; n]);
+    assert!(refused > 0);
+    for block in canvas.budget() {
+        assert_eq!(block.used, block.allowed);
+        assert!(block.allowed <= block.correctable / 2);
+    }
+    assert_eq!(
+        decode::decode(canvas.qrcode()).unwrap(),
+        b"https://example.com/"
+    );
+
+    // Restoring the modules gives the budget back
+    for y in 0..n {
+        for x in 0..n {
+            let _ = canvas.set(y, x, qrcode[y][x].value());
+        }
+    }
+    assert!(canvas.budget().iter().all(|block| block.used == 0));
+    assert_eq!(canvas.qrcode().to_str(), qrcode.to_str());
+
+    // Without threshold, up to every correctable codeword may be damaged
+    canvas.threshold(1f64).draw(0, 0, &vec![vec![false; n]; n]);
+    for block in canvas.budget() {
+        assert_eq!(block.used, block.correctable);
+    }
+    assert!(decode::decode(canvas.qrcode()).is_ok());
+
+    let micro = QRBuilder::new("123").micro().build().unwrap();
+    assert_eq!(ArtCanvas::new(&micro).unwrap_err(), ArtError::Unsupported);
+}
@@ -0,0 +1,61 @@
+use crate::convert::bmp::BmpBuilder;
+use crate::{QRBuilder, Version, ECL};
+
+fn qrcode() -> crate::QRCode {
+    QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap()
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+#[test]
+fn header_describes_a_bottom_up_1_bit_image() {
+    let qrcode = qrcode();
+    let bmp = BmpBuilder::default().margin(1).to_bytes(&qrcode);
+
+    // 23 pixels, rows of 4 bytes
+    assert_eq!(&bmp[..2], b"BM");
+    assert_eq!(u32_at(&bmp, 2) as usize, bmp.len());
+    assert_eq!(bmp.len(), 62 + 23 * 4);
+    assert_eq!(u32_at(&bmp, 10), 62);
+    assert_eq!(u32_at(&bmp, 18), 23);
+    assert_eq!(u32_at(&bmp, 22), 23);
+    assert_eq!(&bmp[28..30], [1, 0]);
+    assert_eq!(&bmp[54..62], [255, 255, 255, 0, 0, 0, 0, 0]);
+}
+
+#[test]
+fn pixels_match_the_modules() {
+    let qrcode = qrcode();
+    let bmp = BmpBuilder::default()
+        .margin(1)
+        .module_size(2)
+        .to_bytes(&qrcode);
+
+    let side = (qrcode.size + 2) * 2;
+    let stride = 8;
+    assert_eq!(bmp.len(), 62 + stride * side);
+    for y in 0..side {
+        // Rows are stored from the bottom up
+        let row = &bmp[62 + (side - 1 - y) * stride..][..stride];
+        for x in 0..side {
+            let (my, mx) = (y / 2, x / 2);
+            let dark = (1..=qrcode.size).contains(&my)
+                && (1..=qrcode.size).contains(&mx)
+                && qrcode[my - 1][mx - 1].value();
+            assert_eq!(row[x / 8] & (0x80 >> (x % 8)) != 0, dark);
+        }
+        // Padding is light
+        assert!(row[(side + 7) / 8..].iter().all(|&byte| byte == 0));
+    }
+}
@@ -27,3 +27,364 @@ fn it_can_output_to_bytes_from_image() {
     // Verify
     assert_eq!(data_uri, expected_data_uri);
 }
+
+#[cfg(feature = "image")]
+#[test]
+// A closure rather than the built-in function, which would be mapped back to its shape
+#[allow(clippy::redundant_closure)]
+fn direct_rasterizer_matches_svg_rasterization() {
+    use crate::convert::image::ImageBuilder;
+    use crate::convert::{Builder, Shape};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    // Custom commands are rasterized through usvg
+    let direct = ImageBuilder::default()
+        .shape(Shape::Square)
+        .to_pixmap(&qrcode)
+        .unwrap();
+    let through_svg = ImageBuilder::default()
+        .shape(Shape::Command(|y, x, module| Shape::Square(y, x, module)))
+        .to_pixmap(&qrcode)
+        .unwrap();
+
+    assert_eq!(direct.width(), through_svg.width());
+    assert_eq!(direct.data(), through_svg.data());
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn it_can_output_jpeg_and_webp_bytes() {
+    use crate::convert::image::{ImageBuilder, ImageFormat};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let jpeg = ImageBuilder::default()
+        .format(ImageFormat::Jpeg { quality: 90 })
+        .to_bytes(&qrcode)
+        .unwrap();
+    assert_eq!(&jpeg[..3], &[0xFF, 0xD8, 0xFF]);
+
+    let webp = ImageBuilder::default()
+        .format(ImageFormat::Webp)
+        .to_bytes(&qrcode)
+        .unwrap();
+    assert_eq!(&webp[..4], b"RIFF");
+    assert_eq!(&webp[8..12], b"WEBP");
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn malformed_image_returns_an_error() {
+    use crate::convert::image::{ImageBuilder, ImageError};
+    use crate::convert::Builder;
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    // The quote closes the `href` attribute, leaving an invalid svg
+    let result = ImageBuilder::default()
+        .image(String::from("\"<"))
+        .to_bytes(&qrcode);
+
+    assert!(matches!(result, Err(ImageError::ImageError(_))));
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn raster_images_are_composited() {
+    use base64::engine::general_purpose;
+    use base64::Engine;
+
+    use crate::convert::image::ImageBuilder;
+    use crate::convert::Builder;
+    use crate::{QRBuilder, ECL};
+
+    use resvg::tiny_skia::{Color, Pixmap};
+
+    // Logos are checked against the error correction, which must be high enough
+    let qrcode = QRBuilder::new("https://example.com/")
+        .ecl(ECL::H)
+        .build()
+        .unwrap();
+
+    let mut logo = Pixmap::new(2, 2).unwrap();
+    logo.fill(Color::from_rgba8(255, 0, 0, 255));
+    let logo = general_purpose::STANDARD.encode(logo.encode_png().unwrap());
+
+    let pixmap = ImageBuilder::default()
+        .fit_width(290)
+        .image(format!("data:image/png;base64,{logo}"))
+        .to_pixmap(&qrcode)
+        .unwrap();
+
+    let center = pixmap.pixel(145, 145).unwrap();
+    assert_eq!(
+        (center.red(), center.green(), center.blue(), center.alpha()),
+        (255, 0, 0, 255)
+    );
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn raster_images_need_error_correction() {
+    use base64::engine::general_purpose;
+    use base64::Engine;
+
+    use crate::convert::image::{ImageBuilder, ImageError};
+    use crate::convert::Builder;
+    use crate::QRBuilder;
+
+    use resvg::tiny_skia::{Color, Pixmap};
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let mut logo = Pixmap::new(2, 2).unwrap();
+    logo.fill(Color::from_rgba8(255, 0, 0, 255));
+    let logo = general_purpose::STANDARD.encode(logo.encode_png().unwrap());
+
+    let mut builder = ImageBuilder::default();
+    builder
+        .fit_width(290)
+        .image(format!("data:image/png;base64,{logo}"));
+
+    assert!(matches!(
+        builder.to_pixmap(&qrcode),
+        Err(ImageError::ImageError(_))
+    ));
+    assert!(builder.to_bytes(&qrcode).is_err());
+
+    // A smaller logo hides few enough modules
+    assert!(builder.image_size(5f64).to_pixmap(&qrcode).is_ok());
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn background_image_needs_contrast() {
+    use base64::engine::general_purpose;
+    use base64::Engine;
+
+    use crate::convert::image::{ImageBuilder, ImageError};
+    use crate::convert::Builder;
+    use crate::QRBuilder;
+
+    use resvg::tiny_skia::{Color, Pixmap};
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let mut photo = Pixmap::new(4, 4).unwrap();
+    photo.fill(Color::from_rgba8(0, 0, 0, 255));
+    let photo = general_purpose::STANDARD.encode(photo.encode_png().unwrap());
+
+    let mut builder = ImageBuilder::default();
+    builder.background_image(format!("data:image/png;base64,{photo}"));
+    assert!(matches!(
+        builder.to_pixmap(&qrcode),
+        Err(ImageError::ImageError(_))
+    ));
+
+    // A faded picture keeps light modules light
+    builder.background_image_opacity(0.2);
+    assert!(builder.to_pixmap(&qrcode).is_ok());
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn print_resolution_is_stored_in_the_metadata() {
+    use crate::convert::image::{ImageBuilder, ImageFormat};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    // 2 inches at the default 300 dpi
+    let png = ImageBuilder::default()
+        .physical_size_mm(50.8)
+        .to_bytes(&qrcode)
+        .unwrap();
+    let pixmap = resvg::tiny_skia::Pixmap::decode_png(&png).unwrap();
+    assert_eq!(pixmap.width(), 600);
+    // pHYs chunk right after IHDR: 11811 pixels per meter
+    assert_eq!(&png[33..41], b"\0\0\0\x09pHYs");
+    assert_eq!(&png[41..45], &11811u32.to_be_bytes());
+    assert_eq!(png[49], 1);
+
+    let jpeg = ImageBuilder::default()
+        .dpi(600)
+        .format(ImageFormat::Jpeg { quality: 90 })
+        .to_bytes(&qrcode)
+        .unwrap();
+    assert_eq!(&jpeg[6..11], b"JFIF\0");
+    // Unit is the inch
+    assert_eq!(&jpeg[13..16], &[1, 0x02, 0x58]);
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn crisp_modules_are_whole_pixels() {
+    use crate::convert::image::{ImageBuilder, Raster};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    // 33 modules, 6 pixels each
+    let pixmap = ImageBuilder::default()
+        .rasterization(Raster::Crisp)
+        .fit_width(200)
+        .to_pixmap(&qrcode)
+        .unwrap();
+    assert_eq!(pixmap.width(), 198);
+
+    for (i, pixel) in pixmap.pixels().iter().enumerate() {
+        let (y, x) = (i / 198 / 6, i % 198 / 6);
+        let dark = (4..29).contains(&y) && (4..29).contains(&x) && qrcode[y - 4][x - 4].value();
+        let expected = if dark { 0 } else { 255 };
+        assert_eq!((pixel.red(), pixel.alpha()), (expected, 255));
+    }
+}
+
+#[cfg(feature = "image")]
+#[test]
+// A closure rather than the built-in function, which would be mapped back to its shape
+#[allow(clippy::redundant_closure)]
+fn multiple_sizes_match_single_renders() {
+    use crate::convert::image::ImageBuilder;
+    use crate::convert::{Builder, Shape};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    for shape in [
+        Shape::Circle,
+        Shape::Command(|y, x, module| Shape::Square(y, x, module)),
+    ] {
+        let mut builder = ImageBuilder::default();
+        builder.shape(shape);
+        let pixmaps = builder.to_pixmaps(&qrcode, &[64, 256, 300]).unwrap();
+
+        assert_eq!(pixmaps.len(), 3);
+        for (pixmap, width) in pixmaps.iter().zip([64, 256, 300]) {
+            let single = builder.fit_width(width).to_pixmap(&qrcode).unwrap();
+            assert_eq!(pixmap.width(), width);
+            assert_eq!(pixmap.data(), single.data());
+        }
+    }
+}
+
+#[cfg(feature = "image")]
+#[test]
+// A closure rather than the built-in function, which would be mapped back to its shape
+#[allow(clippy::redundant_closure)]
+fn render_into_reuses_the_pixmap() {
+    use crate::convert::image::ImageBuilder;
+    use crate::convert::{Builder, Shape};
+    use crate::QRBuilder;
+    use resvg::tiny_skia::Pixmap;
+
+    let large = QRBuilder::new("a".repeat(300)).build().unwrap();
+    let small = QRBuilder::new("small").build().unwrap();
+
+    for shape in [
+        Shape::Square,
+        Shape::Command(|y, x, module| Shape::Circle(y, x, module)),
+    ] {
+        let mut builder = ImageBuilder::default();
+        builder.shape(shape);
+
+        let mut pixmap = Pixmap::new(300, 300).unwrap();
+        builder.render_into(&large, &mut pixmap).unwrap();
+        builder.render_into(&small, &mut pixmap).unwrap();
+
+        let expected = builder.fit_width(300).to_pixmap(&small).unwrap();
+        assert_eq!(pixmap.data(), expected.data());
+    }
+}
+
+#[cfg(feature = "raster")]
+#[test]
+fn square_rasterizer_writes_indexed_png() {
+    use crate::convert::image::{RasterBackend, SquareRasterizer};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let png = SquareRasterizer::default()
+        .module_size(3)
+        .margin(2)
+        .module_color("#102030")
+        .to_png(&qrcode)
+        .unwrap();
+
+    // Chunks: length, type, data and crc
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    let mut chunks = Vec::new();
+    let mut rest = &png[8..];
+    while !rest.is_empty() {
+        let len = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        chunks.push((&rest[4..8], &rest[8..8 + len]));
+        rest = &rest[12 + len..];
+    }
+    let kinds = chunks.iter().map(|(kind, _)| *kind).collect::<Vec<_>>();
+    assert_eq!(kinds, [b"IHDR", b"PLTE", b"IDAT", b"IEND"]);
+
+    // 29 modules of 3 pixels, 1 bit palette indices
+    assert_eq!(chunks[0].1, [0, 0, 0, 87, 0, 0, 0, 87, 1, 3, 0, 0, 0]);
+    assert_eq!(chunks[1].1, [255, 255, 255, 0x10, 0x20, 0x30]);
+
+    // A single stored deflate block
+    let zlib = chunks[2].1;
+    assert_eq!(
+        (zlib[2], u16::from_le_bytes([zlib[3], zlib[4]])),
+        (1, 12 * 87)
+    );
+    let raw = &zlib[7..zlib.len() - 4];
+    for (y, row) in raw.chunks(12).enumerate() {
+        assert_eq!(row[0], 0);
+        for x in 0..87 {
+            let dark = row[1 + x / 8] & (0x80 >> (x % 8)) != 0;
+            let (my, mx) = (y / 3, x / 3);
+            let expected =
+                (2..27).contains(&my) && (2..27).contains(&mx) && qrcode[my - 2][mx - 2].value();
+            assert_eq!(dark, expected);
+        }
+    }
+}
+
+#[cfg(feature = "raster")]
+#[test]
+fn square_rasterizer_inverts_modules_and_margin() {
+    use crate::convert::image::{RasterBackend, SquareRasterizer};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let mut rasterizer = SquareRasterizer::default();
+    rasterizer.module_size(1).margin(1);
+    let plain = rasterizer.rasterize(&qrcode).unwrap();
+    let inverted = rasterizer.invert(true).rasterize(&qrcode).unwrap();
+
+    // Every pixel swapped, margin included
+    assert_eq!(&inverted.data[..4], [0, 0, 0, 255]);
+    for (plain, inverted) in plain.data.chunks(4).zip(inverted.data.chunks(4)) {
+        assert_ne!(plain, inverted);
+    }
+}
+
+#[cfg(feature = "dynamic-image")]
+#[test]
+fn dynamic_image_has_the_rasterized_pixels() {
+    use crate::convert::image::{RasterBackend, SquareRasterizer};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let mut rasterizer = SquareRasterizer::default();
+    rasterizer.module_size(2).module_color("#102030");
+
+    let raster = rasterizer.rasterize(&qrcode).unwrap();
+    let image = rasterizer.to_dynamic_image(&qrcode).unwrap().into_rgba8();
+    assert_eq!(image.dimensions(), (raster.width, raster.height));
+    assert_eq!(image.as_raw(), &raster.data);
+
+    // Top-left finder corner, after the 4 modules of margin
+    assert_eq!(image.get_pixel(8, 8).0, [0x10, 0x20, 0x30, 255]);
+    assert_eq!(image.get_pixel(0, 0).0, [255; 4]);
+}
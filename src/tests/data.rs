@@ -0,0 +1,255 @@
+use crate::data::{
+    Email, EmvcoBuilder, EpcBuilder, Event, Geo, Gs1Builder, MeCard, OtpAuth, Sms, Url, VCard,
+    WifiCredentials, WifiSecurity,
+};
+use crate::QRBuilder;
+
+#[test]
+fn wifi_open() {
+    let wifi = WifiCredentials::new("Guest");
+    assert_eq!(wifi.to_string(), "WIFI:T:nopass;S:Guest;;");
+}
+
+#[test]
+fn wifi_escapes_special_characters() {
+    let mut wifi = WifiCredentials::new(r#"a;b,c:d"e\f"#);
+    wifi.security(WifiSecurity::Wep).password("x;y");
+
+    assert_eq!(
+        wifi.to_string(),
+        r#"WIFI:T:WEP;S:a\;b\,c\:d\"e\\f;P:x\;y;;"#
+    );
+}
+
+#[test]
+fn wifi_open_ignores_password() {
+    let mut wifi = WifiCredentials::new("Guest");
+    wifi.password("unused").security(WifiSecurity::None);
+
+    assert_eq!(wifi.to_string(), "WIFI:T:nopass;S:Guest;;");
+}
+
+#[test]
+fn vcard_escapes_values() {
+    let mut card = VCard::new("Jane", "Doe");
+    card.organization("Doe, Inc.").note("line1\nline2;");
+
+    let card = card.to_string();
+    assert!(card.contains("ORG:Doe\\, Inc.\r\n"));
+    assert!(card.contains("NOTE:line1\\nline2\\;\r\n"));
+    assert!(card.ends_with("END:VCARD"));
+}
+
+#[test]
+fn mecard_all_fields() {
+    let mut card = MeCard::new("Doe,Jane");
+    card.phone("0123")
+        .email("jane@example.com")
+        .url("https://example.com")
+        .address("1 Main St")
+        .note("Hi");
+
+    assert_eq!(
+        card.to_string(),
+        r"MECARD:N:Doe\,Jane;TEL:0123;EMAIL:jane@example.com;URL:https\://example.com;ADR:1 Main St;NOTE:Hi;;"
+    );
+}
+
+#[test]
+fn url_appends_to_existing_query() {
+    let mut url = Url::new("https://example.com/?a=1");
+    url.param("b", "é");
+
+    assert_eq!(url.to_string(), "https://example.com/?a=1&b=%C3%A9");
+}
+
+#[test]
+fn payload_into_builder() {
+    let mut wifi = WifiCredentials::new("Home");
+    wifi.password("secret");
+
+    let from_payload = QRBuilder::new(&wifi).build().unwrap();
+    let from_string = QRBuilder::new(wifi.to_string()).build().unwrap();
+
+    assert_eq!(from_payload.data[..], from_string.data[..]);
+}
+
+#[test]
+fn gs1_separates_variable_length_values() {
+    let mut gs1 = Gs1Builder::new();
+    gs1.ai("10", "LOT1")
+        .ai("01", "09501101020917")
+        .ai("21", "42");
+
+    // (01) has a predefined length, (21) is the last element
+    assert_eq!(gs1.to_string(), "10LOT1\x1d01095011010209172142");
+    assert!(gs1.qr_builder().build().is_ok());
+}
+
+#[test]
+fn emvco_sorts_fields_and_appends_crc() {
+    use crate::data::emvco::PointOfInitiation;
+
+    let mut payment = EmvcoBuilder::new("Shop", "Paris", "FR", 978);
+    payment
+        .amount("12.50")
+        .terminal_label("T1")
+        .bill_number("42")
+        .merchant_account(4, "4111111111111111")
+        .point_of_initiation(PointOfInitiation::Dynamic)
+        .merchant_category(5812);
+
+    let payload = payment.to_string();
+    assert!(payload.starts_with(
+        "00020101021204164111111111111111520458125303978540512.505802FR5904Shop6005Paris\
+         62120102420702T16304"
+    ));
+
+    // Checking the whole payload including its CRC gives 0
+    let (data, crc) = payload.split_at(payload.len() - 4);
+    let crc = u16::from_str_radix(crc, 16).unwrap();
+    let mut check = data.as_bytes().to_vec();
+    check.extend_from_slice(&crc.to_be_bytes());
+    assert_eq!(crate::data::emvco::crc16(&check), 0);
+
+    let long = "a".repeat(150);
+    let cut = EmvcoBuilder::new(long.as_str(), "X", "FR", 978).to_string();
+    assert!(cut.contains(&format!("5999{}6001X", "a".repeat(99))));
+}
+
+#[test]
+fn otp_encodes_secret_and_label() {
+    let mut otp = OtpAuth::hotp("bob: admin", "", 7);
+    otp.secret_bytes(b"Hello!\xde\xad\xbe\xef").period(60);
+    assert_eq!(
+        otp.to_string(),
+        "otpauth://hotp/bob%3A%20admin?secret=JBSWY3DPEHPK3PXP&counter=7"
+    );
+
+    let mut otp = OtpAuth::totp("me", "mzxw6===");
+    otp.period(45).digits(12);
+    assert_eq!(
+        otp.to_string(),
+        "otpauth://totp/me?secret=MZXW6&digits=8&period=45"
+    );
+    assert_eq!(crate::data::otp::base32(b"foobar"), "MZXW6YTBOI");
+}
+
+#[test]
+fn event_folds_long_lines() {
+    let mut event = Event::new("Party", "20250101");
+    event.description(format!("{}é, done", "a".repeat(70)));
+
+    let event = event.to_string();
+    let lines = event.split("\r\n").collect::<Vec<_>>();
+    assert!(lines.iter().all(|line| line.len() <= 75));
+
+    // 12 + 63 octets, then `é` does not fit
+    assert_eq!(lines[3], format!("DESCRIPTION:{}", "a".repeat(63)));
+    assert_eq!(lines[4], " aaaaaaaé\\, done");
+    assert_eq!(lines[5], "END:VEVENT");
+}
+
+#[test]
+fn geo_email_and_sms() {
+    let mut geo = Geo::new(-33.8568, 151.2153);
+    geo.altitude(12.5);
+    assert_eq!(geo.to_string(), "geo:-33.8568,151.2153,12.5");
+
+    let mut email = Email::new("a@example.com,b@example.com");
+    email.cc("c@example.com").body("?");
+    assert_eq!(
+        email.to_string(),
+        "mailto:a@example.com,b@example.com?cc=c%40example.com&body=%3F"
+    );
+
+    assert_eq!(Sms::new("123").to_string(), "SMSTO:123");
+}
+
+#[test]
+fn epc_validates_field_lengths() {
+    use crate::data::epc::EpcError;
+    use crate::ECL;
+
+    let mut transfer = EpcBuilder::new("Jane Doe", "DE89 3704 0044 0532 0130 00");
+    assert_eq!(
+        transfer.to_string(),
+        "BCD\n002\n1\nSCT\n\nJane Doe\nDE89370400440532013000"
+    );
+    transfer.reference("RF18539007547034").purpose("GDDS");
+    assert_eq!(
+        transfer.to_string(),
+        "BCD\n002\n1\nSCT\n\nJane Doe\nDE89370400440532013000\n\nGDDS\nRF18539007547034"
+    );
+
+    let qrcode = transfer.qr_builder().unwrap().build().unwrap();
+    assert!(matches!(qrcode.ecl(), ECL::M));
+
+    assert_eq!(transfer.clone().bic("SHORT").validate(), Err(EpcError::Bic));
+    assert_eq!(
+        transfer.clone().amount_cents(0).validate(),
+        Err(EpcError::Amount)
+    );
+    assert_eq!(
+        transfer.clone().text("a".repeat(141)).validate(),
+        Err(EpcError::Remittance)
+    );
+    assert_eq!(
+        EpcBuilder::new("a".repeat(71), "DE89").validate(),
+        Err(EpcError::Name)
+    );
+    assert_eq!(
+        EpcBuilder::new("Jane", "DE89-3704").validate(),
+        Err(EpcError::Iban)
+    );
+}
+
+#[test]
+fn swissqr_lines_and_constraints() {
+    use crate::data::swissqr::{SwissAddress, SwissQrBuilder, SwissQrError, SwissReference};
+    use crate::ECL;
+
+    let mut creditor = SwissAddress::new("Robert Schneider AG", "2501", "Biel", "CH");
+    creditor.street("Rue du Lac").building_number("1268");
+    let mut bill = SwissQrBuilder::new("CH58 0079 1123 0008 8901 2", creditor.clone());
+    bill.amount_cents(5_000)
+        .reference(SwissReference::Scor("RF18539007547034".into()));
+
+    let payload = bill.to_string();
+    let lines = payload.split('\n').collect::<Vec<_>>();
+    assert_eq!(lines.len(), 31);
+    assert_eq!(lines[..4], ["SPC", "0200", "1", "CH5800791123000889012"]);
+    assert_eq!(
+        lines[4..11],
+        [
+            "S",
+            "Robert Schneider AG",
+            "Rue du Lac",
+            "1268",
+            "2501",
+            "Biel",
+            "CH"
+        ]
+    );
+    assert!(lines[11..18].iter().all(|line| line.is_empty()));
+    assert_eq!(lines[18..20], ["50.00", "CHF"]);
+    assert_eq!(lines[27..], ["SCOR", "RF18539007547034", "", "EPD"]);
+
+    let qrcode = bill.qr_builder().unwrap().build().unwrap();
+    assert!(matches!(qrcode.ecl(), ECL::M));
+
+    // A QR-IBAN needs a QR reference, and a regular IBAN can't have one
+    let mut qr_iban = SwissQrBuilder::new("CH4431999123000889012", creditor.clone());
+    assert_eq!(qr_iban.validate(), Err(SwissQrError::Reference));
+    qr_iban.reference(SwissReference::Qrr("210000000003139471430009017".into()));
+    assert!(qr_iban.validate().is_ok());
+    bill.reference(SwissReference::Qrr("210000000003139471430009017".into()));
+    assert_eq!(bill.validate(), Err(SwissQrError::Reference));
+
+    let debtor = SwissAddress::new("Pia", "", "Rorschach", "CH");
+    assert_eq!(qr_iban.debtor(debtor).validate(), Err(SwissQrError::Debtor));
+    assert_eq!(
+        SwissQrBuilder::new("DE89370400440532013000", creditor).validate(),
+        Err(SwissQrError::Iban)
+    );
+}
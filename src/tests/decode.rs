@@ -0,0 +1,184 @@
+use crate::decode::{decode, decode_matrix, DecodeError};
+use crate::{EciCode, Mode, QRBuilder, Segment, Version, ECL};
+
+#[test]
+fn decode_every_mode() {
+    for input in [
+        "0123456789",
+        "HELLO WORLD $%*+-./:",
+        "https://fast-qr.com/🚀",
+    ] {
+        let qrcode = QRBuilder::new(input).build().unwrap();
+        assert_eq!(decode(&qrcode).unwrap(), input.as_bytes());
+    }
+}
+
+#[test]
+fn decode_every_ecl() {
+    let input = "fast_qr ".repeat(20);
+    for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+        let qrcode = QRBuilder::new(input.clone()).ecl(ecl).build().unwrap();
+        assert_eq!(decode(&qrcode).unwrap(), input.as_bytes());
+    }
+}
+
+#[test]
+fn decode_big_version() {
+    let input = "a".repeat(1000);
+    let qrcode = QRBuilder::new(input.clone())
+        .version(Version::V40)
+        .build()
+        .unwrap();
+    assert_eq!(decode(&qrcode).unwrap(), input.as_bytes());
+}
+
+#[test]
+fn decode_mixed_segments_and_headers() {
+    let segments = vec![
+        Segment::new(Mode::Alphanumeric, "ORDER-").unwrap(),
+        Segment::new(Mode::Numeric, "0123456789").unwrap(),
+    ];
+    let qrcode = QRBuilder::new("")
+        .segments(segments)
+        .eci(EciCode::Utf8)
+        .build()
+        .unwrap();
+    assert_eq!(decode(&qrcode).unwrap(), b"ORDER-0123456789");
+}
+
+#[test]
+fn decode_structured_append() {
+    let input = "fast_qr ".repeat(40);
+    let qrcodes = QRBuilder::new(input.clone())
+        .build_multi(Version::V05)
+        .unwrap();
+
+    let decoded = qrcodes
+        .iter()
+        .flat_map(|qrcode| decode(qrcode).unwrap())
+        .collect::<Vec<u8>>();
+    assert_eq!(decoded, input.as_bytes());
+}
+
+#[test]
+fn decode_corrects_errors() {
+    let input = "https://example.com/";
+    let mut qrcode = QRBuilder::new(input).ecl(ECL::H).build().unwrap();
+
+    // Bottom right corner, the first data codewords
+    for row in qrcode.size - 4..qrcode.size {
+        for column in qrcode.size - 4..qrcode.size {
+            qrcode[row][column].toggle();
+        }
+    }
+    // Damaged format information
+    qrcode[8][0].toggle();
+    qrcode[8][1].toggle();
+
+    assert_eq!(decode(&qrcode).unwrap(), input.as_bytes());
+}
+
+#[test]
+fn decode_too_many_errors() {
+    let mut qrcode = QRBuilder::new("https://example.com/")
+        .ecl(ECL::L)
+        .disable_ecl_boost()
+        .build()
+        .unwrap();
+
+    for row in qrcode.size - 8..qrcode.size {
+        for column in qrcode.size - 8..qrcode.size {
+            qrcode[row][column].toggle();
+        }
+    }
+
+    assert!(decode(&qrcode).is_err());
+}
+
+#[test]
+fn decode_bool_matrix() {
+    let input = "HELLO WORLD";
+    let qrcode = QRBuilder::new(input).build().unwrap();
+
+    let matrix = (0..qrcode.size)
+        .map(|row| qrcode[row].iter().map(|module| module.value()).collect())
+        .collect::<Vec<Vec<bool>>>();
+    assert_eq!(decode_matrix(&matrix).unwrap(), input.as_bytes());
+}
+
+#[test]
+fn decode_invalid_size() {
+    let matrix = vec![vec![false; 22]; 22];
+    assert!(matches!(
+        decode_matrix(&matrix),
+        Err(DecodeError::InvalidSize)
+    ));
+
+    let matrix = vec![vec![false; 21]; 20];
+    assert!(matches!(
+        decode_matrix(&matrix),
+        Err(DecodeError::InvalidSize)
+    ));
+}
+
+#[test]
+fn decode_micro() {
+    let inputs = [
+        ("12345", ECL::L),
+        ("AC-42", ECL::L),
+        ("0123456", ECL::M),
+        ("fast_qr", ECL::M),
+        ("01234567890123456789012", ECL::L),
+        ("HELLO", ECL::Q),
+        ("https://a.io", ECL::L),
+    ];
+
+    for (input, ecl) in inputs {
+        let qrcode = QRBuilder::new(input).micro().ecl(ecl).build().unwrap();
+        assert_eq!(decode(&qrcode).unwrap(), input.as_bytes());
+    }
+}
+
+#[test]
+fn decode_micro_corrects_errors() {
+    let input = "fast_qr";
+    let mut qrcode = QRBuilder::new(input).micro().build().unwrap();
+
+    let last = qrcode.size - 1;
+    qrcode[last][last].toggle();
+    qrcode[last - 1][last].toggle();
+
+    assert_eq!(decode(&qrcode).unwrap(), input.as_bytes());
+}
+
+#[test]
+fn decode_raw_segment() {
+    let bits = [2u8, b'h', b'i']
+        .iter()
+        .flat_map(|byte| (0..8).rev().map(move |i| byte & (1 << i) != 0))
+        .collect::<Vec<bool>>();
+
+    let qrcode = QRBuilder::new("ignored")
+        .segments(vec![Segment::new(Mode::Numeric, "42").unwrap()])
+        .raw_segment(Mode::Byte, &bits)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+    assert_eq!(decode(&qrcode).unwrap(), b"42hi");
+}
+
+#[test]
+fn decode_fnc1() {
+    use crate::Fnc1;
+
+    // `%` would stand for `GS` in Alphanumeric mode
+    let input = "LOT%1234ABCD";
+    let plain = QRBuilder::new(input).build().unwrap();
+    assert_eq!(plain.mode, Some(Mode::Alphanumeric));
+
+    for fnc1 in [Fnc1::First, Fnc1::Second(b'a' + 100)] {
+        let qrcode = QRBuilder::new(input).fnc1(fnc1).build().unwrap();
+        assert_eq!(qrcode.mode, Some(Mode::Byte));
+        assert_eq!(decode(&qrcode).unwrap(), input.as_bytes());
+    }
+}
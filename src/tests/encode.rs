@@ -231,3 +231,280 @@ fn encode_numeric_4() {
     assert_eq!(res[5] & 0b0000_1111, (1) >> 6);
     assert_eq!(res[6] & 0b1111_1100, (1) << 2 & keep_last[8]);
 }
+
+#[test]
+fn encode_eci_header() {
+    let mut compact = CompactQR::new();
+    encode::encode_eci(&mut compact, encode::EciCode::Utf8);
+
+    assert_eq!(compact.len(), 12);
+    assert_eq!(compact.get_data()[..2], [0b0111_0001, 0b1010_0000]);
+}
+
+#[test]
+fn eci_header_can_increase_version() {
+    use crate::{QRBuilder, ECL};
+
+    // 17 bytes is the exact capacity of a V01-L in Byte mode
+    const INPUT: &[u8] = b"https://fast-qr.c";
+
+    let without_eci = QRBuilder::new(INPUT).ecl(ECL::L).build().unwrap();
+    let with_eci = QRBuilder::new(INPUT)
+        .ecl(ECL::L)
+        .eci(encode::EciCode::Utf8)
+        .build()
+        .unwrap();
+
+    assert_eq!(without_eci.size, 21);
+    assert_eq!(with_eci.size, 25);
+}
+
+#[test]
+fn optimal_segments_numeric_only() {
+    use crate::Version;
+
+    let segments = encode::optimal_segments(b"0123456789", Version::V01);
+
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].mode(), Mode::Numeric);
+}
+
+#[test]
+fn optimal_segments_alphanumeric_then_numeric() {
+    use crate::Version;
+
+    const INPUT: &[u8] = b"ABCDEFGH0123456789012345678901234567890123456789";
+    let segments = encode::optimal_segments(INPUT, Version::V01);
+
+    assert_eq!(segments.len(), 2);
+    assert_eq!(segments[0].mode(), Mode::Alphanumeric);
+    assert_eq!(segments[0].data(), b"ABCDEFGH");
+    assert_eq!(segments[1].mode(), Mode::Numeric);
+    assert_eq!(segments[1].data(), &INPUT[8..]);
+}
+
+#[test]
+fn optimal_segments_alphanumeric_then_byte() {
+    use crate::Version;
+
+    // 46 + 60 bits instead of 108 bits in a single Byte segment
+    let segments = encode::optimal_segments(b"ABC123def456", Version::V01);
+
+    assert_eq!(segments.len(), 2);
+    assert_eq!(segments[0].mode(), Mode::Alphanumeric);
+    assert_eq!(segments[0].data(), b"ABC123");
+    assert_eq!(segments[1].mode(), Mode::Byte);
+    assert_eq!(segments[1].data(), b"def456");
+}
+
+#[test]
+fn optimal_segments_keeps_short_runs_in_byte() {
+    use crate::Version;
+
+    let segments = encode::optimal_segments(b"abc12def", Version::V01);
+
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].mode(), Mode::Byte);
+}
+
+#[test]
+fn mixed_segments_reduce_version() {
+    use crate::QRBuilder;
+
+    let input = format!("a{}", "0123456789".repeat(6));
+
+    let mixed = QRBuilder::new(input.clone()).build().unwrap();
+    let byte = QRBuilder::new(input).mode(Mode::Byte).build().unwrap();
+
+    assert_eq!(mixed.size, 29);
+    assert!(byte.size > mixed.size);
+}
+
+#[test]
+fn segment_rejects_unrepresentable_data() {
+    use crate::Segment;
+
+    assert!(Segment::new(Mode::Numeric, "12a").is_none());
+    assert!(Segment::new(Mode::Alphanumeric, "abc").is_none());
+    assert!(Segment::new(Mode::Byte, "abc").is_some());
+}
+
+#[test]
+fn manual_segments() {
+    use crate::{QRBuilder, Segment};
+
+    let segments = vec![
+        Segment::new(Mode::Alphanumeric, "ORDER-").unwrap(),
+        Segment::new(Mode::Numeric, "0123456789").unwrap(),
+    ];
+
+    let qrcode = QRBuilder::new("ignored")
+        .segments(segments)
+        .build()
+        .unwrap();
+    assert_eq!(qrcode.mode, Some(Mode::Alphanumeric));
+}
+
+#[test]
+fn encode_structured_append_header() {
+    let mut compact = CompactQR::new();
+    let header = encode::StructuredAppend {
+        index: 2,
+        total: 4,
+        parity: 0xA5,
+    };
+    encode::encode_structured_append(&mut compact, header);
+
+    assert_eq!(compact.len(), 20);
+    assert_eq!(
+        compact.get_data()[..3],
+        [0b0011_0010, 0b0011_1010, 0b0101_0000]
+    );
+}
+
+#[test]
+fn build_multi_single_symbol() {
+    use crate::{QRBuilder, Version};
+
+    let qrcodes = QRBuilder::new("HELLO WORLD")
+        .build_multi(Version::V05)
+        .unwrap();
+
+    assert_eq!(qrcodes.len(), 1);
+    assert_eq!(qrcodes[0].size, 21);
+}
+
+#[test]
+fn build_multi_splits_input() {
+    use crate::{QRBuilder, Version, ECL};
+
+    let input = "fast_qr ".repeat(40);
+    let qrcodes = QRBuilder::new(input)
+        .ecl(ECL::M)
+        .build_multi(Version::V05)
+        .unwrap();
+
+    assert!(qrcodes.len() > 1);
+    assert!(qrcodes.iter().all(|qr| qr.size <= 37));
+}
+
+#[test]
+fn build_multi_parts_match_the_header_total() {
+    use crate::qr::split_parts;
+
+    // `chunks` of 3 bytes would only give 3 parts out of 4
+    let parts = split_parts(b"0123456789", 4).collect::<Vec<_>>();
+    assert_eq!(parts, [&b"012"[..], b"345", b"67", b"89"]);
+
+    for len in 1..=64 {
+        let input = (0..len).map(|i| i as u8).collect::<Vec<_>>();
+        for total in 1..=len.min(16) {
+            let parts = split_parts(&input, total).collect::<Vec<_>>();
+            assert_eq!(parts.len(), total);
+            assert!(parts.iter().all(|part| !part.is_empty()));
+            assert_eq!(parts.concat(), input);
+        }
+    }
+}
+
+#[test]
+fn build_multi_too_much_data() {
+    use crate::{QRBuilder, Version};
+
+    let input = vec![b'a'; 16 * 100];
+    assert!(QRBuilder::new(input).build_multi(Version::V01).is_err());
+}
+
+#[test]
+fn raw_segment_length_validation() {
+    use crate::qr::QRCodeError;
+    use crate::{QRBuilder, Version, ECL};
+
+    // Version 1 with ECL::L holds 152 data bits, 4 are used by the mode indicator
+    let fits = QRBuilder::new("")
+        .raw_segment(Mode::Byte, &[false; 148])
+        .ecl(ECL::L)
+        .version(Version::V01)
+        .build();
+    assert!(fits.is_ok());
+
+    let too_big = QRBuilder::new("")
+        .raw_segment(Mode::Byte, &[false; 149])
+        .ecl(ECL::L)
+        .version(Version::V01)
+        .build();
+    assert!(matches!(too_big, Err(QRCodeError::SpecifiedVersion)));
+
+    let micro = QRBuilder::new("")
+        .raw_segment(Mode::Numeric, &[false; 8])
+        .micro()
+        .build();
+    assert!(matches!(micro, Err(QRCodeError::MicroUnsupported)));
+}
+
+#[test]
+fn encode_fnc1_header() {
+    let mut compact = CompactQR::new();
+    encode::encode_fnc1(&mut compact, encode::Fnc1::Second(37));
+
+    assert_eq!(compact.len(), 12);
+    assert_eq!(compact.get_data()[..2], [0b1001_0010, 0b0101_0000]);
+}
+
+#[test]
+fn forced_mode_must_represent_input() {
+    use crate::qr::QRCodeError;
+    use crate::QRBuilder;
+
+    let numeric = QRBuilder::new("0123").mode(Mode::Numeric).build().unwrap();
+    assert_eq!(numeric.mode(), Some(Mode::Numeric));
+
+    // Mixed segments would be smaller, the forced mode is kept
+    let byte = QRBuilder::new("HELLO 0123456789012345")
+        .mode(Mode::Byte)
+        .build()
+        .unwrap();
+    assert_eq!(byte.mode(), Some(Mode::Byte));
+
+    let letters = QRBuilder::new("A1").mode(Mode::Numeric).build();
+    assert!(matches!(letters, Err(QRCodeError::InvalidMode)));
+
+    let lowercase = QRBuilder::new("abc")
+        .mode(Mode::Alphanumeric)
+        .micro()
+        .build();
+    assert!(matches!(lowercase, Err(QRCodeError::InvalidMode)));
+}
+
+#[test]
+fn segment_api_writes_the_spec_example() {
+    use crate::encode::BitWriter;
+    use crate::{Segment, Version, ECL};
+
+    // 7.4.3 of the spec: "01234567" in version 1
+    let segment = Segment::new(Mode::Numeric, "01234567").unwrap();
+    let mut writer = BitWriter::new();
+    encode::encode_segment(&mut writer, &segment, Version::V01);
+    assert_eq!(
+        writer.to_string(),
+        "00010000001000000000110001010110011000011"
+    );
+    assert_eq!(writer.len(), segment.bits(Version::V01));
+    assert_eq!(
+        encode::segment_bits(Mode::Numeric, 8, Version::V01),
+        writer.len()
+    );
+    assert_eq!(encode::character_bits(Mode::Alphanumeric, 3), 17);
+    assert_eq!(encode::cci_bits(Version::V10, Mode::Byte), 16);
+    assert_eq!(encode::data_bits(Version::V01, ECL::H), 72);
+
+    let segments = [segment];
+    let version = encode::smallest_version(&segments, ECL::H, 40, Version::V01);
+    assert_eq!(
+        version.map(|version| version as usize),
+        Some(Version::V02 as usize)
+    );
+    let (segments, version) = encode::best_segments(b"HELLO 12345678901234", ECL::M, 0).unwrap();
+    assert_eq!(segments.len(), 2);
+    assert_eq!(version as usize, Version::V01 as usize);
+}
@@ -0,0 +1,45 @@
+use crate::convert::escpos::{Alignment, EscPosBuilder};
+use crate::{QRBuilder, Version, ECL};
+
+fn qrcode() -> crate::QRCode {
+    QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn raster_command_has_the_image_size() {
+    let qrcode = qrcode();
+    let bytes = EscPosBuilder::default()
+        .alignment(Alignment::Right)
+        .margin(1)
+        .scale(2)
+        .to_bytes(&qrcode);
+
+    // 23 modules, 46 dots: rows take 6 bytes
+    assert_eq!(&bytes[..3], b"\x1ba\x02");
+    assert_eq!(&bytes[3..11], b"\x1dv0\x00\x06\x00\x2e\x00");
+    assert_eq!(bytes.len(), 11 + 6 * 46 + 1);
+    assert_eq!(bytes.last(), Some(&b'\n'));
+}
+
+#[test]
+fn dots_are_packed_msb_first() {
+    let qrcode = qrcode();
+    let bytes = EscPosBuilder::default()
+        .margin(0)
+        .scale(3)
+        .to_bytes(&qrcode);
+
+    // 63 dots, rows take 8 bytes
+    let pixels = &bytes[11..bytes.len() - 1];
+    assert_eq!(pixels.len(), 8 * 63);
+    for (y, row) in pixels.chunks(8).enumerate() {
+        for x in 0..64 {
+            let dark = row[x / 8] & (0x80 >> (x % 8)) != 0;
+            assert_eq!(dark, x < 63 && qrcode[y / 3][x / 3].value());
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::convert::svg::SvgBuilder;
+use crate::ffi::*;
+use crate::QRBuilder;
+
+#[test]
+fn ffi_matches_builder() {
+    let data = b"https://example.com/";
+    let expected = QRBuilder::new(&data[..]).build().unwrap();
+
+    unsafe {
+        let qr = fast_qr_build(data.as_ptr(), data.len(), FAST_QR_ECL_AUTO, 0);
+        assert!(!qr.is_null());
+
+        let size = fast_qr_size(qr);
+        assert_eq!(size, expected.size);
+        assert_eq!(fast_qr_module(qr, 0, 0), expected[0][0].value());
+        assert!(!fast_qr_module(qr, size, 0));
+
+        let mut modules = vec![2; size * size];
+        assert_eq!(
+            fast_qr_modules(qr, modules.as_mut_ptr(), modules.len()),
+            size * size
+        );
+        assert_eq!(
+            modules,
+            expected
+                .to_bool_matrix()
+                .concat()
+                .iter()
+                .map(|&b| u8::from(b))
+                .collect::<Vec<_>>()
+        );
+
+        // Too small buffers are left untouched
+        let len = fast_qr_to_svg(qr, core::ptr::null_mut(), 0);
+        let mut svg = vec![0; len];
+        assert_eq!(fast_qr_to_svg(qr, svg.as_mut_ptr(), len - 1), len);
+        assert!(svg.iter().all(|&b| b == 0));
+
+        assert_eq!(fast_qr_to_svg(qr, svg.as_mut_ptr(), len), len);
+        assert_eq!(svg.pop(), Some(0));
+        assert_eq!(
+            String::from_utf8(svg).unwrap(),
+            SvgBuilder::default().to_str(&expected)
+        );
+
+        fast_qr_free(qr);
+    }
+}
+
+#[test]
+fn ffi_rejects_invalid_parameters() {
+    let data = "a".repeat(100);
+
+    unsafe {
+        assert!(fast_qr_build(data.as_ptr(), data.len(), 4, 0).is_null());
+        assert!(fast_qr_build(data.as_ptr(), data.len(), FAST_QR_ECL_H, 41).is_null());
+        assert!(fast_qr_build(data.as_ptr(), data.len(), FAST_QR_ECL_H, 1).is_null());
+
+        let qr = fast_qr_build(core::ptr::null(), 0, FAST_QR_ECL_L, 2);
+        assert_eq!(fast_qr_size(qr), 25);
+        fast_qr_free(qr);
+        fast_qr_free(core::ptr::null_mut());
+    }
+}
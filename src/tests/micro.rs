@@ -0,0 +1,147 @@
+use crate::{Mask, MicroVersion, Mode, QRBuilder, QRCode, Segment, ECL};
+
+/// Compares with the `qrcode` crate, which also implements Micro `QRCode`s
+fn assert_same_matrix(qr: &QRCode, input: &str, version: i16, ecl: qrcode::EcLevel) {
+    let expected = qrcode::QrCode::with_version(input, qrcode::Version::Micro(version), ecl)
+        .unwrap()
+        .to_colors();
+
+    assert_eq!(qr.size, version as usize * 2 + 9);
+    for y in 0..qr.size {
+        for x in 0..qr.size {
+            assert_eq!(
+                qr[y][x].value(),
+                expected[y * qr.size + x] == qrcode::Color::Dark,
+                "({}, {})",
+                x,
+                y
+            );
+        }
+    }
+}
+
+#[test]
+fn micro_m1_numeric() {
+    let qr = QRBuilder::new("12345").micro().build().unwrap();
+
+    assert!(matches!(qr.micro_version, Some(MicroVersion::M1)));
+    assert_same_matrix(&qr, "12345", 1, qrcode::EcLevel::L);
+}
+
+#[test]
+fn micro_m2_alphanumeric() {
+    let qr = QRBuilder::new("AC-42").micro().build().unwrap();
+
+    assert!(matches!(qr.micro_version, Some(MicroVersion::M2)));
+    assert_same_matrix(&qr, "AC-42", 2, qrcode::EcLevel::L);
+}
+
+#[test]
+fn micro_m2_medium() {
+    let qr = QRBuilder::new("0123456")
+        .micro()
+        .ecl(ECL::M)
+        .build()
+        .unwrap();
+
+    assert!(matches!(qr.micro_version, Some(MicroVersion::M2)));
+    assert_same_matrix(&qr, "0123456", 2, qrcode::EcLevel::M);
+}
+
+#[test]
+fn micro_m3_byte() {
+    let qr = QRBuilder::new("fast_qr")
+        .micro()
+        .ecl(ECL::M)
+        .build()
+        .unwrap();
+
+    assert!(matches!(qr.micro_version, Some(MicroVersion::M3)));
+    assert_same_matrix(&qr, "fast_qr", 3, qrcode::EcLevel::M);
+}
+
+#[test]
+fn micro_m4_quartile() {
+    let qr = QRBuilder::new("HELLO").micro().ecl(ECL::Q).build().unwrap();
+
+    assert!(matches!(qr.micro_version, Some(MicroVersion::M4)));
+    assert_same_matrix(&qr, "HELLO", 4, qrcode::EcLevel::Q);
+}
+
+#[test]
+fn micro_m4_byte() {
+    let qr = QRBuilder::new("https://a.io").micro().build().unwrap();
+
+    assert!(matches!(qr.micro_version, Some(MicroVersion::M4)));
+    assert_same_matrix(&qr, "https://a.io", 4, qrcode::EcLevel::L);
+}
+
+#[test]
+fn micro_m3_low_fills_every_module() {
+    // `qrcode` drops the last 4 error correction bits of M3-L, only checks capacity (23 digits)
+    let qr = QRBuilder::new("01234567890123456789012")
+        .micro()
+        .build()
+        .unwrap();
+
+    assert!(matches!(qr.micro_version, Some(MicroVersion::M3)));
+    assert!(QRBuilder::new("012345678901234567890123")
+        .micro_version(MicroVersion::M3)
+        .build()
+        .is_err());
+}
+
+#[test]
+fn micro_forced_version() {
+    let qr = QRBuilder::new("1")
+        .micro_version(MicroVersion::M4)
+        .build()
+        .unwrap();
+    assert_eq!(qr.size, 17);
+}
+
+#[test]
+fn micro_forced_mask() {
+    let qr = QRBuilder::new("1")
+        .micro()
+        .mask(Mask::Diamonds)
+        .build()
+        .unwrap();
+    assert!(matches!(qr.mask, Mask::Diamonds));
+}
+
+#[test]
+fn micro_segments() {
+    let segments = vec![
+        Segment::new(Mode::Alphanumeric, "A").unwrap(),
+        Segment::new(Mode::Numeric, "123").unwrap(),
+    ];
+    let qr = QRBuilder::new("")
+        .micro()
+        .segments(segments)
+        .build()
+        .unwrap();
+
+    assert!(matches!(qr.micro_version, Some(MicroVersion::M2)));
+    assert!(matches!(qr.mode, Some(Mode::Alphanumeric)));
+}
+
+#[test]
+fn micro_unsupported_options() {
+    assert!(QRBuilder::new("1").micro().ecl(ECL::H).build().is_err());
+    assert!(QRBuilder::new("1")
+        .micro()
+        .mask(Mask::Checkerboard)
+        .build()
+        .is_err());
+    assert!(QRBuilder::new("1")
+        .micro()
+        .eci(crate::EciCode::Utf8)
+        .build()
+        .is_err());
+}
+
+#[test]
+fn micro_too_much_data() {
+    assert!(QRBuilder::new("a".repeat(16)).micro().build().is_err());
+}
@@ -1,11 +1,37 @@
+mod animated;
+mod art;
+mod bmp;
 mod bytes;
 mod compact;
+mod data;
 mod datamasking;
+mod decode;
 mod default;
+// Keeps the bit layout of the expected values explicit, i.e. `(589 << 0)`
+#[allow(clippy::identity_op)]
 mod encode;
 mod error_correction;
+mod escpos;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod micro;
+mod netpbm;
 mod polynomials;
+mod qr;
+mod rmqr;
+#[cfg(feature = "low-level")]
+mod rs;
 mod score;
+mod sheet;
+#[cfg(feature = "simd")]
+mod simd;
+#[allow(clippy::bool_assert_comparison)]
 mod structure;
 mod svg;
+mod term;
+#[cfg(feature = "test-utils")]
+mod test_utils;
+mod text;
 mod version;
+mod xbm;
+mod zpl;
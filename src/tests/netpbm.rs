@@ -0,0 +1,81 @@
+use crate::convert::netpbm::{NetpbmBuilder, NetpbmFormat};
+use crate::{QRBuilder, Version, ECL};
+
+fn qrcode() -> crate::QRCode {
+    QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap()
+}
+
+/// Modules of `qrcode` with a margin of 1, row by row
+fn expected(qrcode: &crate::QRCode) -> Vec<bool> {
+    let n = qrcode.size + 2;
+    (0..n * n)
+        .map(|i| {
+            let (y, x) = (i / n, i % n);
+            (1..=qrcode.size).contains(&y)
+                && (1..=qrcode.size).contains(&x)
+                && qrcode[y - 1][x - 1].value()
+        })
+        .collect()
+}
+
+#[test]
+fn plain_formats_list_every_pixel() {
+    let qrcode = qrcode();
+    let mut builder = NetpbmBuilder::default();
+    builder.margin(1);
+
+    let pbm = builder.format(NetpbmFormat::PlainPbm).to_bytes(&qrcode);
+    let pbm = String::from_utf8(pbm).unwrap();
+    assert!(pbm.starts_with("P1\n23 23\n"));
+    assert!(pbm.lines().all(|line| line.len() <= 70));
+    let pixels = pbm.split_whitespace().skip(3).map(|p| p == "1");
+    assert!(pixels.eq(expected(&qrcode)));
+
+    let pgm = builder.format(NetpbmFormat::PlainPgm).to_bytes(&qrcode);
+    let pgm = String::from_utf8(pgm).unwrap();
+    assert!(pgm.starts_with("P2\n23 23\n255\n"));
+    assert!(pgm.lines().all(|line| line.len() <= 70));
+    let pixels = pgm.split_whitespace().skip(4).map(|p| p == "0");
+    assert!(pixels.eq(expected(&qrcode)));
+}
+
+#[test]
+fn raw_formats_pack_pixels() {
+    let qrcode = qrcode();
+    let mut builder = NetpbmBuilder::default();
+    builder.margin(1);
+
+    // 23 pixels wide rows take 3 bytes
+    let pbm = builder.format(NetpbmFormat::RawPbm).to_bytes(&qrcode);
+    let header = b"P4\n23 23\n".len();
+    assert_eq!(pbm.len(), header + 23 * 3);
+    let pixels = pbm[header..]
+        .chunks(3)
+        .flat_map(|row| (0..23).map(move |x| row[x / 8] & (0x80 >> (x % 8)) != 0));
+    assert!(pixels.eq(expected(&qrcode)));
+
+    let pgm = builder.format(NetpbmFormat::RawPgm).to_bytes(&qrcode);
+    let header = b"P5\n23 23\n255\n".len();
+    assert!(pgm[header..].iter().map(|&p| p == 0).eq(expected(&qrcode)));
+}
+
+#[test]
+fn modules_are_scaled() {
+    let qrcode = qrcode();
+    let pgm = NetpbmBuilder::default()
+        .format(NetpbmFormat::RawPgm)
+        .margin(0)
+        .module_size(3)
+        .to_bytes(&qrcode);
+
+    let header = b"P5\n63 63\n255\n".len();
+    assert!(pgm.starts_with(b"P5\n63 63\n255\n"));
+    for (i, &pixel) in pgm[header..].iter().enumerate() {
+        let (y, x) = (i / 63 / 3, i % 63 / 3);
+        assert_eq!(pixel == 0, qrcode[y][x].value());
+    }
+}
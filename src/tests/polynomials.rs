@@ -1166,3 +1166,10 @@ mod generators {
         assert_eq!(gen, GENERATOR_POLYNOMIALS[30]);
     }
 }
+
+#[test]
+fn computed_generator_polynomials() {
+    for (degree, &expected) in GENERATOR_POLYNOMIALS.iter().enumerate() {
+        assert_eq!(crate::polynomials::generator(degree), expected);
+    }
+}
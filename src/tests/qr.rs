@@ -0,0 +1,340 @@
+use crate::{QRBuilder, Version};
+
+#[test]
+fn bool_matrix_matches_modules() {
+    let qrcode = QRBuilder::new("https://example.com/")
+        .version(Version::V03)
+        .build()
+        .unwrap();
+
+    let matrix = qrcode.to_bool_matrix();
+    assert_eq!(matrix.len(), qrcode.size);
+    for (y, row) in matrix.iter().enumerate() {
+        assert_eq!(row.len(), qrcode.size);
+        for (x, &value) in row.iter().enumerate() {
+            assert_eq!(value, qrcode[y][x].value());
+        }
+    }
+
+    // Finder pattern corner
+    assert!(matrix[0][0]);
+    assert!(!matrix[1][1]);
+}
+
+#[test]
+fn iter_modules_is_row_by_row() {
+    let qrcode = QRBuilder::new("Hello").build().unwrap();
+
+    let modules = qrcode.iter_modules().collect::<Vec<_>>();
+    assert_eq!(modules.len(), qrcode.size * qrcode.size);
+    assert_eq!((modules[1].0, modules[1].1), (1, 0));
+    assert_eq!((modules[qrcode.size].0, modules[qrcode.size].1), (0, 1));
+
+    for (x, y, module) in modules {
+        assert_eq!(module, qrcode[y][x]);
+    }
+    assert_eq!(qrcode.rows().count(), qrcode.size);
+}
+
+#[test]
+fn quiet_zone_surrounds_the_matrix() {
+    use crate::ModuleType;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let padded = qrcode.with_quiet_zone();
+    assert_eq!((padded.size, padded.margin), (qrcode.size + 8, 4));
+    assert_eq!(padded.rows().count(), padded.size);
+
+    for (y, row) in padded.rows().enumerate() {
+        for (x, module) in row.iter().enumerate() {
+            match (y.checked_sub(4), x.checked_sub(4)) {
+                (Some(y), Some(x)) if y < qrcode.size && x < qrcode.size => {
+                    assert_eq!(module.0, qrcode[y][x].0);
+                }
+                _ => {
+                    assert!(!module.value());
+                    assert_eq!(module.module_type(), ModuleType::Empty);
+                }
+            }
+        }
+    }
+
+    let micro = QRBuilder::new("1234").micro().build().unwrap();
+    assert_eq!(micro.with_quiet_zone().size, micro.size + 4);
+    assert!(micro
+        .with_margin(0)
+        .to_bool_matrix()
+        .eq(&micro.to_bool_matrix()));
+}
+
+#[test]
+fn modules_know_their_role() {
+    // Version 2
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    assert!(qrcode[0][0].is_finder());
+    assert!(qrcode[7][7].is_empty());
+    assert!(qrcode[6][10].is_timing());
+    assert!(qrcode[8][0].is_format());
+    assert!(qrcode[17][8].is_dark_module() && qrcode[17][8].value());
+    assert!(qrcode[18][18].is_alignment());
+    assert!(qrcode[24][24].is_data());
+    assert!(!qrcode[24][24].is_function());
+}
+
+#[cfg(feature = "boxed")]
+#[test]
+fn boxed_matrix_is_sized_to_the_version() {
+    let qrcode = QRBuilder::new("Hello")
+        .version(Version::V01)
+        .build()
+        .unwrap();
+    assert_eq!(qrcode.data.len(), 21 * 21);
+}
+
+#[test]
+fn ecl_is_boosted_when_data_fits() {
+    use crate::{QRBuilder, Version, ECL};
+
+    let qrcode = QRBuilder::new("https://example.com/")
+        .ecl(ECL::L)
+        .build()
+        .unwrap();
+    assert!(matches!(qrcode.ecl, ECL::Q));
+    assert!(matches!(qrcode.version, Some(Version::V02)));
+
+    let qrcode = QRBuilder::new("https://example.com/")
+        .ecl(ECL::L)
+        .disable_ecl_boost()
+        .build()
+        .unwrap();
+    assert!(matches!(qrcode.ecl, ECL::L));
+
+    // Forcing a bigger version leaves room for the highest level
+    let qrcode = QRBuilder::new("https://example.com/")
+        .version(Version::V05)
+        .build()
+        .unwrap();
+    assert!(matches!(qrcode.ecl, ECL::H));
+}
+
+#[test]
+fn getters_report_selected_parameters() {
+    use crate::{Mask, Mode, QRBuilder, Version, ECL};
+
+    let qrcode = QRBuilder::new("HELLO WORLD")
+        .mask(Mask::Diamonds)
+        .build()
+        .unwrap();
+    assert!(matches!(qrcode.version(), Some(Version::V01)));
+    assert!(matches!(qrcode.ecl(), ECL::Q));
+    assert!(matches!(qrcode.mask(), Mask::Diamonds));
+    assert_eq!(qrcode.mode(), Some(Mode::Alphanumeric));
+
+    let empty = QRBuilder::new("").segments(Vec::new()).build().unwrap();
+    assert_eq!(empty.mode(), Some(Mode::Numeric));
+
+    let micro = QRBuilder::new("123").micro().build().unwrap();
+    assert!(micro.version().is_none());
+    assert!(matches!(micro.ecl(), ECL::L));
+    assert!(micro.mask_scores().is_none());
+}
+
+#[test]
+fn build_many_matches_build() {
+    use crate::{QRBuilder, ECL};
+
+    let inputs = ["first", "SECOND", "3333333", ""];
+    let mut builder = QRBuilder::new("ignored");
+    builder.ecl(ECL::M);
+
+    let qrcodes = builder.build_many(inputs);
+    assert_eq!(qrcodes.len(), inputs.len());
+    for (qrcode, input) in qrcodes.iter().zip(inputs) {
+        let expected = QRBuilder::new(input).ecl(ECL::M).build().unwrap();
+        assert_eq!(qrcode.as_ref().unwrap().to_str(), expected.to_str());
+    }
+
+    let too_long = builder.build_many(["a".repeat(3000)]);
+    assert!(too_long[0].is_err());
+}
+
+#[test]
+fn encoder_reuses_buffers() {
+    use crate::{QRBuilder, QREncoder, ECL};
+
+    let mut builder = QRBuilder::new("ignored");
+    builder.ecl(ECL::L);
+    let mut encoder = QREncoder::new(builder);
+    // Large versions first, so smaller ones must not keep stale bits, then the same version
+    // twice, placed on the function patterns kept from the previous input
+    let inputs = [
+        "a".repeat(1500),
+        "small".to_string(),
+        "other".to_string(),
+        String::new(),
+        "a".repeat(200),
+    ];
+
+    for input in &inputs {
+        let qrcode = encoder.encode_into(input).unwrap();
+        let expected = QRBuilder::new(input.as_str()).ecl(ECL::L).build().unwrap();
+        assert_eq!(qrcode.to_str(), expected.to_str());
+        assert!(qrcode.iter_modules().zip(expected.iter_modules()).all(
+            |((_, _, module), (_, _, expected))| module.module_type() == expected.module_type()
+        ));
+    }
+
+    assert!(encoder.encode_into("a".repeat(3000)).is_err());
+}
+
+#[test]
+fn codewords_are_placed_like_build() {
+    use crate::qr::Codewords;
+    use crate::{Mask, QRBuilder, QRCode, ECL};
+
+    for input in ["https://example.com/", "0123456789", &"a".repeat(500)] {
+        let builder = QRBuilder::new(input);
+        let codewords = builder.build_codewords().unwrap();
+        let expected = builder.build().unwrap();
+        assert!(matches!(codewords.ecl(), ECL::H | ECL::Q));
+
+        let qrcode = QRCode::from_codewords(&codewords, None);
+        assert_eq!(qrcode.to_str(), expected.to_str());
+        assert_eq!(qrcode.mode(), expected.mode());
+        assert_eq!(qrcode.mask() as usize, expected.mask() as usize);
+
+        let forced = QRCode::from_codewords(&codewords, Some(Mask::Meadow));
+        let expected = QRBuilder::new(input).mask(Mask::Meadow).build().unwrap();
+        assert_eq!(forced.to_str(), expected.to_str());
+    }
+
+    // A corrupted codeword is recovered by the error correction
+    let codewords = QRBuilder::new("https://example.com/")
+        .build_codewords()
+        .unwrap();
+    let (version, ecl) = (codewords.version(), codewords.ecl());
+    let mut bytes = codewords.into_bytes();
+    bytes[3] ^= 0xFF;
+    let corrupted = Codewords::new(bytes.clone(), version, ecl).unwrap();
+    let qrcode = QRCode::from_codewords(&corrupted, None);
+    assert_eq!(
+        crate::decode::decode(&qrcode).unwrap(),
+        b"https://example.com/"
+    );
+
+    bytes.pop();
+    assert!(Codewords::new(bytes, version, ecl).is_none());
+    assert!(QRBuilder::new("1").micro().build_codewords().is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trips_qrcodes() {
+    use crate::{MicroVersion, QRBuilder, QRCode, Version};
+
+    let qrcodes = [
+        QRBuilder::new("https://example.com/").build().unwrap(),
+        QRBuilder::new("a".repeat(500)).build().unwrap(),
+        QRBuilder::new("12345")
+            .micro()
+            .micro_version(MicroVersion::M2)
+            .build()
+            .unwrap(),
+    ];
+
+    for qrcode in qrcodes {
+        let json = serde_json::to_string(&qrcode).unwrap();
+        let restored = serde_json::from_str::<QRCode>(&json).unwrap();
+
+        assert_eq!(restored.size, qrcode.size);
+        assert_eq!(restored.to_str(), qrcode.to_str());
+        let types = |qr: &QRCode| {
+            qr.data[..qr.size * qr.size]
+                .iter()
+                .map(|m| m.0)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(types(&restored), types(&qrcode));
+    }
+
+    let mut qrcode = serde_json::to_value(QRBuilder::new("test").build().unwrap()).unwrap();
+    qrcode["version"] = serde_json::to_value(Version::V02).unwrap();
+    assert!(serde_json::from_value::<QRCode>(qrcode).is_err());
+}
+
+#[test]
+fn diff_lists_differing_modules() {
+    use crate::Mask;
+
+    let qrcode = QRBuilder::new("diff")
+        .version(Version::V01)
+        .build()
+        .unwrap();
+    assert!(qrcode.diff(&qrcode.clone()).is_empty());
+
+    let mut other = qrcode.clone();
+    for (x, y) in [(20, 9), (10, 12)] {
+        let value = other[y][x].value();
+        other[y][x].set(!value);
+    }
+    assert_eq!(qrcode.diff(&other), [(20, 9), (10, 12)]);
+
+    // Same modules except for the data, all function patterns but the format match
+    let masked = QRBuilder::new("diff")
+        .version(Version::V01)
+        .mask(Mask::Checkerboard)
+        .build()
+        .unwrap();
+    let diff = qrcode.diff(&masked);
+    assert!(!diff.is_empty());
+    assert!(diff.iter().all(|&(x, y)| !(x < 7 && y < 7)));
+
+    // Modules outside of the smaller one all differ
+    let larger = QRBuilder::new("diff")
+        .version(Version::V02)
+        .build()
+        .unwrap();
+    let diff = qrcode.diff(&larger);
+    assert!(diff.contains(&(24, 24)));
+    assert!(diff.contains(&(21, 0)));
+    assert!(!diff.contains(&(0, 0)));
+}
+
+#[test]
+fn bits_pack_the_modules_in_both_layouts() {
+    use crate::qr::BitLayout;
+
+    let qrcode = QRBuilder::new("bits")
+        .version(Version::V01)
+        .build()
+        .unwrap();
+    let matrix = qrcode.to_bool_matrix();
+    let n = qrcode.size;
+
+    let rows = qrcode.to_bits();
+    assert_eq!(rows.len(), n * 3);
+    for (y, row) in matrix.iter().enumerate() {
+        for (x, &dark) in row.iter().enumerate() {
+            assert_eq!(rows[y * 3 + x / 8] & (0x80 >> (x % 8)) != 0, dark);
+        }
+        // Padding is light
+        assert_eq!(rows[y * 3 + 2] & 0b0000_0111, 0);
+    }
+
+    let pages = qrcode.to_bits_with_layout(BitLayout::ColumnMajor);
+    assert_eq!(pages.len(), 3 * n);
+    for (y, row) in matrix.iter().enumerate() {
+        for (x, &dark) in row.iter().enumerate() {
+            assert_eq!(pages[y / 8 * n + x] & (1 << (y % 8)) != 0, dark);
+        }
+    }
+
+    let padded = qrcode.to_bits_with_quiet_zone();
+    assert_eq!(padded.len(), (n + 8) * 4);
+    assert!(padded[..4 * 4].iter().all(|&byte| byte == 0));
+    assert_eq!(
+        padded,
+        qrcode.with_quiet_zone().to_bits(BitLayout::RowMajor)
+    );
+}
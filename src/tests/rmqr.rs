@@ -0,0 +1,197 @@
+use crate::module::ModuleType;
+use crate::rmqr::{self, RMQRBuilder, RMQRCode, RMQRVersion};
+use crate::{Mode, ECL};
+
+/// Reads the modules back in placement order, unmasked
+fn read_bits(rmqr: &RMQRCode) -> Vec<bool> {
+    let matrix = rmqr::create_matrix(rmqr.version);
+    let mut bits = Vec::new();
+    let mut upward = true;
+
+    for x in (1..rmqr.width - 1).rev().step_by(2) {
+        for i in 0..rmqr.height {
+            let y = if upward { rmqr.height - 1 - i } else { i };
+            for x in [x, x - 1] {
+                if matrix[y][x].module_type() == ModuleType::Data {
+                    bits.push(rmqr[y][x].value() ^ ((y / 2 + x / 3) % 2 == 0));
+                }
+            }
+        }
+        upward = !upward;
+    }
+
+    bits
+}
+
+#[test]
+fn rmqr_codewords_fill_every_version() {
+    for version in RMQRVersion::ALL {
+        let matrix = rmqr::create_matrix(version);
+        let modules = matrix
+            .data
+            .iter()
+            .filter(|module| module.module_type() == ModuleType::Data)
+            .count();
+
+        assert_eq!(matrix.data.len(), version.width() * version.height());
+        for ecl in [ECL::M, ECL::H] {
+            let codewords = rmqr::interleave(&vec![0; version.data_codewords(ecl)], ecl, version);
+
+            // Up to 7 remainder bits are left once every codeword is placed
+            assert_eq!(modules / 8, codewords.len(), "{:?} {:?}", version, ecl);
+        }
+    }
+}
+
+#[test]
+fn rmqr_function_patterns() {
+    let rmqr = RMQRBuilder::new("12345")
+        .version(RMQRVersion::R11x27)
+        .build()
+        .unwrap();
+    let matrix = rmqr.to_bool_matrix();
+
+    // Finder pattern, top left
+    assert_eq!(
+        matrix[0][..8],
+        [true, true, true, true, true, true, true, false]
+    );
+    assert_eq!(
+        matrix[3][..8],
+        [true, false, true, true, true, false, true, false]
+    );
+    assert!(matrix[7][..8].iter().all(|&dark| !dark));
+    // Finder sub-pattern, bottom right
+    assert_eq!(matrix[7][22..], [true, false, false, false, true]);
+    assert_eq!(matrix[8][22..], [true, false, true, false, true]);
+    assert_eq!(matrix[10][22..], [true, true, true, true, true]);
+    // Timing patterns
+    assert!((8..22).all(|x| matrix[0][x] == (x % 2 == 0)));
+    assert!((3..6).all(|y| matrix[y][26] == (y % 2 == 0)));
+}
+
+#[test]
+fn rmqr_format_information() {
+    for version in RMQRVersion::ALL {
+        for ecl in [ECL::M, ECL::H] {
+            let format = rmqr::format_information(version, ecl);
+
+            assert_eq!(
+                format >> 12,
+                (matches!(ecl, ECL::H) as usize) << 5 | version as usize
+            );
+            // Multiple of the generator
+            let mut remainder = format;
+            for i in (12..18).rev() {
+                if remainder & (1 << i) != 0 {
+                    remainder ^= 0b1_1111_0010_0101 << (i - 12);
+                }
+            }
+            assert_eq!(remainder, 0);
+        }
+    }
+}
+
+#[test]
+fn rmqr_encodes_mode_and_count() {
+    let rmqr = RMQRBuilder::new("0123456789")
+        .version(RMQRVersion::R7x43)
+        .build()
+        .unwrap();
+    let bits = read_bits(&rmqr);
+
+    // Numeric mode, then 10 on 4 bits, then "012"
+    let expected = [0, 0, 1, 1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0];
+    let bits = bits[..17].iter().map(|&bit| bit as u8).collect::<Vec<_>>();
+    assert_eq!(bits, expected);
+}
+
+#[test]
+fn rmqr_picks_smallest_version() {
+    // R11x27 is smaller than R7x43
+    let rmqr = RMQRBuilder::new("123456789012").build().unwrap();
+    assert!(matches!(rmqr.version, RMQRVersion::R11x27));
+
+    let rmqr = RMQRBuilder::new("123456789012")
+        .max_height(7)
+        .build()
+        .unwrap();
+    assert!(matches!(rmqr.version, RMQRVersion::R7x43));
+
+    let rmqr = RMQRBuilder::new("123456789012345").build().unwrap();
+    assert!(matches!(rmqr.version, RMQRVersion::R13x27));
+
+    let rmqr = RMQRBuilder::new("123456789012345")
+        .max_height(7)
+        .build()
+        .unwrap();
+    assert!(matches!(rmqr.version, RMQRVersion::R7x59));
+
+    let too_long = [b'a'; 400];
+    assert!(RMQRBuilder::new(&too_long[..]).build().is_err());
+    assert!(RMQRBuilder::new("ABC").mode(Mode::Numeric).build().is_err());
+    assert!(RMQRBuilder::new("A long text that does not fit")
+        .version(RMQRVersion::R7x43)
+        .build()
+        .is_err());
+}
+
+/// Compares every module with a golden matrix, `1` for dark modules
+fn assert_matrix(rmqr: &RMQRCode, expected: &[&str]) {
+    assert_eq!(rmqr.height, expected.len());
+    for (y, (row, expected)) in rmqr.to_bool_matrix().iter().zip(expected).enumerate() {
+        let row = row
+            .iter()
+            .map(|&dark| if dark { '1' } else { '0' })
+            .collect::<String>();
+        assert_eq!(&row, expected, "row {}", y);
+    }
+}
+
+// Golden matrices were read back by a separate decoder following ISO/IEC 23941: both format
+// information copies, the RS syndromes of every block and the segments
+#[test]
+fn rmqr_golden_r7x43_numeric() {
+    let rmqr = RMQRBuilder::new("123456")
+        .version(RMQRVersion::R7x43)
+        .ecl(ECL::M)
+        .build()
+        .unwrap();
+
+    assert_matrix(
+        &rmqr,
+        &[
+            "1111111010101010101011101010101010101011111",
+            "1000001001010000010010110000110011011000101",
+            "1011101010111000111111101100011010111111111",
+            "1011101001100010010111111001111111000010001",
+            "1011101000101001111011100010001110010010101",
+            "1000001011110001110110111111001011111010001",
+            "1111111010101010101011101010101010101011111",
+        ],
+    );
+}
+
+#[test]
+fn rmqr_golden_r9x77_byte_two_blocks() {
+    let rmqr = RMQRBuilder::new("fast_qr")
+        .version(RMQRVersion::R9x77)
+        .ecl(ECL::H)
+        .build()
+        .unwrap();
+
+    assert_matrix(
+        &rmqr,
+        &[
+            "11111110101010101010101011101010101010101010101010111010101010101010101011111",
+            "10000010111100001110001110101010001011000110100111101001001111110000100001101",
+            "10111010111101001011010011101000100001011100011010111001000011111100011111101",
+            "10111010010100001011101011011000100001101001001011110011010001101001001100010",
+            "10111010010010101101010100111101010110011110110111101010011110010001010111111",
+            "10000010110001001101111001000001111110010111101100110111110111000100000110001",
+            "11111110101010010011100111100010010010101011110010111101011000001110011110101",
+            "00000000001011111011101110101100001011100110001111101100001101101011011110001",
+            "10101010101010101010101011101010101010101010101010111010101010101010101011111",
+        ],
+    );
+}
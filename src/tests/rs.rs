@@ -0,0 +1,101 @@
+use crate::rs;
+use crate::{Version, ECL};
+
+#[test]
+fn field_arithmetic_round_trips() {
+    assert_eq!(rs::exp(0), 1);
+    assert_eq!(rs::exp(8), 29);
+    assert_eq!(rs::exp(255), 1);
+    assert_eq!(rs::log(0), None);
+
+    for a in 1..=255u8 {
+        assert_eq!(rs::exp(rs::log(a).unwrap() as usize), a);
+        assert_eq!(rs::divide(rs::multiply(a, 0x53), 0x53), Some(a));
+        assert_eq!(rs::multiply(a, rs::divide(1, a).unwrap()), 1);
+        assert_eq!(rs::add(a, a), 0);
+    }
+    assert_eq!(rs::divide(7, 0), None);
+    assert_eq!(rs::multiply(0, 7), 0);
+}
+
+#[test]
+fn encode_matches_the_spec_example() {
+    // Annex I of the spec: "01234567" in version 1-M
+    let data = [
+        0b0001_0000,
+        0b0010_0000,
+        0b0000_1100,
+        0b0101_0110,
+        0b0110_0001,
+        0b1000_0000,
+        0b1110_1100,
+        0b0001_0001,
+        0b1110_1100,
+        0b0001_0001,
+        0b1110_1100,
+        0b0001_0001,
+        0b1110_1100,
+        0b0001_0001,
+        0b1110_1100,
+        0b0001_0001,
+    ];
+    let ecc = rs::encode(&data, 10).unwrap();
+    assert_eq!(ecc, [165, 36, 212, 193, 237, 54, 199, 135, 44, 85]);
+
+    assert_eq!(rs::encode(&[0; 250], 6), None);
+    assert_eq!(rs::generator(10).len(), 11);
+}
+
+#[test]
+fn correct_fixes_up_to_half_the_ecc_codewords() {
+    let data = (0..40).collect::<Vec<u8>>();
+    let ecc = rs::encode(&data, 16).unwrap();
+    let codewords = [&data[..], &ecc].concat();
+
+    let mut damaged = codewords.clone();
+    for i in (0..damaged.len()).step_by(7).take(8) {
+        damaged[i] ^= 0xA5;
+    }
+    assert_eq!(rs::correct(&mut damaged, 16), Some(8));
+    assert_eq!(damaged, codewords);
+
+    // Too many errors leave the codewords untouched
+    let mut damaged = codewords.clone();
+    for i in (0..damaged.len()).step_by(3).take(12) {
+        damaged[i] ^= 0x3C;
+    }
+    let before = damaged.clone();
+    assert_eq!(rs::correct(&mut damaged, 16), None);
+    assert_eq!(damaged, before);
+}
+
+#[test]
+fn interleave_matches_build_codewords() {
+    use crate::QRBuilder;
+
+    let codewords = QRBuilder::new("https://example.com/interleaving")
+        .ecl(ECL::Q)
+        .disable_ecl_boost()
+        .version(Version::V05)
+        .build_codewords()
+        .unwrap();
+
+    // Version 5-Q: 2 blocks of 15 and 2 blocks of 16 data codewords, read back in order
+    let sizes = [15, 15, 16, 16];
+    let mut blocks = vec![Vec::new(); 4];
+    let mut interleaved = codewords.as_bytes().iter();
+    for i in 0..16 {
+        for (block, &size) in blocks.iter_mut().zip(&sizes) {
+            if i < size {
+                block.push(*interleaved.next().unwrap());
+            }
+        }
+    }
+    let data = blocks.concat();
+
+    assert_eq!(
+        rs::interleave(&data, Version::V05, ECL::Q).unwrap(),
+        codewords.as_bytes()
+    );
+    assert_eq!(rs::interleave(&data[1..], Version::V05, ECL::Q), None);
+}
@@ -378,3 +378,87 @@ fn pattern() {
 
     assert_eq!(test_score_pattern(&line), 40, "pattern, expected 40");
 }
+
+#[test]
+fn penalty_score_for_other_masks() {
+    use crate::{Mask, QRBuilder, Scannability};
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let penalty = qrcode.penalty_score();
+    assert_eq!(
+        penalty.total(),
+        penalty.runs + penalty.blocks + penalty.finder_like + penalty.balance
+    );
+    assert_eq!(qrcode.penalty_score_for(qrcode.mask()), penalty);
+
+    for mask in [Mask::Checkerboard, Mask::Diamonds, Mask::Meadow] {
+        let forced = QRBuilder::new("https://example.com/")
+            .mask(mask)
+            .build()
+            .unwrap();
+        assert_eq!(qrcode.penalty_score_for(mask), forced.penalty_score());
+    }
+
+    assert_eq!(qrcode.scannability(21.0, 4), Scannability::Good);
+    assert_eq!(qrcode.scannability(4.0, 4), Scannability::Fair);
+    assert_eq!(qrcode.scannability(21.0, 2), Scannability::Fair);
+    assert_eq!(qrcode.scannability(2.0, 4), Scannability::Poor);
+}
+
+#[test]
+fn mask_scores_explain_the_selection() {
+    use crate::{Mask, QRBuilder, Version};
+
+    for input in [
+        "https://example.com/",
+        "fast_qr",
+        "0123456789",
+        "HELLO WORLD",
+    ] {
+        let qrcode = QRBuilder::new(input).build().unwrap();
+        let scores = qrcode.mask_scores().unwrap();
+
+        // `min_by_key` keeps the first minimum, like the selection
+        let best = scores
+            .iter()
+            .min_by_key(|(_, score)| score.total())
+            .unwrap();
+        assert_eq!(best.0 as usize, qrcode.mask() as usize, "{input}");
+
+        let candidates = [Mask::VerticalLines, Mask::Fields];
+        let restricted = QRBuilder::new(input)
+            .mask_candidates(&candidates)
+            .build()
+            .unwrap();
+        let best = scores
+            .iter()
+            .filter(|(mask, _)| candidates.iter().any(|&c| c as usize == *mask as usize))
+            .min_by_key(|(_, score)| score.total())
+            .unwrap();
+        assert_eq!(best.0 as usize, restricted.mask() as usize, "{input}");
+        assert_eq!(
+            restricted.mask_scores().unwrap().map(|(_, score)| score),
+            scores.map(|(_, score)| score)
+        );
+    }
+
+    // Masks of large versions are scored in parallel with the `rayon` feature
+    let large = QRBuilder::new("https://example.com/")
+        .version(Version::V32)
+        .build()
+        .unwrap();
+    let best = large
+        .mask_scores()
+        .unwrap()
+        .into_iter()
+        .min_by_key(|(_, score)| score.total())
+        .unwrap();
+    assert_eq!(best.0 as usize, large.mask() as usize);
+
+    let micro = QRBuilder::new("1234")
+        .micro()
+        .mask_candidates(&[Mask::Checkerboard, Mask::Diamonds])
+        .build()
+        .unwrap();
+    assert_eq!(micro.mask() as usize, Mask::Diamonds as usize);
+}
@@ -0,0 +1,36 @@
+#[cfg(feature = "svg")]
+#[test]
+fn cells_are_laid_out_in_pages() {
+    use crate::convert::sheet::SheetBuilder;
+    use crate::QRBuilder;
+
+    let mut sheet = SheetBuilder::new(2);
+    sheet.rows(2).cell_size(30f64).spacing(5f64).margin(10f64);
+    for id in 0..5 {
+        let qrcode = QRBuilder::new(format!("badge {id}")).build().unwrap();
+        sheet.push_with_caption(qrcode, format!("<Badge {id}>"));
+    }
+
+    assert_eq!(sheet.pages(), 2);
+    // 2 cells of 30 and 2 rows of 30 + 6 of caption, spaced by 5, with margins of 10
+    assert_eq!(sheet.page_size(), (85f64, 97f64));
+
+    let first = sheet.to_svg(0);
+    assert_eq!(first.matches("<svg").count(), 5);
+    assert!(first.contains(r#"<svg x="45" y="51" width="30" height="30" "#));
+    assert!(first.contains("&lt;Badge 3&gt;</text>"));
+    assert_eq!(sheet.to_svg(1).matches("<svg").count(), 2);
+
+    let pdf = String::from_utf8(sheet.to_pdf()).unwrap();
+    assert!(pdf.starts_with("%PDF-1.4\n"));
+    assert!(pdf.contains("/Count 2"));
+    assert!(pdf.contains("(<Badge 4>) Tj"));
+    assert!(pdf.ends_with("%%EOF\n"));
+
+    // Each xref entry points to its object
+    let xref = pdf.rfind("xref\n").unwrap();
+    for (i, entry) in pdf[xref..].lines().skip(3).take(7).enumerate() {
+        let offset: usize = entry[..10].parse().unwrap();
+        assert!(pdf[offset..].starts_with(&format!("{} 0 obj", i + 1)));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::datamasking::{self, Mask};
+use crate::score::{test_scalar_dark_modules, test_scalar_score_squares};
+use crate::{hardcode, polynomials, simd, QRBuilder, Version, ECL};
+
+#[test]
+fn division_matches_scalar() {
+    let mut seed = 0x2545_f491_u32;
+    let mut next = || {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        seed as u8
+    };
+
+    for version in [Version::V01, Version::V10, Version::V27, Version::V40] {
+        for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+            let generator = hardcode::get_polynomial(version, ecl);
+            let [(_, size), _] = hardcode::ecc_to_groups(ecl, version);
+            let data = (0..size).map(|_| next()).collect::<Vec<_>>();
+
+            // No SIMD on this CPU
+            let division = match simd::division(&data, generator) {
+                Some(division) => division,
+                None => return,
+            };
+            assert_eq!(division, polynomials::scalar_division(&data, generator));
+        }
+    }
+}
+
+#[test]
+fn scoring_matches_scalar() {
+    let inputs = [
+        "https://example.com/",
+        &"0123456789".repeat(50),
+        &"A".repeat(2000),
+    ];
+
+    for input in inputs {
+        let qr = QRBuilder::new(input).build().unwrap();
+
+        for mask in [Mask::Checkerboard, Mask::Diamonds, Mask::Meadow] {
+            let mut copy = qr.clone();
+            datamasking::mask(&mut copy, mask);
+
+            let squares = match simd::squares(&copy) {
+                Some(squares) => squares,
+                None => return,
+            };
+            assert_eq!(squares, test_scalar_score_squares(&copy));
+            assert_eq!(
+                simd::dark_modules(&copy),
+                Some(test_scalar_dark_modules(&copy))
+            );
+        }
+    }
+}
@@ -49,3 +49,1255 @@ fn check_svg_is_not_inverted() {
         }
     }
 }
+
+#[cfg(feature = "svg")]
+#[test]
+fn it_fills_modules_with_a_gradient() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, Gradient, Shape};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let svg = SvgBuilder::default()
+        .module_gradient(Gradient::linear(
+            45f64,
+            vec![(0f64, "#1e3a8a"), (1f64, "#9333ea")],
+        ))
+        .shape(Shape::Square)
+        .shape_color(Shape::Circle, "#ff0000")
+        .to_str(&qrcode);
+
+    assert!(svg.contains(
+        r##"<defs><linearGradient id="fast_qr_gradient" gradientTransform="rotate(45 .5 .5)"><stop offset="0" stop-color="#1e3a8a"/><stop offset="1" stop-color="#9333ea"/></linearGradient></defs>"##
+    ));
+    assert!(svg.contains(r#"fill="url(#fast_qr_gradient)""#));
+    // Shapes with a specific color are not affected
+    assert!(svg.contains(r##"fill="#ff0000""##));
+
+    let svg = SvgBuilder::default()
+        .module_gradient(Gradient::radial(vec![
+            (0f64, [0, 0, 0]),
+            (1f64, [0, 0, 255]),
+        ]))
+        .to_str(&qrcode);
+
+    assert!(svg.contains(r#"<radialGradient id="fast_qr_gradient">"#));
+    assert!(svg.contains(r##"<stop offset="1" stop-color="#0000ff"/>"##));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn it_colors_finder_patterns() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::Builder;
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let svg = SvgBuilder::default()
+        .finder_color([255, 0, 0, 255])
+        .to_str(&qrcode);
+
+    // Top left finder pattern
+    let finder_path = svg.split("<path").nth(2).unwrap();
+    assert!(finder_path.contains("M4,4h1v1h-1"));
+    assert!(finder_path.contains(r##"fill="#ff0000""##));
+
+    let module_path = svg.split("<path").nth(1).unwrap();
+    assert!(!module_path.contains("M4,4h1v1h-1"));
+    assert!(module_path.contains(r##"fill="#000000""##));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn it_uses_shapes_per_module_type() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, Shape};
+    use crate::{ModuleType, QRBuilder};
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let svg = SvgBuilder::default()
+        .shape(Shape::Square)
+        .shape_for(ModuleType::FinderPattern, Shape::Diamond)
+        .to_str(&qrcode);
+
+    let paths = svg.split("<path").collect::<Vec<_>>();
+    assert_eq!(paths.len(), 3);
+
+    // Top left finder pattern is only drawn as a diamond
+    assert!(!paths[1].contains("M4,4h1v1h-1"));
+    assert!(paths[2].contains("M4.5,4l.5,.5l-.5,.5l-.5,-.5z"));
+    // Data modules are not affected
+    assert!(!paths[2].contains("M12,"));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn it_merges_adjacent_modules() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::Builder;
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let svg = SvgBuilder::default().merge_modules(0f64).to_str(&qrcode);
+    assert_eq!(svg.matches("<path").count(), 1);
+    assert!(!svg.contains("h1v1h-1"));
+    assert!(svg.len() < SvgBuilder::default().to_str(&qrcode).len());
+
+    // Top left finder pattern: outer square, counter clockwise hole, then center
+    assert!(svg.contains("M4,4H11V11H4V4z"));
+    assert!(svg.contains("M5,5V10H10V5H5z"));
+    assert!(svg.contains("M6,6H9V9H6V6z"));
+
+    let rounded = SvgBuilder::default().merge_modules(0.5).to_str(&qrcode);
+    assert!(rounded.contains("M4.5,4H10.5a.5,.5 0 0 1 .5,.5V10.5a.5,.5 0 0 1 -.5,.5"));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn it_draws_modules_with_a_closure() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, Shape};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let height = String::from(".5");
+    let svg = SvgBuilder::default()
+        .shape_fn(move |y, x, _| format!("M{x},{y}h1v{height}h-1"))
+        .shape_color(Shape::Circle, "#ff0000")
+        .to_str(&qrcode);
+
+    let paths = svg.split("<path").collect::<Vec<_>>();
+    assert!(paths[1].contains("M4,4h1v.5h-1"));
+    assert!(paths[1].contains(r##"fill="#000000""##));
+    assert!(paths[2].contains(r##"fill="#ff0000""##));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn it_writes_the_same_svg_as_to_str() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, Shape};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let mut builder = SvgBuilder::default();
+    builder.shape(Shape::Circle).finder_color([255, 0, 0]);
+
+    let mut out = Vec::new();
+    builder.write_to(&qrcode, &mut out).unwrap();
+
+    assert_eq!(String::from_utf8(out).unwrap(), builder.to_str(&qrcode));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn image_bytes_embeds_a_data_uri() {
+    use base64::engine::general_purpose;
+    use base64::Engine;
+
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{base64, Builder};
+    use crate::QRBuilder;
+
+    for len in 0..8 {
+        let bytes = (0..len)
+            .map(|i: u8| i.wrapping_mul(97))
+            .collect::<Vec<u8>>();
+        assert_eq!(base64(&bytes), general_purpose::STANDARD.encode(&bytes));
+    }
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let logo = b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>";
+    let svg = SvgBuilder::default()
+        .image_bytes(logo, "image/svg+xml")
+        .to_str(&qrcode);
+
+    let expected = general_purpose::STANDARD.encode(logo);
+    assert!(svg.contains(&format!(r#"href="data:image/svg+xml;base64,{expected}""#)));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn image_keeps_a_runtime_string() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::Builder;
+    use crate::QRBuilder;
+
+    fn with_logo(user: usize) -> SvgBuilder {
+        let logo = format!("https://example.com/logos/{user}.png");
+        let mut builder = SvgBuilder::default();
+        builder.image(logo);
+        builder
+    }
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let builders = (0..2).map(with_logo).collect::<Vec<_>>();
+
+    assert!(builders[0]
+        .to_str(&qrcode)
+        .contains(r#"href="https://example.com/logos/0.png""#));
+    assert!(builders[1]
+        .to_str(&qrcode)
+        .contains(r#"href="https://example.com/logos/1.png""#));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn image_can_be_placed_off_center() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::Builder;
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let svg = SvgBuilder::default()
+        .image(String::from("logo.png"))
+        .image_size(5f64)
+        .image_position(27f64, 27f64)
+        .to_str(&qrcode);
+
+    // Anchored on its center, in the bottom-right corner of the 33 modules wide svg
+    assert!(svg.contains(r##"<rect x="23.5" y="23.5" width="7" height="7" fill="#ffffff"/>"##));
+    assert!(
+        svg.contains(r#"<image x="24.50" y="24.50" width="5.00" height="5.00" href="logo.png" />"#)
+    );
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn modules_under_the_image_are_knocked_out() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::Builder;
+    use crate::{QRBuilder, ECL};
+
+    let qrcode = QRBuilder::new("https://example.com/")
+        .ecl(ECL::H)
+        .build()
+        .unwrap();
+    let mut builder = SvgBuilder::default();
+    builder.image(String::from("logo.png"));
+    let svg = builder.to_str(&qrcode);
+
+    // The background covers 14 to 23 of the 37 modules wide svg
+    assert!(svg.contains(r##"<rect x="14" y="14" width="9" height="9" fill="#ffffff"/>"##));
+    for y in 14..23 {
+        for x in 14..23 {
+            assert!(!svg.contains(&format!("M{x},{y}h1v1h-1")));
+        }
+    }
+    assert!(builder.check_image(&qrcode).is_ok());
+    assert!(builder.write_to(&qrcode, Vec::new()).is_ok());
+
+    builder.image_size(19f64);
+    assert!(builder.check_image(&qrcode).is_err());
+    assert!(builder.write_to(&qrcode, Vec::new()).is_err());
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn background_image_is_drawn_under_the_modules() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::Builder;
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let svg = SvgBuilder::default()
+        .background_image(String::from("photo.jpg"))
+        .background_image_opacity(0.25)
+        .to_str(&qrcode);
+
+    let picture = r#"<image width="33" height="33" href="photo.jpg" preserveAspectRatio="xMidYMid slice" opacity="0.25"/>"#;
+    let position = svg.find(picture).unwrap();
+    assert!(position < svg.find("<path").unwrap());
+
+    let svg = SvgBuilder::default()
+        .background_image_bytes(b"<svg/>", "image/svg+xml")
+        .to_str(&qrcode);
+    assert!(svg.contains(
+        r#"href="data:image/svg+xml;base64,PHN2Zy8+" preserveAspectRatio="xMidYMid slice"/>"#
+    ));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn blob_rounds_corners_without_neighbors() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, Neighbors, Shape};
+    use crate::{Module, QRBuilder};
+
+    let module = Module::new(true, crate::ModuleType::Data);
+    assert_eq!(
+        Shape::blob(0, 0, module, Neighbors::default()),
+        "M0.5,0h0a.5,.5 0 0 1 0.5,0.5v0a.5,.5 0 0 1 -0.5,0.5h-0a.5,.5 0 0 1 -0.5,-0.5v-0a.5,.5 0 0 1 0.5,-0.5z"
+    );
+    // Only the left corners are rounded next to a module on the right
+    assert_eq!(
+        Shape::blob(0, 0, module, Neighbors(Neighbors::RIGHT)),
+        "M0.5,0h0.5v1h-0.5a.5,.5 0 0 1 -0.5,-0.5v-0a.5,.5 0 0 1 0.5,-0.5z"
+    );
+    assert_eq!(
+        Shape::blob(0, 0, module, Neighbors(Neighbors::TOP | Neighbors::BOTTOM)),
+        "M0,0h1v1h-1v-1z"
+    );
+
+    // The top-left module of the finder pattern only has neighbors on the right and below
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let svg = SvgBuilder::default().shape(Shape::Blob).to_str(&qrcode);
+    assert!(svg.contains("M4.5,4h0.5v1h-1v-0.5a.5,.5 0 0 1 0.5,-0.5z"));
+
+    let shape = |y, x, _, neighbors: Neighbors| format!("M{x},{y}n{}", neighbors.0);
+    let svg = SvgBuilder::default().shape_neighbors(shape).to_str(&qrcode);
+    let expected = Neighbors::RIGHT | Neighbors::BOTTOM;
+    assert!(svg.contains(&format!("M4,4n{expected}")));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn dots_keep_finder_patterns_solid() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, Shape};
+    use crate::{Module, ModuleType, QRBuilder};
+
+    assert_eq!(
+        Shape::dot_sized(2, 3, Module::new(true, ModuleType::Data), 0.25),
+        "M3.25,2.5a0.25,0.25 0 1,1 0.5,0a0.25,0.25 0 1,1 -0.5,0"
+    );
+    assert_eq!(
+        Shape::dot_sized(2, 3, Module::new(true, ModuleType::FinderPattern), 0.25),
+        "M3,2h1v1h-1"
+    );
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let svg = SvgBuilder::default()
+        .shape(Shape::Dot)
+        .dot_radius(0.3)
+        .to_str(&qrcode);
+    assert!(svg.contains("M4,4h1v1h-1"));
+    assert!(svg.contains("a0.3,0.3 0 1,1 0.6,0"));
+    assert!(!svg.contains("a0.4,0.4"));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn errors_chain_their_io_source() {
+    use std::error::Error;
+    use std::io;
+
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::ConvertError;
+    use crate::QRBuilder;
+
+    struct Failing;
+    impl io::Write for Failing {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::Other, "disk full"))
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn render() -> Result<(), Box<dyn Error>> {
+        let qrcode = QRBuilder::new("https://example.com/").build()?;
+        SvgBuilder::default().write_to(&qrcode, Failing)?;
+        Ok(())
+    }
+
+    let err = render().unwrap_err();
+    assert_eq!(err.to_string(), "Failed to write svg: disk full");
+    assert_eq!(err.source().unwrap().to_string(), "disk full");
+
+    let err = SvgBuilder::default()
+        .write_to(&QRBuilder::new("a").build().unwrap(), Failing)
+        .unwrap_err();
+    let err = ConvertError::from(err);
+    assert_eq!(err.to_string(), "Failed to write file: disk full");
+    assert!(err.source().is_some());
+}
+
+#[cfg(all(feature = "svg", feature = "serde"))]
+#[test]
+fn it_serializes_builder_settings() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, Gradient, Shape};
+    use crate::{ModuleType, QRBuilder};
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let mut builder = SvgBuilder::default();
+    builder
+        .margin(2)
+        .shape_color(Shape::Circle, "#1e3a8a")
+        .shape_for(ModuleType::FinderPattern, Shape::RoundedSquare)
+        .module_gradient(Gradient::radial(vec![(0f64, "#000000"), (1f64, "#9333ea")]))
+        .size(2.0, crate::convert::svg::SvgUnit::In);
+
+    let json = serde_json::to_string(&builder).unwrap();
+    let restored = serde_json::from_str::<SvgBuilder>(&json).unwrap();
+    assert_eq!(restored.to_str(&qrcode), builder.to_str(&qrcode));
+
+    // Missing settings keep their default value
+    let partial = serde_json::from_str::<SvgBuilder>(r#"{"margin":0}"#).unwrap();
+    assert_eq!(
+        partial.to_str(&qrcode),
+        SvgBuilder::default().margin(0).to_str(&qrcode)
+    );
+
+    assert!(serde_json::from_str::<SvgBuilder>(r#"{"shapes":[["triangle",null]]}"#).is_err());
+
+    builder.shape_fn(|y, x, _| format!("M{x},{y}h1v1h-1"));
+    assert!(serde_json::to_string(&builder).is_err());
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn svg_has_physical_size_and_rounded_background() {
+    use crate::convert::svg::{SvgBuilder, SvgUnit};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let svg = SvgBuilder::default()
+        .size(1.5, SvgUnit::In)
+        .background_radius(2.5)
+        .to_str(&qrcode);
+    assert!(svg.starts_with(
+        r##"<svg viewBox="0 0 33 33" width="1.5in" height="1.5in" xmlns="http://www.w3.org/2000/svg"><rect width="33px" height="33px" rx="2.5" fill="#ffffff"/>"##
+    ));
+
+    let svg = SvgBuilder::default().to_str(&qrcode);
+    assert!(svg.starts_with(r#"<svg viewBox="0 0 33 33" xmlns"#));
+    assert!(!svg.contains("rx="));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn minified_runs_cover_the_same_modules() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, Shape};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let svg = SvgBuilder::default().margin(2).minify(true).to_str(&qrcode);
+    assert!(svg.contains(r#"<rect width="29" height="29""#));
+
+    let d = svg.split(r#"<path d=""#).nth(1).unwrap();
+    let d = &d[..d.find('"').unwrap()];
+
+    // Every run is `{M|m}x,yh{len}v1h-{len}`, the pen ends at its bottom left corner
+    let mut dark = vec![vec![false; qrcode.size]; qrcode.size];
+    let mut pen: Option<(isize, isize)> = None;
+    for run in d.split(['M', 'm']).skip(1) {
+        let (start, len) = run.split_once('h').unwrap();
+        let (x, y) = start.split_once(',').unwrap();
+        let (x, y) = (x.parse::<isize>().unwrap(), y.parse::<isize>().unwrap());
+        let len = len.split_once('v').unwrap().0.parse::<isize>().unwrap();
+        assert!(run.ends_with(&format!("v1h-{len}")));
+
+        let (x, y) = match pen {
+            Some((px, py)) => (px + x, py + y),
+            None => (x, y),
+        };
+        for i in 0..len {
+            dark[(y - 2) as usize][(x - 2 + i) as usize] = true;
+        }
+        pen = Some((x, y + 1));
+    }
+    assert_eq!(dark, qrcode.to_bool_matrix());
+
+    // Other shapes are left as is
+    let dots = SvgBuilder::default()
+        .shape(Shape::Circle)
+        .minify(true)
+        .to_str(&qrcode);
+    assert_eq!(
+        dots,
+        SvgBuilder::default()
+            .shape(Shape::Circle)
+            .to_str(&qrcode)
+            .replace("px\"", "\"")
+    );
+}
+
+#[cfg(feature = "svgz")]
+#[test]
+fn svgz_is_gzipped_svg() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::QRBuilder;
+    use std::io::Read;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let mut builder = SvgBuilder::default();
+    builder.minify(true);
+
+    let svgz = builder.to_svgz(&qrcode).unwrap();
+    assert_eq!(&svgz[..2], &[0x1f, 0x8b]);
+
+    let mut svg = String::new();
+    flate2::read::GzDecoder::new(&svgz[..])
+        .read_to_string(&mut svg)
+        .unwrap();
+    assert_eq!(svg, builder.to_str(&qrcode));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn scale_keeps_arc_flags() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, Shape};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let svg = SvgBuilder::default()
+        .shape(Shape::Circle)
+        .scale(10)
+        .to_str(&qrcode);
+    assert!(svg.contains(r#"<rect width="330px" height="330px""#));
+    assert!(svg.contains(r#"<path d="M50,45a5,5,0,1,1,0,-1M60,45a5,5,0,1,1,0,-1"#));
+
+    let svg = SvgBuilder::default()
+        .shape(Shape::RoundedSquare)
+        .scale(4)
+        .precision(1)
+        .to_str(&qrcode);
+    assert!(svg.contains(r#"<path d="M16.8,16.8,19.2,16.8,19.2,19.2,16.8,19.2z"#));
+    assert!(svg.contains(r#"stroke-width="1.2""#));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn swiss_cross_is_centered() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::{QRBuilder, Version};
+
+    let qrcode = QRBuilder::new("https://example.com/")
+        .version(Version::V10)
+        .build()
+        .unwrap();
+    assert_eq!(qrcode.size, 57);
+
+    let plain = SvgBuilder::default().to_str(&qrcode);
+    let svg = SvgBuilder::default().swiss_cross(true).to_str(&qrcode);
+    let cross = &svg[plain.len() - "</svg>".len()..svg.len() - "</svg>".len()];
+
+    // 57 * 7 / 46 = 8.674 modules wide, centered on 4 + 28.5
+    assert!(cross.starts_with(
+        r##"<rect x="28.163" y="28.163" width="8.674" height="8.674" fill="#FFFFFF"/>"##
+    ));
+    assert_eq!(cross.matches("<rect").count(), 4);
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn module_color_fn_groups_modules_by_color() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::Builder;
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let dark = qrcode.data[..qrcode.size * qrcode.size]
+        .iter()
+        .filter(|module| module.value())
+        .count();
+
+    // Checkerboard of 2x2 modules, finder patterns keep their own color
+    let svg = SvgBuilder::default()
+        .finder_color([0, 0, 255, 255])
+        .module_color_fn(|x, y, _| match (x / 2 + y / 2) % 2 {
+            0 => [255, 0, 0, 255],
+            _ => [0, 128, 0, 255],
+        })
+        .to_str(&qrcode);
+
+    assert!(svg.contains(r##"fill="#ff0000"/>"##));
+    assert!(svg.contains(r##"fill="#008000"/>"##));
+    assert!(svg.contains(r##"fill="#0000ff"/>"##));
+    assert!(!svg.contains(r##"fill="#000000"/>"##));
+    assert_eq!(svg.matches("<path").count(), 3);
+    assert_eq!(svg.matches('M').count(), dark);
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn shapes_are_layered_in_order() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, Shape};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let svg = SvgBuilder::default()
+        .shape_color(Shape::Square, "#1e3a8a")
+        .shape(Shape::Circle)
+        .shape_color(Shape::Diamond, [255, 0, 0])
+        .to_str(&qrcode);
+
+    let paths = svg.split("<path").skip(1).collect::<Vec<_>>();
+    assert_eq!(paths.len(), 3);
+    assert!(paths[0].starts_with(r#" d="M4,4h1v1h-1"#));
+    assert!(paths[0].contains(r##"fill="#1e3a8a""##));
+    assert!(paths[1].starts_with(r#" d="M5,4.5a.5"#));
+    assert!(paths[1].contains(r##"fill="#000000""##));
+    assert!(paths[2].contains(r##"fill="#ff0000""##));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn frame_and_label_extend_the_svg() {
+    use crate::convert::svg::{FrameStyle, LabelPosition, SvgBuilder, SvgUnit};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    assert_eq!(qrcode.size, 25);
+
+    let svg = SvgBuilder::default()
+        .frame(FrameStyle::Square)
+        .to_str(&qrcode);
+    assert!(svg.starts_with(
+        r#"<svg viewBox="-1 -1 35 35" xmlns="http://www.w3.org/2000/svg"><rect x="-1" y="-1" width="35px" height="35px""#
+    ));
+    assert!(svg.contains(
+        r##"<rect x="-.5" y="-.5" width="34" height="34" fill="none" stroke="#000000" stroke-width="1"/>"##
+    ));
+
+    // Without a frame, the text is drawn in the module color
+    let svg = SvgBuilder::default()
+        .label("Scan <me> & pay", "Arial", LabelPosition::Top)
+        .size(33f64, SvgUnit::Mm)
+        .to_str(&qrcode);
+    assert!(svg.starts_with(r#"<svg viewBox="0 -4 33 37" width="33mm" height="37mm""#));
+    assert!(svg.contains(
+        r##"<text x="16.5" y="-1.125" font-family="Arial" font-size="2.5" font-weight="bold" text-anchor="middle" fill="#000000">Scan &lt;me&gt; &amp; pay</text>"##
+    ));
+
+    // With a frame, the banner is filled and the text uses the background color
+    let svg = SvgBuilder::default()
+        .frame(FrameStyle::Rounded)
+        .label("SCAN ME", "Arial", LabelPosition::Bottom)
+        .scale(2)
+        .to_str(&qrcode);
+    assert!(svg.starts_with(r#"<svg viewBox="-2 -2 70 78""#));
+    assert!(svg.contains(r#"rx="3" fill="none""#));
+    assert!(svg.contains(r##"<rect y="66" width="66" height="8" fill="#000000"/>"##));
+    assert!(svg.contains(r##"fill="#ffffff">SCAN ME</text></svg>"##));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn center_text_replaces_the_image() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, ImageBackgroundShape};
+    use crate::{QRBuilder, ECL};
+
+    let qrcode = QRBuilder::new("https://example.com/")
+        .ecl(ECL::H)
+        .build()
+        .unwrap();
+
+    let mut builder = SvgBuilder::default();
+    builder
+        .image(String::from("logo.png"))
+        .center_text("A&B", 3f64, [255, 0, 0])
+        .image_background_shape(ImageBackgroundShape::RoundedSquare);
+    let svg = builder.to_str(&qrcode);
+
+    assert!(!svg.contains("<image"));
+    assert!(svg
+        .contains(r#"rx="1px"/><text x="18.5" y="19.55" font-family="sans-serif" font-size="3""#));
+    assert!(svg.contains(r##"fill="#ff0000">A&amp;B</text>"##));
+    assert!(builder.check_image(&qrcode).is_ok());
+    // Modules under the background are cleared, like with an image
+    let plain = SvgBuilder::default().to_str(&qrcode);
+    assert!(svg.matches('M').count() < plain.matches('M').count());
+
+    let svg = builder.image(String::from("logo.png")).to_str(&qrcode);
+    assert!(svg.contains("<image") && !svg.contains("<text"));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn eyes_are_styled_separately() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, EyeShape};
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    // Colors only: frames and balls are drawn module by module, in their own paths
+    let svg = SvgBuilder::default()
+        .eye_frame_color([255, 0, 0])
+        .eye_ball_color([0, 0, 255])
+        .to_str(&qrcode);
+    let frame_path = svg.split("<path").nth(2).unwrap();
+    assert!(frame_path.contains("M4,4h1v1h-1") && !frame_path.contains("M6,6h1v1h-1"));
+    assert!(frame_path.contains(r##"fill="#ff0000""##));
+    let ball_path = svg.split("<path").nth(3).unwrap();
+    assert!(ball_path.contains("M6,6h1v1h-1") && !ball_path.contains("M4,4h1v1h-1"));
+    assert!(ball_path.contains(r##"fill="#0000ff""##));
+
+    // Shapes: one path for the three frames, one for the three balls
+    let svg = SvgBuilder::default()
+        .finder_color([0, 255, 0])
+        .eye_frame_shape(EyeShape::Circle)
+        .eye_ball_shape(EyeShape::Square)
+        .to_str(&qrcode);
+    let module_path = svg.split("<path").nth(1).unwrap();
+    assert!(!module_path.contains("M4,4h1v1h-1") && !module_path.contains("M6,6h1v1h-1"));
+    assert!(svg.contains(
+        r##"<path d="M7.5,4a3.5,3.5,0,0,1,3.5,3.5a3.5,3.5,0,0,1,-3.5,3.5a3.5,3.5,0,0,1,-3.5,-3.5a3.5,3.5,0,0,1,3.5,-3.5z"##
+    ));
+    assert!(svg.contains(r##"fill-rule="evenodd" fill="#00ff00""##));
+    assert!(svg.contains(r##"<path d="M6,6h3v3h-3v-3z"##));
+    assert_eq!(svg.matches("h3v3h-3v-3z").count(), 3);
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn build_qr_raises_the_ecl_for_the_image() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::Builder;
+    use crate::{QRBuilder, ECL};
+
+    let builder = QRBuilder::new("https://example.com/");
+
+    // Without an image, the ECL is left to `QRBuilder`
+    let qrcode = SvgBuilder::default().build_qr(&builder).unwrap();
+    assert_eq!(qrcode.to_str(), builder.build().unwrap().to_str());
+
+    let mut svg = SvgBuilder::default();
+    svg.image(String::from("logo.png")).image_size(10f64);
+    let qrcode = svg.build_qr(&builder).unwrap();
+    assert!(matches!(qrcode.ecl(), ECL::H));
+
+    // A forced ECL is kept, and the image is checked against it
+    assert!(svg
+        .build_qr(QRBuilder::new("https://example.com/").ecl(ECL::L))
+        .is_err());
+    assert!(svg.image_size(5f64).build_qr(&builder).is_ok());
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn styled_qr_encodes_and_draws() {
+    use crate::convert::styled::StyledQr;
+    use crate::convert::{svg::SvgBuilder, Builder, Shape};
+    use crate::{QRBuilder, ECL};
+
+    let mut styled = StyledQr::new("https://example.com/");
+    styled.shape(Shape::Circle).module_color([30, 58, 138]);
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let expected = SvgBuilder::default()
+        .shape(Shape::Circle)
+        .module_color([30, 58, 138])
+        .to_str(&qrcode);
+    assert_eq!(styled.to_svg().unwrap(), expected);
+
+    // A logo raises the ECL, unless it is forced
+    styled.logo(b"\x89PNG\r\n\x1a\n").image_size(10f64);
+    assert!(matches!(styled.build().unwrap().ecl(), ECL::H));
+    assert!(styled
+        .to_svg()
+        .unwrap()
+        .contains(r#"href="data:image/png;base64,"#));
+    assert!(styled.ecl(ECL::L).to_svg().is_err());
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn odd_options_do_not_panic() {
+    use crate::convert::{svg::SvgBuilder, Builder, EyeShape};
+    use crate::{QRBuilder, QRCode};
+
+    let micro = QRBuilder::new("123").micro().build().unwrap();
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+
+    let mut builder = SvgBuilder::default();
+    builder
+        .image(String::from("logo.png"))
+        .image_size(f64::NAN)
+        .image_gap(-3f64)
+        .eye_frame_shape(EyeShape::Circle);
+
+    for qr in [&micro, &qrcode, &QRCode::default(0), &QRCode::default(5)] {
+        let _ = builder.to_str(qr);
+        let _ = builder.check_image(qr);
+    }
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn output_matches_render_version_snapshots() {
+    use crate::convert::svg::{render_version, SvgBuilder};
+    use crate::convert::{Builder, EyeShape, Gradient, Shape};
+    use crate::{QRBuilder, Version, ECL};
+
+    // Changing the output needs a new render version, and new snapshots
+    assert_eq!(render_version(), 1);
+
+    let qrcode = QRBuilder::new("https://example.com/")
+        .ecl(ECL::M)
+        .version(Version::V02)
+        .build()
+        .unwrap();
+    assert_eq!(
+        SvgBuilder::default().to_str(&qrcode),
+        include_str!("snapshots/square.svg")
+    );
+
+    let styled = SvgBuilder::default()
+        .shape(Shape::Circle)
+        .eye_frame_shape(EyeShape::RoundedSquare)
+        .module_gradient(Gradient::linear(
+            30f64,
+            vec![(0f64, "#1e3a8a"), (1f64, "#9333ea")],
+        ))
+        .background_color([255, 255, 255, 0])
+        .to_str(&qrcode);
+    assert_eq!(styled, include_str!("snapshots/styled.svg"));
+
+    // Noise and negative zero do not leak into the output
+    let gradient = SvgBuilder::default()
+        .module_gradient(Gradient::linear(
+            -0.0000001,
+            vec![(0.1 + 0.2, "#000000"), (f64::NAN, "#000000")],
+        ))
+        .to_str(&qrcode);
+    assert!(gradient.contains(
+        r##"rotate(0 .5 .5)"><stop offset=".3" stop-color="#000000"/><stop offset="0""##
+    ));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn low_contrast_colors_are_refused() {
+    use crate::convert::{svg::SvgBuilder, Builder, Color, Gradient, Shape};
+    use crate::QRBuilder;
+
+    let white = Color::from("#ffffff");
+    let ratio = Color::from("#777777").contrast_ratio(&white).unwrap();
+    assert!((ratio - 4.48).abs() < 0.01);
+    // Translucent modules are blended with the background
+    assert_eq!(Color::from("#00000000").contrast_ratio(&white), Some(1f64));
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let mut builder = SvgBuilder::default();
+    builder.min_contrast(4.5).module_color("#1e3a8a");
+    assert!(builder
+        .build_qr(&QRBuilder::new("https://example.com/"))
+        .is_ok());
+    assert!(builder.write_to(&qrcode, Vec::new()).is_ok());
+
+    // Every module color counts, the lowest contrast is reported
+    builder.shape_color(Shape::Circle, "#fde047");
+    let err = builder.write_to(&qrcode, Vec::new()).unwrap_err();
+    assert!(err.to_string().starts_with("Contrast ratio 1.32 "));
+
+    let mut gradient = SvgBuilder::default();
+    gradient
+        .min_contrast(3f64)
+        .module_gradient(Gradient::linear(
+            0f64,
+            vec![(0f64, "#000000"), (1f64, "#a3a3a3")],
+        ));
+    assert!(gradient.check_contrast().is_err());
+    // Named colors cannot be checked
+    gradient
+        .module_color("red")
+        .module_gradient(Gradient::radial(vec![(0f64, "navy")]));
+    assert_eq!(gradient.contrast_ratio(), None);
+    assert!(gradient.check_contrast().is_ok());
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn inverted_svg_swaps_modules_and_quiet_zone() {
+    use crate::convert::{svg::SvgBuilder, Builder, EyeShape};
+    use crate::{QRBuilder, Version, ECL};
+
+    let qrcode = QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+    let mut builder = SvgBuilder::default();
+    builder.eye_frame_shape(EyeShape::Circle).invert(true);
+    let svg = builder.to_str(&qrcode);
+
+    // Margin of 4 around the 21x21 code, then the light modules
+    assert!(svg.contains(
+        r##"<path d="M0,0h29v29h-29v-29zM4,4h21v21h-21v-21z" fill-rule="evenodd" fill="#000000"/>"##
+    ));
+    for (x, y, module) in qrcode.iter_modules() {
+        let square = format!("M{},{}h1v1h-1", x + 4, y + 4);
+        assert_eq!(svg.contains(&square), !module.value());
+    }
+
+    let plain = builder.invert(false).to_str(&qrcode);
+    assert!(!plain.contains("M0,0h29"));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn module_gap_shrinks_every_shape() {
+    use crate::convert::{svg::SvgBuilder, Builder, Shape};
+    use crate::{ModuleType, QRBuilder, Version, ECL};
+
+    let qrcode = QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap();
+
+    let svg = SvgBuilder::default()
+        .module_gap(0.2)
+        .minify(true)
+        .to_str(&qrcode);
+    assert!(svg.contains("M4.1,4.1h.8v.8h-.8"));
+
+    // Arcs keep their flags, absolute and relative numbers are scaled around the center
+    let svg = SvgBuilder::default()
+        .margin(0)
+        .module_gap(0.5)
+        .shape(Shape::Circle)
+        .shape_for(ModuleType::FinderPattern, Shape::Diamond)
+        .shape_fn(|y, x, _| format!("M{x},{y}L{},{}", x + 1, y + 1))
+        .to_str(&qrcode);
+    assert!(svg.contains("M.5,.25l.25,.25l-.25,.25l-.25,-.25z"));
+    assert!(svg.contains("M.75,11.5a.25,.25,0,1,1,0,-.05"));
+    assert!(svg.contains("M.25,11.25L.75,11.75"));
+
+    // Scaled after the gap
+    let svg = SvgBuilder::default()
+        .margin(0)
+        .module_gap(0.5)
+        .scale(10)
+        .to_str(&qrcode);
+    assert!(svg.contains("M2.5,2.5h5v5h-5"));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn symbols_are_defined_once_and_used_per_module() {
+    use crate::convert::{svg::SvgBuilder, Builder, Shape};
+    use crate::{ModuleType, QRBuilder};
+
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let dark = qrcode.iter_modules().filter(|(_, _, m)| m.value()).count();
+    let finder = qrcode
+        .iter_modules()
+        .filter(|(_, _, m)| m.value() && m.module_type() == ModuleType::FinderPattern)
+        .count();
+
+    let svg = SvgBuilder::default()
+        .use_symbols(true)
+        .shape(Shape::Blob)
+        .shape_for(ModuleType::FinderPattern, Shape::Diamond)
+        .finder_color("#e11d48")
+        .scale(10)
+        .to_str(&qrcode);
+
+    // Blobs differ by their rounded corners, each shape is defined once
+    let ids = svg.matches(r#"<path id="fast_qr_shape_"#).count();
+    assert!(ids <= 17);
+    assert_eq!(svg.matches("<use ").count(), dark);
+    assert!(svg.contains(r#"<path id="fast_qr_shape_0" d="M5,0l5,5l-5,5l-5,-5z"/>"#));
+    assert!(svg.contains(r##"<g fill="#e11d48"><use href="#fast_qr_shape_0" x="40" y="40"/>"##));
+    assert_eq!(
+        svg.matches(r##"<use href="#fast_qr_shape_0""##).count(),
+        finder
+    );
+
+    // Same drawing as paths
+    let mut builder = SvgBuilder::default();
+    builder.shape(Shape::Circle).module_gap(0.2);
+    let paths = builder.to_str(&qrcode);
+    let symbols = builder.use_symbols(true).to_str(&qrcode);
+    assert!(!paths.contains("<use"));
+    assert_eq!(symbols.matches("<path id=").count(), 1);
+    assert!(symbols.contains(r#"d="M.9,.5a.4,.4,0,1,1,0,-.08""#));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn registered_shapes_round_trip_by_name() {
+    use crate::convert::{svg::SvgBuilder, Builder, Shape};
+    use crate::{QRBuilder, Version};
+
+    let qrcode = QRBuilder::new("Test")
+        .version(Version::V01)
+        .build()
+        .unwrap();
+
+    for (index, &name) in Shape::NAMES.iter().enumerate() {
+        let shape = Shape::from_name(name).unwrap();
+        assert_eq!(<&str>::from(shape), name);
+        assert_eq!(usize::from(shape), index);
+    }
+    assert_eq!(Shape::from_name("triangle"), None);
+    assert_eq!(Shape::from(String::from("Hexagon")), Shape::Hexagon);
+
+    let svg = SvgBuilder::default().shape(Shape::Hexagon).to_str(&qrcode);
+    assert!(svg.contains("M4,4.5l.25,-.433h.5l.25,.433l-.25,.433h-.5z"));
+    let svg = SvgBuilder::default().shape(Shape::Octagon).to_str(&qrcode);
+    assert!(svg.contains("M4.293,4h.414l.293,.293v.414l-.293,.293h-.414l-.293,-.293v-.414z"));
+    let svg = SvgBuilder::default().shape(Shape::Star).to_str(&qrcode);
+    assert!(svg.contains("M4.5,4.048l.147,.298l.329,.048"));
+    let svg = SvgBuilder::default().shape(Shape::Heart).to_str(&qrcode);
+    assert!(svg.contains("M4.5,4.92l-.4,-.42a.256,.256 0 0 1 .4,-.32"));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn margin_band_leaves_the_quiet_zone_empty() {
+    use crate::convert::svg::{MarginStyle, SvgBuilder};
+    use crate::convert::Builder;
+    use crate::{QRBuilder, Version};
+
+    let qrcode = QRBuilder::new("Test")
+        .version(Version::V01)
+        .build()
+        .unwrap();
+    let band = MarginStyle::Band {
+        color: "#1e3a8a".into(),
+        caption: Some("a<b".into()),
+    };
+
+    // No room outside of the quiet zone
+    let svg = SvgBuilder::default()
+        .margin_style(band.clone())
+        .to_str(&qrcode);
+    assert_eq!(svg, SvgBuilder::default().to_str(&qrcode));
+
+    let svg = SvgBuilder::default()
+        .margin(6)
+        .margin_style(band)
+        .scale(10)
+        .to_str(&qrcode);
+    assert!(svg.contains(
+        r##"<path d="M0,0h330v330h-330v-330zM20,20h290v290h-290v-290z" fill-rule="evenodd" fill="#1e3a8a"/>"##
+    ));
+    assert!(svg.contains(r#"<text x="165" y="324.2" font-family="sans-serif" font-size="12""#));
+    assert!(svg.contains(">a&lt;b</text>"));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn diff_highlights_differing_modules() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::Builder;
+    use crate::{QRBuilder, Version};
+
+    let qrcode = QRBuilder::new("diff")
+        .version(Version::V01)
+        .build()
+        .unwrap();
+    let mut other = qrcode.clone();
+    let value = other[12][10].value();
+    other[12][10].set(!value);
+
+    let svg = SvgBuilder::default()
+        .margin(2)
+        .scale(10)
+        .to_diff_str(&qrcode, &other);
+    let color = if value { "#dc2626" } else { "#16a34a" };
+    assert!(svg.ends_with(&format!(
+        r#"<path d="M120,140h10v10h-10" fill="{color}" fill-opacity=".8"/></svg>"#
+    )));
+
+    let same = SvgBuilder::default().to_diff_str(&qrcode, &qrcode);
+    assert_eq!(same, SvgBuilder::default().to_str(&qrcode));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn preset_renders_like_its_builder_across_threads() {
+    use crate::convert::preset::Preset;
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, Shape};
+    use crate::QRBuilder;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Preset>();
+
+    let qrcode = QRBuilder::new("preset").build().unwrap();
+    let mut builder = SvgBuilder::default();
+    builder
+        .shape(Shape::Circle)
+        .module_color_fn(|x, y, _| [(x * 8) as u8, (y * 8) as u8, 0, 255]);
+    let expected = builder.to_str(&qrcode);
+
+    let preset = std::sync::Arc::new(Preset::from(builder));
+    let rendered = {
+        let preset = std::sync::Arc::clone(&preset);
+        let qrcode = qrcode.clone();
+        std::thread::spawn(move || SvgBuilder::from_preset(&preset).to_str(&qrcode))
+    };
+
+    assert_eq!(rendered.join().unwrap(), expected);
+    assert_eq!(SvgBuilder::from_preset(&preset).to_str(&qrcode), expected);
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn builders_are_debug_and_thread_safe() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, Shape};
+
+    fn assert_thread_safe<T: Clone + core::fmt::Debug + Send + Sync>() {}
+    assert_thread_safe::<SvgBuilder>();
+    #[cfg(feature = "image")]
+    assert_thread_safe::<crate::convert::image::ImageBuilder>();
+
+    let mut builder = SvgBuilder::default();
+    builder
+        .shape(Shape::Circle)
+        .shape_fn(|y, x, _| format!("M{x},{y}h1v1h-1"))
+        .module_color_fn(|_, _, _| [0, 0, 0, 255]);
+
+    let debug = format!("{builder:?}");
+    assert!(debug.starts_with("SvgBuilder { commands: [Circle, Custom]"));
+    assert!(debug.contains("module_color_fn: Some(Custom)"));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn margins_offset_the_modules_by_side() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::Builder;
+    use crate::{QRBuilder, Version};
+
+    let qrcode = QRBuilder::new("margins")
+        .version(Version::V01)
+        .build()
+        .unwrap();
+
+    let svg = SvgBuilder::default().margins(1, 2, 3, 4).to_str(&qrcode);
+    assert!(svg.starts_with(r#"<svg viewBox="0 0 27 25""#));
+    assert!(svg.contains(r#"<path d="M4,1h1v1h-1M5,1h1v1h-1"#));
+
+    let mut uniform = SvgBuilder::default();
+    uniform.margins(3, 3, 3, 3);
+    assert_eq!(
+        uniform.to_str(&qrcode),
+        SvgBuilder::default().margin(3).to_str(&qrcode)
+    );
+}
+
+#[cfg(all(feature = "svg", feature = "serde"))]
+#[test]
+fn margins_round_trip_through_serde() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::Builder;
+    use crate::QRBuilder;
+
+    let qrcode = QRBuilder::new("margins").build().unwrap();
+
+    let mut builder = SvgBuilder::default();
+    builder.margins(1, 2, 3, 4);
+    let json = serde_json::to_string(&builder).unwrap();
+    assert!(json.contains(r#""margins":[1,2,3,4]"#));
+    let restored = serde_json::from_str::<SvgBuilder>(&json).unwrap();
+    assert_eq!(restored.to_str(&qrcode), builder.to_str(&qrcode));
+
+    let json = serde_json::to_string(SvgBuilder::default().margin(2)).unwrap();
+    assert!(json.contains(r#""margin":2,"margins":null"#));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn color_strings_are_parsed_and_validated() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, Color, ColorError};
+    use crate::QRBuilder;
+
+    assert_eq!("#ABCD".parse::<Color>(), Ok(Color::from("#aabbccdd")));
+    assert_eq!(Color::parse(" RebeccaPurple "), Ok(Color::from("#663399")));
+    assert_eq!(
+        Color::parse("#12g456"),
+        Err(ColorError::InvalidHex(String::from("#12g456")))
+    );
+    assert_eq!(
+        Color::parse("blurple"),
+        Err(ColorError::UnknownName(String::from("blurple")))
+    );
+
+    let qrcode = QRBuilder::new("colors").build().unwrap();
+    let mut builder = SvgBuilder::default();
+    builder
+        .module_color_str("#1A2B3C")
+        .unwrap()
+        .transparent_background();
+    assert!(builder.module_color_str("not a color").is_err());
+
+    let svg = builder.to_str(&qrcode);
+    assert!(svg.contains(r##"fill="#00000000"/>"##));
+    assert!(svg.contains(r##"fill="#1a2b3c"/>"##));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn float_settings_never_reach_the_svg_as_nan() {
+    use crate::convert::svg::{SvgBuilder, SvgUnit};
+    use crate::convert::{Builder, Shape};
+    use crate::{QRBuilder, ECL};
+
+    let qrcode = QRBuilder::new("https://example.com/")
+        .ecl(ECL::H)
+        .build()
+        .unwrap();
+
+    for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        let mut builder = SvgBuilder::default();
+        builder
+            .shape(Shape::Dot)
+            .dot_radius(value)
+            .module_gap(value)
+            .image("logo.png".to_string())
+            .image_size(value)
+            .image_gap(value)
+            .image_position(value, value)
+            .background_image("background.png".to_string())
+            .background_image_opacity(value)
+            .background_radius(value)
+            .size(value, SvgUnit::Mm);
+        assert!(builder.check_image(&qrcode).is_ok());
+        let svg = builder.to_str(&qrcode);
+        assert!(!svg.contains("NaN") && !svg.contains("inf"), "{value}");
+
+        let merged = builder.merge_modules(value).to_str(&qrcode);
+        assert!(
+            !merged.contains("NaN") && !merged.contains("inf"),
+            "{value}"
+        );
+    }
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn image_background_keeps_its_color_as_is() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, ImageBackgroundShape};
+    use crate::{QRBuilder, ECL};
+
+    let qrcode = QRBuilder::new("https://example.com/")
+        .ecl(ECL::H)
+        .build()
+        .unwrap();
+
+    // Scaled radii used to be replaced in the markup, colors included
+    let svg = SvgBuilder::default()
+        .image("logo.png".to_string())
+        .image_background_shape(ImageBackgroundShape::RoundedSquare)
+        .image_background_color("url(#bg1px)")
+        .scale(10)
+        .to_str(&qrcode);
+
+    assert!(svg.contains(r#"fill="url(#bg1px)" rx="10px"/>"#));
+}
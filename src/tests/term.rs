@@ -0,0 +1,60 @@
+use crate::convert::term::{TermBuilder, TermColor};
+use crate::{QRBuilder, Version, ECL};
+
+fn qrcode() -> crate::QRCode {
+    QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn term_full_blocks_match_modules() {
+    let qrcode = qrcode();
+    let out = TermBuilder::default()
+        .margin(0)
+        .half_blocks(false)
+        .to_str(&qrcode);
+
+    let lines = out.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), qrcode.size);
+    for (y, line) in lines.iter().enumerate() {
+        let chars = line.chars().collect::<Vec<_>>();
+        assert_eq!(chars.len(), qrcode.size * 2);
+        for x in 0..qrcode.size {
+            assert_eq!(chars[x * 2] == '█', qrcode[y][x].value());
+        }
+    }
+}
+
+#[test]
+fn term_half_blocks_merge_two_rows() {
+    let qrcode = qrcode();
+    let out = TermBuilder::default().to_str(&qrcode);
+
+    // 21 modules + 2 * 2 margin, rounded up
+    assert_eq!(out.lines().count(), 13);
+    // Margin, then the two top rows of the finder pattern
+    let lines = out.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], " ".repeat(25));
+    assert!(lines[1].starts_with("  █▀▀▀▀▀█ "));
+
+    let inverted = TermBuilder::default().invert(true).to_str(&qrcode);
+    let lines = inverted.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "█".repeat(25));
+    assert!(lines[1].starts_with("██ ▄▄▄▄▄ █"));
+}
+
+#[test]
+fn term_colors_use_ansi_codes() {
+    let qrcode = qrcode();
+    let out = TermBuilder::default()
+        .module_color(TermColor::TrueColor([30, 58, 138]))
+        .background_color(TermColor::Ansi256(231))
+        .to_str(&qrcode);
+
+    assert!(out.contains("\x1b[38;2;30;58;138m"));
+    assert!(out.contains("\x1b[48;5;231m"));
+    assert!(out.lines().all(|line| line.ends_with("\x1b[0m")));
+}
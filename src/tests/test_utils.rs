@@ -0,0 +1,70 @@
+use crate::placement::MASKS;
+use crate::test_utils::{
+    assert_matrix_eq, compare_matrix, data_codewords, error_correction_blocks, format_information,
+    generator_polynomial, parse_matrix, total_codewords, Mismatch,
+};
+use crate::{QRBuilder, Version, ECL};
+
+#[test]
+fn format_information_is_a_bch_codeword() {
+    for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+        for mask in MASKS {
+            let bits = u32::from_str_radix(&format_information(ecl, mask), 2).unwrap();
+
+            // Unmasked, the 15 bits are a multiple of the generator 10100110111
+            let mut remainder = bits ^ 0b101_0100_0001_0010;
+            for shift in (0..=4).rev() {
+                if remainder & (1 << (shift + 10)) != 0 {
+                    remainder ^= 0b101_0011_0111 << shift;
+                }
+            }
+            assert_eq!(remainder, 0);
+            assert_eq!((bits ^ 0b101_0100_0001_0010) >> 10 & 0b111, mask as u32);
+        }
+    }
+}
+
+#[test]
+fn codeword_tables_are_consistent() {
+    for version in Version::ALL {
+        for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+            let blocks = error_correction_blocks(version, ecl);
+            let data = blocks.iter().map(|(count, len)| count * len).sum::<usize>();
+            let count = blocks.iter().map(|(count, _)| count).sum::<usize>();
+            let error = generator_polynomial(version, ecl).len() - 1;
+
+            assert_eq!(data, data_codewords(version, ecl));
+            assert_eq!(data + count * error, total_codewords(version));
+        }
+    }
+}
+
+#[test]
+fn compare_matrix_reports_mismatches() {
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let mut reference = qrcode.to_bool_matrix();
+    assert_matrix_eq(&qrcode, &reference, 0);
+
+    reference[0][1] = !reference[0][1];
+    assert_eq!(
+        compare_matrix(&qrcode, &reference, 0),
+        [Mismatch {
+            row: 0,
+            column: 1,
+            expected: true
+        }]
+    );
+
+    // Every module of a missing row differs
+    reference.pop();
+    assert_eq!(
+        compare_matrix(&qrcode, &reference, 0).len(),
+        1 + qrcode.size
+    );
+
+    let text = "#1.\n\n 0█\n";
+    assert_eq!(
+        parse_matrix(text),
+        [vec![true, true, false], vec![false, false, true]]
+    );
+}
@@ -0,0 +1,92 @@
+use crate::convert::text::{TextBuilder, TextFormat};
+use crate::{QRBuilder, Version, ECL};
+
+fn qrcode() -> crate::QRCode {
+    QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn braille_packs_eight_modules_per_char() {
+    let qrcode = qrcode();
+    let out = TextBuilder::default()
+        .format(TextFormat::Braille)
+        .margin(0)
+        .to_str(&qrcode);
+
+    // 21 modules, rounded up
+    let lines = out.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 6);
+    for (y, line) in lines.iter().enumerate() {
+        let chars = line.chars().collect::<Vec<_>>();
+        assert_eq!(chars.len(), 11);
+        for (x, &c) in chars.iter().enumerate() {
+            let pattern = c as u32 - 0x2800;
+            for (dy, bits) in [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]]
+                .iter()
+                .enumerate()
+            {
+                for (dx, &bit) in bits.iter().enumerate() {
+                    let (y, x) = (y * 4 + dy, x * 2 + dx);
+                    let dark = y < qrcode.size && x < qrcode.size && qrcode[y][x].value();
+                    assert_eq!(pattern & bit != 0, dark);
+                }
+            }
+        }
+    }
+
+    // Corner of the finder pattern: its dark left column and the top of its light ring
+    assert_eq!(lines[0].chars().next(), Some('⡏'));
+}
+
+#[test]
+fn html_has_one_cell_per_module() {
+    let qrcode = qrcode();
+    let mut builder = TextBuilder::default();
+    builder.margin(1).module_size(2).module_color("#112233");
+
+    let table = builder.format(TextFormat::HtmlTable).to_str(&qrcode);
+    assert!(table.starts_with("<table"));
+    assert!(table.ends_with("</table>"));
+    assert_eq!(table.matches("<tr>").count(), 23);
+    assert_eq!(table.matches("<td ").count(), 23 * 23);
+    assert_eq!(
+        table.matches("background:#112233").count(),
+        qrcode.data[..21 * 21].iter().filter(|m| m.value()).count()
+    );
+
+    let grid = builder.format(TextFormat::HtmlDiv).to_str(&qrcode);
+    assert!(grid.starts_with(r#"<div style="display:grid;grid-template-columns:repeat(23,2px)">"#));
+    assert_eq!(grid.matches("<div ").count(), 23 * 23 + 1);
+}
+
+#[test]
+fn blocks_match_the_terminal_output() {
+    use crate::convert::term::TermBuilder;
+
+    let qrcode = qrcode();
+    assert_eq!(
+        TextBuilder::default().invert(true).to_str(&qrcode),
+        TermBuilder::default().invert(true).to_str(&qrcode)
+    );
+}
+
+#[test]
+fn ascii_uses_two_characters_per_module() {
+    let qrcode = qrcode();
+    let out = TextBuilder::default()
+        .format(TextFormat::Ascii)
+        .margin(0)
+        .to_str(&qrcode);
+
+    assert!(out.is_ascii());
+    for (y, line) in out.lines().enumerate() {
+        assert_eq!(line.len(), qrcode.size * 2);
+        for x in 0..qrcode.size {
+            assert_eq!(&line[x * 2..x * 2 + 2] == "##", qrcode[y][x].value());
+        }
+    }
+}
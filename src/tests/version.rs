@@ -1,4 +1,5 @@
-use crate::{Mask, QRCode};
+use crate::qr::QRCodeError;
+use crate::{Mask, QRBuilder, QRCode, Version, ECL};
 
 #[test]
 fn version_format_l_mask0() {
@@ -7,7 +8,7 @@ fn version_format_l_mask0() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V05);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::L);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -43,7 +44,7 @@ fn version_format_l_mask1() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V03);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::L);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -79,7 +80,7 @@ fn version_format_l_mask2() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V06);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::L);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -115,7 +116,7 @@ fn version_format_l_mask3() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V03);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::L);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -151,7 +152,7 @@ fn version_format_l_mask4() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V06);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::L);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -187,7 +188,7 @@ fn version_format_l_mask5() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V06);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::L);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -225,7 +226,7 @@ fn version_format_l_mask6() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V06);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::L);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -262,7 +263,7 @@ fn version_format_l_mask7() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V05);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::L);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -298,7 +299,7 @@ fn version_format_m_mask0() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V01);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::M);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -334,7 +335,7 @@ fn version_format_m_mask1() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V04);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::M);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -370,7 +371,7 @@ fn version_format_m_mask2() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V02);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::M);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -406,7 +407,7 @@ fn version_format_m_mask3() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V06);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::M);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -442,7 +443,7 @@ fn version_format_m_mask4() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V01);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::M);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -478,7 +479,7 @@ fn version_format_m_mask5() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V02);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::M);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -514,7 +515,7 @@ fn version_format_m_mask6() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V01);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::M);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -550,7 +551,7 @@ fn version_format_m_mask7() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V03);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::M);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -586,7 +587,7 @@ fn version_format_q_mask0() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V04);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::Q);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -622,7 +623,7 @@ fn version_format_q_mask1() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V02);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::Q);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -658,7 +659,7 @@ fn version_format_q_mask2() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V04);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::Q);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -694,7 +695,7 @@ fn version_format_q_mask3() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V05);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::Q);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -730,7 +731,7 @@ fn version_format_q_mask4() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V01);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::Q);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -766,7 +767,7 @@ fn version_format_q_mask5() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V05);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::Q);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -802,7 +803,7 @@ fn version_format_q_mask6() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V02);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::Q);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -838,7 +839,7 @@ fn version_format_q_mask7() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V01);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::Q);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -874,7 +875,7 @@ fn version_format_h_mask0() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V06);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::H);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -910,7 +911,7 @@ fn version_format_h_mask1() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V02);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::H);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -946,7 +947,7 @@ fn version_format_h_mask2() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V04);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::H);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -982,7 +983,7 @@ fn version_format_h_mask3() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V03);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::H);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -1018,7 +1019,7 @@ fn version_format_h_mask4() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V02);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::H);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -1054,7 +1055,7 @@ fn version_format_h_mask5() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V04);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::H);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -1090,7 +1091,7 @@ fn version_format_h_mask6() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V02);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::H);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -1126,7 +1127,7 @@ fn version_format_h_mask7() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V01);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::H);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -1162,7 +1163,7 @@ fn version_format_l_mask0_version23() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V23);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::L);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -1247,7 +1248,7 @@ fn version_format_l_mask1_version29() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V29);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::L);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -1332,7 +1333,7 @@ fn version_format_l_mask2_version40() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V40);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::L);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -1417,7 +1418,7 @@ fn version_format_l_mask3_version8() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V08);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::L);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -1502,7 +1503,7 @@ fn version_format_l_mask4_version36() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V36);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::L);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -1587,7 +1588,7 @@ fn version_format_l_mask5_version22() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V22);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::L);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -1672,7 +1673,7 @@ fn version_format_l_mask6_version10() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V10);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::L);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -1757,7 +1758,7 @@ fn version_format_l_mask7_version17() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V17);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::L);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -1842,7 +1843,7 @@ fn version_format_m_mask0_version14() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V14);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::M);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -1927,7 +1928,7 @@ fn version_format_m_mask1_version30() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V30);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::M);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -2012,7 +2013,7 @@ fn version_format_m_mask2_version37() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V37);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::M);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -2097,7 +2098,7 @@ fn version_format_m_mask3_version22() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V22);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::M);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -2182,7 +2183,7 @@ fn version_format_m_mask4_version31() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V31);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::M);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -2267,7 +2268,7 @@ fn version_format_m_mask5_version13() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V13);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::M);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -2352,7 +2353,7 @@ fn version_format_m_mask6_version22() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V22);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::M);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -2437,7 +2438,7 @@ fn version_format_m_mask7_version7() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V07);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::M);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -2522,7 +2523,7 @@ fn version_format_q_mask0_version20() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V20);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::Q);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -2607,7 +2608,7 @@ fn version_format_q_mask1_version33() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V33);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::Q);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -2692,7 +2693,7 @@ fn version_format_q_mask2_version24() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V24);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::Q);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -2777,7 +2778,7 @@ fn version_format_q_mask3_version18() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V18);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::Q);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -2862,7 +2863,7 @@ fn version_format_q_mask4_version31() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V31);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::Q);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -2947,7 +2948,7 @@ fn version_format_q_mask5_version17() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V17);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::Q);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -3032,7 +3033,7 @@ fn version_format_q_mask6_version11() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V11);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::Q);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -3117,7 +3118,7 @@ fn version_format_q_mask7_version15() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V15);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::Q);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -3202,7 +3203,7 @@ fn version_format_h_mask0_version35() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V35);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::H);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -3287,7 +3288,7 @@ fn version_format_h_mask1_version15() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V15);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::H);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -3372,7 +3373,7 @@ fn version_format_h_mask2_version15() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V15);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::H);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -3457,7 +3458,7 @@ fn version_format_h_mask3_version7() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V07);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::H);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -3542,7 +3543,7 @@ fn version_format_h_mask4_version7() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V07);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::H);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -3627,7 +3628,7 @@ fn version_format_h_mask5_version20() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V20);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::H);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -3712,7 +3713,7 @@ fn version_format_h_mask6_version20() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V20);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::H);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -3797,7 +3798,7 @@ fn version_format_h_mask7_version17() {
     const VERSION: Option<crate::version::Version> = Some(crate::version::Version::V17);
     const LEVEL: Option<crate::ecl::ECL> = Some(crate::ecl::ECL::H);
 
-    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK);
+    let q = QRCode::new(CONTENT.as_bytes(), LEVEL, VERSION, None, MASK, false);
     if q.is_err() {
         assert_eq!(true, false, "Couldn't create QR");
     };
@@ -3874,3 +3875,148 @@ fn version_format_h_mask7_version17() {
         assert_eq!(tmp2.map(|x| x.value()), expected2);
     }
 }
+
+#[test]
+fn max_capacity_matches_version_table() {
+    use crate::{Mode, Version, ECL};
+
+    for version in Version::ALL {
+        for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+            for mode in [Mode::Numeric, Mode::Alphanumeric, Mode::Byte] {
+                let capacity = version.max_capacity(ecl, mode);
+                let fits = Version::get(mode, ecl, capacity).map(|v| v as usize);
+                let overflows = Version::get(mode, ecl, capacity + 1).map(|v| v as usize);
+
+                assert!(fits <= Some(version as usize));
+                assert_ne!(overflows, Some(version as usize));
+            }
+        }
+    }
+}
+
+#[test]
+fn estimate_version_matches_build() {
+    use crate::{QRBuilder, Version};
+
+    for len in [0, 20, 100, 1000] {
+        let input = "fast_qr ".repeat(len);
+        let builder = QRBuilder::new(input);
+        let estimate = builder.estimate_version().map(|v| v.size());
+        assert_eq!(estimate, builder.build().ok().map(|qr| qr.size));
+    }
+
+    let too_small = QRBuilder::new("a".repeat(100))
+        .version(Version::V01)
+        .estimate_version();
+    assert!(too_small.is_none());
+}
+
+#[test]
+fn version_range_clamps_and_limits() {
+    // "https://example.com/" needs V02
+    let small = QRBuilder::new("https://example.com/")
+        .version_range(Version::V04, Version::V06)
+        .build()
+        .unwrap();
+    assert_eq!(
+        small.version().map(|v| v as usize),
+        Some(Version::V04 as usize)
+    );
+
+    let fits = QRBuilder::new("https://example.com/")
+        .version_range(Version::V06, Version::V01)
+        .build()
+        .unwrap();
+    assert_eq!(
+        fits.version().map(|v| v as usize),
+        Some(Version::V02 as usize)
+    );
+
+    let too_big = QRBuilder::new("a".repeat(100))
+        .version_range(Version::V01, Version::V03)
+        .build();
+    assert!(matches!(too_big, Err(QRCodeError::VersionRange)));
+}
+
+#[test]
+fn validate_explains_failures() {
+    use crate::qr::ValidationError;
+    use crate::{MicroVersion, Mode};
+
+    assert!(QRBuilder::new("https://example.com/").validate().is_ok());
+
+    let invalid = QRBuilder::new("12a4").mode(Mode::Numeric).validate();
+    assert!(matches!(
+        invalid,
+        Err(ValidationError::InvalidCharacter {
+            mode: Mode::Numeric,
+            index: 2
+        })
+    ));
+
+    // Version 40 holds 1666 data codewords at ECL::Q
+    let too_long = QRBuilder::new("a".repeat(3000)).validate();
+    assert!(matches!(
+        too_long,
+        Err(ValidationError::TooLong { capacity: 13328 })
+    ));
+
+    let range = QRBuilder::new("a".repeat(100))
+        .version_range(Version::V01, Version::V03)
+        .validate();
+    assert!(matches!(
+        range,
+        Err(ValidationError::VersionOutOfRange {
+            needed: Version::V08,
+            max: Version::V03
+        })
+    ));
+
+    let micro = QRBuilder::new("12345678")
+        .micro_version(MicroVersion::M1)
+        .validate();
+    assert!(matches!(
+        micro,
+        Err(ValidationError::MicroVersionTooSmall {
+            needed: MicroVersion::M2,
+            version: MicroVersion::M1
+        })
+    ));
+
+    let unsupported = QRBuilder::new("1").micro().ecl(ECL::H).validate();
+    assert!(matches!(
+        unsupported,
+        Err(ValidationError::MicroUnsupported)
+    ));
+
+    // Everything `validate` accepts builds
+    for len in [0, 10, 100, 1000, 3000] {
+        let builder = QRBuilder::new("fast_qr ".repeat(len));
+        assert_eq!(builder.validate().is_ok(), builder.build().is_ok());
+    }
+}
+
+#[test]
+fn version_conversions() {
+    use crate::VersionError;
+
+    for (index, &version) in Version::ALL.iter().enumerate() {
+        assert_eq!(
+            Version::try_from(index + 1).unwrap() as usize,
+            version as usize
+        );
+        assert_eq!(
+            Version::from_size(version.size()).unwrap() as usize,
+            version as usize
+        );
+    }
+
+    assert_eq!(Version::try_from(0).unwrap_err(), VersionError::Number(0));
+    assert_eq!(Version::try_from(41).unwrap_err(), VersionError::Number(41));
+    for size in [0, 11, 17, 22, 23, 181, usize::MAX] {
+        assert_eq!(
+            Version::from_size(size).unwrap_err(),
+            VersionError::Size(size)
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use crate::convert::xbm::XbmBuilder;
+use crate::{QRBuilder, Version, ECL};
+
+fn qrcode() -> crate::QRCode {
+    QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn source_declares_the_bitmap() {
+    let qrcode = qrcode();
+    let xbm = XbmBuilder::default().margin(1).to_str(&qrcode);
+
+    let mut lines = xbm.lines();
+    assert_eq!(lines.next(), Some("#define qr_width 23"));
+    assert_eq!(lines.next(), Some("#define qr_height 23"));
+    assert_eq!(lines.next(), Some("static unsigned char qr_bits[] = {"));
+    assert!(xbm.ends_with(" };\n"));
+
+    let bytes = xbm
+        .split(['{', '}'])
+        .nth(1)
+        .unwrap()
+        .split(',')
+        .map(|byte| u8::from_str_radix(byte.trim().trim_start_matches("0x"), 16).unwrap())
+        .collect::<Vec<_>>();
+    // 3 bytes per row, the leftmost pixel in the least significant bit
+    assert_eq!(bytes.len(), 23 * 3);
+    for y in 0..23 {
+        for x in 0..23 {
+            let dark = (1..=qrcode.size).contains(&y)
+                && (1..=qrcode.size).contains(&x)
+                && qrcode[y - 1][x - 1].value();
+            assert_eq!(bytes[y * 3 + x / 8] & (1 << (x % 8)) != 0, dark);
+        }
+    }
+}
+
+#[test]
+fn name_is_a_c_identifier() {
+    let qrcode = qrcode();
+
+    let xbm = XbmBuilder::default().name("2fa-code").to_str(&qrcode);
+    assert!(xbm.starts_with("#define _2fa_code_width 29\n"));
+
+    let xbm = XbmBuilder::default().name("").to_str(&qrcode);
+    assert!(xbm.starts_with("#define qr_width 29\n"));
+}
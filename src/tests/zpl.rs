@@ -0,0 +1,59 @@
+use crate::convert::zpl::ZplBuilder;
+use crate::{QRBuilder, Version, ECL};
+
+fn qrcode() -> crate::QRCode {
+    QRBuilder::new("Test")
+        .ecl(ECL::M)
+        .version(Version::V01)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn graphic_field_has_every_dot() {
+    let qrcode = qrcode();
+    let zpl = ZplBuilder::default()
+        .margin(1)
+        .module_size(2)
+        .to_str(&qrcode);
+
+    // 23 modules, 46 dots: rows take 6 bytes
+    let prefix = "^XA^FO0,0^GFA,276,276,6,";
+    assert!(zpl.starts_with(prefix));
+    assert!(zpl.ends_with("^FS^XZ"));
+
+    let hex = &zpl[prefix.len()..zpl.len() - "^FS^XZ".len()];
+    assert_eq!(hex.len(), 276 * 2);
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect::<Vec<_>>();
+    for (y, row) in bytes.chunks(6).enumerate() {
+        for x in 0..46 {
+            let dark = row[x / 8] & (0x80 >> (x % 8)) != 0;
+            let (my, mx) = (y / 2, x / 2);
+            let expected = (1..=21).contains(&my) && (1..=21).contains(&mx);
+            assert_eq!(dark, expected && qrcode[my - 1][mx - 1].value());
+        }
+    }
+}
+
+#[test]
+fn width_in_millimeters_sets_the_module_size() {
+    let qrcode = qrcode();
+    // 29 modules in 1 inch at 300 dpi: 10 dots each
+    let zpl = ZplBuilder::default()
+        .position(10, 20)
+        .fit_width_mm(25.4, 300)
+        .to_str(&qrcode);
+    assert!(zpl.starts_with("^XA^FO10,20^GFA,10730,10730,37,"));
+}
+
+#[test]
+fn native_field_escapes_commands() {
+    let qrcode = QRBuilder::new("a^b_c").ecl(ECL::H).build().unwrap();
+    let zpl = ZplBuilder::default()
+        .module_size(3)
+        .to_native(&qrcode, "a^b_c");
+    assert_eq!(zpl, "^XA^FO0,0^BQN,2,3^FH^FDHA,a_5Eb_5Fc^FS^XZ");
+}
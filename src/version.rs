@@ -2,9 +2,11 @@
 
 use crate::ecl::ECL;
 use crate::encode::Mode;
+use crate::hardcode;
 
 /// Enum containing all possible `QRCode` versions
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "wasm-bindgen", wasm_bindgen::prelude::wasm_bindgen)]
 pub enum Version {
     /// Version n°01
@@ -89,7 +91,68 @@ pub enum Version {
     V40 = 39,
 }
 
+/// Value that does not match any [`Version`], see [`Version::try_from`] and
+/// [`Version::from_size`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionError {
+    /// Version number is not between 1 and 40
+    Number(usize),
+    /// Size is not one of 21, 25, ..., 177 modules
+    Size(usize),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VersionError {}
+
+impl core::fmt::Display for VersionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            VersionError::Number(number) => write!(f, "No version {number}, expected 1 to 40"),
+            VersionError::Size(size) => {
+                write!(f, "No version of size {size}, expected 21, 25, ..., 177")
+            }
+        }
+    }
+}
+
+/// Converts a version number, 1 to 40
+///
+/// ```rust
+/// use fast_qr::{Version, VersionError};
+///
+/// assert!(matches!(Version::try_from(7), Ok(Version::V07)));
+/// assert_eq!(Version::try_from(41).unwrap_err(), VersionError::Number(41));
+/// ```
+impl TryFrom<usize> for Version {
+    type Error = VersionError;
+
+    fn try_from(number: usize) -> Result<Self, Self::Error> {
+        match number
+            .checked_sub(1)
+            .and_then(|index| Version::ALL.get(index))
+        {
+            Some(&version) => Ok(version),
+            None => Err(VersionError::Number(number)),
+        }
+    }
+}
+
 impl Version {
+    /// Every `Version`, from the smallest to the largest
+    pub(crate) const ALL: [Version; 40] = {
+        use Version::{
+            V01, V02, V03, V04, V05, V06, V07, V08, V09, V10, V11, V12, V13, V14, V15, V16, V17,
+            V18, V19, V20, V21, V22, V23, V24, V25, V26, V27, V28, V29, V30, V31, V32, V33, V34,
+            V35, V36, V37, V38, V39, V40,
+        };
+
+        [
+            V01, V02, V03, V04, V05, V06, V07, V08, V09, V10, V11, V12, V13, V14, V15, V16, V17,
+            V18, V19, V20, V21, V22, V23, V24, V25, V26, V27, V28, V29, V30, V31, V32, V33, V34,
+            V35, V36, V37, V38, V39, V40,
+        ]
+    };
+
     /// Computes the best `Version` according to `mode`, `ecl` and `len`
     #[must_use]
     #[allow(clippy::too_many_lines)]
@@ -626,61 +689,64 @@ impl Version {
         }
     }
 
+    /// Returns the number of characters a single `mode` segment can hold with this version and
+    /// `ecl`, without ECI nor other header.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::{Mode, Version, ECL};
+    ///
+    /// assert_eq!(Version::V01.max_capacity(ECL::L, Mode::Numeric), 41);
+    /// assert_eq!(Version::V40.max_capacity(ECL::H, Mode::Byte), 1273);
+    /// ```
+    #[must_use]
+    pub const fn max_capacity(self, ecl: ECL, mode: Mode) -> usize {
+        let available = hardcode::data_bits(self, ecl) - 4 - hardcode::cci_bits(self, mode);
+
+        match mode {
+            Mode::Numeric => {
+                let remaining = match available % 10 {
+                    7..=9 => 2,
+                    4..=6 => 1,
+                    _ => 0,
+                };
+                available / 10 * 3 + remaining
+            }
+            Mode::Alphanumeric => available / 11 * 2 + (available % 11 >= 6) as usize,
+            Mode::Byte => available / 8,
+        }
+    }
+
+    /// Returns the `Version` of a [`crate::QRCode`] of `size` modules per side
+    ///
+    /// # Errors
+    /// - `VersionError::Size` if `size` is not one of 21, 25, ..., 177
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::{Version, VersionError};
+    ///
+    /// assert!(matches!(Version::from_size(25), Ok(Version::V02)));
+    /// assert_eq!(Version::from_size(17).unwrap_err(), VersionError::Size(17));
+    /// ```
+    pub const fn from_size(size: usize) -> Result<Self, VersionError> {
+        if size < Version::V01.size() || size > Version::V40.size() || size % 4 != 1 {
+            return Err(VersionError::Size(size));
+        }
+
+        Ok(Version::ALL[(size - Version::V01.size()) / 4])
+    }
+
     /// Returns `Version` based on the size of the [`crate::QRCode`]
     ///
     /// # Panics
     /// Function panics if `n` is not included in `(21..=177).step_by(4)`
     #[must_use]
-    #[cfg(any(test, feature = "svg", feature = "image", debug_assertions))]
+    #[cfg(any(test, debug_assertions))]
     pub(crate) const fn from_n(n: usize) -> Self {
-        use Version::{
-            V01, V02, V03, V04, V05, V06, V07, V08, V09, V10, V11, V12, V13, V14, V15, V16, V17,
-            V18, V19, V20, V21, V22, V23, V24, V25, V26, V27, V28, V29, V30, V31, V32, V33, V34,
-            V35, V36, V37, V38, V39, V40,
-        };
-
-        match n {
-            21 => V01,
-            25 => V02,
-            29 => V03,
-            33 => V04,
-            37 => V05,
-            41 => V06,
-            45 => V07,
-            49 => V08,
-            53 => V09,
-            57 => V10,
-            61 => V11,
-            65 => V12,
-            69 => V13,
-            73 => V14,
-            77 => V15,
-            81 => V16,
-            85 => V17,
-            89 => V18,
-            93 => V19,
-            97 => V20,
-            101 => V21,
-            105 => V22,
-            109 => V23,
-            113 => V24,
-            117 => V25,
-            121 => V26,
-            125 => V27,
-            129 => V28,
-            133 => V29,
-            137 => V30,
-            141 => V31,
-            145 => V32,
-            149 => V33,
-            153 => V34,
-            157 => V35,
-            161 => V36,
-            165 => V37,
-            169 => V38,
-            173 => V39,
-            177 => V40,
-            _ => panic!("Invalid matrix size"),
+        match Self::from_size(n) {
+            Ok(version) => version,
+            Err(_) => panic!("Invalid matrix size"),
         }
     }
 
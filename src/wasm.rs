@@ -1,3 +1,5 @@
+#[cfg(all(feature = "svg", feature = "wasm-bindgen"))]
+use crate::convert::image::{RasterBackend, SquareRasterizer};
 use crate::QRCode;
 #[cfg(feature = "svg")]
 use crate::{convert, Version, ECL};
@@ -16,7 +18,7 @@ fn bool_to_u8(qr: QRCode) -> Vec<u8> {
 #[cfg_attr(feature = "wasm-bindgen", wasm_bindgen)]
 #[must_use]
 pub fn qr(content: &str) -> Vec<u8> {
-    let qrcode = QRCode::new(content.as_bytes(), None, None, None, None);
+    let qrcode = QRCode::new(content.as_bytes(), None, None, None, None, true);
     qrcode.map(bool_to_u8).unwrap_or(Vec::new())
 }
 
@@ -39,21 +41,28 @@ pub struct SvgOptions {
     image_background_shape: convert::ImageBackgroundShape,
     image_size: Vec<f64>,
     image_position: Vec<f64>,
+
+    finder_color: Vec<u8>,
+    gradient: Option<(f64, Vec<u8>, Vec<u8>)>,
+    merge_radius: Option<f64>,
 }
 
 #[cfg_attr(feature = "wasm-bindgen", wasm_bindgen)]
 #[cfg(feature = "svg")]
 impl SvgOptions {
+    /// Parses `#RRGGBB[AA]`, returns an empty code if `color` is invalid
     fn color_to_code(color: String) -> Vec<u8> {
-        let mut color = color;
-        if color.starts_with('#') {
-            color.remove(0);
+        let color = color.strip_prefix('#').unwrap_or(&color);
+        if !color.is_ascii() || !matches!(color.len(), 6 | 8) {
+            return Vec::new();
         }
-        let color = color.as_bytes();
-        let color = color.chunks_exact(2);
-        let color = color.map(|x| u8::from_str_radix(std::str::from_utf8(x).unwrap(), 16).unwrap());
 
-        let mut color = color.collect::<Vec<u8>>();
+        let color = (0..color.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&color[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>();
+
+        let mut color = color.unwrap_or_default();
         if color.len() == 3 {
             color.push(255);
         }
@@ -144,6 +153,43 @@ impl SvgOptions {
         }
     }
 
+    /// Updates the color of the finder patterns. Takes a string in the format `#RRGGBB[AA]`.
+    pub fn finder_color(self, finder_color: String) -> Self {
+        let code = Self::color_to_code(finder_color);
+        if code.len() != 4 {
+            return self;
+        }
+
+        Self {
+            finder_color: code,
+            ..self
+        }
+    }
+
+    /// Fills the modules with a linear gradient, `angle` in degrees (0 is left to right).
+    /// Takes strings in the format `#RRGGBB[AA]`.
+    pub fn linear_gradient(self, angle: f64, start: String, end: String) -> Self {
+        let start = Self::color_to_code(start);
+        let end = Self::color_to_code(end);
+        if start.len() != 4 || end.len() != 4 {
+            return self;
+        }
+
+        Self {
+            gradient: Some((angle, start, end)),
+            ..self
+        }
+    }
+
+    /// Merges adjacent modules into smooth shapes, `corner_radius` being between 0 and 0.5
+    /// (unit being module size).
+    pub fn merge_modules(self, corner_radius: f64) -> Self {
+        Self {
+            merge_radius: Some(corner_radius),
+            ..self
+        }
+    }
+
     /// Updates the error correction level of the QRCode (can increase the size of the QRCode)
     pub fn ecl(self, ecl: ECL) -> Self {
         Self {
@@ -182,6 +228,10 @@ impl SvgOptions {
             image_background_shape: convert::ImageBackgroundShape::Square,
             image_size: vec![],
             image_position: vec![],
+
+            finder_color: vec![],
+            gradient: None,
+            merge_radius: None,
         }
     }
 }
@@ -192,7 +242,14 @@ impl SvgOptions {
 pub fn qr_svg(content: &str, options: SvgOptions) -> String {
     use crate::convert::svg::SvgBuilder;
     use crate::convert::Builder;
-    let qrcode = QRCode::new(content.as_bytes(), options.ecl, options.version, None, None);
+    let qrcode = QRCode::new(
+        content.as_bytes(),
+        options.ecl,
+        options.version,
+        None,
+        None,
+        true,
+    );
 
     let mut builder = SvgBuilder::default();
     builder.shape(options.shape);
@@ -213,13 +270,96 @@ pub fn qr_svg(content: &str, options: SvgOptions) -> String {
         builder.image_gap(gap);
     }
 
-    if options.image_size.len() == 2 {
+    if options.image_position.len() == 2 {
         let x = options.image_position[0];
         let y = options.image_position[1];
         builder.image_position(x, y);
     }
 
+    if !options.finder_color.is_empty() {
+        builder.finder_color(options.finder_color);
+    }
+    if let Some((angle, start, end)) = options.gradient {
+        builder.module_gradient(convert::Gradient::linear(
+            angle,
+            vec![(0.0, start), (1.0, end)],
+        ));
+    }
+    if let Some(corner_radius) = options.merge_radius {
+        builder.merge_modules(corner_radius);
+    }
+
     qrcode
         .map(|qrcode| builder.to_str(&qrcode))
         .unwrap_or(String::new())
 }
+
+/// Builds the QR code and its square rasterizer, in the colors and margin of `options`
+#[cfg(all(feature = "svg", feature = "wasm-bindgen"))]
+fn qr_raster(
+    content: &str,
+    options: &SvgOptions,
+    module_size: u32,
+) -> Option<(QRCode, SquareRasterizer)> {
+    let qrcode = QRCode::new(
+        content.as_bytes(),
+        options.ecl,
+        options.version,
+        None,
+        None,
+        true,
+    )
+    .ok()?;
+
+    let mut rasterizer = SquareRasterizer::default();
+    rasterizer
+        .module_size(module_size)
+        .margin(options.margin)
+        .module_color(options.module_color.clone())
+        .background_color(options.background_color.clone());
+    Some((qrcode, rasterizer))
+}
+
+/// Draws the QR code on `context` at (0, 0), `module_size` pixels per module, without going
+/// through an svg. Modules are squares, only the colors, margin, ECL and version of `options`
+/// are used.
+///
+/// Returns the side of the drawing in pixels, 0 if nothing was drawn.
+#[cfg(all(feature = "svg", feature = "wasm-bindgen"))]
+#[wasm_bindgen]
+pub fn qr_canvas(
+    content: &str,
+    options: SvgOptions,
+    context: &web_sys::CanvasRenderingContext2d,
+    module_size: u32,
+) -> u32 {
+    let image = match qr_raster(content, &options, module_size)
+        .and_then(|(qrcode, rasterizer)| rasterizer.rasterize(&qrcode).ok())
+    {
+        Some(image) => image,
+        None => return 0,
+    };
+
+    let data = web_sys::ImageData::new_with_u8_clamped_array_and_sh(
+        wasm_bindgen::Clamped(&image.data),
+        image.width,
+        image.height,
+    );
+    match data.and_then(|data| context.put_image_data(&data, 0.0, 0.0)) {
+        Ok(()) => image.width,
+        Err(_) => 0,
+    }
+}
+
+/// Generates the PNG image of the QR code, a `Uint8Array` in JavaScript, `module_size` pixels
+/// per module. Modules are squares, only the colors, margin, ECL and version of `options` are
+/// used.
+///
+/// Returns an empty array if `content` is too large.
+#[cfg(all(feature = "svg", feature = "wasm-bindgen"))]
+#[wasm_bindgen]
+pub fn qr_png(content: &str, options: SvgOptions, module_size: u32) -> Vec<u8> {
+    qr_raster(content, &options, module_size)
+        .and_then(|(qrcode, rasterizer)| rasterizer.to_png(&qrcode).ok())
+        .unwrap_or_default()
+}